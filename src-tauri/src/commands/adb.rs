@@ -3,10 +3,15 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use crate::commands::validate_output_dir;
+use crate::commands::{operation_error, validate_output_dir};
 use crate::error::AppError;
 use crate::models::{FlashProgress, OperationCompleteEvent, OperationOutputEvent};
+use crate::services::adb_cli::{self, AdbCliDevice, AdbCliStatus, AdbRebootTarget};
+use crate::services::antumbra::AntumbraExecutor;
 use crate::services::config::load_settings;
+use crate::services::events;
+use crate::services::output_layout;
+use crate::services::paths;
 use adb_client::usb::{find_all_connected_adb_devices, ADBDeviceInfo, ADBUSBDevice};
 use adb_client::{ADBDeviceExt, ADBListItem, ADBListItemType, RebootType, RustADBError};
 use base64::{engine::general_purpose::STANDARD, Engine};
@@ -22,7 +27,7 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Once;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -471,10 +476,22 @@ pub async fn adb_framebuffer_save(
     let output_dir = settings
         .default_output_path
         .ok_or_else(|| AppError::command("Default output path not configured"))?;
-    validate_output_dir(&output_dir, "Output directory")?;
 
     let filename = format!("adb_screenshot_{}.png", Utc::now().format("%Y%m%d_%H%M%S"));
     let output_path = PathBuf::from(output_dir).join(filename);
+    let output_path = output_layout::resolve(
+        &app,
+        &operation_id,
+        &output_path.to_string_lossy(),
+        false,
+        "screenshot",
+        Some(&device_id),
+    )
+    .map(PathBuf::from)
+    .map_err(|e| AppError::io(e.to_string()))?;
+    if let Some(parent) = output_path.parent() {
+        validate_output_dir(&parent.to_string_lossy(), "Output directory")?;
+    }
 
     emit_operation_output(
         &app,
@@ -535,7 +552,7 @@ fn emit_operation_output(app: &AppHandle, operation_id: &str, line: &str, is_std
         timestamp: Utc::now().to_rfc3339(),
         is_stderr,
     };
-    let _ = app.emit("operation:output", event);
+    let _ = events::emit(app, "operation:output", event);
 }
 
 fn emit_operation_complete(
@@ -549,7 +566,7 @@ fn emit_operation_complete(
         success,
         error,
     };
-    let _ = app.emit("operation:complete", event);
+    let _ = events::emit(app, "operation:complete", event);
 }
 
 fn emit_operation_progress(app: &AppHandle, current: u64, total: u64, operation: &str) {
@@ -558,13 +575,17 @@ fn emit_operation_progress(app: &AppHandle, current: u64, total: u64, operation:
     }
     let percentage = (current as f64 / total as f64 * 100.0) as f32;
     let event = FlashProgress {
-        current,
-        total,
+        current: Some(current),
+        total: Some(total),
         percentage,
         partition_name: "adb-transfer".to_string(),
         operation: operation.to_string(),
+        operation_id: None,
+        speed_bytes_per_sec: None,
+        instant_speed_bytes_per_sec: None,
+        eta_secs: None,
     };
-    let _ = app.emit("operation:progress", event);
+    let _ = events::emit(app, "operation:progress", event);
 }
 
 fn emit_output_bytes(app: &AppHandle, operation_id: &str, data: &[u8], is_stderr: bool) {
@@ -1019,3 +1040,41 @@ fn fallback_pm_install(
         }
     }
 }
+
+/// Whether the platform adb binary is on PATH, and its version
+#[tauri::command]
+pub async fn adb_cli_status() -> Result<AdbCliStatus, AppError> {
+    tokio::task::spawn_blocking(adb_cli::status).await.map_err(|e| AppError::command(e.to_string()))
+}
+
+/// Devices seen by the platform adb server, including unauthorized ones
+#[tauri::command]
+pub async fn adb_cli_devices() -> Result<Vec<AdbCliDevice>, AppError> {
+    adb_cli::devices().await.map_err(|e| AppError::command(e.to_string()))
+}
+
+/// Reboot a booted device through the platform adb binary, e.g. into the
+/// bootloader before flashing, streaming adb's output as operation events
+#[tauri::command]
+pub async fn adb_cli_reboot(
+    app: AppHandle,
+    serial: String,
+    target: AdbRebootTarget,
+    operation_id: String,
+) -> Result<(), AppError> {
+    let adb = adb_cli::binary().map_err(|e| AppError::command(e.to_string()))?;
+    let working_dir = paths::config_dir().map_err(|e| AppError::io(e.to_string()))?;
+    log::info!(
+        operation_id = operation_id.as_str();
+        "Rebooting {} to {:?} with {:?} (operation_id: {})",
+        serial,
+        target,
+        adb,
+        operation_id
+    );
+
+    let executor = AntumbraExecutor::with_binary(adb, working_dir);
+    let args = adb_cli::reboot_args(&serial, target);
+    executor.execute_streaming(app, operation_id, args).await.map_err(operation_error)?;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::commands::validate_input_file;
+use crate::error::AppError;
+use crate::models::da::DaFile;
+use crate::services::config::RecentItemKind;
+use crate::services::da_parser::DaParser;
+use crate::services::recent_items;
+
+#[tauri::command]
+pub async fn inspect_da_file(file_path: String) -> Result<DaFile, AppError> {
+    validate_input_file(&file_path, "DA file")?;
+    let da = DaParser::parse(&file_path)?;
+    recent_items::record(RecentItemKind::Da, &file_path);
+    log::info!(
+        "Inspected DA '{}' ({} supported SoCs): {}",
+        da.identifier,
+        da.entries.len(),
+        file_path
+    );
+    Ok(da)
+}
@@ -3,10 +3,12 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use crate::commands::validate_da_preloader_paths;
-use crate::error::AppError;
-use crate::models::{Partition, PartitionListResult};
-use crate::services::antumbra::AntumbraExecutor;
+use crate::commands::{operation_error, validate_da_preloader_paths, validate_output_parent};
+use crate::error::{AppError, ErrorCategory};
+use crate::models::{Partition, PartitionListResult, SCHEMA_VERSION};
+use crate::services::executor::create_executor;
+use crate::services::partition_table::{self, ExportFormat};
+use std::path::Path;
 use tauri::{AppHandle, Window};
 use uuid::Uuid;
 
@@ -21,7 +23,7 @@ pub async fn reboot_device(
 
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
 
-    let executor = AntumbraExecutor::new(&app)?;
+    let executor = create_executor(&app)?;
     let operation_id = Uuid::new_v4().to_string();
 
     let mut args = vec!["reboot".to_string(), mode, "-d".to_string(), da_path];
@@ -32,10 +34,7 @@ pub async fn reboot_device(
     }
 
     // Execute reboot command with streaming
-    executor
-        .execute_streaming(app, operation_id, args)
-        .await
-        .map_err(|e| AppError::command(e.to_string()))?;
+    executor.execute_streaming(app, operation_id, args).await.map_err(operation_error)?;
 
     Ok(())
 }
@@ -50,7 +49,7 @@ pub async fn shutdown_device(
 
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
 
-    let executor = AntumbraExecutor::new(&app)?;
+    let executor = create_executor(&app)?;
     let operation_id = Uuid::new_v4().to_string();
 
     let mut args = vec!["shutdown".to_string(), "-d".to_string(), da_path];
@@ -61,10 +60,7 @@ pub async fn shutdown_device(
     }
 
     // Execute shutdown command with streaming
-    executor
-        .execute_streaming(app, operation_id, args)
-        .await
-        .map_err(|e| AppError::command(e.to_string()))?;
+    executor.execute_streaming(app, operation_id, args).await.map_err(operation_error)?;
 
     Ok(())
 }
@@ -75,12 +71,52 @@ pub async fn list_partitions(
     da_path: String,
     preloader_path: Option<String>,
     _window: Window,
+) -> Result<PartitionListResult, AppError> {
+    fetch_partitions(app, da_path, preloader_path).await
+}
+
+/// Write the partition table to `path` as CSV or JSON (by extension unless
+/// `format` says otherwise). Uses the last listed table unless `refresh` is
+/// set or none was listed yet, in which case `pgpt` runs with `da_path`.
+#[tauri::command]
+pub async fn export_partition_list(
+    app: AppHandle,
+    path: String,
+    format: Option<ExportFormat>,
+    da_path: Option<String>,
+    preloader_path: Option<String>,
+    refresh: Option<bool>,
+) -> Result<usize, AppError> {
+    validate_output_parent(&path, "Export file")?;
+    let cached = partition_table::cached().filter(|_| !refresh.unwrap_or(false));
+    let partitions = match (cached, da_path) {
+        (Some(partitions), _) => partitions,
+        (None, Some(da_path)) => fetch_partitions(app, da_path, preloader_path).await?.partitions,
+        (None, None) => {
+            return Err(AppError::other_with_category(
+                "No partition table listed yet; a DA is needed to read it",
+                ErrorCategory::Validation,
+            ));
+        }
+    };
+
+    let path = Path::new(&path);
+    let format = format.unwrap_or_else(|| ExportFormat::from_path(path));
+    partition_table::export(path, format, &partitions).map_err(|e| AppError::io(e.to_string()))?;
+    log::info!("Exported {} partitions to {}", partitions.len(), path.display());
+    Ok(partitions.len())
+}
+
+async fn fetch_partitions(
+    app: AppHandle,
+    da_path: String,
+    preloader_path: Option<String>,
 ) -> Result<PartitionListResult, AppError> {
     log::info!("Listing partitions with DA: {}", da_path);
 
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
 
-    let executor = AntumbraExecutor::new(&app)?;
+    let executor = create_executor(&app)?;
     let operation_id = Uuid::new_v4().to_string();
 
     let mut args = vec!["pgpt".to_string(), "-d".to_string(), da_path];
@@ -94,13 +130,20 @@ pub async fn list_partitions(
     let output = executor
         .execute_streaming(app, operation_id.clone(), args)
         .await
-        .map_err(|e| AppError::command(e.to_string()))?;
+        .map_err(operation_error)?;
 
     // Parse the output into partitions
-    let partitions = parse_pgpt_output(&output)?;
+    let partitions = match executor.parse_partitions(&output) {
+        Some(partitions) if partitions.is_empty() => {
+            return Err(AppError::Parse("No partitions found in output".to_string()));
+        }
+        Some(partitions) => partitions,
+        None => parse_pgpt_output(&output)?,
+    };
+    partition_table::remember(&partitions);
 
     // Return both partitions and operation_id
-    Ok(PartitionListResult { partitions, operation_id })
+    Ok(PartitionListResult { partitions, operation_id, schema_version: SCHEMA_VERSION })
 }
 
 fn parse_pgpt_output(output: &str) -> Result<Vec<Partition>, AppError> {
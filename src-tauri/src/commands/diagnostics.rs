@@ -3,38 +3,165 @@
     SPDX-FileCopyrightText: 2026 Shomy
 */
 
-use crate::error::AppError;
+use crate::commands::{ensure_writes_allowed, operation_error, validate_output_parent};
+use crate::error::{AppError, ErrorCategory};
+use crate::error_codes::{self, ErrorCodeInfo};
+use crate::models::schema::{self, SchemaCatalog};
 use crate::services::antumbra::{self, AntumbraCommandInfo, get_last_command_info, AntumbraExecutor};
+use crate::services::command_history::{self, CommandHistoryPage};
 use crate::services::config;
+use crate::services::disk_space::{self, DiskSpace};
+use crate::services::failure_replay::{self, ReplayReport};
+use crate::services::log_query::{self, LogQuery, LogQueryPage};
+use crate::services::log_rotation::{self, LogFileInfo};
+use crate::services::log_tail;
+use crate::services::network_diagnostics::{self, NetworkDiagnostics};
+use crate::services::operation_stats::{self, OperationStats};
+use crate::services::operation_report;
+use crate::services::paths;
+use crate::services::redaction;
+use crate::services::self_test::{self, SelfTestReport};
+use crate::services::session_script::{self, ScriptFormat};
+use crate::services::structured_log;
+use crate::services::usage_metrics::{self, UsageReport};
+use crate::services::usb_drivers::{self, UsbDriverReport};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 #[tauri::command]
 pub async fn get_wrapper_log_path() -> Result<String, AppError> {
-    let log_dir = dirs::config_dir()
-        .map(|dir| dir.join("penumbra-wrapper"))
-        .unwrap_or_else(|| std::env::temp_dir().join("penumbra-wrapper"));
-
-    Ok(log_dir.join("penumbra-wrapper.log").display().to_string())
+    Ok(paths::wrapper_log_file().display().to_string())
 }
 
+/// `redact` runs the export redaction pass, for logs about to be shared
 #[tauri::command]
-pub async fn read_wrapper_log() -> Result<String, AppError> {
-    let log_dir = dirs::config_dir()
-        .map(|dir| dir.join("penumbra-wrapper"))
-        .unwrap_or_else(|| std::env::temp_dir().join("penumbra-wrapper"));
-    let log_path = log_dir.join("penumbra-wrapper.log");
+pub async fn read_wrapper_log(redact: Option<bool>) -> Result<String, AppError> {
+    let log_path = paths::wrapper_log_file();
     let contents = std::fs::read_to_string(&log_path).unwrap_or_default();
-    Ok(contents)
+    Ok(if redact.unwrap_or(false) { redaction::redact_for_export(&contents) } else { contents })
+}
+
+/// Write the wrapper log (with rotated segments, oldest first) and antumbra.log
+/// to `destination` as one file, redacted unless `redact_exports` is off
+#[tauri::command]
+pub async fn export_logs(destination: String) -> Result<String, AppError> {
+    let mut bundle = String::new();
+    let log_path = paths::wrapper_log_file();
+    let segments = log_rotation::list_segments(&log_path, log_rotation::MAX_LOG_FILES);
+    let antumbra_log = paths::antumbra_log_file().ok();
+    let files = segments
+        .iter()
+        .rev()
+        .map(|segment| std::path::PathBuf::from(&segment.path))
+        .chain(antumbra_log.into_iter().filter(|path| path.exists()));
+    for path in files {
+        let contents = std::fs::read(&path).map_err(|e| AppError::io(e.to_string()))?;
+        bundle.push_str(&format!("===== {} =====\n", path.display()));
+        bundle.push_str(&String::from_utf8_lossy(&contents));
+        if !bundle.ends_with('\n') {
+            bundle.push('\n');
+        }
+    }
+
+    std::fs::write(&destination, redaction::redact_for_export(&bundle))
+        .map_err(|e| AppError::io(format!("Failed to write {}: {}", destination, e)))?;
+    log::info!("Exported logs to {}", destination);
+    Ok(destination)
+}
+
+/// Write an HTML (for a .html destination) or JSON record of the given
+/// operations: device info, per-partition results, hashes, durations and the
+/// full antumbra command lines
+#[tauri::command]
+pub async fn export_operation_report(
+    operation_ids: Vec<String>,
+    destination: String,
+    device: Option<BTreeMap<String, String>>,
+) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let report = operation_report::build(&operation_ids, device.unwrap_or_default());
+        operation_report::write(&report, std::path::Path::new(&destination))?;
+        log::info!(
+            "Exported report for {} operation(s) to {}",
+            operation_ids.len(),
+            destination
+        );
+        Ok(destination)
+    })
+    .await
+    .map_err(|e| AppError::other(e.to_string()))?
+    .map_err(|e: anyhow::Error| AppError::io(e.to_string()))
 }
 
+/// Write the operations run since the app started as a shell script (or a
+/// batch file for a .bat/.cmd destination, unless `format` says otherwise)
+/// that invokes antumbra directly. Returns the number of commands written.
+/// The script is written unredacted so it stays runnable; it is a local
+/// artifact, not something to attach to a bug report.
 #[tauri::command]
-pub async fn read_antumbra_log(app: AppHandle) -> Result<String, AppError> {
-    let config_dir = app.path().app_config_dir().map_err(|e| AppError::other(e.to_string()))?;
-    let log_path = config_dir.join("antumbra.log");
+pub async fn export_session_script(
+    destination: String,
+    format: Option<ScriptFormat>,
+) -> Result<usize, AppError> {
+    validate_output_parent(&destination, "Script file")?;
+    let commands =
+        session_script::session_commands(&command_history::all(), structured_log::session_id());
+    if commands.is_empty() {
+        return Err(AppError::other_with_category(
+            "No operations have run in this session yet",
+            ErrorCategory::Validation,
+        ));
+    }
+
+    let path = std::path::Path::new(&destination);
+    let format = format.unwrap_or_else(|| ScriptFormat::from_path(path));
+    session_script::export(path, format, &commands).map_err(|e| AppError::io(e.to_string()))?;
+    log::info!("Exported {} session command(s) to {}", commands.len(), destination);
+    Ok(commands.len())
+}
+
+/// JSON Schema of the event and result models, with the schema version
+/// every emitted event and versioned result carries
+#[tauri::command]
+pub async fn get_schemas() -> Result<SchemaCatalog, AppError> {
+    Ok(schema::catalog())
+}
+
+/// Stream lines appended to the wrapper log as `log:tail` events
+#[tauri::command]
+pub async fn tail_wrapper_log(app: AppHandle) -> Result<(), AppError> {
+    log_tail::start(app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_wrapper_log_tail() -> Result<(), AppError> {
+    log_tail::stop();
+    Ok(())
+}
+
+/// Search the wrapper log by level, text and time, one page at a time
+#[tauri::command]
+pub async fn query_log(query: LogQuery) -> Result<LogQueryPage, AppError> {
+    tokio::task::spawn_blocking(move || log_query::query(&query))
+        .await
+        .map_err(|e| AppError::other(e.to_string()))?
+        .map_err(|e| AppError::parse(e.to_string()))
+}
+
+/// The wrapper log and its rotated segments, newest first
+#[tauri::command]
+pub async fn get_log_files() -> Result<Vec<LogFileInfo>, AppError> {
+    Ok(log_rotation::list_segments(&paths::wrapper_log_file(), log_rotation::MAX_LOG_FILES))
+}
+
+#[tauri::command]
+pub async fn read_antumbra_log(redact: Option<bool>) -> Result<String, AppError> {
+    let log_path = paths::antumbra_log_file().map_err(|e| AppError::other(e.to_string()))?;
     let contents = std::fs::read_to_string(&log_path).unwrap_or_default();
-    Ok(contents)
+    Ok(if redact.unwrap_or(false) { redaction::redact_for_export(&contents) } else { contents })
 }
 
 #[tauri::command]
@@ -42,10 +169,107 @@ pub async fn get_last_antumbra_command() -> Result<Option<AntumbraCommandInfo>,
     Ok(get_last_command_info())
 }
 
+/// Past antumbra invocations, newest first, `limit` (default 50) at a time
+#[tauri::command]
+pub async fn get_command_history(
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<CommandHistoryPage, AppError> {
+    Ok(command_history::page(offset.unwrap_or(0), limit.unwrap_or(50)))
+}
+
+/// Per-subcommand durations and throughput aggregated from the command history
+#[tauri::command]
+pub async fn get_statistics() -> Result<Vec<OperationStats>, AppError> {
+    Ok(operation_stats::statistics())
+}
+
+/// DNS, TCP and GitHub API checks with timings, plus the proxy in effect
+#[tauri::command]
+pub async fn diagnose_network() -> Result<NetworkDiagnostics, AppError> {
+    log::info!("Starting network diagnostics");
+    Ok(network_diagnostics::diagnose().await)
+}
+
+/// Description and remediation steps for a code such as `PW-USB-001`
+#[tauri::command]
+pub async fn lookup_error_code(code: String) -> Result<ErrorCodeInfo, AppError> {
+    error_codes::lookup(&code).cloned().ok_or_else(|| {
+        let message = format!("Unknown error code {}", code);
+        AppError::other_with_category(message, ErrorCategory::Validation)
+    })
+}
+
+/// Check the install without a device: binary, `--version`, writable
+/// directories and the scatter parser, as a pass/fail checklist
+#[tauri::command]
+pub async fn run_self_test(app: AppHandle) -> Result<SelfTestReport, AppError> {
+    log::info!("Running self-test");
+    let report = tokio::task::spawn_blocking(move || self_test::run(&app))
+        .await
+        .map_err(|e| AppError::other(e.to_string()))?;
+    log::info!("Self-test {}", if report.passed { "passed" } else { "failed" });
+    Ok(report)
+}
+
+/// Re-run the last failed antumbra command with verbose logging
+/// (`RUST_LOG=trace`, full backtraces) and capture its output and antumbra.log
+/// into a dedicated troubleshooting log. `dry_run` only writes the log header
+/// with the command that would run, without touching the device.
+#[tauri::command]
+pub async fn replay_last_failure(
+    app: AppHandle,
+    dry_run: Option<bool>,
+) -> Result<ReplayReport, AppError> {
+    let dry_run = dry_run.unwrap_or(false);
+    let entry = failure_replay::last_failure().ok_or_else(|| {
+        AppError::other_with_category("No failed command to replay", ErrorCategory::Validation)
+    })?;
+    if !dry_run && failure_replay::is_write(&entry) {
+        ensure_writes_allowed("replay")?;
+    }
+    failure_replay::replay(&app, entry, dry_run).await.map_err(operation_error)
+}
+
+/// The anonymous usage report exactly as `upload_usage_metrics` would send it
+#[tauri::command]
+pub async fn preview_usage_metrics() -> Result<UsageReport, AppError> {
+    Ok(usage_metrics::preview())
+}
+
+/// Send the usage report (opt-in only) and reset the counters
+#[tauri::command]
+pub async fn upload_usage_metrics() -> Result<UsageReport, AppError> {
+    usage_metrics::upload().await.map_err(|e| AppError::other(format!("{:#}", e)))
+}
+
+#[tauri::command]
+pub async fn clear_usage_metrics() -> Result<(), AppError> {
+    usage_metrics::clear();
+    Ok(())
+}
+
+/// Total and free bytes on the volume that holds (or will hold) `path`
+#[tauri::command]
+pub async fn get_disk_space(path: String) -> Result<DiskSpace, AppError> {
+    tokio::task::spawn_blocking(move || disk_space::query(std::path::Path::new(&path)))
+        .await
+        .map_err(|e| AppError::other(e.to_string()))?
+        .map_err(|e| AppError::io(e.to_string()))
+}
+
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+/// Below this much free space the diagnostics warn about backups
+const LOW_DISK_SPACE_GB: f64 = 16.0;
+
 #[derive(Debug, Serialize, Deserialize)]
-pub struct WindowsDiagnostics {
+pub struct EnvironmentDiagnostics {
+    /// `std::env::consts::OS`: "windows", "linux", "macos", ...
+    pub platform: String,
     pub os_info: String,
     pub binary_location: Option<String>,
+    /// Set when antumbra_path_override points the wrapper at a custom binary
+    pub binary_overridden: bool,
     pub binary_version: Option<String>,
     pub config_location: String,
     pub config_exists: bool,
@@ -54,16 +278,23 @@ pub struct WindowsDiagnostics {
     pub running_antumbra_processes: Vec<String>,
     pub permissions_ok: bool,
     pub network_connectivity: bool,
+    /// MediaTek USB interfaces plus driver packages and class filters on
+    /// Windows, or udev rules and ModemManager on Linux
+    pub usb_drivers: UsbDriverReport,
     pub recommendations: Vec<String>,
 }
 
+/// Check the antumbra binary, config, permissions, USB drivers or udev rules
+/// and connectivity on the current OS
 #[tauri::command]
-pub async fn check_windows_environment(app: AppHandle) -> Result<WindowsDiagnostics, AppError> {
-    log::info!("Starting Windows environment diagnostics");
-    
-    let mut diagnostics = WindowsDiagnostics {
+pub async fn check_environment(app: AppHandle) -> Result<EnvironmentDiagnostics, AppError> {
+    log::info!("Starting environment diagnostics");
+
+    let mut diagnostics = EnvironmentDiagnostics {
+        platform: std::env::consts::OS.to_string(),
         os_info: get_os_info(),
         binary_location: None,
+        binary_overridden: false,
         binary_version: None,
         config_location: String::new(),
         config_exists: false,
@@ -72,10 +303,17 @@ pub async fn check_windows_environment(app: AppHandle) -> Result<WindowsDiagnost
         running_antumbra_processes: Vec::new(),
         permissions_ok: false,
         network_connectivity: false,
+        usb_drivers: UsbDriverReport::default(),
         recommendations: Vec::new(),
     };
 
     // Check binary location and version
+    match antumbra::get_antumbra_path_override() {
+        Ok(overridden) => diagnostics.binary_overridden = overridden.is_some(),
+        Err(e) => diagnostics
+            .recommendations
+            .push(format!("{}. Fix or clear the custom antumbra path.", e)),
+    }
     if let Ok(Some(binary_path)) = antumbra::get_existing_antumbra_path(&app) {
         diagnostics.binary_location = Some(binary_path.display().to_string());
         
@@ -86,7 +324,7 @@ pub async fn check_windows_environment(app: AppHandle) -> Result<WindowsDiagnost
         }
     } else {
         diagnostics.recommendations.push(
-            "antumbra.exe not found in expected locations. Please ensure it's installed."
+            "antumbra binary not found in expected locations. Please ensure it's installed."
                 .to_string(),
         );
     }
@@ -98,7 +336,7 @@ pub async fn check_windows_environment(app: AppHandle) -> Result<WindowsDiagnost
         
         if diagnostics.config_exists {
             if let Ok(contents) = tokio::fs::read_to_string(&config_path).await {
-                diagnostics.config_contents = Some(contents);
+                diagnostics.config_contents = Some(redaction::redact_for_export(&contents));
             }
         }
         
@@ -121,25 +359,39 @@ pub async fn check_windows_environment(app: AppHandle) -> Result<WindowsDiagnost
     }
 
     // Check for running antumbra processes
-    #[cfg(windows)]
-    {
-        diagnostics.running_antumbra_processes = check_running_antumbra();
-        if !diagnostics.running_antumbra_processes.is_empty() {
-            diagnostics.recommendations.push(
-                "antumbra.exe is currently running. This may prevent updates. Close it first."
-                    .to_string(),
-            );
+    diagnostics.running_antumbra_processes = check_running_antumbra();
+    if !diagnostics.running_antumbra_processes.is_empty() {
+        diagnostics.recommendations.push(
+            "antumbra is currently running. This may prevent updates. Close it first.".to_string(),
+        );
+    }
+
+    // Check free space where backups are written
+    let settings = config::load_settings().unwrap_or_default();
+    let backup_dir = match settings.default_output_path.filter(|path| !path.is_empty()) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => paths::config_dir(),
+    };
+    if let Ok(space) = disk_space::query(&backup_dir) {
+        let free_gb = space.free_bytes as f64 / GIB;
+        diagnostics.disk_space_gb = Some((free_gb * 10.0).round() / 10.0);
+        if free_gb < LOW_DISK_SPACE_GB {
+            diagnostics.recommendations.push(format!(
+                "Only {:.1} GB free on the volume holding {}. A full backup may not fit.",
+                free_gb, space.path
+            ));
         }
     }
 
+    // Check the drivers bound to MediaTek BROM/preloader interfaces
+    diagnostics.usb_drivers =
+        tokio::task::spawn_blocking(usb_drivers::scan).await.unwrap_or_default();
+    diagnostics.recommendations.extend(usb_drivers::findings(&diagnostics.usb_drivers));
+
     // Check network connectivity to GitHub
-    diagnostics.network_connectivity = check_github_connectivity();
-    if !diagnostics.network_connectivity {
-        diagnostics.recommendations.push(
-            "Cannot connect to GitHub API. Check your internet connection or firewall."
-                .to_string(),
-        );
-    }
+    let network = network_diagnostics::diagnose().await;
+    diagnostics.network_connectivity = network.github_api.ok;
+    diagnostics.recommendations.extend(network_diagnostics::findings(&network));
 
     // General recommendations based on findings
     if diagnostics.binary_version.is_some() && diagnostics.config_exists {
@@ -153,7 +405,7 @@ pub async fn check_windows_environment(app: AppHandle) -> Result<WindowsDiagnost
         }
     }
 
-    log::info!("Windows diagnostics completed: {:?}", diagnostics);
+    log::info!("Environment diagnostics completed: {:?}", diagnostics);
     Ok(diagnostics)
 }
 
@@ -172,7 +424,25 @@ fn get_os_info() -> String {
 
 #[cfg(not(windows))]
 fn get_os_info() -> String {
-    format!("{} (Windows diagnostics not applicable)", std::env::consts::OS)
+    // PRETTY_NAME from os-release on Linux, uname elsewhere
+    let pretty_name = std::fs::read_to_string("/etc/os-release").ok().and_then(|release| {
+        release.lines().find_map(|line| {
+            line.strip_prefix("PRETTY_NAME=").map(|name| name.trim_matches('"').to_string())
+        })
+    });
+    let kernel = std::process::Command::new("uname")
+        .args(["-sr"])
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|kernel| !kernel.is_empty());
+
+    match (pretty_name, kernel) {
+        (Some(name), Some(kernel)) => format!("{} ({})", name, kernel),
+        (Some(name), None) => name,
+        (None, Some(kernel)) => kernel,
+        (None, None) => std::env::consts::OS.to_string(),
+    }
 }
 
 #[cfg(windows)]
@@ -198,22 +468,19 @@ fn check_running_antumbra() -> Vec<String> {
     }
 }
 
-fn check_github_connectivity() -> bool {
-    use std::thread;
-    use std::time::Duration;
-    
-    let (sender, receiver) = std::sync::mpsc::channel();
-    
-    thread::spawn(move || {
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        let result = rt.block_on(async {
-            reqwest::get("https://api.github.com/repos/rdndds/penumbra").await
-                .map(|response| response.status().is_success())
-                .unwrap_or(false)
-        });
-        let _ = sender.send(result);
-    });
-    
-    // Wait up to 5 seconds for the check
-    receiver.recv_timeout(Duration::from_secs(5)).unwrap_or(false)
+#[cfg(not(windows))]
+fn check_running_antumbra() -> Vec<String> {
+    // comm is the bare name on Linux and the full path on macOS
+    std::process::Command::new("ps")
+        .args(["-A", "-o", "pid=,comm="])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.trim().split_once(char::is_whitespace))
+                .filter(|(_, command)| command.trim().ends_with("antumbra"))
+                .map(|(pid, _)| pid.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
 }
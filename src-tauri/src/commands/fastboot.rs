@@ -5,11 +5,12 @@
 
 use crate::error::AppError;
 use crate::models::{OperationCompleteEvent, OperationOutputEvent};
+use crate::services::events;
 use chrono::Utc;
 use serde::Serialize;
 use std::io::Write;
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 use uuid::Uuid;
 
 const VENDOR_ID: u16 = 0x0E8D;
@@ -201,7 +202,7 @@ fn emit_status(app: &AppHandle, status: FastbootStatus, message: &str) {
         status,
         message: message.to_string(),
     };
-    let _ = app.emit("fastboot:status", payload);
+    let _ = events::emit(app, "fastboot:status", payload);
 }
 
 fn emit_operation_output(app: &AppHandle, operation_id: &str, line: &str, is_stderr: bool) {
@@ -211,7 +212,7 @@ fn emit_operation_output(app: &AppHandle, operation_id: &str, line: &str, is_std
         timestamp: Utc::now().to_rfc3339(),
         is_stderr,
     };
-    let _ = app.emit("operation:output", event);
+    let _ = events::emit(app, "operation:output", event);
 }
 
 fn emit_operation_complete(
@@ -225,7 +226,7 @@ fn emit_operation_complete(
         success,
         error,
     };
-    let _ = app.emit("operation:complete", event);
+    let _ = events::emit(app, "operation:complete", event);
 }
 
 #[derive(Debug)]
@@ -3,8 +3,11 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
+use crate::commands::{ensure_writes_allowed, operation_error};
 use crate::error::AppError;
 use crate::models::{OperationCompleteEvent, OperationOutputEvent};
+use crate::services::events;
+use crate::services::fastboot_cli::{self, FastbootCliStatus, FastbootStep};
 use chrono::Utc;
 use fastboot_protocol::nusb::{self as fastboot_nusb, NusbFastBoot, NusbFastBootOpenError};
 use fastboot_protocol::protocol::FastBootResponse;
@@ -12,7 +15,7 @@ use nusb;
 use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::path::Path;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 
 const SPARSE_MAGIC: u32 = 0xed26ff3a;
 const FLASH_CHUNK_SIZE: usize = 1024 * 1024;
@@ -174,6 +177,11 @@ pub async fn fastboot_flash(
     image_path: String,
     operation_id: String,
 ) -> Result<(), AppError> {
+    if let Err(err) = ensure_writes_allowed("fastboot flash") {
+        emit_operation_output(&app, &operation_id, &err.message(), true);
+        emit_operation_complete(&app, &operation_id, false, Some(err.message()));
+        return Err(err);
+    }
     let image_path_ref = Path::new(&image_path);
     if !image_path_ref.is_file() {
         let message = format!("Image file not found: {image_path}");
@@ -327,6 +335,11 @@ pub async fn fastboot_erase(
     partition: String,
     operation_id: String,
 ) -> Result<(), AppError> {
+    if let Err(err) = ensure_writes_allowed("fastboot erase") {
+        emit_operation_output(&app, &operation_id, &err.message(), true);
+        emit_operation_complete(&app, &operation_id, false, Some(err.message()));
+        return Err(err);
+    }
     let info = match find_device_info(&device_id) {
         Ok(info) => info,
         Err(err) => {
@@ -576,7 +589,7 @@ fn emit_operation_output(app: &AppHandle, operation_id: &str, line: &str, is_std
         timestamp: Utc::now().to_rfc3339(),
         is_stderr,
     };
-    let _ = app.emit("operation:output", event);
+    let _ = events::emit(app, "operation:output", event);
 }
 
 fn emit_operation_complete(
@@ -590,7 +603,7 @@ fn emit_operation_complete(
         success,
         error,
     };
-    let _ = app.emit("operation:complete", event);
+    let _ = events::emit(app, "operation:complete", event);
 }
 
 async fn is_sparse_image(path: &Path) -> Result<bool, AppError> {
@@ -703,3 +716,34 @@ fn map_fastbootd_error(err: String) -> AppError {
     }
     AppError::command(format!("Fastboot reboot failed: {err}"))
 }
+
+/// Whether the platform fastboot binary is on PATH, and its version
+#[tauri::command]
+pub async fn fastboot_cli_status() -> Result<FastbootCliStatus, AppError> {
+    tokio::task::spawn_blocking(fastboot_cli::status)
+        .await
+        .map_err(|e| AppError::command(e.to_string()))
+}
+
+/// Run a post-flash step such as `flashing unlock` with the platform fastboot
+/// binary, streaming its output as operation events
+#[tauri::command]
+pub async fn fastboot_cli_run(
+    app: AppHandle,
+    step: FastbootStep,
+    serial: Option<String>,
+    operation_id: String,
+) -> Result<(), AppError> {
+    if step.is_write() {
+        ensure_writes_allowed(&format!("fastboot {:?}", step))?;
+    }
+    log::info!(
+        operation_id = operation_id.as_str();
+        "Running fastboot step {:?} (serial: {:?}, operation_id: {})",
+        step,
+        serial,
+        operation_id
+    );
+    fastboot_cli::run(app, operation_id, step, serial.as_deref()).await.map_err(operation_error)?;
+    Ok(())
+}
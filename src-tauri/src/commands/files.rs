@@ -0,0 +1,56 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::commands::validate_input_file;
+use crate::error::{AppError, ErrorCategory};
+use crate::services::file_hash::{self, FileHash, HashAlgorithm};
+use crate::services::file_slice::{self, FileSlice, MAX_SLICE_LENGTH};
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Hash a local file with `algorithm` (SHA-256 by default), emitting
+/// `hash:progress` events tagged with `operation_id`. When `expected` is
+/// given the result says whether it matched.
+#[tauri::command]
+pub async fn hash_file(
+    app: AppHandle,
+    path: String,
+    algorithm: Option<HashAlgorithm>,
+    expected: Option<String>,
+    operation_id: String,
+) -> Result<FileHash, AppError> {
+    validate_input_file(&path, "File")?;
+    let algorithm = algorithm.unwrap_or(HashAlgorithm::Sha256);
+    let result = tokio::task::spawn_blocking(move || {
+        file_hash::hash_file(&app, &operation_id, Path::new(&path), algorithm, expected.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::other(e.to_string()))?
+    .map_err(|e| AppError::io(format!("{:#}", e)))?;
+
+    log::info!("Hashed {} ({:?}): {}", result.path, algorithm, result.hash);
+    Ok(result)
+}
+
+/// Up to `length` bytes (at most 1 MiB) of a local file from `offset`, base64
+/// encoded, for the hex viewer
+#[tauri::command]
+pub async fn read_file_slice(
+    path: String,
+    offset: u64,
+    length: u64,
+) -> Result<FileSlice, AppError> {
+    validate_input_file(&path, "File")?;
+    if length > MAX_SLICE_LENGTH {
+        return Err(AppError::other_with_category(
+            format!("At most {} bytes can be read at once", MAX_SLICE_LENGTH),
+            ErrorCategory::Validation,
+        ));
+    }
+    tokio::task::spawn_blocking(move || file_slice::read_slice(Path::new(&path), offset, length))
+        .await
+        .map_err(|e| AppError::other(e.to_string()))?
+        .map_err(|e| AppError::io(format!("{:#}", e)))
+}
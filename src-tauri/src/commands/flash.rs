@@ -3,9 +3,13 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use crate::commands::{validate_da_preloader_paths, validate_input_file};
+use crate::commands::{
+    ensure_writes_allowed, operation_error, validate_da_preloader_paths, validate_input_file,
+};
 use crate::error::AppError;
-use crate::services::antumbra::AntumbraExecutor;
+use crate::services::config::RecentItemKind;
+use crate::services::executor::create_executor;
+use crate::services::recent_items;
 use tauri::{AppHandle, Window};
 
 #[tauri::command]
@@ -18,17 +22,22 @@ pub async fn flash_partition(
     operation_id: String,
     _window: Window,
 ) -> Result<(), AppError> {
+    ensure_writes_allowed("flash")?;
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
     validate_input_file(&image_path, "Image file")?;
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
     log::info!(
+        operation_id = operation_id.as_str(), partition = partition.as_str();
         "Flashing partition '{}' with image: {} (operation_id: {})",
         partition,
         image_path,
         operation_id
     );
 
-    let executor = AntumbraExecutor::new(&app)?;
+    recent_items::record(RecentItemKind::Da, &da_path);
+    recent_items::record(RecentItemKind::Image, &image_path);
+
+    let executor = create_executor(&app)?;
 
     // Build command arguments
     let mut args =
@@ -40,10 +49,7 @@ pub async fn flash_partition(
     }
 
     // Execute with streaming output using frontend-provided operation_id
-    executor
-        .execute_streaming(app, operation_id, args)
-        .await
-        .map_err(|e| AppError::command(e.to_string()))?;
+    executor.execute_streaming(app, operation_id, args).await.map_err(operation_error)?;
 
     Ok(())
 }
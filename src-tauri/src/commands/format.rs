@@ -3,9 +3,9 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use crate::commands::validate_da_preloader_paths;
+use crate::commands::{ensure_writes_allowed, operation_error, validate_da_preloader_paths};
 use crate::error::AppError;
-use crate::services::antumbra::AntumbraExecutor;
+use crate::services::executor::create_executor;
 use tauri::{AppHandle, Window};
 
 #[tauri::command]
@@ -17,11 +17,15 @@ pub async fn format_partition(
     operation_id: String,
     _window: Window,
 ) -> Result<(), AppError> {
-    log::info!("Formatting partition '{}' (operation_id: {})", partition, operation_id);
+    log::info!(
+        operation_id = operation_id.as_str(), partition = partition.as_str();
+        "Formatting partition '{}' (operation_id: {})", partition, operation_id
+    );
 
+    ensure_writes_allowed("format")?;
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
 
-    let executor = AntumbraExecutor::new(&app)?;
+    let executor = create_executor(&app)?;
 
     // Build command arguments: format <partition> -d <da> [-p <pl>]
     let mut args = vec!["format".to_string(), partition.clone(), "-d".to_string(), da_path];
@@ -32,10 +36,7 @@ pub async fn format_partition(
     }
 
     // Execute with streaming output using frontend-provided operation_id
-    executor
-        .execute_streaming(app, operation_id, args)
-        .await
-        .map_err(|e| AppError::command(e.to_string()))?;
+    executor.execute_streaming(app, operation_id, args).await.map_err(operation_error)?;
 
     Ok(())
 }
@@ -0,0 +1,39 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::commands::{ensure_writes_allowed, validate_da_preloader_paths};
+use crate::error::AppError;
+use crate::services::job_file::{self, JobFile, JobReport};
+use std::path::Path;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+/// Run a YAML or JSON job file step by step through the operation queue.
+/// Progress arrives as `job:step` events; the report is returned at the end.
+#[tauri::command]
+pub async fn run_job_file(
+    app: AppHandle,
+    path: String,
+    job_id: Option<String>,
+) -> Result<JobReport, AppError> {
+    let job = JobFile::load(Path::new(&path)).map_err(|e| AppError::other(format!("{:#}", e)))?;
+    if job.has_writes() {
+        ensure_writes_allowed("job file")?;
+    }
+    validate_da_preloader_paths(&job.da, job.preloader.as_deref())?;
+
+    let job_id = job_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    log::info!("Running job file {} ({} steps, job_id: {})", path, job.steps.len(), job_id);
+    let report =
+        job_file::run(&app, &job_id, &job).await.map_err(|e| AppError::command(e.to_string()))?;
+    log::info!(
+        "Job {} finished: {} succeeded, {} failed, {} skipped",
+        job_id,
+        report.succeeded,
+        report.failed,
+        report.skipped
+    );
+    Ok(report)
+}
@@ -3,40 +3,100 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
+pub mod adb;
+pub mod da;
 pub mod device;
 pub mod diagnostics;
-pub mod adb;
 pub mod erase;
 pub mod fastboot;
 pub mod fastboot_tools;
+pub mod files;
 pub mod flash;
 pub mod format;
+pub mod job;
+pub mod plugins;
+pub mod preloader;
+pub mod queue;
 pub mod read;
 pub mod scatter;
+pub mod session;
 pub mod settings;
 pub mod tools;
 pub mod updates;
+pub mod workspace;
 
 use crate::error::AppError;
-use crate::services::antumbra::{kill_current_process, AntumbraExecutor};
-use std::fs::OpenOptions;
+use crate::services::antumbra::{kill_all_processes, kill_operation_process, AntumbraFailure};
+use crate::services::config::load_settings;
+use crate::services::executor::create_executor;
+use crate::services::mock_executor;
+use crate::services::operation_queue::{self, DeviceBusy};
+use std::fs::{File, OpenOptions};
+use std::io::Read;
 use std::path::Path;
 use tauri::AppHandle;
 use uuid::Uuid;
 
+const PRELOADER_MIN_SIZE: u64 = 4 * 1024;
+const PRELOADER_MAX_SIZE: u64 = 4 * 1024 * 1024;
+const PRELOADER_HEADER_SCAN: usize = 0x1000;
+const PRELOADER_BOOT_HEADERS: [&[u8]; 3] = [b"EMMC_BOOT", b"UFS_BOOT", b"COMBO_BOOT"];
+const PRELOADER_GFH_MAGIC: &[u8] = b"MMM\x01";
+
 #[tauri::command]
 pub async fn get_antumbra_version(app: AppHandle) -> Result<String, AppError> {
-    let executor = AntumbraExecutor::new(&app)?;
+    let executor = create_executor(&app)?;
     executor.get_version().map_err(|e| AppError::command(e.to_string()))
 }
 
+/// Cancel the antumbra process for `operation_id`, or every running process when omitted
 #[tauri::command]
-pub async fn cancel_operation(app: AppHandle) -> Result<(), AppError> {
-    let _ = AntumbraExecutor::new(&app)?;
-    kill_current_process().map_err(|e| AppError::command(e.to_string()))?;
+pub async fn cancel_operation(
+    app: AppHandle,
+    operation_id: Option<String>,
+) -> Result<(), AppError> {
+    let _ = create_executor(&app)?;
+    match operation_id {
+        Some(operation_id) => {
+            // Graceful termination waits for antumbra to exit, keep it off the async runtime
+            let id = operation_id.clone();
+            let found = tokio::task::spawn_blocking(move || kill_operation_process(&id))
+                .await
+                .map_err(|e| AppError::command(e.to_string()))?
+                .map_err(|e| AppError::command(e.to_string()))?;
+            let found = found || mock_executor::cancel(&operation_id);
+            let released = operation_queue::release_pause(&app, Some(&operation_id));
+            if !found && !operation_queue::cancel_queued(&app, &operation_id) && !released {
+                log::info!("No running or queued operation {}", operation_id);
+            }
+        }
+        None => {
+            operation_queue::release_pause(&app, None);
+            mock_executor::cancel_all();
+            tokio::task::spawn_blocking(kill_all_processes)
+                .await
+                .map_err(|e| AppError::command(e.to_string()))?
+                .map_err(|e| AppError::command(e.to_string()))?;
+        }
+    }
     Ok(())
 }
 
+/// Map an antumbra execution failure to an `AppError`, keeping typed failures
+pub(crate) fn operation_error(err: anyhow::Error) -> AppError {
+    if let Some(failure) = err.downcast_ref::<AntumbraFailure>() {
+        return AppError::from_antumbra_failure(
+            failure.exit_code,
+            failure.cancelled,
+            &failure.stderr,
+        );
+    }
+    match err.downcast::<DeviceBusy>() {
+        Ok(busy) => AppError::Busy { running_operation_id: busy.running_operation_id },
+        Err(err) => AppError::command(err.to_string()),
+    }
+}
+
 pub(crate) fn validate_da_preloader_paths(
     da_path: &str,
     preloader_path: Option<&str>,
@@ -44,6 +104,52 @@ pub(crate) fn validate_da_preloader_paths(
     validate_input_file(da_path, "DA file")?;
     if let Some(path) = preloader_path {
         validate_input_file(path, "Preloader file")?;
+        validate_preloader_file(path)?;
+    }
+    Ok(())
+}
+
+/// Reject files that don't look like a preloader before antumbra tries to
+/// send them mid-handshake
+pub(crate) fn validate_preloader_file(path: &str) -> Result<(), AppError> {
+    let size = std::fs::metadata(path)
+        .map_err(|err| {
+            AppError::command(format!("Preloader file not readable: {} ({})", path, err))
+        })?
+        .len();
+    if !(PRELOADER_MIN_SIZE..=PRELOADER_MAX_SIZE).contains(&size) {
+        return Err(AppError::command(format!(
+            "Preloader file has an unexpected size ({} bytes): {}. Make sure you selected a preloader, not a DA or partition image.",
+            size, path
+        )));
+    }
+
+    let mut header = Vec::with_capacity(PRELOADER_HEADER_SCAN);
+    File::open(path)
+        .and_then(|file| file.take(PRELOADER_HEADER_SCAN as u64).read_to_end(&mut header))
+        .map_err(|err| {
+            AppError::command(format!("Preloader file not readable: {} ({})", path, err))
+        })?;
+
+    if !looks_like_preloader(&header) {
+        return Err(AppError::command(format!(
+            "File does not look like a preloader (no EMMC_BOOT/UFS_BOOT or MMM header): {}",
+            path
+        )));
+    }
+    Ok(())
+}
+
+fn looks_like_preloader(header: &[u8]) -> bool {
+    PRELOADER_BOOT_HEADERS.iter().any(|magic| header.starts_with(magic))
+        || header.windows(PRELOADER_GFH_MAGIC.len()).any(|w| w == PRELOADER_GFH_MAGIC)
+}
+
+/// Refuse a device-modifying `action` while the `safe_mode` setting is on
+pub(crate) fn ensure_writes_allowed(action: &str) -> Result<(), AppError> {
+    if load_settings().map(|settings| settings.safe_mode).unwrap_or(false) {
+        log::warn!("Safe mode blocked {}", action);
+        return Err(AppError::safe_mode(action));
     }
     Ok(())
 }
@@ -103,3 +209,21 @@ fn validate_writable_dir(path: &Path, label: &str) -> Result<(), AppError> {
     let _ = std::fs::remove_file(&temp_path);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_like_preloader() {
+        assert!(looks_like_preloader(b"EMMC_BOOT\0\0\0"));
+        assert!(looks_like_preloader(b"UFS_BOOT\0\0\0"));
+
+        let mut raw = vec![0u8; 0x400];
+        raw[0x200..0x204].copy_from_slice(b"MMM\x01");
+        assert!(looks_like_preloader(&raw));
+
+        assert!(!looks_like_preloader(b"MTK_DOWNLOAD_AGENT"));
+        assert!(!looks_like_preloader(&[0u8; 0x400]));
+    }
+}
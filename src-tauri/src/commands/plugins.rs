@@ -0,0 +1,44 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::commands::{ensure_writes_allowed, operation_error};
+use crate::error::AppError;
+use crate::services::antumbra::AntumbraExecutor;
+use crate::services::plugins::{self, PluginToolInfo};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn list_plugin_tools() -> Result<Vec<PluginToolInfo>, AppError> {
+    Ok(plugins::list_tools())
+}
+
+/// Run a plugin tool with streamed output, queueing and cancellation just
+/// like antumbra operations. Returns the tool's stdout.
+#[tauri::command]
+pub async fn run_plugin_tool(
+    app: AppHandle,
+    plugin_id: String,
+    tool_id: String,
+    params: HashMap<String, String>,
+    operation_id: String,
+) -> Result<String, AppError> {
+    let tool = plugins::prepare(&plugin_id, &tool_id, &params)
+        .map_err(|e| AppError::other(format!("{:#}", e)))?;
+    if tool.writes_device {
+        ensure_writes_allowed(&format!("plugin tool {}", tool_id))?;
+    }
+    log::info!(
+        operation_id = operation_id.as_str();
+        "Running plugin tool {}/{}: {:?} (operation_id: {})",
+        plugin_id,
+        tool_id,
+        tool.executable,
+        operation_id
+    );
+
+    let executor = AntumbraExecutor::with_binary(tool.executable, tool.working_dir);
+    executor.execute_streaming(app, operation_id, tool.args).await.map_err(operation_error)
+}
@@ -0,0 +1,43 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::commands::{validate_input_file, validate_preloader_file};
+use crate::error::AppError;
+use crate::models::preloader::{PreloaderEntry, PreloaderMetadata};
+use crate::services::preloader_library;
+use std::path::Path;
+
+/// Stored preloaders, filtered by model or chipset when `query` is given
+#[tauri::command]
+pub async fn list_preloaders(query: Option<String>) -> Result<Vec<PreloaderEntry>, AppError> {
+    preloader_library::list(query.as_deref()).map_err(|e| AppError::io(format!("{:#}", e)))
+}
+
+/// Copy a preloader file into the library with what is known about it
+#[tauri::command]
+pub async fn import_preloader(
+    file_path: String,
+    metadata: PreloaderMetadata,
+) -> Result<PreloaderEntry, AppError> {
+    validate_input_file(&file_path, "Preloader file")?;
+    validate_preloader_file(&file_path)?;
+    let entry = preloader_library::import(Path::new(&file_path), metadata)
+        .map_err(|e| AppError::io(format!("{:#}", e)))?;
+    log::info!("Imported preloader {} as {}", file_path, entry.id);
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn update_preloader(
+    id: String,
+    metadata: PreloaderMetadata,
+) -> Result<PreloaderEntry, AppError> {
+    preloader_library::update(&id, metadata).map_err(|e| AppError::other(format!("{:#}", e)))
+}
+
+#[tauri::command]
+pub async fn remove_preloader(id: String) -> Result<(), AppError> {
+    preloader_library::remove(&id).map_err(|e| AppError::other(format!("{:#}", e)))
+}
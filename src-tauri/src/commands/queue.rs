@@ -0,0 +1,117 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::error::AppError;
+use crate::models::OperationCancelledEvent;
+use crate::services::antumbra::kill_all_processes;
+use crate::services::operation_queue::{self, QueuedOperationInfo};
+use crate::services::operation_registry::{self, RunningOperation};
+use crate::services::event_replay::{self, ReplayedEvent};
+use crate::services::{close_guard, events, mock_executor};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, WebviewWindow};
+
+#[tauri::command]
+pub async fn list_queued_operations() -> Result<Vec<QueuedOperationInfo>, AppError> {
+    Ok(operation_queue::list_queued())
+}
+
+/// Operations currently executing, so the frontend can rebuild its state after a reload
+#[tauri::command]
+pub async fn list_running_operations() -> Result<Vec<RunningOperation>, AppError> {
+    Ok(operation_registry::list())
+}
+
+/// Recent output and progress events of a running operation after `since`,
+/// so a remounted console can catch up
+#[tauri::command]
+pub async fn replay_operation_events(
+    operation_id: String,
+    since: Option<u64>,
+) -> Result<Vec<ReplayedEvent>, AppError> {
+    Ok(event_replay::replay(&operation_id, since))
+}
+
+/// Route operation events to the calling window: only those of
+/// `operation_ids`, or of every operation when omitted
+#[tauri::command]
+pub async fn subscribe_operation_events(
+    window: WebviewWindow,
+    operation_ids: Option<Vec<String>>,
+) -> Result<(), AppError> {
+    events::subscribe(window.label(), operation_ids);
+    Ok(())
+}
+
+/// Stop sending operation events to the calling window
+#[tauri::command]
+pub async fn unsubscribe_operation_events(window: WebviewWindow) -> Result<(), AppError> {
+    events::unsubscribe(window.label());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn cancel_queued_operation(app: AppHandle, operation_id: String) -> Result<(), AppError> {
+    if !operation_queue::cancel_queued(&app, &operation_id) {
+        return Err(AppError::other(format!("Operation {} is not queued", operation_id)));
+    }
+    Ok(())
+}
+
+/// Hold the queue at a running or queued operation until `resume_operation`
+/// or the operation is cancelled; operations queued ahead of it still run
+#[tauri::command]
+pub async fn pause_operation(app: AppHandle, operation_id: String) -> Result<(), AppError> {
+    operation_queue::pause(&app, &operation_id).map_err(|e| AppError::other(e.to_string()))
+}
+
+#[tauri::command]
+pub async fn resume_operation(app: AppHandle, operation_id: String) -> Result<(), AppError> {
+    operation_queue::resume(&app, &operation_id).map_err(|e| AppError::other(e.to_string()))
+}
+
+/// Operations stopped by `cancel_all_operations`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelAllResult {
+    pub running: Vec<String>,
+    pub queued: Vec<String>,
+}
+
+/// Stop everything: drain the queue first so nothing new starts, then cancel
+/// every running operation. Emits `operation:cancelled` for each of them.
+#[tauri::command]
+pub async fn cancel_all_operations(app: AppHandle) -> Result<CancelAllResult, AppError> {
+    let queued: Vec<String> = operation_queue::drain_waiting(&app)
+        .into_iter()
+        .map(|operation| operation.operation_id)
+        .collect();
+    let running: Vec<String> =
+        operation_registry::list().into_iter().map(|operation| operation.operation_id).collect();
+
+    mock_executor::cancel_all();
+    let killed = tokio::task::spawn_blocking(kill_all_processes)
+        .await
+        .map_err(|e| AppError::command(e.to_string()))?;
+
+    let cancelled = running.iter().map(|id| (id, true)).chain(queued.iter().map(|id| (id, false)));
+    for (operation_id, was_running) in cancelled {
+        let event = OperationCancelledEvent { operation_id: operation_id.clone(), was_running };
+        let _ = events::emit(&app, "operation:cancelled", event);
+    }
+    log::info!("Cancelled {} running and {} queued operation(s)", running.len(), queued.len());
+
+    killed.map_err(|e| AppError::command(e.to_string()))?;
+    Ok(CancelAllResult { running, queued })
+}
+
+/// Answer `app:close-requested-while-busy`: cancel everything and close when
+/// `proceed` is true, otherwise keep the window open
+#[tauri::command]
+pub async fn confirm_close_while_busy(app: AppHandle, proceed: bool) -> Result<(), AppError> {
+    if !close_guard::confirm(app, proceed).await {
+        return Err(AppError::other("No close request is waiting for confirmation"));
+    }
+    Ok(())
+}
@@ -3,9 +3,13 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use crate::commands::{validate_da_preloader_paths, validate_output_parent};
+use crate::commands::{operation_error, validate_da_preloader_paths, validate_output_parent};
 use crate::error::AppError;
-use crate::services::antumbra::AntumbraExecutor;
+use crate::services::config::RecentItemKind;
+use crate::services::executor::create_executor;
+use crate::services::output_layout;
+use crate::services::recent_items;
+use std::path::Path;
 use tauri::{AppHandle, Window};
 
 #[tauri::command]
@@ -16,18 +20,34 @@ pub async fn read_partition(
     output_path: String,
     preloader_path: Option<String>,
     operation_id: String,
+    device_label: Option<String>,
     _window: Window,
 ) -> Result<(), AppError> {
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
+    let output_path = output_layout::resolve(
+        &app,
+        &operation_id,
+        &output_path,
+        false,
+        "read",
+        device_label.as_deref(),
+    )
+    .map_err(|e| AppError::io(e.to_string()))?;
     validate_output_parent(&output_path, "Output file")?;
     log::info!(
+        operation_id = operation_id.as_str(), partition = partition.as_str();
         "Reading partition '{}' to file: {} (operation_id: {})",
         partition,
         output_path,
         operation_id
     );
 
-    let executor = AntumbraExecutor::new(&app)?;
+    recent_items::record(RecentItemKind::Da, &da_path);
+    if let Some(parent) = Path::new(&output_path).parent() {
+        recent_items::record(RecentItemKind::OutputDir, &parent.to_string_lossy());
+    }
+
+    let executor = create_executor(&app)?;
 
     // Build command arguments: upload <partition> <output_file> -d <da> [-p <pl>]
     let mut args =
@@ -39,10 +59,7 @@ pub async fn read_partition(
     }
 
     // Execute with streaming output using frontend-provided operation_id
-    executor
-        .execute_streaming(app, operation_id, args)
-        .await
-        .map_err(|e| AppError::command(e.to_string()))?;
+    executor.execute_streaming(app, operation_id, args).await.map_err(operation_error)?;
 
     Ok(())
 }
@@ -3,17 +3,124 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
+use crate::commands::{validate_input_file, validate_output_parent};
 use crate::error::AppError;
-use crate::models::scatter::{ScatterFile, ScatterPartition};
+use crate::models::scatter::{ScatterFile, ScatterPartition, ScatterSummary};
+use crate::services::config::RecentItemKind;
+use crate::services::file_hash::{self, HashAlgorithm};
+use crate::services::image_matcher::{ImageCandidate, ImageMatcher};
+use crate::services::launch_file;
+use crate::services::recent_items;
 use crate::services::scatter_parser::ScatterParser;
+use crate::services::scatter_watcher;
+use crate::services::scatter_writer::ScatterWriter;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageMatch {
+    pub partition_name: String,
+    pub best: Option<String>,
+    pub candidates: Vec<ImageCandidate>, // Absolute paths, best first
+    pub ambiguous: bool,
+    /// Size of the best match in bytes
+    pub size: Option<u64>,
+    /// SHA-256 of the best match, when hashing was asked for
+    pub sha256: Option<String>,
+}
+
+/// Images hashed at once. Firmware folders hold 20+ multi-GB images; a few
+/// parallel reads keep fast disks busy without thrashing slow ones.
+const IMAGE_HASH_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BootFileCandidates {
+    pub da_files: Vec<String>,
+    pub preloader_files: Vec<String>,
+    pub suggested_da: Option<String>,
+    pub suggested_preloader: Option<String>,
+}
 
 #[tauri::command]
-pub async fn parse_scatter_file(file_path: String) -> Result<ScatterFile, AppError> {
+pub async fn parse_scatter_file(
+    file_path: String,
+    storage_type: Option<String>,
+) -> Result<ScatterFile, AppError> {
     // Parse scatter file (auto-detects XML vs TXT format)
-    ScatterParser::parse(&file_path)
+    let scatter = ScatterParser::parse(&file_path, storage_type.as_deref())?;
+    recent_items::record(RecentItemKind::Scatter, &file_path);
+    Ok(scatter)
+}
+
+/// Emit `app:open-scatter` for a scatter file the app was launched with.
+/// Called by the frontend once it listens; returns whether there was one.
+#[tauri::command]
+pub async fn open_launch_scatter(app: AppHandle) -> Result<bool, AppError> {
+    Ok(launch_file::open_pending(&app))
+}
+
+#[tauri::command]
+pub async fn watch_scatter_file(app: AppHandle, scatter_path: String) -> Result<(), AppError> {
+    validate_input_file(&scatter_path, "Scatter file")?;
+    scatter_watcher::watch_scatter(app, scatter_path).map_err(|e| AppError::other(e.to_string()))
+}
+
+#[tauri::command]
+pub async fn unwatch_scatter_file() -> Result<(), AppError> {
+    scatter_watcher::stop_watching();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn scatter_summary(scatter: ScatterFile) -> Result<ScatterSummary, AppError> {
+    Ok(scatter.summary())
+}
+
+#[tauri::command]
+pub async fn scatter_set_download(
+    mut scatter: ScatterFile,
+    partition_name: String,
+    is_download: bool,
+) -> Result<ScatterFile, AppError> {
+    let partition = scatter
+        .partition_mut(&partition_name)
+        .ok_or_else(|| AppError::invalid_partition(partition_name.clone()))?;
+    partition.is_download = is_download;
+    Ok(scatter)
+}
+
+#[tauri::command]
+pub async fn scatter_set_file_name(
+    mut scatter: ScatterFile,
+    partition_name: String,
+    file_name: Option<String>,
+) -> Result<ScatterFile, AppError> {
+    let partition = scatter
+        .partition_mut(&partition_name)
+        .ok_or_else(|| AppError::invalid_partition(partition_name.clone()))?;
+    // Empty or "NONE" means the partition has no image, same as when parsing
+    partition.file_name = file_name.filter(|f| !f.trim().is_empty() && f != "NONE");
+    Ok(scatter)
+}
+
+#[tauri::command]
+pub async fn scatter_move_partition(
+    mut scatter: ScatterFile,
+    from_index: usize,
+    to_index: usize,
+) -> Result<ScatterFile, AppError> {
+    scatter.move_partition(from_index, to_index).map_err(AppError::invalid_partition)?;
+    Ok(scatter)
+}
+
+#[tauri::command]
+pub async fn save_scatter_file(scatter: ScatterFile, output_path: String) -> Result<(), AppError> {
+    validate_output_parent(&output_path, "Scatter file")?;
+    log::info!("Saving scatter file with {} partitions to {}", scatter.partitions.len(), output_path);
+    ScatterWriter::save(&scatter, &output_path)
 }
 
 #[tauri::command]
@@ -21,19 +128,189 @@ pub async fn detect_image_files(
     scatter_path: String,
     partitions: Vec<ScatterPartition>,
 ) -> Result<HashMap<String, String>, AppError> {
+    let matches = match_image_files(&scatter_path, &partitions)?;
+
+    let mut image_map: HashMap<String, String> = HashMap::new();
+    for image_match in matches {
+        if let Some(best) = image_match.best {
+            image_map.insert(image_match.partition_name, best);
+        }
+    }
+
+    log::info!("[ImageDetect] Successfully detected {} images", image_map.len());
+
+    Ok(image_map)
+}
+
+/// Like `detect_image_files`, but returns every scored candidate per partition
+/// so the user can pick when several files match equally well. With `hash`
+/// set the best matches are also hashed, several at a time.
+#[tauri::command]
+pub async fn detect_image_candidates(
+    scatter_path: String,
+    partitions: Vec<ScatterPartition>,
+    hash: Option<bool>,
+) -> Result<Vec<ImageMatch>, AppError> {
+    let mut matches = match_image_files(&scatter_path, &partitions)?;
+    if hash.unwrap_or(false) {
+        let paths = matches.iter().filter_map(|image_match| image_match.best.clone()).collect();
+        let hashes =
+            file_hash::hash_paths(paths, HashAlgorithm::Sha256, IMAGE_HASH_CONCURRENCY).await;
+        let hashed = matches.iter_mut().filter(|image_match| image_match.best.is_some());
+        for (image_match, hash) in hashed.zip(hashes) {
+            match hash {
+                Ok(hash) => image_match.sha256 = Some(hash),
+                Err(err) => log::warn!("[ImageDetect] Failed to hash image: {:#}", err),
+            }
+        }
+    }
+    Ok(matches)
+}
+
+fn match_image_files(
+    scatter_path: &str,
+    partitions: &[ScatterPartition],
+) -> Result<Vec<ImageMatch>, AppError> {
     // Extract directory from scatter path
-    let scatter_path_obj = Path::new(&scatter_path);
+    let scatter_path_obj = Path::new(scatter_path);
     let scatter_dir = scatter_path_obj
         .parent()
         .ok_or_else(|| AppError::Parse("Invalid scatter path".to_string()))?;
 
     log::info!("[ImageDetect] Scanning directory: {}", scatter_dir.display());
 
-    // Collect all files from scatter directory
+    // Collect all files from scatter directory and its images/ subdirectory
+    let all_files = list_firmware_files(scatter_dir);
+
+    log::info!("[ImageDetect] Total files found: {}", all_files.len());
+    log::debug!("[ImageDetect] Files: {:?}", all_files);
+
+    // Match partitions to image files
+    let downloadable_partitions: Vec<&ScatterPartition> =
+        partitions.iter().filter(|p| p.is_download).collect();
+
+    log::info!(
+        "[ImageDetect] Processing {} downloadable partitions",
+        downloadable_partitions.len()
+    );
+
+    let to_full_path = |file: &str| -> Result<String, AppError> {
+        scatter_dir
+            .join(file)
+            .to_str()
+            .map(|path| path.to_string())
+            .ok_or_else(|| AppError::Parse("Invalid file path".to_string()))
+    };
+
+    let mut matches = Vec::new();
+    for partition in downloadable_partitions {
+        let candidates = ImageMatcher::candidates(
+            &partition.partition_name,
+            partition.file_name.as_deref(),
+            &all_files,
+        );
+
+        let Some(best) = candidates.first() else {
+            log::debug!("[ImageDetect] ✗ No match for: {}", partition.partition_name);
+            continue;
+        };
+
+        let ambiguous = ImageMatcher::is_ambiguous(&candidates);
+        if ambiguous {
+            log::info!(
+                "[ImageDetect] Ambiguous match for {}: {:?}",
+                partition.partition_name,
+                candidates.iter().map(|c| c.file.as_str()).collect::<Vec<_>>()
+            );
+        }
+        log::info!(
+            "[ImageDetect] Added: {} → {} (score {})",
+            partition.partition_name,
+            best.file,
+            best.score
+        );
+
+        let best = to_full_path(&best.file)?;
+        let candidates = candidates
+            .into_iter()
+            .map(|candidate| {
+                Ok(ImageCandidate { file: to_full_path(&candidate.file)?, score: candidate.score })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        let size = fs::metadata(&best).ok().map(|metadata| metadata.len());
+        matches.push(ImageMatch {
+            partition_name: partition.partition_name.clone(),
+            best: Some(best),
+            candidates,
+            ambiguous,
+            size,
+            sha256: None,
+        });
+    }
+
+    Ok(matches)
+}
+
+/// Locate DA and preloader binaries in a firmware folder so they can be
+/// suggested for the `da_path`/`preloader_path` settings.
+/// Accepts either the folder itself or a file inside it (e.g. the scatter).
+#[tauri::command]
+pub async fn detect_boot_files(path: String) -> Result<BootFileCandidates, AppError> {
+    let path_obj = Path::new(&path);
+    let firmware_dir = if path_obj.is_dir() {
+        path_obj
+    } else {
+        path_obj.parent().ok_or_else(|| AppError::Parse("Invalid firmware path".to_string()))?
+    };
+
+    log::info!("[BootDetect] Scanning directory: {}", firmware_dir.display());
+
+    let mut da_files: Vec<(u8, String)> = Vec::new();
+    let mut preloader_files: Vec<String> = Vec::new();
+
+    for file in list_firmware_files(firmware_dir) {
+        let file_name = file.rsplit('/').next().unwrap_or(&file).to_lowercase();
+        if !file_name.ends_with(".bin") {
+            continue;
+        }
+
+        let full_path = firmware_dir.join(&file).display().to_string();
+        if file_name.starts_with("mtk_allinone_da") {
+            da_files.push((0, full_path));
+        } else if file_name.starts_with("da_") {
+            da_files.push((1, full_path));
+        } else if file_name.starts_with("preloader") {
+            preloader_files.push(full_path);
+        }
+    }
+
+    // AllInOne DAs cover the most chipsets, so suggest them first
+    da_files.sort();
+    preloader_files.sort();
+    let da_files: Vec<String> = da_files.into_iter().map(|(_, path)| path).collect();
+
+    log::info!(
+        "[BootDetect] Found {} DA file(s) and {} preloader(s)",
+        da_files.len(),
+        preloader_files.len()
+    );
+
+    Ok(BootFileCandidates {
+        suggested_da: da_files.first().cloned(),
+        suggested_preloader: preloader_files.first().cloned(),
+        da_files,
+        preloader_files,
+    })
+}
+
+/// List file names in a firmware directory, plus `images/<name>` for files in
+/// its images/ subdirectory
+fn list_firmware_files(dir: &Path) -> Vec<String> {
     let mut all_files: Vec<String> = Vec::new();
 
-    // Read files in scatter directory (root level)
-    if let Ok(entries) = fs::read_dir(scatter_dir) {
+    // Read files in directory (root level)
+    if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.filter_map(Result::ok) {
             if let Ok(file_type) = entry.file_type() {
                 if file_type.is_file() {
@@ -45,10 +322,10 @@ pub async fn detect_image_files(
         }
     }
 
-    log::info!("[ImageDetect] Found {} files in root", all_files.len());
+    log::debug!("Found {} files in {}", all_files.len(), dir.display());
 
     // Also check images/ subdirectory
-    let images_dir = scatter_dir.join("images");
+    let images_dir = dir.join("images");
     if images_dir.exists() && images_dir.is_dir() {
         if let Ok(entries) = fs::read_dir(&images_dir) {
             for entry in entries.filter_map(Result::ok) {
@@ -61,98 +338,8 @@ pub async fn detect_image_files(
                 }
             }
         }
-        log::info!("[ImageDetect] Checked images/ subdirectory");
+        log::debug!("Checked images/ subdirectory of {}", dir.display());
     }
 
-    log::info!("[ImageDetect] Total files found: {}", all_files.len());
-    log::debug!("[ImageDetect] Files: {:?}", all_files);
-
-    // Match partitions to image files
-    let mut image_map: HashMap<String, String> = HashMap::new();
-    let downloadable_partitions: Vec<&ScatterPartition> =
-        partitions.iter().filter(|p| p.is_download).collect();
-
-    log::info!(
-        "[ImageDetect] Processing {} downloadable partitions",
-        downloadable_partitions.len()
-    );
-
-    for partition in downloadable_partitions {
-        let partition_name_lower = partition.partition_name.to_lowercase();
-
-        // Get scatter file_name (if specified and not "NONE")
-        let scatter_file_name = partition
-            .file_name
-            .as_ref()
-            .filter(|f| !f.is_empty() && *f != "NONE")
-            .map(|f| f.to_lowercase());
-
-        log::debug!(
-            "[ImageDetect] Checking partition: {}, file_name: {:?}",
-            partition.partition_name,
-            scatter_file_name
-        );
-
-        // Find matching file (case-insensitive)
-        let matching_file = all_files.iter().find(|file| {
-            let file_lower = file.to_lowercase();
-
-            // Priority 1: Check scatter file_name
-            if let Some(ref sf_name) = scatter_file_name {
-                // Check exact match or match in subdirectory
-                if file_lower == *sf_name || file_lower.ends_with(&format!("/{}", sf_name)) {
-                    log::debug!(
-                        "[ImageDetect] ✓ Matched by file_name: {} → {}",
-                        partition.partition_name,
-                        file
-                    );
-                    return true;
-                }
-            }
-
-            // Priority 2: Check partition name with .img extension
-            if file_lower == format!("{}.img", partition_name_lower)
-                || file_lower.ends_with(&format!("/{}.img", partition_name_lower))
-            {
-                log::debug!(
-                    "[ImageDetect] ✓ Matched by .img: {} → {}",
-                    partition.partition_name,
-                    file
-                );
-                return true;
-            }
-
-            // Priority 3: Check partition name with .bin extension
-            if file_lower == format!("{}.bin", partition_name_lower)
-                || file_lower.ends_with(&format!("/{}.bin", partition_name_lower))
-            {
-                log::debug!(
-                    "[ImageDetect] ✓ Matched by .bin: {} → {}",
-                    partition.partition_name,
-                    file
-                );
-                return true;
-            }
-
-            false
-        });
-
-        // If matched, add to map with full absolute path
-        if let Some(matched_file) = matching_file {
-            let full_path = scatter_dir.join(matched_file);
-            let full_path_str = full_path
-                .to_str()
-                .ok_or_else(|| AppError::Parse("Invalid file path".to_string()))?
-                .to_string();
-
-            image_map.insert(partition.partition_name.clone(), full_path_str);
-            log::info!("[ImageDetect] Added: {} → {}", partition.partition_name, matched_file);
-        } else {
-            log::debug!("[ImageDetect] ✗ No match for: {}", partition.partition_name);
-        }
-    }
-
-    log::info!("[ImageDetect] Successfully detected {} images", image_map.len());
-
-    Ok(image_map)
+    all_files
 }
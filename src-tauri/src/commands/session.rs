@@ -0,0 +1,33 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::commands::validate_input_file;
+use crate::error::AppError;
+use crate::models::session::RecordedSessionInfo;
+use crate::services::session_recorder;
+use std::path::Path;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn list_recorded_sessions() -> Result<Vec<RecordedSessionInfo>, AppError> {
+    session_recorder::list_sessions().map_err(|e| AppError::other(e.to_string()))
+}
+
+/// Replay a recorded session through the regular operation events
+#[tauri::command]
+pub async fn replay_session(
+    app: AppHandle,
+    session_path: String,
+    operation_id: String,
+    speed: Option<f64>,
+) -> Result<String, AppError> {
+    validate_input_file(&session_path, "Session file")?;
+    let session = session_recorder::load_session(Path::new(&session_path))
+        .map_err(|e| AppError::parse(e.to_string()))?;
+
+    session_recorder::replay(&app, session, &operation_id, speed.unwrap_or(1.0))
+        .await
+        .map_err(|e| AppError::command(e.to_string()))
+}
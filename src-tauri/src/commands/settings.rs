@@ -4,15 +4,114 @@
 */
 
 use crate::error::AppError;
-use crate::services::config::{AppSettings, load_settings, save_settings};
+use crate::services::config::{
+    load_settings, modify_settings, AppSettings, PartitionSet, RecentItem, RecentItemKind,
+};
+use crate::services::secrets::{self, SecretKey};
+use crate::services::{i18n, partition_sets, recent_items, usage_metrics};
 use tauri::AppHandle;
 
 #[tauri::command]
 pub async fn get_settings(_app: AppHandle) -> Result<AppSettings, AppError> {
-    load_settings().map_err(|e| AppError::other(e.to_string()))
+    let mut settings = load_settings().map_err(|e| AppError::other(e.to_string()))?;
+    // Never sent to the webview, even from the config.json fallback; the UI
+    // goes through has_secret/set_secret instead
+    settings.github_token = None;
+    Ok(settings)
 }
 
 #[tauri::command]
-pub async fn update_settings(_app: AppHandle, settings: AppSettings) -> Result<(), AppError> {
-    save_settings(&settings).map_err(|e| AppError::other(e.to_string()))
+pub async fn update_settings(_app: AppHandle, mut settings: AppSettings) -> Result<(), AppError> {
+    let usage_metrics_enabled = settings.usage_metrics_enabled;
+    modify_settings(|current| {
+        // Switched through the version commands; a stale form must not undo that
+        settings.active_antumbra_version = current.active_antumbra_version.take();
+        settings.recent_items = std::mem::take(&mut current.recent_items);
+        settings.partition_sets = std::mem::take(&mut current.partition_sets);
+        settings.binary_checksum = current.binary_checksum.take();
+        // Only changed through set_secret, the form never carries it
+        settings.github_token = current.github_token.take();
+        *current = settings;
+        Ok(())
+    })
+    .map_err(|e| AppError::other(e.to_string()))?;
+    // Opting out also forgets what was counted so far
+    if !usage_metrics_enabled {
+        usage_metrics::clear();
+    }
+    i18n::reload();
+    Ok(())
+}
+
+/// Locales with a bundled backend message catalog
+#[tauri::command]
+pub async fn list_locales() -> Result<Vec<String>, AppError> {
+    Ok(i18n::available_locales().into_iter().map(str::to_string).collect())
+}
+
+/// Recently used paths of `kind` (all kinds when omitted), newest first
+#[tauri::command]
+pub async fn get_recent_items(kind: Option<RecentItemKind>) -> Result<Vec<RecentItem>, AppError> {
+    Ok(recent_items::list(kind))
+}
+
+#[tauri::command]
+pub async fn clear_recent_items(kind: Option<RecentItemKind>) -> Result<(), AppError> {
+    recent_items::clear(kind).map_err(|e| AppError::other(e.to_string()))
+}
+
+#[tauri::command]
+pub async fn list_partition_sets() -> Result<Vec<PartitionSet>, AppError> {
+    partition_sets::list().map_err(|e| AppError::other(e.to_string()))
+}
+
+/// Create or overwrite the named partition selection
+#[tauri::command]
+pub async fn save_partition_set(
+    name: String,
+    partitions: Vec<String>,
+) -> Result<PartitionSet, AppError> {
+    partition_sets::save(&name, partitions).map_err(|e| AppError::other(e.to_string()))
+}
+
+#[tauri::command]
+pub async fn rename_partition_set(
+    name: String,
+    new_name: String,
+) -> Result<PartitionSet, AppError> {
+    partition_sets::rename(&name, &new_name).map_err(|e| AppError::other(e.to_string()))
+}
+
+#[tauri::command]
+pub async fn delete_partition_set(name: String) -> Result<(), AppError> {
+    partition_sets::delete(&name).map_err(|e| AppError::other(e.to_string()))
+}
+
+/// Whether a secret is stored, without revealing it
+#[tauri::command]
+pub async fn has_secret(key: SecretKey) -> Result<bool, AppError> {
+    if key == SecretKey::GithubToken {
+        return Ok(secrets::github_token().is_some());
+    }
+    secrets::get(key).map(|value| value.is_some()).map_err(|e| AppError::other(format!("{:#}", e)))
+}
+
+/// Store a secret in the OS keyring; a blank value removes it. The GitHub
+/// token falls back to config.json where there is no keyring.
+#[tauri::command]
+pub async fn set_secret(key: SecretKey, value: String) -> Result<(), AppError> {
+    let stored = match key {
+        SecretKey::GithubToken => secrets::set_github_token(&value),
+        _ => secrets::set(key, &value),
+    };
+    stored.map_err(|e| AppError::other(format!("{:#}", e)))
+}
+
+#[tauri::command]
+pub async fn delete_secret(key: SecretKey) -> Result<(), AppError> {
+    let deleted = match key {
+        SecretKey::GithubToken => secrets::set_github_token(""),
+        _ => secrets::delete(key),
+    };
+    deleted.map_err(|e| AppError::other(format!("{:#}", e)))
 }
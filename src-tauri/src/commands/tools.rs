@@ -3,9 +3,14 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use crate::commands::{validate_da_preloader_paths, validate_output_dir};
+use crate::commands::{
+    ensure_writes_allowed, operation_error, validate_da_preloader_paths, validate_output_dir,
+};
 use crate::error::AppError;
-use crate::services::antumbra::AntumbraExecutor;
+use crate::services::config::RecentItemKind;
+use crate::services::executor::create_executor;
+use crate::services::output_layout;
+use crate::services::recent_items;
 use tauri::{AppHandle, Window};
 
 #[tauri::command]
@@ -16,9 +21,20 @@ pub async fn read_all_partitions(
     skip_partitions: Vec<String>,
     preloader_path: Option<String>,
     operation_id: String,
+    device_label: Option<String>,
     _window: Window,
 ) -> Result<(), AppError> {
+    let output_dir = output_layout::resolve(
+        &app,
+        &operation_id,
+        &output_dir,
+        true,
+        "read-all",
+        device_label.as_deref(),
+    )
+    .map_err(|e| AppError::io(e.to_string()))?;
     log::info!(
+        operation_id = operation_id.as_str();
         "Reading all partitions to directory: {} (operation_id: {}, skip: {:?})",
         output_dir,
         operation_id,
@@ -27,8 +43,10 @@ pub async fn read_all_partitions(
 
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
     validate_output_dir(&output_dir, "Output directory")?;
+    recent_items::record(RecentItemKind::Da, &da_path);
+    recent_items::record(RecentItemKind::OutputDir, &output_dir);
 
-    let executor = AntumbraExecutor::new(&app)?;
+    let executor = create_executor(&app)?;
 
     // Build command arguments: read-all <output_dir> -d <da> [-p <pl>] [--skip partition1,partition2,...]
     let mut args = vec!["read-all".to_string(), output_dir, "-d".to_string(), da_path];
@@ -47,10 +65,7 @@ pub async fn read_all_partitions(
     }
 
     // Execute with streaming output using frontend-provided operation_id
-    executor
-        .execute_streaming(app, operation_id, args)
-        .await
-        .map_err(|e| AppError::command(e.to_string()))?;
+    executor.execute_streaming(app, operation_id, args).await.map_err(operation_error)?;
 
     Ok(())
 }
@@ -64,11 +79,15 @@ pub async fn seccfg_operation(
     operation_id: String,
     _window: Window,
 ) -> Result<(), AppError> {
-    log::info!("Seccfg operation '{}' (operation_id: {})", action, operation_id);
+    log::info!(
+        operation_id = operation_id.as_str();
+        "Seccfg operation '{}' (operation_id: {})", action, operation_id
+    );
 
+    ensure_writes_allowed("seccfg")?;
     validate_da_preloader_paths(&da_path, preloader_path.as_deref())?;
 
-    let executor = AntumbraExecutor::new(&app)?;
+    let executor = create_executor(&app)?;
 
     // Build command arguments: seccfg <action> -d <da> [-p <pl>]
     let mut args = vec!["seccfg".to_string(), action.clone(), "-d".to_string(), da_path];
@@ -79,10 +98,7 @@ pub async fn seccfg_operation(
     }
 
     // Execute with streaming output using frontend-provided operation_id
-    executor
-        .execute_streaming(app, operation_id, args)
-        .await
-        .map_err(|e| AppError::command(e.to_string()))?;
+    executor.execute_streaming(app, operation_id, args).await.map_err(operation_error)?;
 
     Ok(())
 }
@@ -6,8 +6,11 @@
 use crate::error::AppError;
 use crate::services::antumbra::get_antumbra_updatable_path as resolve_antumbra_updatable_path;
 use crate::services::antumbra_update::{
-    AntumbraUpdateInfo, AntumbraUpdateResult, check_for_updates, download_and_install,
+    AntumbraRelease, AntumbraUpdateInfo, AntumbraUpdateResult, check_for_updates,
+    cancel_download, download_and_install, install_from_file, list_installed_versions,
+    list_releases, rollback, set_active_version, DownloadCancelled, InstalledAntumbraVersion,
 };
+use std::path::PathBuf;
 use tauri::AppHandle;
 
 #[tauri::command]
@@ -17,11 +20,70 @@ pub async fn get_antumbra_updatable_path(app: AppHandle) -> Result<String, AppEr
 }
 
 #[tauri::command]
-pub async fn check_antumbra_update(app: AppHandle) -> Result<AntumbraUpdateInfo, AppError> {
-    check_for_updates(&app).await.map_err(|e| e.into())
+pub async fn check_antumbra_update(
+    app: AppHandle,
+    force: Option<bool>,
+) -> Result<AntumbraUpdateInfo, AppError> {
+    check_for_updates(&app, force.unwrap_or(false)).await.map_err(|e| e.into())
 }
 
+/// Install the latest antumbra release, or the one tagged `tag` to pin or downgrade.
+/// Passing the `approved` update check installs exactly the release it reported.
 #[tauri::command]
-pub async fn download_antumbra_update(app: AppHandle) -> Result<AntumbraUpdateResult, AppError> {
-    download_and_install(&app).await.map_err(|e| e.into())
+pub async fn download_antumbra_update(
+    app: AppHandle,
+    tag: Option<String>,
+    approved: Option<AntumbraUpdateInfo>,
+) -> Result<AntumbraUpdateResult, AppError> {
+    download_and_install(&app, tag.as_deref(), approved.as_ref()).await.map_err(|e| {
+        if e.is::<DownloadCancelled>() {
+            AppError::Cancelled
+        } else {
+            e.into()
+        }
+    })
+}
+
+/// Abort the running antumbra download. Returns false when none is in progress.
+#[tauri::command]
+pub async fn cancel_antumbra_download() -> Result<bool, AppError> {
+    Ok(cancel_download())
+}
+
+#[tauri::command]
+pub async fn list_antumbra_releases() -> Result<Vec<AntumbraRelease>, AppError> {
+    list_releases().await.map_err(|e| e.into())
+}
+
+/// Restore the antumbra binary that the last update replaced
+#[tauri::command]
+pub async fn rollback_antumbra_update(app: AppHandle) -> Result<AntumbraUpdateResult, AppError> {
+    rollback(&app).await.map_err(|e| e.into())
+}
+
+/// Install antumbra from a local file, optionally checking its SHA-256
+#[tauri::command]
+pub async fn install_antumbra_from_file(
+    app: AppHandle,
+    path: String,
+    checksum: Option<String>,
+) -> Result<AntumbraUpdateResult, AppError> {
+    install_from_file(&app, &PathBuf::from(path), checksum.as_deref()).await.map_err(|e| e.into())
+}
+
+#[tauri::command]
+pub async fn list_installed_antumbra_versions(
+    app: AppHandle,
+) -> Result<Vec<InstalledAntumbraVersion>, AppError> {
+    list_installed_versions(&app).map_err(|e| e.into())
+}
+
+/// Switch the antumbra version in use without downloading it again.
+/// `None` selects the default `bin/antumbra` binary.
+#[tauri::command]
+pub async fn set_active_antumbra_version(
+    app: AppHandle,
+    version: Option<String>,
+) -> Result<(), AppError> {
+    set_active_version(&app, version.as_deref()).map_err(|e| e.into())
 }
@@ -0,0 +1,35 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::error::AppError;
+use crate::models::workspace::{OpenedWorkspace, Workspace};
+use crate::models::SCHEMA_VERSION;
+use crate::services::workspace::{self, workspace_folder, WORKSPACE_FILE};
+use std::path::Path;
+
+/// Open a workspace folder (or its `workspace.json`) and return its selections
+#[tauri::command]
+pub async fn open_workspace(path: String) -> Result<OpenedWorkspace, AppError> {
+    let folder = workspace_folder(Path::new(&path));
+    if !folder.join(WORKSPACE_FILE).is_file() {
+        return Err(AppError::command(format!("No workspace found in {}", folder.display())));
+    }
+    let workspace = workspace::load(&folder).map_err(|e| AppError::parse(format!("{:#}", e)))?;
+    log::info!("Opened workspace {}", folder.display());
+    Ok(OpenedWorkspace {
+        folder: folder.display().to_string(),
+        workspace,
+        schema_version: SCHEMA_VERSION,
+    })
+}
+
+/// Save the current selections as the workspace in `path`
+#[tauri::command]
+pub async fn save_workspace(path: String, workspace: Workspace) -> Result<(), AppError> {
+    let folder = workspace_folder(Path::new(&path));
+    workspace::save(&folder, workspace).map_err(|e| AppError::io(format!("{:#}", e)))?;
+    log::info!("Saved workspace {}", folder.display());
+    Ok(())
+}
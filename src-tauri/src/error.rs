@@ -3,7 +3,9 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use serde::{Deserialize, Serialize};
+use crate::error_codes;
+use crate::services::i18n::{t, t_with};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Error categories for better error classification and user guidance
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -26,9 +28,10 @@ impl ErrorCategory {
 }
 
 /// Comprehensive error type for all application errors
-/// Provides structured error information with categories and suggestions
+/// Provides structured error information with categories and suggestions.
+/// Serialized errors also carry a stable `error_code`, see `error_codes`.
 #[derive(Debug, thiserror::Error, Serialize, Deserialize, Clone)]
-#[serde(tag = "type")]
+#[serde(tag = "type", remote = "Self")]
 pub enum AppError {
     #[error("IO error: {message}")]
     #[serde(rename = "io")]
@@ -48,12 +51,47 @@ pub enum AppError {
 
     #[error("Device not connected")]
     #[serde(rename = "device_not_connected")]
-    DeviceNotConnected,
+    DeviceNotConnected {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
 
     #[error("Operation cancelled")]
     #[serde(rename = "cancelled")]
     Cancelled,
 
+    #[error("Device busy: operation {running_operation_id} is still running")]
+    #[serde(rename = "busy")]
+    Busy { running_operation_id: String },
+
+    #[error("Device requires authentication: {message}")]
+    #[serde(rename = "auth_required")]
+    AuthRequired {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+
+    #[error("DA does not match the device: {message}")]
+    #[serde(rename = "da_mismatch")]
+    DaMismatch {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+
+    #[error("Device handshake timed out: {message}")]
+    #[serde(rename = "handshake_timeout")]
+    HandshakeTimeout {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+
+    #[error("{message}")]
+    #[serde(rename = "safe_mode")]
+    SafeMode { message: String, action: String },
+
     #[error("Invalid partition: {0}")]
     #[serde(rename = "invalid_partition")]
     InvalidPartition(String),
@@ -80,6 +118,50 @@ pub enum AppError {
     },
 }
 
+/// Well-known antumbra failures, recognized by stderr substrings (lowercase)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AntumbraFailureKind {
+    AuthRequired,
+    DaMismatch,
+    HandshakeTimeout,
+    PartitionNotFound,
+    DeviceNotFound,
+}
+
+/// Checked in order; the first matching signature wins. Each kind becomes an
+/// `AppError` variant and so shares that variant's error code.
+const ANTUMBRA_FAILURE_PATTERNS: &[(&[&str], AntumbraFailureKind)] = &[
+    (
+        &["sec_auth_needed", "auth needed", "authentication required", "sla required"],
+        AntumbraFailureKind::AuthRequired,
+    ),
+    (
+        &[
+            "da mismatch",
+            "hw code mismatch",
+            "unsupported da",
+            "da is not compatible",
+            "invalid da",
+        ],
+        AntumbraFailureKind::DaMismatch,
+    ),
+    (
+        &["handshake timeout", "handshake timed out", "handshake failed"],
+        AntumbraFailureKind::HandshakeTimeout,
+    ),
+    (
+        &["partition not found", "no such partition", "unknown partition"],
+        AntumbraFailureKind::PartitionNotFound,
+    ),
+    (
+        &["device not found", "no device found", "no mtk device"],
+        AntumbraFailureKind::DeviceNotFound,
+    ),
+];
+
+/// Exit code antumbra reports when interrupted by SIGINT/CTRL_BREAK
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
 #[allow(dead_code)]
 impl AppError {
     /// Create a new IO error
@@ -124,6 +206,12 @@ impl AppError {
         AppError::Parse(message.into())
     }
 
+    /// Device-modifying `action` refused because safe mode is on
+    pub fn safe_mode(action: impl Into<String>) -> Self {
+        let action = action.into();
+        AppError::SafeMode { message: t_with("error.safe_mode", &[("action", &action)]), action }
+    }
+
     /// Create a new Other error
     pub fn other(message: impl Into<String>) -> Self {
         AppError::Other {
@@ -140,13 +228,64 @@ impl AppError {
         }
     }
 
+    /// Map a failed antumbra run to the most specific error for its exit code and stderr.
+    /// `cancelled` says whether the operation was stopped on request; a process
+    /// killed any other way (no exit code) is a failure like the rest.
+    pub fn from_antumbra_failure(exit_code: Option<i32>, cancelled: bool, stderr: &str) -> Self {
+        if cancelled || exit_code == Some(INTERRUPTED_EXIT_CODE) {
+            return AppError::Cancelled;
+        }
+
+        let matched = ANTUMBRA_FAILURE_PATTERNS.iter().find_map(|(patterns, kind)| {
+            let line = stderr.lines().find(|line| {
+                let line = line.to_lowercase();
+                patterns.iter().any(|pattern| line.contains(pattern))
+            })?;
+            Some((*kind, line))
+        });
+        let kind = matched.map(|(kind, _)| kind);
+
+        // The line that matched is the message, or else the last one; the full
+        // stderr is kept as output
+        let message = matched
+            .map(|(_, line)| line)
+            .or_else(|| stderr.lines().rev().find(|line| !line.trim().is_empty()))
+            .unwrap_or("antumbra exited without output")
+            .trim()
+            .to_string();
+        let output = Some(stderr.to_string());
+        let exit = match exit_code {
+            Some(code) => format!("exit code {}", code),
+            None => "killed by a signal".to_string(),
+        };
+
+        match kind {
+            Some(AntumbraFailureKind::AuthRequired) => AppError::AuthRequired { message, output },
+            Some(AntumbraFailureKind::DaMismatch) => AppError::DaMismatch { message, output },
+            Some(AntumbraFailureKind::HandshakeTimeout) => {
+                AppError::HandshakeTimeout { message, output }
+            }
+            Some(AntumbraFailureKind::PartitionNotFound) => AppError::InvalidPartition(message),
+            Some(AntumbraFailureKind::DeviceNotFound) => AppError::DeviceNotConnected { output },
+            None => AppError::Command {
+                message: format!("Antumbra process failed ({}): {}", exit, message),
+                output,
+            },
+        }
+    }
+
     /// Get the error category for classification
     pub fn category(&self) -> ErrorCategory {
         match self {
             AppError::Io { .. } => ErrorCategory::FileSystem,
             AppError::Command { .. } => ErrorCategory::Command,
-            AppError::DeviceNotConnected => ErrorCategory::Validation,
+            AppError::DeviceNotConnected { .. } => ErrorCategory::Validation,
             AppError::Cancelled => ErrorCategory::Unknown,
+            AppError::Busy { .. } => ErrorCategory::Command,
+            AppError::AuthRequired { .. } => ErrorCategory::Permission,
+            AppError::DaMismatch { .. } => ErrorCategory::Validation,
+            AppError::HandshakeTimeout { .. } => ErrorCategory::Command,
+            AppError::SafeMode { .. } => ErrorCategory::Permission,
             AppError::InvalidPartition(_) => ErrorCategory::Validation,
             AppError::Parse(_) => ErrorCategory::Validation,
             AppError::Update { category, .. } => category.clone(),
@@ -161,34 +300,70 @@ impl AppError {
             AppError::Io { message, .. } => {
                 let msg_lower = message.to_lowercase();
                 if msg_lower.contains("permission") || msg_lower.contains("access denied") {
-                    Some("Run as Administrator or check folder permissions".to_string())
+                    Some(t("suggestion.run_as_admin_folder"))
                 } else if msg_lower.contains("not found") || msg_lower.contains("does not exist") {
-                    Some("Check that the file or directory exists".to_string())
+                    Some(t("suggestion.check_path_exists"))
                 } else {
                     None
                 }
             }
             AppError::Command { message, .. } => {
                 if message.contains("antumbra") {
-                    Some("Ensure antumbra binary is installed and accessible".to_string())
+                    Some(t("suggestion.install_antumbra"))
                 } else {
                     None
                 }
             }
-            AppError::DeviceNotConnected => {
-                Some("Connect your device and ensure it's in the correct mode (BROM/preloader)".to_string())
-            }
+            AppError::DeviceNotConnected { .. } => Some(t("suggestion.connect_device")),
+            AppError::Busy { .. } => Some(t("suggestion.wait_for_operation")),
+            AppError::AuthRequired { .. } => Some(t("suggestion.auth_required")),
+            AppError::DaMismatch { .. } => Some(t("suggestion.da_mismatch")),
+            AppError::HandshakeTimeout { .. } => Some(t("suggestion.handshake_timeout")),
+            AppError::SafeMode { .. } => Some(t("suggestion.disable_safe_mode")),
+            AppError::InvalidPartition(_) => Some(t("suggestion.invalid_partition")),
             _ => None,
         }
     }
 
+    /// Stable catalog code, e.g. `PW-USB-001`
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            AppError::Io { .. } => error_codes::FILE_SYSTEM,
+            AppError::Command { .. } => error_codes::COMMAND_FAILED,
+            AppError::DeviceNotConnected { .. } => error_codes::DEVICE_NOT_CONNECTED,
+            AppError::Cancelled => error_codes::CANCELLED,
+            AppError::Busy { .. } => error_codes::BUSY,
+            AppError::AuthRequired { .. } => error_codes::AUTH_REQUIRED,
+            AppError::DaMismatch { .. } => error_codes::DA_MISMATCH,
+            AppError::HandshakeTimeout { .. } => error_codes::HANDSHAKE_TIMEOUT,
+            AppError::SafeMode { .. } => error_codes::SAFE_MODE,
+            AppError::InvalidPartition(_) => error_codes::INVALID_PARTITION,
+            AppError::Parse(_) => error_codes::PARSE,
+            AppError::Update { category, .. } => match category {
+                ErrorCategory::Network => error_codes::UPDATE_NETWORK,
+                ErrorCategory::Permission => error_codes::UPDATE_PERMISSION,
+                ErrorCategory::Validation => error_codes::UPDATE_VALIDATION,
+                ErrorCategory::FileSystem => error_codes::UPDATE_FILE_SYSTEM,
+                _ => error_codes::UPDATE_OTHER,
+            },
+            AppError::Other { .. } => error_codes::OTHER,
+        }
+    }
+
     /// Get the error message
     pub fn message(&self) -> String {
         match self {
             AppError::Io { message, .. } => message.clone(),
             AppError::Command { message, .. } => message.clone(),
-            AppError::DeviceNotConnected => "Device not connected".to_string(),
-            AppError::Cancelled => "Operation cancelled".to_string(),
+            AppError::DeviceNotConnected { .. } => t("error.device_not_connected"),
+            AppError::Cancelled => t("error.cancelled"),
+            AppError::Busy { running_operation_id } => {
+                t_with("error.busy", &[("operation_id", running_operation_id)])
+            }
+            AppError::AuthRequired { message, .. } => message.clone(),
+            AppError::DaMismatch { message, .. } => message.clone(),
+            AppError::HandshakeTimeout { message, .. } => message.clone(),
+            AppError::SafeMode { message, .. } => message.clone(),
             AppError::InvalidPartition(msg) => msg.clone(),
             AppError::Parse(msg) => msg.clone(),
             AppError::Update { message, .. } => message.clone(),
@@ -197,6 +372,24 @@ impl AppError {
     }
 }
 
+/// The derived, `type`-tagged representation plus `error_code`
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = AppError::serialize(self, serde_json::value::Serializer)
+            .map_err(serde::ser::Error::custom)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.insert("error_code".to_string(), self.error_code().into());
+        }
+        value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AppError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        AppError::deserialize(deserializer)
+    }
+}
+
 impl From<std::io::Error> for AppError {
     fn from(err: std::io::Error) -> Self {
         let code = err.raw_os_error();
@@ -219,7 +412,7 @@ impl From<anyhow::Error> for AppError {
             return AppError::Update {
                 message: err_str,
                 category: ErrorCategory::Permission,
-                suggestion: Some("Close antumbra.exe and try again".to_string()),
+                suggestion: Some(t("suggestion.close_antumbra")),
             };
         }
         
@@ -229,7 +422,7 @@ impl From<anyhow::Error> for AppError {
             return AppError::Update {
                 message: err_str,
                 category: ErrorCategory::Permission,
-                suggestion: Some("Run as Administrator or check antivirus settings".to_string()),
+                suggestion: Some(t("suggestion.run_as_admin_antivirus")),
             };
         }
         
@@ -242,7 +435,7 @@ impl From<anyhow::Error> for AppError {
             return AppError::Update {
                 message: err_str,
                 category: ErrorCategory::Network,
-                suggestion: Some("Check your internet connection and try again".to_string()),
+                suggestion: Some(t("suggestion.check_connection")),
             };
         }
         
@@ -252,7 +445,7 @@ impl From<anyhow::Error> for AppError {
             return AppError::Update {
                 message: err_str,
                 category: ErrorCategory::Validation,
-                suggestion: Some("Download may be corrupted. Try downloading again".to_string()),
+                suggestion: Some(t("suggestion.download_corrupted")),
             };
         }
         
@@ -262,7 +455,7 @@ impl From<anyhow::Error> for AppError {
             return AppError::Update {
                 message: err_str,
                 category: ErrorCategory::FileSystem,
-                suggestion: Some("Free up disk space and try again".to_string()),
+                suggestion: Some(t("suggestion.free_disk_space")),
             };
         }
         
@@ -291,6 +484,58 @@ mod tests {
         assert_eq!(update_err.category(), ErrorCategory::Network);
     }
 
+    #[test]
+    fn test_antumbra_failure_mapping() {
+        let stderr = "Handshake...\nError: SEC_AUTH_NEEDED\nExiting";
+        let err = AppError::from_antumbra_failure(Some(1), false, stderr);
+        assert!(
+            matches!(err, AppError::AuthRequired { ref message, .. } if message == "Error: SEC_AUTH_NEEDED")
+        );
+        assert_eq!(err.category(), ErrorCategory::Permission);
+
+        let err = AppError::from_antumbra_failure(Some(1), false, "Partition not found: boot_c");
+        assert!(matches!(err, AppError::InvalidPartition(_)));
+
+        let err = AppError::from_antumbra_failure(Some(2), false, "something odd");
+        assert!(matches!(err, AppError::Command { .. }));
+
+        let err = AppError::from_antumbra_failure(Some(1), false, "No MTK device");
+        assert!(matches!(
+            err,
+            AppError::DeviceNotConnected { output: Some(ref out) } if out == "No MTK device"
+        ));
+
+        let cancelled = AppError::from_antumbra_failure(None, true, "");
+        assert!(matches!(cancelled, AppError::Cancelled));
+        let killed = AppError::from_antumbra_failure(None, false, "Writing boot_a");
+        assert!(
+            matches!(killed, AppError::Command { ref message, .. } if message.contains("signal"))
+        );
+    }
+
+    #[test]
+    fn test_serialized_error_carries_catalog_code() {
+        let value = serde_json::to_value(AppError::DeviceNotConnected { output: None }).unwrap();
+        assert_eq!(value["type"], "device_not_connected");
+        assert_eq!(value["error_code"], "PW-USB-001");
+
+        let value = serde_json::to_value(AppError::io_with_code("denied", 5)).unwrap();
+        assert_eq!(value["code"], 5);
+        assert_eq!(value["error_code"], error_codes::FILE_SYSTEM);
+
+        let err: AppError = serde_json::from_value(value).unwrap();
+        assert!(matches!(err, AppError::Io { code: Some(5), .. }));
+
+        for code in [AppError::Cancelled.error_code(), AppError::parse("x").error_code()] {
+            let info = error_codes::lookup(&code.to_lowercase()).unwrap();
+            assert_eq!(info.code, code);
+        }
+        let mut codes: Vec<&str> = error_codes::CATALOG.iter().map(|info| info.code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), error_codes::CATALOG.len());
+    }
+
     #[test]
     fn test_suggestion_for_permission_error() {
         let io_err = AppError::Io { 
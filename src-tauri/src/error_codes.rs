@@ -0,0 +1,200 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Stable codes for every `AppError` variant. Codes are part of the public
+//! contract (users quote them in bug reports, docs link to them): never
+//! renumber or reuse one, only add new codes.
+
+use serde::Serialize;
+
+/// Description and remediation for one error code
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorCodeInfo {
+    pub code: &'static str,
+    /// `AppError` variant (serialized `type`) the code belongs to
+    pub error_type: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub remediation: &'static [&'static str],
+}
+
+pub const FILE_SYSTEM: &str = "PW-FS-001";
+pub const COMMAND_FAILED: &str = "PW-CMD-001";
+pub const DEVICE_NOT_CONNECTED: &str = "PW-USB-001";
+pub const HANDSHAKE_TIMEOUT: &str = "PW-USB-002";
+pub const AUTH_REQUIRED: &str = "PW-DEV-001";
+pub const DA_MISMATCH: &str = "PW-DEV-002";
+pub const CANCELLED: &str = "PW-OP-001";
+pub const BUSY: &str = "PW-OP-002";
+pub const SAFE_MODE: &str = "PW-OP-003";
+pub const INVALID_PARTITION: &str = "PW-PART-001";
+pub const PARSE: &str = "PW-PARSE-001";
+pub const UPDATE_NETWORK: &str = "PW-UPD-001";
+pub const UPDATE_PERMISSION: &str = "PW-UPD-002";
+pub const UPDATE_VALIDATION: &str = "PW-UPD-003";
+pub const UPDATE_FILE_SYSTEM: &str = "PW-UPD-004";
+pub const UPDATE_OTHER: &str = "PW-UPD-005";
+pub const OTHER: &str = "PW-GEN-001";
+
+pub const CATALOG: &[ErrorCodeInfo] = &[
+    ErrorCodeInfo {
+        code: FILE_SYSTEM,
+        error_type: "io",
+        title: "File system error",
+        description: "A file or folder could not be read, written or created.",
+        remediation: &[
+            "Check that the path exists and is spelled correctly.",
+            "Make sure the folder is writable, or run the app with sufficient rights.",
+            "Check that the disk is not full.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: COMMAND_FAILED,
+        error_type: "command",
+        title: "antumbra failed",
+        description: "antumbra exited with an error that does not match a known failure.",
+        remediation: &[
+            "Read the command output attached to the error.",
+            "Check that antumbra is installed and up to date.",
+            "Export the logs and report the issue if it persists.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: DEVICE_NOT_CONNECTED,
+        error_type: "device_not_connected",
+        title: "Device not connected",
+        description: "No MediaTek device in BROM or preloader mode was found.",
+        remediation: &[
+            "Power the device off, hold the volume keys and plug the USB cable in.",
+            "Try another USB cable or port, preferably directly on the computer.",
+            "Run the environment check to verify USB drivers or udev rules.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: HANDSHAKE_TIMEOUT,
+        error_type: "handshake_timeout",
+        title: "Handshake timed out",
+        description: "The device was found but did not answer the BROM/preloader handshake.",
+        remediation: &[
+            "Reconnect the device; it only stays in BROM mode for a few seconds.",
+            "Make sure no other tool (e.g. ModemManager) is holding the serial port.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: AUTH_REQUIRED,
+        error_type: "auth_required",
+        title: "Authentication required",
+        description: "The device enforces secure boot authentication (SLA/DAA).",
+        remediation: &[
+            "Use a DA and auth file signed for this device.",
+            "Check whether an exploit-capable DA is available for the chipset.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: DA_MISMATCH,
+        error_type: "da_mismatch",
+        title: "DA does not match the device",
+        description: "The selected Download Agent does not support the connected chipset.",
+        remediation: &[
+            "Select the DA that belongs to the device's firmware.",
+            "Inspect the DA file to see which hardware codes it supports.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: CANCELLED,
+        error_type: "cancelled",
+        title: "Operation cancelled",
+        description: "The operation was stopped before it finished.",
+        remediation: &["Run the operation again if it was cancelled by mistake."],
+    },
+    ErrorCodeInfo {
+        code: BUSY,
+        error_type: "busy",
+        title: "Device busy",
+        description: "Another operation is still using the device.",
+        remediation: &["Wait for the running operation to finish, or cancel it."],
+    },
+    ErrorCodeInfo {
+        code: SAFE_MODE,
+        error_type: "safe_mode",
+        title: "Blocked by safe mode",
+        description: "Safe mode refuses operations that modify the device.",
+        remediation: &["Turn off safe mode in the settings to allow writes."],
+    },
+    ErrorCodeInfo {
+        code: INVALID_PARTITION,
+        error_type: "invalid_partition",
+        title: "Invalid partition",
+        description: "The partition does not exist on the device or its name is invalid.",
+        remediation: &[
+            "Reload the partition table and pick a partition from the list.",
+            "Check the slot suffix (_a/_b) on A/B devices.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: PARSE,
+        error_type: "parse",
+        title: "Parse error",
+        description: "A file or command output could not be understood.",
+        remediation: &[
+            "Check that the file is a valid scatter, DA or image file.",
+            "Re-download the firmware if the file may be corrupted.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: UPDATE_NETWORK,
+        error_type: "update",
+        title: "Update failed: network",
+        description: "GitHub or the update mirror could not be reached.",
+        remediation: &[
+            "Check the internet connection and the proxy settings.",
+            "Run the network diagnostics.",
+            "Add a GitHub token if the API rate limit is exhausted.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: UPDATE_PERMISSION,
+        error_type: "update",
+        title: "Update failed: permission",
+        description: "The antumbra binary could not be replaced.",
+        remediation: &[
+            "Close any running antumbra process.",
+            "Allow the app in your antivirus, or run it with sufficient rights.",
+        ],
+    },
+    ErrorCodeInfo {
+        code: UPDATE_VALIDATION,
+        error_type: "update",
+        title: "Update failed: verification",
+        description: "The downloaded release did not pass checksum or signature checks.",
+        remediation: &["Retry the download; the file may have been corrupted in transit."],
+    },
+    ErrorCodeInfo {
+        code: UPDATE_FILE_SYSTEM,
+        error_type: "update",
+        title: "Update failed: disk",
+        description: "The release could not be written to disk.",
+        remediation: &["Free some disk space and retry."],
+    },
+    ErrorCodeInfo {
+        code: UPDATE_OTHER,
+        error_type: "update",
+        title: "Update failed",
+        description: "The antumbra update failed for an unclassified reason.",
+        remediation: &["Read the error message and export the logs if it persists."],
+    },
+    ErrorCodeInfo {
+        code: OTHER,
+        error_type: "other",
+        title: "Unexpected error",
+        description: "An error without a more specific classification.",
+        remediation: &["Read the error message and export the logs if it persists."],
+    },
+];
+
+/// Catalog entry for `code`, case-insensitive
+pub fn lookup(code: &str) -> Option<&'static ErrorCodeInfo> {
+    CATALOG.iter().find(|info| info.code.eq_ignore_ascii_case(code.trim()))
+}
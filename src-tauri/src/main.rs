@@ -8,31 +8,68 @@
 
 mod commands;
 mod error;
+mod error_codes;
 mod models;
 mod services;
 
 fn init_logging() {
-    let log_dir = dirs::config_dir()
-        .map(|dir| dir.join("penumbra-wrapper"))
-        .unwrap_or_else(|| std::env::temp_dir().join("penumbra-wrapper"));
+    use services::log_rotation::{self, RotatingFile};
 
-    let _ = std::fs::create_dir_all(&log_dir);
-    let log_file = log_dir.join("penumbra-wrapper.log");
+    let log_path = services::paths::wrapper_log_file();
+    if let Some(log_dir) = log_path.parent() {
+        let _ = std::fs::create_dir_all(log_dir);
+    }
+    log_rotation::prune_old_segments(
+        &log_path,
+        log_rotation::MAX_LOG_FILES,
+        log_rotation::MAX_LOG_AGE,
+    );
 
-    let log_file = match fern::log_file(log_file) {
-        Ok(file) => file,
+    let log_file =
+        RotatingFile::open(&log_path, log_rotation::MAX_LOG_BYTES, log_rotation::MAX_LOG_FILES);
+    let log_file: Box<dyn std::io::Write + Send> = match log_file {
+        Ok(file) => Box::new(file),
         Err(err) => {
             eprintln!("Failed to open log file: {}", err);
             return env_logger::init();
         }
     };
 
+    let json_path = log_path.with_file_name(services::structured_log::JSON_LOG_FILE);
+    log_rotation::prune_old_segments(
+        &json_path,
+        log_rotation::MAX_LOG_FILES,
+        log_rotation::MAX_LOG_AGE,
+    );
+    let json_file =
+        RotatingFile::open(&json_path, log_rotation::MAX_LOG_BYTES, log_rotation::MAX_LOG_FILES);
+    let json_dispatch = match json_file {
+        Ok(file) => Some(
+            fern::Dispatch::new()
+                .format(|out, message, record| {
+                    let line = services::structured_log::to_json(record, message);
+                    out.finish(format_args!("{}", line))
+                })
+                .level(log::LevelFilter::Debug)
+                .chain(Box::new(file) as Box<dyn std::io::Write + Send>),
+        ),
+        Err(err) => {
+            eprintln!("Failed to open JSON log file: {}", err);
+            None
+        }
+    };
+
+    let event_dispatch = fern::Dispatch::new()
+        .level(log::LevelFilter::Info)
+        .chain(fern::Output::call(services::structured_log::emit));
+
     let file_dispatch = fern::Dispatch::new()
         .format(|out, message, record| {
             out.finish(format_args!(
-                "{} [{}] {}",
+                "{} [{}] {} {}",
                 chrono::Utc::now().to_rfc3339(),
                 record.level(),
+                services::structured_log::context_prefix(record),
                 message
             ))
         })
@@ -42,19 +79,24 @@ fn init_logging() {
     let stdout_dispatch = fern::Dispatch::new()
         .format(|out, message, record| {
             out.finish(format_args!(
-                "{} [{}] {}",
+                "{} [{}] {} {}",
                 chrono::Utc::now().to_rfc3339(),
                 record.level(),
+                services::structured_log::context_prefix(record),
                 message
             ))
         })
         .level(log::LevelFilter::Info)
         .chain(std::io::stdout());
 
-    let logger = fern::Dispatch::new()
+    let mut logger = fern::Dispatch::new()
         .level(log::LevelFilter::Debug)
         .chain(stdout_dispatch)
-        .chain(file_dispatch);
+        .chain(file_dispatch)
+        .chain(event_dispatch);
+    if let Some(json_dispatch) = json_dispatch {
+        logger = logger.chain(json_dispatch);
+    }
 
     if logger.apply().is_err() {
         env_logger::init();
@@ -63,36 +105,106 @@ fn init_logging() {
 
 #[tokio::main]
 async fn main() {
+    services::paths::init_config_dir_override(std::env::args());
+    services::launch_file::init(std::env::args());
     init_logging();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_notification::init())
         .invoke_handler(tauri::generate_handler![
             commands::get_antumbra_version,
             commands::cancel_operation,
+            commands::queue::list_queued_operations,
+            commands::queue::cancel_queued_operation,
+            commands::queue::cancel_all_operations,
+            commands::queue::confirm_close_while_busy,
+            commands::queue::pause_operation,
+            commands::queue::resume_operation,
+            commands::queue::replay_operation_events,
+            commands::queue::subscribe_operation_events,
+            commands::queue::unsubscribe_operation_events,
+            commands::queue::list_running_operations,
             commands::device::list_partitions,
+            commands::device::export_partition_list,
             commands::device::reboot_device,
             commands::device::shutdown_device,
             commands::flash::flash_partition,
+            commands::job::run_job_file,
+            commands::plugins::list_plugin_tools,
+            commands::plugins::run_plugin_tool,
             commands::read::read_partition,
             commands::format::format_partition,
             commands::erase::erase_partition,
             commands::tools::read_all_partitions,
             commands::tools::seccfg_operation,
             commands::scatter::parse_scatter_file,
+            commands::scatter::open_launch_scatter,
+            commands::scatter::watch_scatter_file,
+            commands::scatter::unwatch_scatter_file,
+            commands::scatter::scatter_summary,
+            commands::scatter::scatter_set_download,
+            commands::scatter::scatter_set_file_name,
+            commands::scatter::scatter_move_partition,
+            commands::scatter::save_scatter_file,
             commands::scatter::detect_image_files,
+            commands::scatter::detect_image_candidates,
+            commands::scatter::detect_boot_files,
+            commands::da::inspect_da_file,
+            commands::preloader::list_preloaders,
+            commands::preloader::import_preloader,
+            commands::preloader::update_preloader,
+            commands::preloader::remove_preloader,
+            commands::session::list_recorded_sessions,
+            commands::session::replay_session,
             commands::settings::get_settings,
             commands::settings::update_settings,
+            commands::settings::list_locales,
+            commands::settings::get_recent_items,
+            commands::settings::clear_recent_items,
+            commands::settings::list_partition_sets,
+            commands::settings::save_partition_set,
+            commands::settings::rename_partition_set,
+            commands::settings::delete_partition_set,
+            commands::settings::has_secret,
+            commands::settings::set_secret,
+            commands::settings::delete_secret,
+            commands::workspace::open_workspace,
+            commands::workspace::save_workspace,
             commands::updates::get_antumbra_updatable_path,
             commands::updates::check_antumbra_update,
             commands::updates::download_antumbra_update,
+            commands::updates::cancel_antumbra_download,
+            commands::updates::list_antumbra_releases,
+            commands::updates::rollback_antumbra_update,
+            commands::updates::install_antumbra_from_file,
+            commands::updates::list_installed_antumbra_versions,
+            commands::updates::set_active_antumbra_version,
             commands::diagnostics::get_wrapper_log_path,
             commands::diagnostics::read_wrapper_log,
+            commands::diagnostics::get_log_files,
+            commands::diagnostics::export_logs,
+            commands::diagnostics::export_operation_report,
+            commands::diagnostics::export_session_script,
+            commands::diagnostics::get_schemas,
+            commands::diagnostics::query_log,
+            commands::diagnostics::tail_wrapper_log,
+            commands::diagnostics::stop_wrapper_log_tail,
             commands::diagnostics::read_antumbra_log,
             commands::diagnostics::get_last_antumbra_command,
-            commands::diagnostics::check_windows_environment,
+            commands::diagnostics::get_command_history,
+            commands::diagnostics::get_statistics,
+            commands::diagnostics::check_environment,
+            commands::diagnostics::diagnose_network,
+            commands::diagnostics::run_self_test,
+            commands::diagnostics::replay_last_failure,
+            commands::diagnostics::lookup_error_code,
+            commands::diagnostics::get_disk_space,
+            commands::diagnostics::preview_usage_metrics,
+            commands::diagnostics::upload_usage_metrics,
+            commands::diagnostics::clear_usage_metrics,
             commands::fastboot::force_fastboot,
             commands::adb::adb_list_devices,
             commands::adb::adb_shell_command,
@@ -105,6 +217,9 @@ async fn main() {
             commands::adb::adb_system_action,
             commands::adb::adb_reboot,
             commands::adb::adb_framebuffer_save,
+            commands::adb::adb_cli_status,
+            commands::adb::adb_cli_devices,
+            commands::adb::adb_cli_reboot,
             commands::adb::adb_auth_check,
             commands::fastboot_tools::fastboot_list_devices,
             commands::fastboot_tools::fastboot_getvar_all,
@@ -114,17 +229,40 @@ async fn main() {
             commands::fastboot_tools::fastboot_reboot,
             commands::fastboot_tools::fastboot_set_active_slot,
             commands::fastboot_tools::fastboot_reboot_fastbootd,
+            commands::fastboot_tools::fastboot_cli_status,
+            commands::fastboot_tools::fastboot_cli_run,
+            commands::files::hash_file,
+            commands::files::read_file_slice,
         ])
-        .setup(|_app| {
+        .setup(|app| {
             // Initialize services on startup
-            log::info!("PenumbraWrapper starting...");
+            services::structured_log::attach(app.handle().clone());
+            log::info!(
+                "PenumbraWrapper starting (session {})...",
+                services::structured_log::session_id()
+            );
+            services::paths::migrate_legacy_files(app.handle());
+            services::config_watcher::start(app.handle().clone());
+            tauri::async_runtime::spawn_blocking(services::secrets::migrate_plaintext_secrets);
+            tauri::async_runtime::spawn_blocking(services::output_buffer::prune_spill_files);
             Ok(())
         })
-        .on_window_event(|_window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                let _ = services::antumbra::kill_current_process();
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                services::close_guard::on_close_requested(window, api);
             }
+            tauri::WindowEvent::Destroyed => services::events::forget_window(window.label()),
+            _ => {}
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, _event| {
+            // macOS delivers file-association opens as an event, not as arguments
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = _event {
+                for path in urls.iter().filter_map(|url| url.to_file_path().ok()) {
+                    services::launch_file::open_when_ready(_app, path);
+                }
+            }
+        });
 }
@@ -0,0 +1,23 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DaEntry {
+    pub hw_code: String,     // "0x0766"
+    pub hw_sub_code: String, // "0x8a00"
+    pub hw_version: String,  // "0xca00"
+    pub sw_version: String,  // "0x0000"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DaFile {
+    pub identifier: String, // "MTK_AllInOne_DA_v3"
+    pub version: u32,
+    pub entries: Vec<DaEntry>,
+    pub file_path: String,
+}
@@ -3,11 +3,27 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
+pub mod da;
+pub mod preloader;
 pub mod scatter;
+pub mod schema;
+pub mod session;
+pub mod workspace;
 
+use crate::services::operation_registry::RunningOperation;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Version of the event and result payloads. Bumped whenever a field is
+/// removed, renamed or changes meaning; added fields don't bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Serde default for the `schema_version` of result models
+pub fn schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Partition {
     pub name: String,
     pub start: String,
@@ -16,34 +32,62 @@ pub struct Partition {
     pub display_size: Option<String>, // Human readable (e.g., "512 KiB")
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PartitionListResult {
     pub partitions: Vec<Partition>,
     pub operation_id: String,
+    #[serde(default = "schema_version")]
+    pub schema_version: u32,
 }
 
-// Reserved for future progress tracking features
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FlashProgress {
-    pub current: u64,
-    pub total: u64,
+    /// Bytes done and in total; unknown when antumbra only prints a percentage
+    #[serde(default)]
+    pub current: Option<u64>,
+    #[serde(default)]
+    pub total: Option<u64>,
     pub percentage: f32,
     pub partition_name: String,
     pub operation: String, // "read" or "write"
+    #[serde(default)]
+    pub operation_id: Option<String>,
+    /// Smoothed over recent samples
+    #[serde(default)]
+    pub speed_bytes_per_sec: Option<f64>,
+    /// Since the previous progress event
+    #[serde(default)]
+    pub instant_speed_bytes_per_sec: Option<f64>,
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
 }
 
-// Reserved for future structured logging features
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A backend log record, written to the JSON log and emitted as `log:event`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct LogEvent {
     pub timestamp: String,
     pub level: String,
+    pub module: String,
     pub message: String,
+    pub session_id: String,
+    pub operation_id: Option<String>,
     pub partition_name: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Lines appended to the wrapper log since the previous `log:tail`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LogTailEvent {
+    pub lines: Vec<String>,
+}
+
+/// Lines antumbra appended to antumbra.log while an operation ran
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AntumbraLogEvent {
+    pub operation_id: String,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OperationOutputEvent {
     pub operation_id: String,
     pub line: String,
@@ -51,9 +95,78 @@ pub struct OperationOutputEvent {
     pub is_stderr: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OperationOutputBatchEvent {
+    pub operation_id: String,
+    pub lines: Vec<OperationOutputEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueuePositionEvent {
+    pub operation_id: String,
+    pub position: usize, // 0 = running, 1 = next in line
+    pub queue_length: usize,
+    /// The queue is held by `pause_operation`
+    pub paused: bool,
+}
+
+/// Sent when the window is closed while operations run; the close is held
+/// until `confirm_close_while_busy` answers or `timeout_secs` pass
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CloseRequestedWhileBusyEvent {
+    pub operations: Vec<RunningOperation>,
+    pub timeout_secs: u64,
+}
+
+/// Sent by `cancel_all_operations` for every operation it stopped
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OperationCancelledEvent {
+    pub operation_id: String,
+    /// False when the operation was still waiting in the queue
+    pub was_running: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OperationHeartbeatEvent {
+    pub operation_id: String,
+    pub elapsed_ms: u64,
+    pub last_output_age_ms: u64, // Time since antumbra last printed anything
+    /// From the statistics of earlier runs; None without history
+    pub estimated_remaining_ms: Option<u64>,
+}
+
+/// CPU and memory of the running antumbra process, every few seconds
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OperationResourcesEvent {
+    pub operation_id: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub peak_memory_bytes: u64,
+    /// Set while the process looks wedged (idle CPU, runaway memory)
+    pub warning: Option<String>,
+}
+
+/// Where an operation's output actually goes once the output layout is applied
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OperationOutputPathEvent {
+    pub operation_id: String,
+    pub path: String,
+    pub folder: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OperationCompleteEvent {
     pub operation_id: String,
     pub success: bool,
     pub error: Option<String>,
 }
+
+/// Sent on `hash:progress` while `hash_file` reads a file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HashProgressEvent {
+    pub operation_id: String,
+    pub path: String,
+    pub bytes_hashed: u64,
+    pub total_bytes: u64,
+    pub percentage: f32,
+}
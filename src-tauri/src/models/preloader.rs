@@ -0,0 +1,33 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// What the user knows about a preloader: which phone it belongs to and where it came from
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PreloaderMetadata {
+    #[serde(default)]
+    pub model: Option<String>, // "Redmi Note 12 (tapas)"
+    #[serde(default)]
+    pub chipset: Option<String>, // "MT6789"
+    #[serde(default)]
+    pub source: Option<String>, // Firmware package or dump it was taken from
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// A preloader stored in the library under the config directory
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PreloaderEntry {
+    pub id: String,
+    pub file_name: String, // Name of the imported file
+    pub path: String,      // Library copy passed to antumbra
+    pub sha256: String,
+    pub size: u64,
+    pub imported_at: String,
+    #[serde(flatten)]
+    pub metadata: PreloaderMetadata,
+}
@@ -3,9 +3,10 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ScatterPartition {
     pub index: String,             // "SYS0"
     pub partition_name: String,    // "preloader"
@@ -21,15 +22,53 @@ pub struct ScatterPartition {
     pub operation_type: String,    // "UPDATE", "BOOTLOADERS", "INVISIBLE"
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ScatterFile {
     pub platform: String,     // "MT6781"
     pub project: String,      // "x670_h814"
     pub storage_type: String, // "EMMC" or "UFS"
+    #[serde(default)]
+    pub available_storage_types: Vec<String>, // Every storage_type section found in the file
     pub partitions: Vec<ScatterPartition>,
     pub file_path: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScatterRegionSummary {
+    pub region: String,          // "EMMC_USER", "UFS_LU2"
+    pub partition_count: usize,
+    pub download_count: usize,
+    pub total_size: u64,         // Sum of all partition sizes in bytes
+    pub download_size: u64,      // Sum of downloadable partition sizes in bytes
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScatterSummary {
+    pub partition_count: usize,
+    pub download_count: usize,
+    pub invisible_count: usize,
+    pub total_download_size: u64,
+    pub regions: Vec<ScatterRegionSummary>,
+    #[serde(default = "super::schema_version")]
+    pub schema_version: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScatterChangedEvent {
+    pub scatter_path: String,
+    pub scatter_changed: bool, // The scatter itself was modified or removed
+    pub changed_files: Vec<String>, // Added, removed or modified files
+}
+
+/// A scatter file opened through the OS file association
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OpenScatterEvent {
+    pub path: String,
+    pub scatter: Option<ScatterFile>,
+    /// Why parsing failed, when `scatter` is None
+    pub error: Option<String>,
+}
+
 impl ScatterFile {
     /// Get only partitions with is_download = true
     #[allow(dead_code)]
@@ -37,10 +76,258 @@ impl ScatterFile {
         self.partitions.iter().filter(|p| p.is_download).collect()
     }
 
+    /// Compute download totals and per-region statistics, regions in scatter order
+    pub fn summary(&self) -> ScatterSummary {
+        let mut regions: Vec<ScatterRegionSummary> = Vec::new();
+        let mut download_count = 0;
+        let mut invisible_count = 0;
+        let mut total_download_size = 0u64;
+
+        for partition in &self.partitions {
+            let size = Self::parse_hex(&partition.partition_size).unwrap_or(0);
+
+            let region_name =
+                if partition.region.is_empty() { "UNKNOWN" } else { partition.region.as_str() };
+            let index = match regions.iter().position(|r| r.region == region_name) {
+                Some(index) => index,
+                None => {
+                    regions.push(ScatterRegionSummary {
+                        region: region_name.to_string(),
+                        partition_count: 0,
+                        download_count: 0,
+                        total_size: 0,
+                        download_size: 0,
+                    });
+                    regions.len() - 1
+                }
+            };
+            let region = &mut regions[index];
+            region.partition_count += 1;
+            region.total_size = region.total_size.saturating_add(size);
+
+            if partition.is_download {
+                download_count += 1;
+                total_download_size = total_download_size.saturating_add(size);
+                region.download_count += 1;
+                region.download_size = region.download_size.saturating_add(size);
+            }
+
+            if partition.operation_type.eq_ignore_ascii_case("INVISIBLE") {
+                invisible_count += 1;
+            }
+        }
+
+        ScatterSummary {
+            partition_count: self.partitions.len(),
+            download_count,
+            invisible_count,
+            total_download_size,
+            regions,
+            schema_version: super::SCHEMA_VERSION,
+        }
+    }
+
+    /// Look up a partition by name for in-place editing
+    pub fn partition_mut(&mut self, partition_name: &str) -> Option<&mut ScatterPartition> {
+        self.partitions.iter_mut().find(|p| p.partition_name == partition_name)
+    }
+
+    /// Move a partition to a new position and renumber SYS indices to match.
+    /// The start addresses in the partition's region are laid out again in the
+    /// new order, so the move is refused when the region isn't packed back to back.
+    pub fn move_partition(&mut self, from: usize, to: usize) -> Result<(), String> {
+        let len = self.partitions.len();
+        if from >= len || to >= len {
+            return Err(format!("Cannot move partition {} to {} ({} partitions)", from, to, len));
+        }
+
+        let mut partitions = self.partitions.clone();
+        let partition = partitions.remove(from);
+        let region = partition.region.clone();
+        partitions.insert(to, partition);
+        Self::relayout_region(&self.partitions, &mut partitions, &region)?;
+
+        for (i, partition) in partitions.iter_mut().enumerate() {
+            partition.index = format!("SYS{}", i);
+        }
+        self.partitions = partitions;
+        Ok(())
+    }
+
+    /// Give the partitions of `region` in `after` consecutive start addresses,
+    /// beginning where the region started in `before`. The physical addresses
+    /// keep their offset from the linear ones.
+    fn relayout_region(
+        before: &[ScatterPartition],
+        after: &mut [ScatterPartition],
+        region: &str,
+    ) -> Result<(), String> {
+        let mut start = None;
+        let mut offset = None;
+        let mut next = None;
+        let mut last_size = None;
+        for partition in before.iter().filter(|p| p.region == region) {
+            let name = &partition.partition_name;
+            let parse = |value: &str| {
+                Self::parse_hex(value).map_err(|_| {
+                    format!("Cannot move partitions in {}: {} has an invalid address", region, name)
+                })
+            };
+            let linear = parse(&partition.linear_start_addr)?;
+            let physical = parse(&partition.physical_start_addr)?;
+            let size = parse(&partition.partition_size)?;
+
+            if next.is_some_and(|next| next != linear)
+                || offset.is_some_and(|offset| offset != physical.wrapping_sub(linear))
+            {
+                return Err(format!(
+                    "Cannot move partitions in {}: {} doesn't start where the previous one ends",
+                    region, name
+                ));
+            }
+            start.get_or_insert(linear);
+            offset.get_or_insert(physical.wrapping_sub(linear));
+            next = Some(linear.saturating_add(size));
+            last_size = Some((name.clone(), size));
+        }
+        let (Some(mut next), Some(offset)) = (start, offset) else {
+            return Ok(());
+        };
+
+        // A last partition without a size takes the rest of the region
+        let after_last = after.iter().rev().find(|p| p.region == region);
+        if let Some((name, 0)) = last_size {
+            if after_last.is_some_and(|p| p.partition_name != name) {
+                return Err(format!("{} takes the rest of {} and must stay last", name, region));
+            }
+        }
+
+        for partition in after.iter_mut().filter(|p| p.region == region) {
+            partition.linear_start_addr = format!("0x{:x}", next);
+            partition.physical_start_addr = format!("0x{:x}", next.wrapping_add(offset));
+            next = next.saturating_add(Self::parse_hex(&partition.partition_size).unwrap_or(0));
+        }
+        Ok(())
+    }
+
     /// Parse hex string to u64
-    #[allow(dead_code)]
     pub fn parse_hex(hex_str: &str) -> Result<u64, std::num::ParseIntError> {
         let cleaned = hex_str.trim_start_matches("0x").trim_start_matches("0X");
         u64::from_str_radix(cleaned, 16)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partition(name: &str, region: &str, size: &str, download: bool, op: &str) -> ScatterPartition {
+        ScatterPartition {
+            index: String::new(),
+            partition_name: name.to_string(),
+            file_name: None,
+            is_download: download,
+            partition_type: String::new(),
+            linear_start_addr: "0x0".to_string(),
+            physical_start_addr: "0x0".to_string(),
+            partition_size: size.to_string(),
+            region: region.to_string(),
+            storage: String::new(),
+            operation_type: op.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_summary_totals_by_region() {
+        let scatter = ScatterFile {
+            platform: "MT6781".to_string(),
+            project: "test".to_string(),
+            storage_type: "EMMC".to_string(),
+            available_storage_types: vec!["EMMC".to_string()],
+            partitions: vec![
+                partition("preloader", "EMMC_BOOT1", "0x40000", true, "BOOTLOADERS"),
+                partition("proinfo", "EMMC_USER", "0x300000", false, "INVISIBLE"),
+                partition("boot", "EMMC_USER", "0x2000000", true, "UPDATE"),
+            ],
+            file_path: String::new(),
+        };
+
+        let summary = scatter.summary();
+        assert_eq!(summary.partition_count, 3);
+        assert_eq!(summary.download_count, 2);
+        assert_eq!(summary.invisible_count, 1);
+        assert_eq!(summary.total_download_size, 0x40000 + 0x2000000);
+        assert_eq!(summary.regions.len(), 2);
+        assert_eq!(summary.regions[0].region, "EMMC_BOOT1");
+        assert_eq!(summary.regions[1].partition_count, 2);
+        assert_eq!(summary.regions[1].total_size, 0x300000 + 0x2000000);
+        assert_eq!(summary.regions[1].download_size, 0x2000000);
+    }
+
+    fn at(mut partition: ScatterPartition, linear: &str, physical: &str) -> ScatterPartition {
+        partition.linear_start_addr = linear.to_string();
+        partition.physical_start_addr = physical.to_string();
+        partition
+    }
+
+    fn scatter(partitions: Vec<ScatterPartition>) -> ScatterFile {
+        ScatterFile {
+            platform: "MT6781".to_string(),
+            project: "test".to_string(),
+            storage_type: "EMMC".to_string(),
+            available_storage_types: vec!["EMMC".to_string()],
+            partitions,
+            file_path: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_move_partition_recomputes_start_addresses() {
+        let mut scatter = scatter(vec![
+            at(partition("preloader", "EMMC_BOOT1", "0x40000", true, "BOOTLOADERS"), "0x0", "0x0"),
+            at(partition("proinfo", "EMMC_USER", "0x300000", false, "INVISIBLE"), "0x0", "0x0"),
+            at(
+                partition("nvram", "EMMC_USER", "0x500000", false, "BINREGION"),
+                "0x300000",
+                "0x300000",
+            ),
+            at(partition("boot", "EMMC_USER", "0x2000000", true, "UPDATE"), "0x800000", "0x800000"),
+        ]);
+
+        scatter.move_partition(3, 1).unwrap();
+        let layout: Vec<_> = scatter
+            .partitions
+            .iter()
+            .map(|p| (p.index.as_str(), p.partition_name.as_str(), p.linear_start_addr.as_str()))
+            .collect();
+        assert_eq!(
+            layout,
+            vec![
+                ("SYS0", "preloader", "0x0"),
+                ("SYS1", "boot", "0x0"),
+                ("SYS2", "proinfo", "0x2000000"),
+                ("SYS3", "nvram", "0x2300000"),
+            ]
+        );
+        assert_eq!(scatter.partitions[3].physical_start_addr, "0x2300000");
+    }
+
+    #[test]
+    fn test_move_partition_refuses_conflicting_layouts() {
+        let gap = vec![
+            at(partition("proinfo", "EMMC_USER", "0x300000", false, "INVISIBLE"), "0x0", "0x0"),
+            at(partition("boot", "EMMC_USER", "0x2000000", true, "UPDATE"), "0x400000", "0x400000"),
+        ];
+        let mut with_gap = scatter(gap);
+        assert!(with_gap.move_partition(1, 0).is_err());
+        assert_eq!(with_gap.partitions[0].partition_name, "proinfo");
+
+        let rest = vec![
+            at(partition("boot", "EMMC_USER", "0x2000000", true, "UPDATE"), "0x0", "0x0"),
+            at(partition("userdata", "EMMC_USER", "0x0", true, "UPDATE"), "0x2000000", "0x2000000"),
+        ];
+        assert!(scatter(rest).move_partition(1, 0).is_err());
+
+        assert!(scatter(Vec::new()).move_partition(0, 1).is_err());
+    }
+}
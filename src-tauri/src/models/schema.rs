@@ -0,0 +1,89 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use super::da::{DaEntry, DaFile};
+use super::preloader::{PreloaderEntry, PreloaderMetadata};
+use super::scatter::{
+    OpenScatterEvent, ScatterChangedEvent, ScatterFile, ScatterPartition, ScatterRegionSummary,
+    ScatterSummary,
+};
+use super::session::{RecordedSession, RecordedSessionInfo, SessionLine};
+use super::workspace::{OpenedWorkspace, Workspace};
+use super::*;
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// JSON Schema of every model, for frontends and scripts built against the
+/// backend's events and results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaCatalog {
+    pub schema_version: u32,
+    /// By model name, e.g. "FlashProgress"
+    pub schemas: BTreeMap<String, serde_json::Value>,
+}
+
+pub fn catalog() -> SchemaCatalog {
+    let mut schemas = BTreeMap::new();
+    macro_rules! add {
+        ($($model:ty),* $(,)?) => {
+            $(add::<$model>(&mut schemas);)*
+        };
+    }
+    add!(
+        Partition,
+        PartitionListResult,
+        FlashProgress,
+        LogEvent,
+        LogTailEvent,
+        AntumbraLogEvent,
+        OperationOutputEvent,
+        OperationOutputBatchEvent,
+        QueuePositionEvent,
+        CloseRequestedWhileBusyEvent,
+        OperationCancelledEvent,
+        OperationHeartbeatEvent,
+        OperationResourcesEvent,
+        OperationOutputPathEvent,
+        OperationCompleteEvent,
+        HashProgressEvent,
+        DaEntry,
+        DaFile,
+        PreloaderMetadata,
+        PreloaderEntry,
+        ScatterPartition,
+        ScatterFile,
+        ScatterRegionSummary,
+        ScatterSummary,
+        ScatterChangedEvent,
+        OpenScatterEvent,
+        SessionLine,
+        RecordedSession,
+        RecordedSessionInfo,
+        Workspace,
+        OpenedWorkspace,
+    );
+    SchemaCatalog { schema_version: SCHEMA_VERSION, schemas }
+}
+
+fn add<T: JsonSchema>(schemas: &mut BTreeMap<String, serde_json::Value>) {
+    let schema = serde_json::to_value(schema_for!(T)).unwrap_or_default();
+    schemas.insert(T::schema_name(), schema);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_model_by_name() {
+        let catalog = catalog();
+        assert_eq!(catalog.schema_version, SCHEMA_VERSION);
+        let progress = &catalog.schemas["FlashProgress"];
+        assert_eq!(progress["title"], "FlashProgress");
+        assert!(progress["properties"]["partition_name"].is_object());
+        assert!(catalog.schemas.contains_key("OpenedWorkspace"));
+    }
+}
@@ -0,0 +1,39 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionLine {
+    pub offset_ms: u64, // Time since the process was spawned
+    pub line: String,
+    pub is_stderr: bool,
+    /// Ended by a bare '\r' (a progress redraw); missing in older recordings
+    #[serde(default)]
+    pub redraw: bool,
+}
+
+/// Everything antumbra printed during one operation, as saved to a session file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecordedSession {
+    pub operation_id: String,
+    pub args: Vec<String>,
+    pub started_at: String,
+    pub exit_code: Option<i32>,
+    pub lines: Vec<SessionLine>,
+    #[serde(default = "super::schema_version")]
+    pub schema_version: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecordedSessionInfo {
+    pub path: String,
+    pub operation_id: String,
+    pub command: String,
+    pub started_at: String,
+    pub exit_code: Option<i32>,
+    pub line_count: usize,
+}
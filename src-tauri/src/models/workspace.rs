@@ -0,0 +1,43 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Selections saved to `workspace.json` in a project folder. Paths inside the
+/// folder are stored relative to it so the folder can be moved or shared.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Workspace {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub scatter_path: Option<String>,
+    #[serde(default)]
+    pub storage_type: Option<String>,
+    #[serde(default)]
+    pub da_path: Option<String>,
+    #[serde(default)]
+    pub preloader_path: Option<String>,
+    /// Image file chosen for each partition
+    #[serde(default)]
+    pub images: BTreeMap<String, String>,
+    #[serde(default)]
+    pub selected_partitions: Vec<String>,
+    /// Where partition backups are read to
+    #[serde(default)]
+    pub backup_dir: Option<String>,
+    #[serde(default)]
+    pub saved_at: Option<String>,
+}
+
+/// A workspace as opened, with every path made absolute
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OpenedWorkspace {
+    pub folder: String,
+    pub workspace: Workspace,
+    #[serde(default = "super::schema_version")]
+    pub schema_version: u32,
+}
@@ -0,0 +1,146 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! The platform `adb` binary, when installed, for moving a booted device
+//! into a flashable state. Unlike the built-in USB client it also reaches
+//! devices already claimed by a running adb server.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Stdio;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdbCliDevice {
+    pub serial: String,
+    /// "device", "unauthorized", "recovery", "sideload", ...
+    pub state: String,
+    pub model: Option<String>,
+    pub product: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdbCliStatus {
+    pub path: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Where `adb reboot` sends the device
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdbRebootTarget {
+    Normal,
+    Bootloader,
+    Recovery,
+    Fastboot,
+    Edl,
+}
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// `adb` from PATH, if installed
+pub fn find() -> Option<PathBuf> {
+    let name = if cfg!(windows) { "adb.exe" } else { "adb" };
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(name)).find(|candidate| candidate.is_file())
+}
+
+/// `adb` from PATH, or an error telling the user to install it
+pub fn binary() -> Result<PathBuf> {
+    find().context("adb was not found on PATH; install Android platform-tools")
+}
+
+pub fn status() -> AdbCliStatus {
+    let path = find();
+    let version = path.as_ref().and_then(|adb| {
+        let mut command = std::process::Command::new(adb);
+        #[cfg(windows)]
+        std::os::windows::process::CommandExt::creation_flags(&mut command, CREATE_NO_WINDOW);
+        let output = command.arg("version").output().ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout.lines().next().map(|line| line.trim().to_string())
+    });
+    AdbCliStatus { path: path.map(|path| path.to_string_lossy().to_string()), version }
+}
+
+pub async fn devices() -> Result<Vec<AdbCliDevice>> {
+    let mut command = tokio::process::Command::new(binary()?);
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+    let output = command
+        .args(["devices", "-l"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .context("Failed to run adb devices")?;
+    if !output.status.success() {
+        anyhow::bail!("adb devices failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(parse_devices(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Arguments for `adb -s <serial> reboot <target>`
+pub fn reboot_args(serial: &str, target: AdbRebootTarget) -> Vec<String> {
+    let mut args = vec!["-s".to_string(), serial.to_string(), "reboot".to_string()];
+    let target = match target {
+        AdbRebootTarget::Normal => None,
+        AdbRebootTarget::Bootloader => Some("bootloader"),
+        AdbRebootTarget::Recovery => Some("recovery"),
+        AdbRebootTarget::Fastboot => Some("fastboot"),
+        AdbRebootTarget::Edl => Some("edl"),
+    };
+    args.extend(target.map(str::to_string));
+    args
+}
+
+fn parse_devices(output: &str) -> Vec<AdbCliDevice> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with("List of devices") && !line.starts_with('*'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let serial = fields.next()?.to_string();
+            let state = fields.next()?.to_string();
+            let mut device = AdbCliDevice { serial, state, model: None, product: None };
+            for field in fields {
+                match field.split_once(':') {
+                    Some(("model", model)) => device.model = Some(model.to_string()),
+                    Some(("product", product)) => device.product = Some(product.to_string()),
+                    _ => {}
+                }
+            }
+            Some(device)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_adb_devices_output() {
+        let output = "\
+* daemon started successfully
+List of devices attached
+0123456789ABCDEF       device usb:1-1 product:lake model:Moto_G7 device:lake transport_id:1
+emulator-5554          unauthorized transport_id:2
+
+";
+        let devices = parse_devices(output);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].serial, "0123456789ABCDEF");
+        assert_eq!(devices[0].model.as_deref(), Some("Moto_G7"));
+        assert_eq!(devices[1].state, "unauthorized");
+        assert_eq!(devices[1].product, None);
+
+        assert_eq!(reboot_args("abc", AdbRebootTarget::Normal), ["-s", "abc", "reboot"]);
+        assert_eq!(reboot_args("abc", AdbRebootTarget::Edl), ["-s", "abc", "reboot", "edl"]);
+    }
+}
@@ -3,58 +3,314 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use crate::models::{OperationCompleteEvent, OperationOutputEvent};
+use crate::models::{
+    FlashProgress, OperationCompleteEvent, OperationHeartbeatEvent, OperationOutputBatchEvent,
+    OperationOutputEvent, OperationResourcesEvent,
+};
+use crate::services::antumbra_progress::{
+    parse_progress_line, ProgressTracker, PROGRESS_EVENT_INTERVAL,
+};
+use crate::services::command_history::{self, HistoryEntry};
+use crate::services::config::AppSettings;
+use crate::services::events;
+use crate::services::executor::CommandExecutor;
+use crate::services::log_tail;
+use crate::services::notifications;
+use crate::services::{operation_queue, operation_registry, operation_stats};
+use crate::services::output_buffer::OutputBuffer;
+use crate::services::resource_monitor::{self, ResourceMonitor};
+use crate::services::session_recorder::SessionRecorder;
+use crate::services::sleep_inhibitor;
+use crate::services::structured_log;
 use anyhow::{Context, Result};
 use chrono::Utc;
-use std::collections::HashSet;
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Emitter, Manager};
-use tokio::io::AsyncReadExt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::process::Command as TokioCommand;
 
 pub struct AntumbraExecutor {
     binary_path: PathBuf,
     working_dir: PathBuf,
+    /// Extra environment for the spawned process
+    envs: Vec<(String, String)>,
+}
+
+/// Everything a finished process printed, whether or not it succeeded
+#[derive(Debug)]
+pub struct StreamedOutput {
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    /// The operation was cancelled on request while it ran
+    pub cancelled: bool,
+    pub stdout: String,
+    pub stderr: String,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct AntumbraCommandInfo {
+    #[serde(default)]
+    pub id: String,
     pub command: String,
     pub args: Vec<String>,
     pub working_dir: String,
     pub started_at: String,
+    #[serde(default)]
+    pub operation_id: Option<String>,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub success: Option<bool>,
+    /// Bytes written or read by a successful run, when they can be measured
+    #[serde(default)]
+    pub bytes: Option<u64>,
+    /// App run that issued the command; history is kept across restarts
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// How long antumbra gets to close the USB session after SIGINT/CTRL_BREAK
+fn cancel_grace_period() -> Duration {
+    crate::services::config::load_settings().unwrap_or_default().cancel_grace_period()
+}
+/// Extra wait after SIGTERM before falling back to SIGKILL
+#[cfg(unix)]
+const TERM_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+/// How often `operation:heartbeat` is emitted while antumbra is running
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(3);
+
+/// A streamed antumbra run that exited unsuccessfully
+#[derive(Debug, thiserror::Error)]
+#[error("Antumbra process failed: {stderr}")]
+pub struct AntumbraFailure {
+    pub exit_code: Option<i32>,
+    /// Stopped through `kill_operation_process` or `kill_all_processes`
+    pub cancelled: bool,
+    pub stderr: String,
 }
 
-static LAST_COMMAND: OnceLock<Mutex<Option<AntumbraCommandInfo>>> = OnceLock::new();
-static CURRENT_PID: OnceLock<Mutex<Option<u32>>> = OnceLock::new();
+/// Running antumbra processes keyed by operation_id
+static RUNNING_PROCESSES: OnceLock<Mutex<HashMap<String, RunningProcess>>> = OnceLock::new();
+
+/// A spawned antumbra process and the handle used to reach its children.
+/// On Unix the pid doubles as the process group id, on Windows the process
+/// is assigned to a Job Object so helpers it spawns can be terminated with it.
+#[derive(Debug, Clone, Copy)]
+struct RunningProcess {
+    pid: u32,
+    #[cfg(windows)]
+    job: Option<usize>,
+}
 
 fn binary_name() -> &'static str {
     if cfg!(windows) { "antumbra.exe" } else { "antumbra" }
 }
 
-fn emit_stream_line(
+/// Drops lines repeated within a time window, so bursts of identical output
+/// collapse while legitimately repeated messages ("Retrying...") still show up
+struct LineDeduplicator {
+    window: Duration,
+    dedup_stderr: bool,
+    seen: HashMap<String, Instant>,
+}
+
+impl LineDeduplicator {
+    /// Entries kept before expired lines are pruned
+    const PRUNE_THRESHOLD: usize = 1024;
+
+    fn new(window: Duration, dedup_stderr: bool) -> Self {
+        Self { window, dedup_stderr, seen: HashMap::new() }
+    }
+
+    fn from_settings(settings: &AppSettings) -> Self {
+        Self::new(Duration::from_millis(settings.output_dedup_window_ms), settings.dedup_stderr)
+    }
+
+    fn should_emit(&mut self, line: &str, is_stderr: bool, now: Instant) -> bool {
+        if self.window.is_zero() || (is_stderr && !self.dedup_stderr) {
+            return true;
+        }
+
+        if self.seen.len() >= Self::PRUNE_THRESHOLD {
+            let window = self.window;
+            self.seen.retain(|_, last| now.duration_since(*last) < window);
+        }
+
+        match self.seen.insert(line.to_string(), now) {
+            Some(last) => now.duration_since(last) >= self.window,
+            None => true,
+        }
+    }
+}
+
+/// Coalesces output lines into `operation:output_batch` events so chatty
+/// operations cost at most ~20 IPC messages per second
+#[derive(Clone, Default)]
+pub(crate) struct OutputBatcher {
+    pending: Arc<Mutex<Vec<OperationOutputEvent>>>,
+}
+
+impl OutputBatcher {
+    const FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+    fn push(&self, event: OperationOutputEvent) {
+        match self.pending.lock() {
+            Ok(mut pending) => pending.push(event),
+            Err(_) => log::warn!("Failed to lock output batch; dropping line"),
+        }
+    }
+
+    pub(crate) fn flush(&self, app: &AppHandle, operation_id: &str) {
+        let lines = match self.pending.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(_) => return,
+        };
+        if lines.is_empty() {
+            return;
+        }
+        let event = OperationOutputBatchEvent { operation_id: operation_id.to_string(), lines };
+        let _ = events::emit(app, "operation:output_batch", event);
+    }
+
+    /// Flush on a fixed interval until the returned guard is dropped
+    pub(crate) fn spawn_flusher(&self, app: &AppHandle, operation_id: &str) -> FlushTask {
+        let batcher = self.clone();
+        let app = app.clone();
+        let operation_id = operation_id.to_string();
+        FlushTask(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Self::FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                batcher.flush(&app, &operation_id);
+            }
+        }))
+    }
+}
+
+pub(crate) struct FlushTask(tokio::task::JoinHandle<()>);
+
+impl Drop for FlushTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// State shared by the stdout and stderr readers of one antumbra process
+#[derive(Clone)]
+pub(crate) struct StreamState {
+    dedup: Arc<Mutex<LineDeduplicator>>,
+    pub(crate) batcher: OutputBatcher,
+    last_output: Arc<AtomicU64>,
+    progress: Arc<Mutex<ProgressTracker>>,
+    /// Progress lines only reach the console as rate-limited progress events;
+    /// they are still kept in the output
+    compact_progress: bool,
+    recorder: Option<Arc<SessionRecorder>>,
+}
+
+impl StreamState {
+    pub(crate) fn new(
+        operation_id: &str,
+        args: &[String],
+        settings: &AppSettings,
+        last_output: Arc<AtomicU64>,
+    ) -> Self {
+        let rate = args.first().and_then(|command| operation_stats::average_bytes_per_sec(command));
+        let duration = operation_stats::estimate_duration_ms(args).map(Duration::from_millis);
+        let compact_progress = settings.compact_progress_output;
+        let interval = if compact_progress { PROGRESS_EVENT_INTERVAL } else { Duration::ZERO };
+        let progress = ProgressTracker::new(operation_id, args)
+            .with_historical_rate(rate)
+            .with_historical_duration(duration)
+            .with_min_interval(interval);
+        Self {
+            dedup: Arc::new(Mutex::new(LineDeduplicator::from_settings(settings))),
+            batcher: OutputBatcher::default(),
+            last_output,
+            progress: Arc::new(Mutex::new(progress)),
+            compact_progress,
+            recorder: None,
+        }
+    }
+
+    /// Progress held back by the rate limit, to emit once the process is done
+    pub(crate) fn flush_progress(&self) -> Option<FlashProgress> {
+        self.progress.lock().ok().and_then(|mut tracker| tracker.flush())
+    }
+}
+
+fn finish_recording(recorder: Option<&SessionRecorder>, exit_code: Option<i32>) {
+    if let Some(recorder) = recorder {
+        if let Err(err) = recorder.finish(exit_code) {
+            log::warn!("Failed to save session recording: {:#}", err);
+        }
+    }
+}
+
+/// Claim the device for an operation, reporting a failed operation when it can't.
+/// Waits in the queue unless `queue_operations` is disabled, in which case a busy
+/// device fails immediately with `DeviceBusy`.
+pub(crate) async fn acquire_device(
+    app: &AppHandle,
+    operation_id: &str,
+    args: &[String],
+    settings: &AppSettings,
+) -> Result<operation_queue::QueueTicket> {
+    let label = args.first().cloned().unwrap_or_default();
+    let ticket = if settings.queue_operations {
+        operation_queue::acquire(app, operation_id, &label).await
+    } else {
+        operation_queue::try_acquire(app, operation_id, &label)
+    };
+    ticket.inspect_err(|err| {
+        let complete_event = OperationCompleteEvent {
+            operation_id: operation_id.to_string(),
+            success: false,
+            error: Some(err.to_string()),
+        };
+        let _ = events::emit(app, "operation:complete", complete_event);
+    })
+}
+
+pub(crate) fn emit_stream_line(
     app: &AppHandle,
     operation_id: &str,
     is_stderr: bool,
-    lines_storage: &Arc<Mutex<Vec<String>>>,
-    seen_lines: &Arc<Mutex<HashSet<String>>>,
+    lines_storage: &Arc<Mutex<OutputBuffer>>,
+    state: &StreamState,
     line: String,
+    redraw: bool,
 ) {
-    let should_emit = match seen_lines.lock() {
-        Ok(mut seen) => {
-            if seen.contains(&line) {
-                false
-            } else {
-                seen.insert(line.clone());
-                true
-            }
+    if let Some(recorder) = &state.recorder {
+        recorder.record(&line, is_stderr, redraw);
+    }
+
+    let mut to_console = true;
+    if let Some(sample) = parse_progress_line(&line) {
+        let progress = state.progress.lock().ok().and_then(|mut tracker| tracker.update(sample));
+        if let Some(progress) = progress {
+            let _ = events::emit(app, "operation:progress", progress);
         }
+        // The typed event already carries the progress; thousands of
+        // near-identical lines would only slow the console down
+        to_console = !state.compact_progress;
+    }
+
+    let should_emit = match state.dedup.lock() {
+        Ok(mut dedup) => dedup.should_emit(&line, is_stderr, Instant::now()),
         Err(_) => {
-            log::warn!("Failed to lock seen lines; emitting anyway");
+            log::warn!("Failed to lock dedup cache; emitting anyway");
             true
         }
     };
@@ -69,6 +325,10 @@ fn emit_stream_line(
         log::warn!("Failed to lock output storage");
     }
 
+    if !to_console {
+        return;
+    }
+
     let timestamp = Utc::now().to_rfc3339();
     let event = OperationOutputEvent {
         operation_id: operation_id.to_string(),
@@ -76,9 +336,12 @@ fn emit_stream_line(
         timestamp,
         is_stderr,
     };
-    let _ = app.emit("operation:output", event);
+    state.batcher.push(event);
 }
 
+/// Bytes read from antumbra's output per call
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
 /// Read from a stream and emit lines split by either '\n' or '\r'
 /// This handles progress bars that use carriage returns to update in place
 async fn stream_lines<R>(
@@ -86,61 +349,158 @@ async fn stream_lines<R>(
     app: AppHandle,
     operation_id: String,
     is_stderr: bool,
-    lines_storage: Arc<Mutex<Vec<String>>>,
-    seen_lines: Arc<Mutex<HashSet<String>>>,
-    last_output: Arc<AtomicU64>,
+    lines_storage: Arc<Mutex<OutputBuffer>>,
+    state: StreamState,
 ) where
     R: AsyncReadExt + Unpin,
 {
-    let mut buffer = Vec::new();
-    let mut byte = [0u8; 1];
+    let mut splitter = LineSplitter::default();
+    let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+    let emit = |bytes: &[u8], redraw: bool| {
+        if let Some(line) = clean_line(bytes) {
+            emit_stream_line(&app, &operation_id, is_stderr, &lines_storage, &state, line, redraw);
+        }
+    };
 
     loop {
-        match reader.read_exact(&mut byte).await {
-            Ok(_) => {
-                last_output.store(now_millis(), Ordering::Relaxed);
-                if byte[0] == b'\n' || byte[0] == b'\r' {
-                    // Emit line if buffer is not empty
-                    if !buffer.is_empty() {
-                        if let Ok(line) = String::from_utf8(buffer.clone()) {
-                            let line = line.trim().to_string();
-                            if !line.is_empty() {
-                                emit_stream_line(
-                                    &app,
-                                    &operation_id,
-                                    is_stderr,
-                                    &lines_storage,
-                                    &seen_lines,
-                                    line,
-                                );
-                            }
-                        }
-                        buffer.clear();
-                    }
-                } else {
-                    buffer.push(byte[0]);
-                }
+        match reader.read(&mut chunk).await {
+            Ok(0) => break,
+            Err(err) if is_pty_closed(&err) => break,
+            Err(err) => {
+                log::warn!("Failed to read output of operation {}: {}", operation_id, err);
+                break;
+            }
+            Ok(read) => {
+                state.last_output.store(now_millis(), Ordering::Relaxed);
+                splitter.push(&chunk[..read], &emit);
             }
-            Err(_) => break, // EOF or error
         }
     }
 
     // Emit remaining buffer if any
-    if !buffer.is_empty() {
-        if let Ok(line) = String::from_utf8(buffer) {
-            let line = line.trim().to_string();
-            if !line.is_empty() {
-                emit_stream_line(
-                    &app,
-                    &operation_id,
-                    is_stderr,
-                    &lines_storage,
-                    &seen_lines,
-                    line,
-                );
+    splitter.finish(emit);
+}
+
+/// A PTY master fails reads with EIO once every slave handle is closed, which
+/// is how the end of antumbra's output shows up in PTY mode
+#[cfg(unix)]
+fn is_pty_closed(err: &std::io::Error) -> bool {
+    err.raw_os_error() == Some(libc::EIO)
+}
+
+#[cfg(not(unix))]
+fn is_pty_closed(_err: &std::io::Error) -> bool {
+    false
+}
+
+/// Splits output on '\n' and '\r', holding a partial line until the rest arrives.
+/// Lines ended by a bare '\r' are reported as redraws; "\r\n" is a plain newline.
+#[derive(Default)]
+struct LineSplitter {
+    pending: Vec<u8>,
+    /// `pending` is a line whose '\r' ended the last chunk, so whether it is
+    /// a redraw depends on the next byte
+    pending_cr: bool,
+}
+
+impl LineSplitter {
+    /// Call `on_line` for every non-empty line completed by `chunk`, with
+    /// whether it was a redraw
+    fn push(&mut self, chunk: &[u8], mut on_line: impl FnMut(&[u8], bool)) {
+        let mut rest = chunk;
+        if self.pending_cr {
+            let Some(&next) = rest.first() else { return };
+            self.pending_cr = false;
+            self.emit(next != b'\n', &mut on_line);
+            if next == b'\n' {
+                rest = &rest[1..];
+            }
+        }
+        while let Some(end) = rest.iter().position(|&byte| byte == b'\n' || byte == b'\r') {
+            let terminator = rest[end];
+            self.pending.extend_from_slice(&rest[..end]);
+            rest = &rest[end + 1..];
+            let redraw = match (terminator, rest.first()) {
+                (b'\n', _) => false,
+                (_, Some(b'\n')) => {
+                    rest = &rest[1..];
+                    false
+                }
+                (_, Some(_)) => true,
+                (_, None) => {
+                    self.pending_cr = true;
+                    return;
+                }
+            };
+            self.emit(redraw, &mut on_line);
+        }
+        self.pending.extend_from_slice(rest);
+    }
+
+    /// Emit whatever is left at the end of the stream
+    fn finish(&mut self, mut on_line: impl FnMut(&[u8], bool)) {
+        let redraw = std::mem::take(&mut self.pending_cr);
+        self.emit(redraw, &mut on_line);
+    }
+
+    fn emit(&mut self, redraw: bool, on_line: &mut impl FnMut(&[u8], bool)) {
+        if !self.pending.is_empty() {
+            on_line(&self.pending, redraw);
+            self.pending.clear();
+        }
+    }
+}
+
+/// Decode a raw output line and strip terminal styling.
+/// Invalid UTF-8 (Windows code pages, binary noise) is replaced rather than dropped.
+/// Returns None when nothing printable is left.
+fn clean_line(bytes: &[u8]) -> Option<String> {
+    let line = strip_ansi(&String::from_utf8_lossy(bytes));
+    let line = line.trim();
+    if line.is_empty() {
+        None
+    } else {
+        Some(line.to_string())
+    }
+}
+
+/// Remove ANSI escape sequences (colors, cursor movement, window titles)
+fn strip_ansi(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte in @..~
+            Some('[') => {
+                for next in chars.by_ref() {
+                    if ('@'..='~').contains(&next) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ESC \
+            Some(']') => {
+                while let Some(next) = chars.next() {
+                    if next == '\x07' {
+                        break;
+                    }
+                    if next == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
             }
+            // Two-character sequences such as ESC 7 / ESC 8
+            _ => {}
         }
     }
+
+    result
 }
 
 impl AntumbraExecutor {
@@ -154,22 +514,37 @@ impl AntumbraExecutor {
             log::info!("Antumbra binary size: {} bytes", metadata.len());
         }
 
-        // Ensure binary is executable
+        // Ensure binary is executable. Only touch it when needed, since a
+        // user-provided binary may live somewhere we can't write to.
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
             let mut perms = std::fs::metadata(&binary_path)?.permissions();
-            perms.set_mode(0o755);
-            std::fs::set_permissions(&binary_path, perms)?;
+            if perms.mode() & 0o111 == 0 {
+                perms.set_mode(0o755);
+                std::fs::set_permissions(&binary_path, perms)?;
+            }
         }
 
-        Ok(Self { binary_path, working_dir })
+        Ok(Self { binary_path, working_dir, envs: Vec::new() })
+    }
+
+    /// Run another executable, such as a plugin tool, through the same
+    /// queueing, streaming and cancellation machinery as antumbra
+    pub fn with_binary(binary_path: PathBuf, working_dir: PathBuf) -> Self {
+        Self { binary_path, working_dir, envs: Vec::new() }
+    }
+
+    /// Add environment variables to every process this executor spawns
+    pub fn with_envs(mut self, envs: &[(&str, &str)]) -> Self {
+        self.envs.extend(envs.iter().map(|(key, value)| (key.to_string(), value.to_string())));
+        self
     }
 
     /// Execute antumbra without streaming (legacy/fallback method)
     #[allow(dead_code)]
     pub async fn execute(&self, args: Vec<String>) -> Result<String> {
-        store_last_command(&self.binary_path, &self.working_dir, &args);
+        let history = store_last_command(&self.binary_path, &self.working_dir, &args, None);
         log::info!("Executing antumbra with args: {:?} (cwd: {:?})", args, self.working_dir);
 
         let output = create_hidden_command(&self.binary_path, &args)
@@ -178,6 +553,7 @@ impl AntumbraExecutor {
             .stderr(Stdio::piped())
             .output()
             .context("Failed to execute antumbra")?;
+        history.finish(output.status.success(), output.status.code());
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -194,107 +570,262 @@ impl AntumbraExecutor {
         operation_id: String,
         args: Vec<String>,
     ) -> Result<String> {
-        store_last_command(&self.binary_path, &self.working_dir, &args);
+        let started = Instant::now();
+        let command = args.first().cloned().unwrap_or_default();
+        let result = self.execute_captured(app.clone(), operation_id, args).await;
+        let result = result.and_then(|output| {
+            if !output.success {
+                let (exit_code, cancelled) = (output.exit_code, output.cancelled);
+                return Err(AntumbraFailure { exit_code, cancelled, stderr: output.stderr }.into());
+            }
+            Ok(output.stdout)
+        });
+        let error = result.as_ref().err().map(|err| err.to_string());
+        notifications::operation_finished(&app, &command, error.as_deref(), started.elapsed());
+        result
+    }
+
+    /// Like `execute_streaming`, but a non-zero exit is returned as output
+    /// rather than an error, with stderr kept on success too
+    pub async fn execute_captured(
+        &self,
+        app: AppHandle,
+        operation_id: String,
+        args: Vec<String>,
+    ) -> Result<StreamedOutput> {
+        let scope = operation_id.clone();
+        structured_log::in_operation(scope, self.run_streaming(app, operation_id, args)).await
+    }
+
+    async fn run_streaming(
+        &self,
+        app: AppHandle,
+        operation_id: String,
+        args: Vec<String>,
+    ) -> Result<StreamedOutput> {
+        // Read once so the whole run sees the same settings
+        let settings = crate::services::config::load_settings().unwrap_or_default();
+
+        // Wait for any other device operation to finish first
+        let _ticket = acquire_device(&app, &operation_id, &args, &settings).await?;
+
+        let history =
+            store_last_command(&self.binary_path, &self.working_dir, &args, Some(&operation_id));
         log::info!(
+            operation_id = operation_id.as_str();
             "Executing antumbra (streaming) with args: {:?} (cwd: {:?})",
             args,
             self.working_dir
         );
 
+        let _antumbra_log = log_tail::follow_antumbra_log(app.clone(), &operation_id);
+
+        let use_pty = settings.pty_mode;
+        let command = args.first().map(String::as_str).unwrap_or_default();
+        let limits = settings.operation_limits(command);
+        let _awake = settings.prevent_sleep.then(sleep_inhibitor::acquire);
+        #[cfg(unix)]
+        let pty = if use_pty {
+            open_pty().map_err(|err| log::warn!("Falling back to pipes: {:#}", err)).ok()
+        } else {
+            None
+        };
+        #[cfg(not(unix))]
+        if use_pty {
+            log::warn!("PTY mode is not supported on this platform, using pipes");
+        }
+
         let mut child = {
         #[cfg(windows)]
         {
             let mut cmd = TokioCommand::new(&self.binary_path);
             cmd.args(&args)
                 .current_dir(&self.working_dir)
+                .envs(self.envs.iter().map(|(key, value)| (key, value)))
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped());
-            // CREATE_NO_WINDOW flag to hide console window
-            cmd.creation_flags(0x08000000);
+            // CREATE_NO_WINDOW hides the console window, CREATE_NEW_PROCESS_GROUP
+            // lets cancellation send CTRL_BREAK to antumbra alone
+            cmd.creation_flags(0x08000000 | 0x00000200);
             cmd
         }
         #[cfg(not(windows))]
         {
-            TokioCommand::new(&self.binary_path)
-                .args(&args)
+            let mut cmd = TokioCommand::new(&self.binary_path);
+            cmd.args(&args)
                 .current_dir(&self.working_dir)
+                .envs(self.envs.iter().map(|(key, value)| (key, value)))
                 .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
+                .stderr(Stdio::piped());
+            // Own process group so cancellation can signal antumbra and its helpers together
+            #[cfg(unix)]
+            cmd.process_group(0);
+            #[cfg(unix)]
+            if let Some(pty) = &pty {
+                cmd.stdout(pty.slave.try_clone()?).stderr(pty.slave.try_clone()?);
+            }
+            cmd
         }
     }
     .spawn()
     .context("Failed to spawn antumbra process")?;
 
-        set_current_pid(child.id());
+        let _registration = register_process(&operation_id, &child);
+        let _running = operation_registry::register(&operation_id, &args, child.id(), false);
 
-        let stdout = child.stdout.take().context("Failed to take stdout")?;
-        let stderr = child.stderr.take().context("Failed to take stderr")?;
+        // With a PTY both streams arrive interleaved on the master side. Dropping
+        // our slave handle here lets reads end once antumbra exits.
+        #[cfg(unix)]
+        let pty_master = pty.map(|pty| tokio::fs::File::from_std(pty.master));
+        #[cfg(not(unix))]
+        let pty_master: Option<tokio::fs::File> = None;
+
+        let interleaved = pty_master.is_some();
+        let (stdout, stderr): (Box<dyn AsyncRead + Unpin + Send>, _) = match pty_master {
+            Some(master) => (Box::new(master), None),
+            None => (
+                Box::new(child.stdout.take().context("Failed to take stdout")?),
+                Some(child.stderr.take().context("Failed to take stderr")?),
+            ),
+        };
 
         // Collect all output for return value
-        let stdout_lines = Arc::new(Mutex::new(Vec::new()));
-        let stderr_lines = Arc::new(Mutex::new(Vec::new()));
+        let stdout_lines =
+            Arc::new(Mutex::new(OutputBuffer::from_settings(&settings, &operation_id, "stdout")));
+        let stderr_lines =
+            Arc::new(Mutex::new(OutputBuffer::from_settings(&settings, &operation_id, "stderr")));
         let last_output = Arc::new(AtomicU64::new(now_millis()));
 
-        // Shared deduplication cache across both stdout and stderr
-        let seen_lines = Arc::new(Mutex::new(HashSet::new()));
+        // Shared deduplication cache and progress parser across both stdout and stderr
+        let mut state = StreamState::new(&operation_id, &args, &settings, last_output.clone());
+        let recorder =
+            SessionRecorder::start_if_enabled(&settings, &operation_id, &args).map(Arc::new);
+        state.recorder = recorder.clone();
+
+        let batcher = state.batcher.clone();
+        let flush_task = batcher.spawn_flusher(&app, &operation_id);
+        let final_state = state.clone();
 
         let app_clone1 = app.clone();
         let op_id_clone1 = operation_id.clone();
         let stdout_lines_clone = stdout_lines.clone();
-        let seen_clone1 = seen_lines.clone();
-        let last_output_clone1 = last_output.clone();
+        let state_clone1 = state.clone();
         let stdout_task = tokio::spawn(async move {
-            stream_lines(
-                stdout,
-                app_clone1,
-                op_id_clone1,
-                false,
-                stdout_lines_clone,
-                seen_clone1,
-                last_output_clone1,
-            )
-            .await;
+            stream_lines(stdout, app_clone1, op_id_clone1, false, stdout_lines_clone, state_clone1)
+                .await;
         });
 
         let app_clone2 = app.clone();
         let op_id_clone2 = operation_id.clone();
         let stderr_lines_clone = stderr_lines.clone();
-        let seen_clone2 = seen_lines.clone();
-        let last_output_clone2 = last_output.clone();
-        let stderr_task = tokio::spawn(async move {
-            stream_lines(
-                stderr,
-                app_clone2,
-                op_id_clone2,
-                true,
-                stderr_lines_clone,
-                seen_clone2,
-                last_output_clone2,
-            )
-            .await;
+        let stderr_task = stderr.map(|stderr| {
+            tokio::spawn(async move {
+                stream_lines(stderr, app_clone2, op_id_clone2, true, stderr_lines_clone, state)
+                    .await;
+            })
         });
 
-        // Wait for process to complete or timeout due to inactivity
-        let timeout_secs = 30u64;
+        // Wait for process to complete or hit the inactivity / total duration limit
+        let started = Instant::now();
+        let mut last_heartbeat = started;
+        let estimated_ms = operation_stats::estimate_duration_ms(&args);
+        let mut resources = child.id().map(ResourceMonitor::new);
+        let mut last_resources = started;
+        let mut resource_warning: Option<String> = None;
         let mut interval = tokio::time::interval(Duration::from_secs(1));
         let status = loop {
             tokio::select! {
                 status = child.wait() => break status.context("Failed to wait for process")?,
                 _ = interval.tick() => {
                     let last = last_output.load(Ordering::Relaxed);
-                    if now_millis().saturating_sub(last) > timeout_secs * 1000 {
-                        let _ = child.kill().await;
-                        clear_current_pid();
-                        let error_msg = format!(
+                    let last_output_age_ms = now_millis().saturating_sub(last);
+                    if last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+                        last_heartbeat = Instant::now();
+                        let heartbeat = OperationHeartbeatEvent {
+                            operation_id: operation_id.clone(),
+                            elapsed_ms: started.elapsed().as_millis() as u64,
+                            last_output_age_ms,
+                            estimated_remaining_ms: estimated_ms.map(|estimate| {
+                                estimate.saturating_sub(started.elapsed().as_millis() as u64)
+                            }),
+                        };
+                        let _ = events::emit(&app, "operation:heartbeat", heartbeat);
+                    }
+                    if last_resources.elapsed() >= resource_monitor::SAMPLE_INTERVAL {
+                        last_resources = Instant::now();
+                        let sample = resources.as_mut().and_then(|monitor| {
+                            let sample = monitor.sample()?;
+                            Some((sample, monitor.warning(sample.memory_bytes)))
+                        });
+                        if let Some((sample, warning)) = sample {
+                            if let Some(message) = warning.as_ref() {
+                                if resource_warning.as_ref() != Some(message) {
+                                    log::warn!("{}", message);
+                                }
+                            }
+                            resource_warning.clone_from(&warning);
+                            let event = OperationResourcesEvent {
+                                operation_id: operation_id.clone(),
+                                cpu_percent: sample.cpu_percent,
+                                memory_bytes: sample.memory_bytes,
+                                peak_memory_bytes: sample.peak_memory_bytes,
+                                warning,
+                            };
+                            let _ = events::emit(&app, "operation:resources", event);
+                        }
+                    }
+                    let idle = limits
+                        .inactivity_timeout
+                        .filter(|timeout| last_output_age_ms > timeout.as_millis() as u64);
+                    let overran =
+                        limits.max_duration.filter(|limit| started.elapsed() > *limit);
+                    let timed_out = match (idle, overran) {
+                        (Some(timeout), _) => Some(format!(
                             "Antumbra process timed out after {}s without output",
-                            timeout_secs
-                        );
+                            timeout.as_secs()
+                        )),
+                        (None, Some(limit)) => Some(format!(
+                            "Antumbra process exceeded the maximum duration of {}s",
+                            limit.as_secs()
+                        )),
+                        (None, None) => None,
+                    };
+                    if let Some(error_msg) = timed_out {
+                        // Take down helpers antumbra spawned too, off the async runtime.
+                        // antumbra is reaped meanwhile: as a zombie it would keep its
+                        // group looking alive until every grace period ran out.
+                        let (killed, exited) = match registered_process(&operation_id) {
+                            Some(process) => {
+                                let kill = tokio::task::spawn_blocking(move || {
+                                    kill_process_tree(process)
+                                });
+                                let (killed, exited) = tokio::join!(kill, child.wait());
+                                let killed =
+                                    killed.map_err(anyhow::Error::from).and_then(|result| result);
+                                (killed, exited)
+                            }
+                            None => {
+                                let killed = child.kill().await.map_err(anyhow::Error::from);
+                                (killed, child.wait().await)
+                            }
+                        };
+                        if let Err(err) = killed {
+                            log::warn!("Failed to kill timed out antumbra process: {:#}", err);
+                        }
+                        let exit_code = exited.ok().and_then(|status| status.code());
+                        history.finish(false, exit_code);
+                        if let Some(progress) = final_state.flush_progress() {
+                            let _ = events::emit(&app, "operation:progress", progress);
+                        }
+                        drop(flush_task);
+                        batcher.flush(&app, &operation_id);
+                        finish_recording(recorder.as_deref(), None);
                         let complete_event = OperationCompleteEvent {
                             operation_id: operation_id.clone(),
                             success: false,
                             error: Some(error_msg.clone()),
                         };
-                        let _ = app.emit("operation:complete", complete_event);
+                        let _ = events::emit(&app, "operation:complete", complete_event);
                         anyhow::bail!(error_msg);
                     }
                 }
@@ -302,25 +833,35 @@ impl AntumbraExecutor {
         };
 
         // Wait for streaming tasks to complete
-        let _ = tokio::join!(stdout_task, stderr_task);
+        let _ = stdout_task.await;
+        if let Some(stderr_task) = stderr_task {
+            let _ = stderr_task.await;
+        }
+        if let Some(progress) = final_state.flush_progress() {
+            let _ = events::emit(&app, "operation:progress", progress);
+        }
+        drop(flush_task);
+        batcher.flush(&app, &operation_id);
+        finish_recording(recorder.as_deref(), status.code());
+        history.finish(status.success(), status.code());
 
         // Collect all output
         let stdout_output = match stdout_lines.lock() {
-            Ok(lines) => lines.join("\n"),
+            Ok(mut lines) => lines.finish(),
             Err(_) => {
                 log::warn!("Failed to lock stdout storage for join");
                 String::new()
             }
         };
         let stderr_output = match stderr_lines.lock() {
-            Ok(lines) => lines.join("\n"),
+            Ok(mut lines) => lines.finish(),
             Err(_) => {
                 log::warn!("Failed to lock stderr storage for join");
                 String::new()
             }
         };
-
-        clear_current_pid();
+        // A PTY has no separate stderr, so errors are classified from everything
+        let stderr_output = if interleaved { stdout_output.clone() } else { stderr_output };
 
         // Emit completion event
         let complete_event = OperationCompleteEvent {
@@ -329,18 +870,20 @@ impl AntumbraExecutor {
             error: if status.success() { None } else { Some(stderr_output.clone()) },
         };
 
-        app.emit("operation:complete", complete_event)
+        events::emit(&app, "operation:complete", complete_event)
             .context("Failed to emit completion event")?;
 
-        if !status.success() {
-            anyhow::bail!("Antumbra process failed: {}", stderr_output);
-        }
-
-        Ok(stdout_output)
+        Ok(StreamedOutput {
+            exit_code: status.code(),
+            success: status.success(),
+            cancelled: operation_registry::is_cancelled(&operation_id),
+            stdout: stdout_output,
+            stderr: stderr_output,
+        })
     }
 
+    /// Version probes aren't device operations, so they stay out of the history
     pub fn get_version(&self) -> Result<String> {
-        store_last_command(&self.binary_path, &self.working_dir, &["--version".to_string()]);
         let output = create_hidden_command(&self.binary_path, &["--version".to_string()])
             .current_dir(&self.working_dir)
             .stdout(Stdio::piped())
@@ -357,62 +900,284 @@ impl AntumbraExecutor {
 
 }
 
-fn set_current_pid(pid: Option<u32>) {
-    let store = CURRENT_PID.get_or_init(|| Mutex::new(None));
-    if let Ok(mut guard) = store.lock() {
-        *guard = pid;
+impl CommandExecutor for AntumbraExecutor {
+    fn execute_streaming(
+        &self,
+        app: AppHandle,
+        operation_id: String,
+        args: Vec<String>,
+    ) -> BoxFuture<'_, Result<String>> {
+        Box::pin(AntumbraExecutor::execute_streaming(self, app, operation_id, args))
+    }
+
+    fn get_version(&self) -> Result<String> {
+        AntumbraExecutor::get_version(self)
     }
 }
 
-fn clear_current_pid() {
-    set_current_pid(None);
+/// Pseudo-terminal pair handed to antumbra in PTY mode
+#[cfg(unix)]
+struct Pty {
+    master: std::fs::File,
+    slave: std::fs::File,
 }
 
-pub fn kill_current_process() -> Result<()> {
-    let store = CURRENT_PID.get_or_init(|| Mutex::new(None));
-    let pid = store.lock().ok().and_then(|guard| *guard);
+#[cfg(unix)]
+fn open_pty() -> Result<Pty> {
+    use std::ffi::CStr;
+    use std::os::fd::FromRawFd;
 
-    if let Some(pid) = pid {
-        log::info!("Cancelling antumbra process (pid: {})", pid);
-        #[cfg(unix)]
-        unsafe {
-            let result = libc::kill(pid as i32, libc::SIGKILL);
-            if result != 0 {
-                return Err(anyhow::anyhow!("Failed to kill process pid {}", pid));
+    // ptsname() returns a static buffer, serialize access to it
+    static PTSNAME_LOCK: Mutex<()> = Mutex::new(());
+
+    unsafe {
+        let master_fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+        if master_fd < 0 {
+            return Err(std::io::Error::last_os_error()).context("Failed to open pseudo-terminal");
+        }
+        let master = std::fs::File::from_raw_fd(master_fd);
+
+        if libc::grantpt(master_fd) != 0 || libc::unlockpt(master_fd) != 0 {
+            return Err(std::io::Error::last_os_error()).context("Failed to unlock pseudo-terminal");
+        }
+
+        let slave_path = {
+            let _guard = PTSNAME_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let name = libc::ptsname(master_fd);
+            if name.is_null() {
+                return Err(std::io::Error::last_os_error())
+                    .context("Failed to resolve pseudo-terminal name");
             }
+            CStr::from_ptr(name).to_owned()
+        };
+
+        let slave_fd = libc::open(slave_path.as_ptr(), libc::O_RDWR | libc::O_NOCTTY);
+        if slave_fd < 0 {
+            return Err(std::io::Error::last_os_error())
+                .context("Failed to open pseudo-terminal slave");
         }
+        let slave = std::fs::File::from_raw_fd(slave_fd);
+
+        // Progress bars size themselves from the terminal width
+        let size = libc::winsize { ws_row: 40, ws_col: 120, ws_xpixel: 0, ws_ypixel: 0 };
+        libc::ioctl(slave_fd, libc::TIOCSWINSZ, &size);
+
+        Ok(Pty { master, slave })
+    }
+}
+
+/// Keeps an operation's process in the registry until dropped
+struct ProcessRegistration {
+    operation_id: String,
+}
+
+impl Drop for ProcessRegistration {
+    fn drop(&mut self) {
+        let store = RUNNING_PROCESSES.get_or_init(|| Mutex::new(HashMap::new()));
+        let removed = store.lock().ok().and_then(|mut guard| guard.remove(&self.operation_id));
         #[cfg(windows)]
-        {
-            kill_windows_process(pid)?;
+        if let Some(RunningProcess { job: Some(job), .. }) = removed {
+            close_job(job);
         }
-        #[cfg(not(any(unix, windows)))]
-        {
-            return Err(anyhow::anyhow!("Process cancellation not supported on this platform"));
+        #[cfg(not(windows))]
+        let _ = removed;
+    }
+}
+
+fn register_process(operation_id: &str, child: &tokio::process::Child) -> ProcessRegistration {
+    if let Some(pid) = child.id() {
+        let process = RunningProcess {
+            pid,
+            #[cfg(windows)]
+            job: assign_to_job(child),
+        };
+        let store = RUNNING_PROCESSES.get_or_init(|| Mutex::new(HashMap::new()));
+        if let Ok(mut guard) = store.lock() {
+            guard.insert(operation_id.to_string(), process);
+        }
+    }
+    ProcessRegistration { operation_id: operation_id.to_string() }
+}
+
+fn registered_process(operation_id: &str) -> Option<RunningProcess> {
+    let store = RUNNING_PROCESSES.get_or_init(|| Mutex::new(HashMap::new()));
+    store.lock().ok().and_then(|guard| guard.get(operation_id).copied())
+}
+
+/// Kill the antumbra process tree running for `operation_id`.
+/// Returns false when no process is registered for it.
+pub fn kill_operation_process(operation_id: &str) -> Result<bool> {
+    match registered_process(operation_id) {
+        Some(process) => {
+            log::info!(
+                "Cancelling antumbra process (operation: {}, pid: {})",
+                operation_id,
+                process.pid
+            );
+            operation_registry::mark_cancelled(operation_id);
+            kill_process_tree(process)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Kill every registered antumbra process
+pub fn kill_all_processes() -> Result<()> {
+    let store = RUNNING_PROCESSES.get_or_init(|| Mutex::new(HashMap::new()));
+    let running: Vec<(String, RunningProcess)> = store
+        .lock()
+        .map(|guard| guard.iter().map(|(id, process)| (id.clone(), *process)).collect())
+        .unwrap_or_default();
+
+    let mut last_error = None;
+    for (operation_id, process) in running {
+        log::info!(
+            "Cancelling antumbra process (operation: {}, pid: {})",
+            operation_id,
+            process.pid
+        );
+        operation_registry::mark_cancelled(&operation_id);
+        if let Err(err) = kill_process_tree(process) {
+            log::warn!("{}", err);
+            last_error = Some(err);
+        }
+    }
+
+    match last_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Stop antumbra and anything it spawned, giving it a chance to close the USB
+/// session cleanly before it is killed outright. Blocks for up to the grace period.
+fn kill_process_tree(process: RunningProcess) -> Result<()> {
+    #[cfg(unix)]
+    {
+        terminate_unix_process_group(process.pid)
+    }
+    #[cfg(windows)]
+    {
+        kill_windows_process(process.pid, process.job)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        Err(anyhow::anyhow!(
+            "Process cancellation not supported on this platform (pid {})",
+            process.pid
+        ))
+    }
+}
+
+#[cfg(unix)]
+fn terminate_unix_process_group(pid: u32) -> Result<()> {
+    // antumbra leads its own process group, a negative pid addresses the whole group
+    let pgid = -(pid as libc::pid_t);
+    let is_alive = || unsafe { libc::kill(pgid, 0) == 0 };
+
+    let signals = [(libc::SIGINT, cancel_grace_period()), (libc::SIGTERM, TERM_GRACE_PERIOD)];
+    for (signal, grace) in signals {
+        if unsafe { libc::kill(pgid, signal) } != 0 {
+            // Nothing left to signal
+            return Ok(());
+        }
+        if wait_for_exit(is_alive, grace) {
+            log::info!("Antumbra process {} exited after signal {}", pid, signal);
+            return Ok(());
         }
     }
 
-    clear_current_pid();
+    log::warn!("Antumbra process {} ignored SIGINT/SIGTERM, sending SIGKILL", pid);
+    if unsafe { libc::kill(pgid, libc::SIGKILL) } != 0 && is_alive() {
+        return Err(anyhow::anyhow!("Failed to kill process group {}", pid));
+    }
     Ok(())
 }
 
+#[cfg(unix)]
+fn wait_for_exit(is_alive: impl Fn() -> bool, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if !is_alive() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    !is_alive()
+}
+
+/// Put a freshly spawned process into a new Job Object. Children inherit the
+/// job, so terminating it takes down the whole tree.
 #[cfg(windows)]
-fn kill_windows_process(pid: u32) -> Result<()> {
-    use winapi::um::processthreadsapi::{OpenProcess, TerminateProcess};
+fn assign_to_job(child: &tokio::process::Child) -> Option<usize> {
     use winapi::um::handleapi::CloseHandle;
-    use winapi::um::winnt::{PROCESS_TERMINATE, HANDLE};
+    use winapi::um::jobapi2::{AssignProcessToJobObject, CreateJobObjectW};
+
+    let process = child.raw_handle()?;
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
+        if job.is_null() {
+            log::warn!("Failed to create job object for antumbra process");
+            return None;
+        }
+        if AssignProcessToJobObject(job, process as _) == 0 {
+            log::warn!("Failed to assign antumbra process to job object");
+            CloseHandle(job);
+            return None;
+        }
+        Some(job as usize)
+    }
+}
+
+#[cfg(windows)]
+fn close_job(job: usize) {
+    unsafe {
+        winapi::um::handleapi::CloseHandle(job as winapi::um::winnt::HANDLE);
+    }
+}
+
+#[cfg(windows)]
+fn kill_windows_process(pid: u32, job: Option<usize>) -> Result<()> {
     use winapi::um::errhandlingapi::GetLastError;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::jobapi2::TerminateJobObject;
+    use winapi::um::processthreadsapi::{OpenProcess, TerminateProcess};
+    use winapi::um::synchapi::WaitForSingleObject;
+    use winapi::um::winbase::WAIT_OBJECT_0;
+    use winapi::um::winnt::{HANDLE, PROCESS_TERMINATE, SYNCHRONIZE};
 
     unsafe {
-        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        let handle = OpenProcess(PROCESS_TERMINATE | SYNCHRONIZE, 0, pid);
         if handle.is_null() {
             let error = GetLastError();
             return Err(anyhow::anyhow!("Failed to open process {}: Error code {}", pid, error));
         }
 
-        let result = TerminateProcess(handle as HANDLE, 1);
+        let grace_ms = cancel_grace_period().as_millis() as u32;
+        if send_ctrl_break(pid) && WaitForSingleObject(handle, grace_ms) == WAIT_OBJECT_0 {
+            CloseHandle(handle);
+            // Sweep up any helpers antumbra left behind
+            if let Some(job) = job {
+                TerminateJobObject(job as HANDLE, 1);
+            }
+            log::info!("Antumbra process {} exited after CTRL_BREAK", pid);
+            return Ok(());
+        }
+
+        log::warn!("Antumbra process {} did not exit gracefully, terminating", pid);
+        let result = match job {
+            Some(job) => TerminateJobObject(job as HANDLE, 1),
+            None => TerminateProcess(handle as HANDLE, 1),
+        };
         if result == 0 {
             let error = GetLastError();
-            return Err(anyhow::anyhow!("Failed to terminate process {}: Error code {}", pid, error));
+            CloseHandle(handle);
+            return Err(anyhow::anyhow!(
+                "Failed to terminate process {}: Error code {}",
+                pid,
+                error
+            ));
         }
 
         CloseHandle(handle);
@@ -421,6 +1186,38 @@ fn kill_windows_process(pid: u32) -> Result<()> {
     }
 }
 
+/// Serializes `send_ctrl_break`, since a process can attach to one console at a time
+#[cfg(windows)]
+static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Send CTRL_BREAK to antumbra's process group. The GUI has no console of its
+/// own, so it briefly attaches to antumbra's hidden one, ignoring the event
+/// itself while attached. Returns false when the event could not be sent.
+#[cfg(windows)]
+fn send_ctrl_break(pid: u32) -> bool {
+    use winapi::um::consoleapi::SetConsoleCtrlHandler;
+    use winapi::um::wincon::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT,
+    };
+
+    let _guard = CONSOLE_LOCK.lock();
+    unsafe {
+        FreeConsole();
+        if AttachConsole(pid) == 0 {
+            log::warn!("Failed to attach to the console of antumbra process {}", pid);
+            return false;
+        }
+        SetConsoleCtrlHandler(None, 1);
+        // antumbra is spawned in its own process group, so the break only reaches it
+        let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) != 0;
+        FreeConsole();
+        // The event is delivered asynchronously; stay immune until it has been
+        std::thread::sleep(Duration::from_millis(100));
+        SetConsoleCtrlHandler(None, 0);
+        sent
+    }
+}
+
 fn create_hidden_command(binary_path: &std::path::Path, args: &[String]) -> std::process::Command {
     #[cfg(windows)]
     {
@@ -439,31 +1236,66 @@ fn create_hidden_command(binary_path: &std::path::Path, args: &[String]) -> std:
     }
 }
 
-pub fn get_antumbra_updatable_path(app: &AppHandle) -> Result<PathBuf> {
-    let config_dir = app.path().app_config_dir().context("Failed to get config directory")?;
-    let bin_dir = config_dir.join("bin");
+/// Directory holding the managed antumbra binaries
+pub fn get_antumbra_bin_dir(_app: &AppHandle) -> Result<PathBuf> {
+    let bin_dir = crate::services::paths::bin_dir()?;
     std::fs::create_dir_all(&bin_dir).context("Failed to create antumbra bin directory")?;
+    Ok(bin_dir)
+}
+
+/// The managed antumbra binary in use: the active version when one is
+/// selected and installed, otherwise `bin/antumbra`
+pub fn get_antumbra_updatable_path(app: &AppHandle) -> Result<PathBuf> {
+    let bin_dir = get_antumbra_bin_dir(app)?;
+    let active = crate::services::config::load_settings()
+        .ok()
+        .and_then(|settings| settings.active_antumbra_version);
+    if let Some(version) = active {
+        let path = get_versioned_antumbra_path(app, &version)?;
+        if path.exists() {
+            return Ok(path);
+        }
+        log::warn!("Active antumbra version {} is not installed, using the default binary", version);
+    }
     Ok(bin_dir.join(binary_name()))
 }
 
+/// `bin/<version>/antumbra`, where side-by-side versions are installed
+pub fn get_versioned_antumbra_path(app: &AppHandle, version: &str) -> Result<PathBuf> {
+    let valid = !version.is_empty()
+        && version != "."
+        && version != ".."
+        && !version.contains(['/', '\\', ':']);
+    if !valid {
+        anyhow::bail!("Invalid antumbra version name: {:?}", version);
+    }
+    Ok(get_antumbra_bin_dir(app)?.join(version).join(binary_name()))
+}
+
+/// `bin/antumbra`, used when no version is active
+pub fn get_default_antumbra_path(app: &AppHandle) -> Result<PathBuf> {
+    Ok(get_antumbra_bin_dir(app)?.join(binary_name()))
+}
+
 pub fn get_last_command_info() -> Option<AntumbraCommandInfo> {
-    LAST_COMMAND.get_or_init(|| Mutex::new(None)).lock().ok().and_then(|guard| guard.clone())
+    command_history::last()
 }
 
 /// Sync detected antumbra version to configuration if config version is null
 pub fn sync_detected_version_to_config(_app: &AppHandle, detected_version: &str) -> Result<()> {
-    use crate::services::config::{load_settings, save_settings};
-    
-    // Load current settings
-    let mut settings = load_settings()
-        .context("Failed to load settings for version sync")?;
+    use crate::services::config::modify_settings;
     
     // Only update if version is None or different
-    if settings.antumbra_version.is_none() || 
-       settings.antumbra_version.as_ref() != Some(&detected_version.to_string()) {
+    let synced = modify_settings(|settings| {
+        if settings.antumbra_version.as_deref() == Some(detected_version) {
+            return Ok(false);
+        }
         settings.antumbra_version = Some(detected_version.to_string());
-        save_settings(&settings)
-            .context("Failed to save synced version to config")?;
+        Ok(true)
+    })
+    .context("Failed to sync detected version to config")?;
+    
+    if synced {
         log::info!("Synced detected antumbra version '{}' to configuration", detected_version);
     } else {
         log::debug!("Configuration already contains version {}, no sync needed", detected_version);
@@ -472,7 +1304,7 @@ pub fn sync_detected_version_to_config(_app: &AppHandle, detected_version: &str)
     Ok(())
 }
 
-fn get_antumbra_working_dir(app: &AppHandle, binary_path: &PathBuf) -> Result<PathBuf> {
+fn get_antumbra_working_dir(_app: &AppHandle, binary_path: &PathBuf) -> Result<PathBuf> {
     if let Some(parent) = binary_path.parent() {
         if parent.is_dir() {
             if is_dir_writable(parent) {
@@ -486,7 +1318,7 @@ fn get_antumbra_working_dir(app: &AppHandle, binary_path: &PathBuf) -> Result<Pa
         }
     }
 
-    let config_dir = app.path().app_config_dir().context("Failed to get config directory")?;
+    let config_dir = crate::services::paths::config_dir()?;
     std::fs::create_dir_all(&config_dir).context("Failed to create antumbra working directory")?;
     Ok(config_dir)
 }
@@ -502,25 +1334,54 @@ fn is_dir_writable(path: &std::path::Path) -> bool {
     false
 }
 
-fn store_last_command(binary_path: &PathBuf, working_dir: &PathBuf, args: &[String]) {
-    let info = AntumbraCommandInfo {
+fn store_last_command(
+    binary_path: &PathBuf,
+    working_dir: &PathBuf,
+    args: &[String],
+    operation_id: Option<&str>,
+) -> HistoryEntry {
+    command_history::record(AntumbraCommandInfo {
+        id: String::new(),
         command: binary_path.display().to_string(),
         args: args.to_vec(),
         working_dir: working_dir.display().to_string(),
         started_at: chrono::Utc::now().to_rfc3339(),
-    };
-
-    let store = LAST_COMMAND.get_or_init(|| Mutex::new(None));
-    if let Ok(mut guard) = store.lock() {
-        *guard = Some(info);
-    }
+        operation_id: operation_id.map(str::to_string),
+        finished_at: None,
+        duration_ms: None,
+        exit_code: None,
+        success: None,
+        bytes: None,
+        session_id: Some(structured_log::session_id().to_string()),
+    })
 }
 
 fn now_millis() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
 }
 
+/// The user-configured antumbra binary, if any. A configured path that does
+/// not exist is an error rather than a silent fallback to the managed binary.
+pub fn get_antumbra_path_override() -> Result<Option<PathBuf>> {
+    let configured = crate::services::config::load_settings()
+        .ok()
+        .and_then(|settings| settings.antumbra_path_override)
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    let Some(path) = configured.map(PathBuf::from) else {
+        return Ok(None);
+    };
+    if !path.is_file() {
+        anyhow::bail!("Configured antumbra binary not found at {:?}", path);
+    }
+    Ok(Some(path))
+}
+
 pub fn get_existing_antumbra_path(app: &AppHandle) -> Result<Option<PathBuf>> {
+    if let Some(path) = get_antumbra_path_override()? {
+        return Ok(Some(path));
+    }
+
     let updatable_path = get_antumbra_updatable_path(app)?;
     if updatable_path.exists() {
         return Ok(Some(updatable_path));
@@ -546,3 +1407,66 @@ fn get_antumbra_path(app: &AppHandle) -> Result<PathBuf> {
 
     anyhow::bail!("Antumbra binary not found at {:?}", fallback_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_ansi_sequences() {
+        assert_eq!(strip_ansi("\x1b[32mOK\x1b[0m done"), "OK done");
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1Gwriting"), "writing");
+        assert_eq!(strip_ansi("\x1b]0;antumbra\x07title"), "title");
+        assert_eq!(clean_line(b"\x1b[2K"), None);
+    }
+
+    #[test]
+    fn splits_chunks_on_newlines_and_carriage_returns() {
+        let mut splitter = LineSplitter::default();
+        let mut lines = Vec::new();
+        let mut collect = |line: &[u8], redraw: bool| {
+            lines.push((String::from_utf8_lossy(line).to_string(), redraw))
+        };
+        splitter.push(b"Reading 10%\rReading 2", &mut collect);
+        splitter.push(b"0%\r\nReading 30%\r", &mut collect);
+        splitter.push(b"\nDone\n\npar", &mut collect);
+        splitter.push(b"tial", &mut collect);
+        assert_eq!(splitter.pending, b"partial");
+        splitter.finish(&mut collect);
+        let lines: Vec<(&str, bool)> =
+            lines.iter().map(|(line, redraw)| (line.as_str(), *redraw)).collect();
+        assert_eq!(
+            lines,
+            [
+                ("Reading 10%", true),
+                ("Reading 20%", false),
+                ("Reading 30%", false),
+                ("Done", false),
+                ("partial", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn dedup_is_windowed_and_skips_stderr() {
+        let mut dedup = LineDeduplicator::new(Duration::from_secs(2), false);
+        let start = Instant::now();
+        assert!(dedup.should_emit("Retrying...", false, start));
+        assert!(!dedup.should_emit("Retrying...", false, start + Duration::from_secs(1)));
+        assert!(dedup.should_emit("Retrying...", false, start + Duration::from_secs(4)));
+        assert!(dedup.should_emit("error", true, start));
+        assert!(dedup.should_emit("error", true, start));
+
+        let mut disabled = LineDeduplicator::new(Duration::ZERO, true);
+        assert!(disabled.should_emit("line", false, start));
+        assert!(disabled.should_emit("line", false, start));
+    }
+
+    #[test]
+    fn keeps_lines_with_invalid_utf8() {
+        assert_eq!(
+            clean_line(b"Fehler: Ger\xe4t getrennt").as_deref(),
+            Some("Fehler: Ger\u{fffd}t getrennt")
+        );
+    }
+}
@@ -0,0 +1,452 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::FlashProgress;
+use std::time::{Duration, Instant};
+
+/// Weight of the newest sample in the smoothed speed; lower is steadier
+const SPEED_SMOOTHING: f64 = 0.3;
+
+/// Minimum time between two progress events when they are rate limited
+pub const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Progress values recognized in a single line of antumbra output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressSample {
+    pub percentage: Option<f32>,
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+}
+
+/// Turns antumbra's progress lines into `FlashProgress` events for one operation
+pub struct ProgressTracker {
+    operation_id: String,
+    operation: String,
+    partition_name: String,
+    last_percentage: Option<f32>,
+    /// Previous progress value and when it arrived, for the instantaneous speed
+    last_sample: Option<(Instant, u64)>,
+    /// Exponential moving average of the rate, in progress units per second
+    smoothed_rate: Option<f64>,
+    /// Throughput of earlier runs, used for the ETA until this run has its own
+    historical_rate: Option<f64>,
+    /// Typical duration of earlier runs, for the ETA of percentage-only output
+    historical_duration: Option<Duration>,
+    /// Events closer together than this are held back, except the final one
+    min_interval: Duration,
+    last_emitted: Option<Instant>,
+    /// Latest event held back by `min_interval`, until a newer one or `flush`
+    pending: Option<FlashProgress>,
+}
+
+impl ProgressTracker {
+    /// Derive the operation kind and partition from the antumbra arguments
+    pub fn new(operation_id: &str, args: &[String]) -> Self {
+        let command = args.first().map(String::as_str).unwrap_or_default();
+        let operation = match command {
+            "download" => "write",
+            "upload" => "read",
+            other => other,
+        };
+        let partition_name = match command {
+            "download" | "upload" | "erase" | "format" => args.get(1).cloned().unwrap_or_default(),
+            _ => String::new(),
+        };
+
+        Self {
+            operation_id: operation_id.to_string(),
+            operation: operation.to_string(),
+            partition_name,
+            last_percentage: None,
+            last_sample: None,
+            smoothed_rate: None,
+            historical_rate: None,
+            historical_duration: None,
+            min_interval: Duration::ZERO,
+            last_emitted: None,
+            pending: None,
+        }
+    }
+
+    /// Seed the ETA with the bytes/sec of earlier runs of the same subcommand
+    pub fn with_historical_rate(mut self, bytes_per_sec: Option<f64>) -> Self {
+        self.historical_rate = bytes_per_sec.filter(|rate| *rate > 0.0);
+        self
+    }
+
+    /// Seed the ETA of output without byte counts with the typical duration
+    pub fn with_historical_duration(mut self, duration: Option<Duration>) -> Self {
+        self.historical_duration = duration.filter(|duration| !duration.is_zero());
+        self
+    }
+
+    /// Emit at most one event per `interval`; samples in between still feed
+    /// the speed and ETA
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Return a progress event when `sample` carries new progress
+    pub fn update(&mut self, sample: ProgressSample) -> Option<FlashProgress> {
+        self.update_at(sample, Instant::now())
+    }
+
+    /// The last event held back by the rate limit, so a run ending below 100%
+    /// still reports where it stopped
+    pub fn flush(&mut self) -> Option<FlashProgress> {
+        self.pending.take()
+    }
+
+    fn update_at(&mut self, sample: ProgressSample, now: Instant) -> Option<FlashProgress> {
+        // Percentage-only output is tracked in per-mille for the rate and ETA,
+        // but never reported as byte counts
+        let (current, total) = match (sample.current, sample.total) {
+            (Some(current), Some(total)) if total > 0 => (current, total),
+            _ => {
+                let percentage = sample.percentage?;
+                ((percentage * 10.0).round() as u64, 1000)
+            }
+        };
+        let percentage = sample
+            .percentage
+            .unwrap_or((current as f64 / total as f64 * 100.0) as f32)
+            .clamp(0.0, 100.0);
+
+        if self.last_percentage == Some(percentage) {
+            return None;
+        }
+        self.last_percentage = Some(percentage);
+
+        let byte_counts = sample.current.is_some() && sample.total.is_some();
+        let instant_rate = self.sample_rate(current, now);
+        let remaining = total.saturating_sub(current) as f64;
+
+        // Without byte counts the rates are in per-mille, not bytes/sec
+        let (speed_bytes_per_sec, instant_speed_bytes_per_sec) =
+            if byte_counts { (self.smoothed_rate, instant_rate) } else { (None, None) };
+        let eta_secs = match self.smoothed_rate {
+            Some(rate) => Some((remaining / rate).round() as u64),
+            None if byte_counts => {
+                self.historical_rate.map(|rate| (remaining / rate).round() as u64)
+            }
+            None => self.historical_duration.map(|duration| {
+                (duration.as_secs_f64() * (1.0 - percentage as f64 / 100.0)).round() as u64
+            }),
+        };
+
+        let progress = FlashProgress {
+            current: byte_counts.then_some(current),
+            total: byte_counts.then_some(total),
+            percentage,
+            partition_name: self.partition_name.clone(),
+            operation: self.operation.clone(),
+            operation_id: Some(self.operation_id.clone()),
+            speed_bytes_per_sec,
+            instant_speed_bytes_per_sec,
+            eta_secs,
+        };
+
+        let finished = percentage >= 100.0;
+        let too_soon = self.last_emitted.is_some_and(|last| now - last < self.min_interval);
+        if too_soon && !finished {
+            self.pending = Some(progress);
+            return None;
+        }
+        self.last_emitted = Some(now);
+        self.pending = None;
+        Some(progress)
+    }
+
+    /// Rate since the previous sample, folded into `smoothed_rate`
+    fn sample_rate(&mut self, current: u64, now: Instant) -> Option<f64> {
+        let previous = self.last_sample.replace((now, current));
+        let (at, value) = previous?;
+        let elapsed = now.duration_since(at).as_secs_f64();
+        if elapsed <= 0.0 || current <= value {
+            return None;
+        }
+        let rate = (current - value) as f64 / elapsed;
+        self.smoothed_rate = Some(match self.smoothed_rate {
+            Some(smoothed) => SPEED_SMOOTHING * rate + (1.0 - SPEED_SMOOTHING) * smoothed,
+            None => rate,
+        });
+        Some(rate)
+    }
+}
+
+/// Recognize a percentage and/or a transferred/total pair ("12.5 MiB / 64 MiB",
+/// "0x1000/0x8000") in a line of antumbra output
+pub fn parse_progress_line(line: &str) -> Option<ProgressSample> {
+    let transfer = parse_transfer(line);
+    let percentage = parse_percentage(line, transfer.is_some());
+
+    if percentage.is_none() && transfer.is_none() {
+        return None;
+    }
+
+    Some(ProgressSample {
+        percentage,
+        current: transfer.map(|(current, _)| current),
+        total: transfer.map(|(_, total)| total),
+    })
+}
+
+/// The percentage ending an antumbra progress line: "Writing boot_a: 45%",
+/// "[=====>    ] 45.2 %" or "16.0 MiB / 64.0 MiB (25%)" after a transfer
+/// pair. A percentage inside a message ("write failed at 37%") is not one.
+fn parse_percentage(line: &str, has_transfer: bool) -> Option<f32> {
+    let line = line.trim_end();
+    let (line, parenthesized) = match line.strip_suffix(')') {
+        Some(inner) => (inner, true),
+        None => (line, false),
+    };
+    let number = line.strip_suffix('%')?.trim_end();
+    let start =
+        number.rfind(|c: char| !(c.is_ascii_digit() || c == '.')).map(|idx| idx + 1).unwrap_or(0);
+    let value =
+        number[start..].parse::<f32>().ok().filter(|value| (0.0..=100.0).contains(value))?;
+
+    let prefix = number[..start].trim_end();
+    let anchored = if parenthesized {
+        prefix
+            .strip_suffix('(')
+            .is_some_and(|before| has_transfer || before.trim_end().ends_with(']'))
+    } else {
+        prefix.is_empty() || prefix.ends_with(':') || prefix.ends_with(']')
+    };
+    anchored.then_some(value)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Size(f64, Option<u64>),
+    Slash,
+    Other,
+}
+
+fn parse_transfer(line: &str) -> Option<(u64, u64)> {
+    let spaced = line.replace('/', " / ");
+    let words: Vec<&str> = spaced
+        .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | ','))
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while idx < words.len() {
+        let word = words[idx];
+        if word == "/" {
+            tokens.push(Token::Slash);
+        } else if let Some(value) = parse_hex(word) {
+            tokens.push(Token::Size(value as f64, Some(1)));
+        } else if let Some((value, unit)) = split_number(word) {
+            match unit_multiplier(unit) {
+                Some(multiplier) => tokens.push(Token::Size(value, multiplier)),
+                None => tokens.push(Token::Other),
+            }
+            // A separate unit word ("12.5 MiB") belongs to the number before it
+            if unit.is_empty() {
+                if let Some(Some(multiplier)) = words.get(idx + 1).map(|next| unit_multiplier(next))
+                {
+                    tokens.pop();
+                    tokens.push(Token::Size(value, Some(multiplier)));
+                    idx += 1;
+                }
+            }
+        } else {
+            tokens.push(Token::Other);
+        }
+        idx += 1;
+    }
+
+    tokens.windows(3).find_map(|window| match window {
+        [Token::Size(current, current_unit), Token::Slash, Token::Size(total, Some(total_unit))] => {
+            // "12.5/64 MiB" uses the total's unit for both sides
+            let current_unit = current_unit.unwrap_or(*total_unit);
+            let current = (current * current_unit as f64) as u64;
+            let total = (total * *total_unit as f64) as u64;
+            (total > 0 && current <= total).then_some((current, total))
+        }
+        _ => None,
+    })
+}
+
+fn parse_hex(word: &str) -> Option<u64> {
+    let digits = word.strip_prefix("0x").or_else(|| word.strip_prefix("0X"))?;
+    u64::from_str_radix(digits, 16).ok()
+}
+
+fn split_number(word: &str) -> Option<(f64, &str)> {
+    let end = word.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(word.len());
+    let value = word[..end].parse::<f64>().ok()?;
+    Some((value, &word[end..]))
+}
+
+/// Byte multiplier for a size unit. `Some(None)` marks a bare number.
+fn unit_multiplier(unit: &str) -> Option<Option<u64>> {
+    let multiplier = match unit.to_ascii_lowercase().as_str() {
+        "" => return Some(None),
+        "b" | "bytes" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    Some(Some(multiplier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_percentage_only() {
+        let sample = parse_progress_line("Writing boot_a: 45%").unwrap();
+        assert_eq!(sample.percentage, Some(45.0));
+        assert_eq!(sample.current, None);
+        assert_eq!(parse_progress_line("[=====>    ] 45.2 %").unwrap().percentage, Some(45.2));
+        assert!(parse_progress_line("Found 3 partitions").is_none());
+    }
+
+    #[test]
+    fn ignores_percentages_inside_messages() {
+        assert!(parse_progress_line("write failed at 37%").is_none());
+        assert!(parse_progress_line("Battery at 15% (charge before flashing)").is_none());
+        assert!(parse_progress_line("error: 37% of blocks bad, aborting").is_none());
+    }
+
+    #[test]
+    fn percentage_only_output_has_no_byte_counts() {
+        let args = vec!["read-all".to_string()];
+        let mut tracker = ProgressTracker::new("op", &args);
+        let sample = ProgressSample { percentage: Some(40.0), current: None, total: None };
+        let progress = tracker.update_at(sample, Instant::now()).unwrap();
+        assert_eq!(progress.percentage, 40.0);
+        assert_eq!((progress.current, progress.total), (None, None));
+    }
+
+    #[test]
+    fn parses_transferred_bytes() {
+        let sample = parse_progress_line("[=====>    ] 16.0 MiB / 64.0 MiB (25%)").unwrap();
+        assert_eq!(sample.percentage, Some(25.0));
+        assert_eq!(sample.current, Some(16 * 1024 * 1024));
+        assert_eq!(sample.total, Some(64 * 1024 * 1024));
+
+        let sample = parse_progress_line("Read 0x1000/0x4000").unwrap();
+        assert_eq!((sample.current, sample.total), (Some(0x1000), Some(0x4000)));
+    }
+
+    #[test]
+    fn tracker_reports_speed_and_eta() {
+        let args = vec!["upload".to_string(), "boot_a".to_string()];
+        let mut tracker = ProgressTracker::new("op", &args);
+        let start = Instant::now();
+        let sample = |current| ProgressSample {
+            percentage: None,
+            current: Some(current),
+            total: Some(4000),
+        };
+
+        let first = tracker.update_at(sample(0), start).unwrap();
+        assert_eq!(first.operation, "read");
+        assert_eq!(first.partition_name, "boot_a");
+        assert_eq!(first.eta_secs, None);
+
+        let second = tracker.update_at(sample(1000), start + Duration::from_secs(1)).unwrap();
+        assert_eq!(second.percentage, 25.0);
+        assert_eq!(second.speed_bytes_per_sec, Some(1000.0));
+        assert_eq!(second.eta_secs, Some(3));
+
+        assert!(tracker.update_at(sample(1000), start + Duration::from_secs(2)).is_none());
+    }
+
+    #[test]
+    fn speed_is_smoothed_across_samples() {
+        let args = vec!["upload".to_string(), "boot_a".to_string()];
+        let mut tracker = ProgressTracker::new("op", &args);
+        let start = Instant::now();
+        let sample = |current| ProgressSample {
+            percentage: None,
+            current: Some(current),
+            total: Some(10_000),
+        };
+
+        tracker.update_at(sample(0), start).unwrap();
+        tracker.update_at(sample(1000), start + Duration::from_secs(1)).unwrap();
+        let burst = tracker.update_at(sample(3000), start + Duration::from_secs(2)).unwrap();
+        assert_eq!(burst.instant_speed_bytes_per_sec, Some(2000.0));
+        assert!((burst.speed_bytes_per_sec.unwrap() - 1300.0).abs() < 1e-6);
+        assert_eq!(burst.eta_secs, Some(5));
+    }
+
+    #[test]
+    fn historical_duration_estimates_percentage_only_eta() {
+        let args = vec!["read-all".to_string()];
+        let mut tracker = ProgressTracker::new("op", &args)
+            .with_historical_duration(Some(Duration::from_secs(100)));
+        let start = Instant::now();
+        let sample = |percentage| ProgressSample {
+            percentage: Some(percentage),
+            current: None,
+            total: None,
+        };
+
+        assert_eq!(tracker.update_at(sample(0.0), start).unwrap().eta_secs, Some(100));
+        let progress = tracker.update_at(sample(10.0), start + Duration::from_secs(10)).unwrap();
+        assert_eq!(progress.speed_bytes_per_sec, None);
+        assert_eq!(progress.eta_secs, Some(90));
+    }
+
+    #[test]
+    fn historical_rate_seeds_first_eta() {
+        let args = vec!["download".to_string(), "boot_a".to_string()];
+        let mut tracker = ProgressTracker::new("op", &args).with_historical_rate(Some(500.0));
+        let sample = ProgressSample { percentage: None, current: Some(0), total: Some(4000) };
+
+        let first = tracker.update_at(sample, Instant::now()).unwrap();
+        assert_eq!(first.speed_bytes_per_sec, None);
+        assert_eq!(first.eta_secs, Some(8));
+    }
+
+    #[test]
+    fn rate_limit_drops_intermediate_events_but_not_the_last() {
+        let args = vec!["download".to_string(), "boot_a".to_string()];
+        let mut tracker =
+            ProgressTracker::new("op", &args).with_min_interval(Duration::from_millis(100));
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let sample = |percentage| ProgressSample {
+            percentage: Some(percentage),
+            current: None,
+            total: None,
+        };
+
+        assert!(tracker.update_at(sample(1.0), at(0)).is_some());
+        assert!(tracker.update_at(sample(2.0), at(50)).is_none());
+        assert!(tracker.update_at(sample(3.0), at(120)).is_some());
+        assert_eq!(tracker.update_at(sample(100.0), at(130)).unwrap().percentage, 100.0);
+        assert!(tracker.flush().is_none());
+    }
+
+    #[test]
+    fn flush_returns_the_last_held_back_event() {
+        let args = vec!["upload".to_string(), "boot_a".to_string()];
+        let mut tracker =
+            ProgressTracker::new("op", &args).with_min_interval(Duration::from_millis(100));
+        let start = Instant::now();
+        let sample = |percentage| ProgressSample {
+            percentage: Some(percentage),
+            current: None,
+            total: None,
+        };
+
+        assert!(tracker.update_at(sample(10.0), start).is_some());
+        assert!(tracker.update_at(sample(37.0), start + Duration::from_millis(40)).is_none());
+        assert_eq!(tracker.flush().unwrap().percentage, 37.0);
+        assert!(tracker.flush().is_none());
+    }
+}
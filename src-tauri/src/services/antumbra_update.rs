@@ -3,20 +3,29 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use crate::services::antumbra::{get_antumbra_updatable_path, get_existing_antumbra_path};
-use crate::services::config::{load_settings, save_settings};
+use crate::services::antumbra::{
+    get_antumbra_bin_dir, get_antumbra_path_override, get_antumbra_updatable_path,
+    get_default_antumbra_path, get_existing_antumbra_path, get_versioned_antumbra_path,
+};
+use crate::services::config::{load_settings, modify_settings, BinaryChecksum, UpdateChannel};
+use crate::services::events;
+use crate::services::http;
+use crate::services::i18n::{t, t_with};
 use anyhow::{Context, Result};
 use log::warn;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
-use std::io::Write as StdWrite;
-use std::path::Path;
-use std::time::{Duration, Instant};
+use std::io::{Read as StdRead, Write as StdWrite};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use tauri::AppHandle;
-use tauri::Emitter;
 use tokio::fs::File;
 use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::Notify;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AntumbraUpdateInfo {
@@ -29,6 +38,11 @@ pub struct AntumbraUpdateInfo {
     pub asset_url: Option<String>,
     pub checksum: Option<String>,
     pub message: Option<String>,
+    /// Release tag the asset belongs to, so an install can target exactly it
+    #[serde(default)]
+    pub latest_tag: Option<String>,
+    #[serde(default)]
+    pub signature_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -50,7 +64,7 @@ pub struct DownloadProgress {
 
 impl DownloadProgress {
     pub fn emit(&self, app: &AppHandle) {
-        let _ = app.emit("antumbra-download-progress", self);
+        let _ = events::emit(app, "antumbra-download-progress", self);
     }
 }
 
@@ -63,10 +77,55 @@ struct ReleaseAsset {
 #[derive(Debug, Deserialize)]
 struct ReleaseInfo {
     tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
     assets: Vec<ReleaseAsset>,
 }
 
-pub async fn check_for_updates(app: &AppHandle) -> Result<AntumbraUpdateInfo> {
+/// A published antumbra release that can be installed by tag
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AntumbraRelease {
+    pub tag: String,
+    pub name: Option<String>,
+    pub published_at: Option<String>,
+    pub prerelease: bool,
+    /// Whether the release ships a binary for this platform
+    pub has_asset: bool,
+}
+
+
+const RELEASES_API: &str = "https://api.github.com/repos/rdndds/penumbra/releases";
+
+/// minisign public key (base64) that antumbra release assets are signed with,
+/// embedded at build time. Without it signatures can't be checked.
+const RELEASE_SIGNING_KEY: Option<&str> = option_env!("ANTUMBRA_SIGNING_KEY");
+
+/// How long a user-supplied binary gets to answer `--version`
+const VERSION_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Compare the installed antumbra with the latest release. Unless `force` is
+/// set, a release response fetched within the check interval is reused.
+pub async fn check_for_updates(app: &AppHandle, force: bool) -> Result<AntumbraUpdateInfo> {
+    if let Some(path) = get_antumbra_path_override()? {
+        return Ok(AntumbraUpdateInfo {
+            installed_version: load_settings().ok().and_then(|settings| settings.antumbra_version),
+            installed_path: Some(path.display().to_string()),
+            latest_version: None,
+            update_available: false,
+            supported: false,
+            asset_name: None,
+            asset_url: None,
+            checksum: None,
+            message: Some(t("update.custom_binary")),
+            latest_tag: None,
+            signature_url: None,
+        });
+    }
+
     let installed_path = get_existing_antumbra_path(app)?;
     
     // Try to get version from config first
@@ -80,10 +139,10 @@ pub async fn check_for_updates(app: &AppHandle) -> Result<AntumbraUpdateInfo> {
         match get_installed_version(app).await {
             Ok(version) => {
                 // Try to save this version to config for future checks
-                if let Ok(mut settings) = load_settings() {
+                let _ = modify_settings(|settings| {
                     settings.antumbra_version = Some(version.clone());
-                    let _ = save_settings(&settings);
-                }
+                    Ok(())
+                });
                 Some(version)
             }
             Err(_) => None,
@@ -93,10 +152,11 @@ pub async fn check_for_updates(app: &AppHandle) -> Result<AntumbraUpdateInfo> {
     };
     
     let installed_checksum = match &installed_path {
-        Some(path) => compute_file_checksum(path).ok(),
+        Some(path) => installed_checksum(path).ok(),
         None => None,
     };
-    let latest = fetch_latest_release().await;
+    let max_age = if force { Duration::ZERO } else { update_check_interval() };
+    let latest = fetch_latest_release(max_age).await;
 
     match latest {
         Ok(release) => {
@@ -108,13 +168,15 @@ pub async fn check_for_updates(app: &AppHandle) -> Result<AntumbraUpdateInfo> {
                         installed_path: installed_path
                             .as_ref()
                             .map(|path| path.display().to_string()),
-                        latest_version: Some(release.tag_name),
+                        latest_version: Some(release.tag_name.clone()),
                         update_available: false,
                         supported: false,
                         asset_name: None,
                         asset_url: None,
                         checksum: None,
                         message: Some(err.to_string()),
+                        latest_tag: Some(release.tag_name),
+                        signature_url: None,
                     });
                 }
             };
@@ -153,16 +215,19 @@ pub async fn check_for_updates(app: &AppHandle) -> Result<AntumbraUpdateInfo> {
                 }
             };
 
+            let signature_url = signature_url(&release, &asset_name);
             Ok(AntumbraUpdateInfo {
                 installed_version,
                 installed_path: installed_path.as_ref().map(|path| path.display().to_string()),
-                latest_version: latest_version.or(Some(release.tag_name)),
+                latest_version: latest_version.or(Some(release.tag_name.clone())),
                 update_available,
                 supported: true,
                 asset_name: Some(asset_name),
                 asset_url: Some(asset_url),
                 checksum: Some(checksum),
                 message: None,
+                latest_tag: Some(release.tag_name),
+                signature_url,
             })
         }
         Err(err) => Ok(AntumbraUpdateInfo {
@@ -175,51 +240,392 @@ pub async fn check_for_updates(app: &AppHandle) -> Result<AntumbraUpdateInfo> {
             asset_url: None,
             checksum: None,
             message: Some(err.to_string()),
+            latest_tag: None,
+            signature_url: None,
         }),
     }
 }
 
-pub async fn download_and_install(app: &AppHandle) -> Result<AntumbraUpdateResult> {
-    download_and_install_with_progress(app).await
+/// The exact release asset an install downloads
+struct InstallTarget {
+    tag: String,
+    asset_name: String,
+    asset_url: String,
+    checksum: String,
+    signature_url: Option<String>,
 }
 
-pub async fn download_and_install_with_progress(app: &AppHandle) -> Result<AntumbraUpdateResult> {
+impl InstallTarget {
+    async fn from_release(release: &ReleaseInfo) -> Result<Self> {
+        let (asset_name, asset_url, checksum) = find_asset_and_checksum(release).await?;
+        let signature_url = signature_url(release, &asset_name);
+        Ok(Self { tag: release.tag_name.clone(), asset_name, asset_url, checksum, signature_url })
+    }
+
+    /// Reuse the asset from an earlier update check instead of asking GitHub
+    /// again, so a release published in between can't be installed unseen
+    fn from_checked(info: &AntumbraUpdateInfo) -> Result<Self> {
+        let (Some(tag), Some(asset_name), Some(asset_url), Some(checksum)) =
+            (&info.latest_tag, &info.asset_name, &info.asset_url, &info.checksum)
+        else {
+            anyhow::bail!("The approved update is incomplete, check for updates again");
+        };
+        if !asset_candidates()?.contains(&asset_name.as_str()) {
+            anyhow::bail!("Release asset {} is not for this platform", asset_name);
+        }
+        if !asset_url.starts_with("https://") {
+            anyhow::bail!("Refusing to download release asset over {}", asset_url);
+        }
+        Ok(Self {
+            tag: tag.clone(),
+            asset_name: asset_name.clone(),
+            asset_url: asset_url.clone(),
+            checksum: checksum.clone(),
+            signature_url: info.signature_url.clone(),
+        })
+    }
+}
+
+/// Install the update the user approved when given, otherwise the release
+/// tagged `tag`, or the latest release when both are `None`
+pub async fn download_and_install(
+    app: &AppHandle,
+    tag: Option<&str>,
+    approved: Option<&AntumbraUpdateInfo>,
+) -> Result<AntumbraUpdateResult> {
+    download_and_install_with_progress(app, tag, approved).await
+}
+
+pub async fn download_and_install_with_progress(
+    app: &AppHandle,
+    tag: Option<&str>,
+    approved: Option<&AntumbraUpdateInfo>,
+) -> Result<AntumbraUpdateResult> {
+    if get_antumbra_path_override()?.is_some() {
+        anyhow::bail!(t("update.custom_binary"));
+    }
+
     // Fetch release info
-    emit_progress(app, "fetching", 0, 0, 1, 3, "Fetching release information...");
-    let release = fetch_latest_release().await?;
-    let (_asset_name, asset_url, checksum) = find_asset_and_checksum(&release).await?;
-    
-    let target_path = get_antumbra_updatable_path(app)?;
+    emit_progress(app, "fetching", 0, 0, 1, 3, &t("update.fetching"));
+    let target = match (approved, tag) {
+        (Some(info), _) => InstallTarget::from_checked(info)?,
+        (None, Some(tag)) => InstallTarget::from_release(&fetch_release_by_tag(tag).await?).await?,
+        (None, None) => {
+            let release = fetch_latest_release(update_check_interval()).await?;
+            InstallTarget::from_release(&release).await?
+        }
+    };
+    let mirrors = mirror_urls(&target.tag, &target.asset_name);
+
+    // Each release gets its own directory so older versions stay installed
+    let target_path = get_versioned_antumbra_path(app, &target.tag)?;
     if let Some(parent) = target_path.parent() {
         fs::create_dir_all(parent).context("Failed to create antumbra bin directory")?;
     }
 
     // Download directly to temp file with retry logic and progress
     let temp_path = target_path.with_extension("download");
-    download_file_with_retry_and_progress(app, &asset_url, &temp_path, &checksum).await?;
+    let download = CancelToken::register();
+    let downloaded = download_file_with_retry_and_progress(
+        app,
+        &target.asset_url,
+        &mirrors,
+        &temp_path,
+        &target.checksum,
+        &download.token,
+    )
+    .await;
+    if let Err(err) = downloaded {
+        if err.is::<DownloadCancelled>() {
+            cleanup_temp_file(&temp_path);
+            emit_progress(app, "cancelled", 0, 0, 1, 3, &t("update.cancelled"));
+        }
+        return Err(err);
+    }
+
+    emit_progress(app, "verifying", 0, 0, 1, 3, &t("update.verifying_signature"));
+    let verified = verify_release_signature(
+        &target.tag,
+        &target.asset_name,
+        target.signature_url.as_deref(),
+        &temp_path,
+    )
+    .await;
+    if let Err(err) = verified {
+        cleanup_temp_file(&temp_path);
+        return Err(err);
+    }
 
     // Replace the old binary with the new one
-    emit_progress(app, "replacing", 0, 0, 1, 3, "Replacing binary...");
-    safe_replace_binary(&target_path, &temp_path).await?;
+    emit_progress(app, "replacing", 0, 0, 1, 3, &t("update.replacing"));
+    install_binary(&target_path, &temp_path, Some(&target.tag)).await?;
+    set_active_version(app, Some(&target.tag))?;
+
+    emit_progress(app, "completed", 0, 0, 1, 3, &t("update.completed"));
+    Ok(AntumbraUpdateResult { version: target.tag, path: target_path.display().to_string() })
+}
+
+/// Move a verified binary into place, keeping the replaced one as
+/// `antumbra.bak` and recording `version` as the installed version.
+/// An unknown version is cleared so the next update check detects it.
+async fn install_binary(target_path: &Path, temp_path: &Path, version: Option<&str>) -> Result<()> {
+    let previous_version = load_settings().ok().and_then(|settings| settings.antumbra_version);
+    let backed_up = safe_replace_binary(target_path, temp_path).await?;
 
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&target_path)?.permissions();
+        let mut perms = fs::metadata(target_path)?.permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&target_path, perms)?;
+        fs::set_permissions(target_path, perms)?;
+    }
+
+    #[cfg(target_os = "macos")]
+    remove_quarantine(target_path);
+
+    // Remember which version the backup is so a rollback can restore it
+    if backed_up {
+        let version_path = backup_version_path(target_path);
+        let result = match &previous_version {
+            Some(previous) => fs::write(&version_path, previous),
+            None => fs::remove_file(&version_path).or(Ok(())),
+        };
+        if let Err(e) = result {
+            warn!("Failed to record backup antumbra version: {}", e);
+        }
     }
 
     // Save the new version to config
-    if let Ok(mut settings) = load_settings() {
-        settings.antumbra_version = Some(release.tag_name.clone());
-        if let Err(e) = save_settings(&settings) {
+    let saved = modify_settings(|settings| {
+        settings.antumbra_version = version.map(str::to_string);
+        Ok(())
+    });
+    if let Err(e) = saved {
+        warn!("Failed to save antumbra version to config: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Install a user-supplied antumbra binary, for machines that can't reach GitHub.
+/// When `expected_checksum` is given the file must match it (SHA-256, hex).
+pub async fn install_from_file(
+    app: &AppHandle,
+    source_path: &Path,
+    expected_checksum: Option<&str>,
+) -> Result<AntumbraUpdateResult> {
+    if !source_path.is_file() {
+        anyhow::bail!("Antumbra binary not found at {:?}", source_path);
+    }
+
+    // Local binaries have no trustworthy version name, so they go to the default slot
+    let target_path = get_default_antumbra_path(app)?;
+    let temp_path = target_path.with_extension("download");
+    fs::copy(source_path, &temp_path)
+        .with_context(|| format!("Failed to copy antumbra binary from {:?}", source_path))?;
+
+    if let Some(expected) = expected_checksum.map(str::trim).filter(|c| !c.is_empty()) {
+        if !verify_file_checksum(&temp_path, expected)? {
+            cleanup_temp_file(&temp_path);
+            anyhow::bail!("Checksum mismatch: the file does not match the provided SHA-256");
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&temp_path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&temp_path, perms)?;
+    }
+
+    #[cfg(target_os = "macos")]
+    remove_quarantine(&temp_path);
+
+    let version = probe_version(&temp_path).await;
+    if version.is_none() {
+        warn!("Could not detect the version of {:?}", source_path);
+    }
+
+    install_binary(&target_path, &temp_path, version.as_deref()).await?;
+    // Only switch to the default slot once it holds the new binary. Switching
+    // forgets the default binary's version, so the detected one is saved again.
+    set_active_version(app, None)?;
+    if let Some(version) = &version {
+        let saved = modify_settings(|settings| {
+            settings.antumbra_version = Some(version.clone());
+            Ok(())
+        });
+        if let Err(e) = saved {
             warn!("Failed to save antumbra version to config: {}", e);
         }
     }
 
-    emit_progress(app, "completed", 0, 0, 1, 3, "Update completed successfully!");
-    Ok(AntumbraUpdateResult { version: release.tag_name, path: target_path.display().to_string() })
+    let version = version.unwrap_or_else(|| "unknown".to_string());
+    log::info!("Installed antumbra {} from {:?}", version, source_path);
+    Ok(AntumbraUpdateResult { version, path: target_path.display().to_string() })
+}
+
+/// Run `path --version`, giving up after `VERSION_PROBE_TIMEOUT` so a binary
+/// that hangs (or waits for a device) can't stall the install
+async fn probe_version(path: &Path) -> Option<String> {
+    let output = tokio::process::Command::new(path)
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    match tokio::time::timeout(VERSION_PROBE_TIMEOUT, output).await {
+        Ok(Ok(output)) => {
+            Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .filter(|version| !version.is_empty())
+        }
+        Ok(Err(e)) => {
+            warn!("Failed to run {:?} --version: {}", path, e);
+            None
+        }
+        Err(_) => {
+            warn!("{:?} --version did not finish within {:?}", path, VERSION_PROBE_TIMEOUT);
+            None
+        }
+    }
+}
+
+/// Gatekeeper refuses to run quarantined binaries that aren't notarized, which
+/// is the case for antumbra copied from a browser download
+#[cfg(target_os = "macos")]
+fn remove_quarantine(path: &Path) {
+    let result =
+        std::process::Command::new("xattr").args(["-d", "com.apple.quarantine"]).arg(path).output();
+    match result {
+        // xattr fails when the attribute isn't set, which is fine
+        Ok(output) if !output.status.success() => {
+            log::debug!("xattr: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(_) => log::info!("Removed quarantine attribute from {:?}", path),
+        Err(e) => warn!("Failed to run xattr on {:?}: {}", path, e),
+    }
+}
+
+/// An antumbra version installed under `bin/<version>/`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstalledAntumbraVersion {
+    pub version: String,
+    pub path: String,
+    pub active: bool,
+}
+
+/// Versions installed side by side, sorted by name
+pub fn list_installed_versions(app: &AppHandle) -> Result<Vec<InstalledAntumbraVersion>> {
+    let bin_dir = get_antumbra_bin_dir(app)?;
+    let active = load_settings().ok().and_then(|settings| settings.active_antumbra_version);
+
+    let mut versions = Vec::new();
+    for entry in fs::read_dir(&bin_dir).context("Failed to read antumbra bin directory")? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let version = entry.file_name().to_string_lossy().to_string();
+        let Ok(path) = get_versioned_antumbra_path(app, &version) else {
+            continue;
+        };
+        if path.exists() {
+            versions.push(InstalledAntumbraVersion {
+                active: active.as_deref() == Some(version.as_str()),
+                path: path.display().to_string(),
+                version,
+            });
+        }
+    }
+    versions.sort_by(|a, b| a.version.cmp(&b.version));
+    Ok(versions)
+}
+
+/// Switch to an installed version, or back to `bin/antumbra` with `None`.
+/// The version being replaced is remembered for `rollback`.
+pub fn set_active_version(app: &AppHandle, version: Option<&str>) -> Result<()> {
+    if let Some(version) = version {
+        let path = get_versioned_antumbra_path(app, version)?;
+        if !path.exists() {
+            anyhow::bail!("Antumbra {} is not installed", version);
+        }
+    }
+
+    let previous_path = previous_version_path(app)?;
+    let switched = modify_settings(|settings| {
+        if settings.active_antumbra_version.as_deref() == version {
+            return Ok(false);
+        }
+
+        let previous = settings.active_antumbra_version.clone().unwrap_or_default();
+        if let Err(e) = fs::write(&previous_path, previous) {
+            warn!("Failed to remember the previous antumbra version: {}", e);
+        }
+
+        settings.active_antumbra_version = version.map(str::to_string);
+        // The default binary's version is unknown here; the next check detects it
+        settings.antumbra_version = version.map(str::to_string);
+        Ok(true)
+    })
+    .context("Failed to save the active antumbra version")?;
+    if switched {
+        log::info!("Active antumbra version: {}", version.unwrap_or("default"));
+    }
+    Ok(())
+}
+
+fn previous_version_path(app: &AppHandle) -> Result<PathBuf> {
+    Ok(get_antumbra_bin_dir(app)?.join("previous_version"))
+}
+
+fn backup_path(target_path: &Path) -> PathBuf {
+    target_path.with_extension("bak")
+}
+
+fn backup_version_path(target_path: &Path) -> PathBuf {
+    target_path.with_extension("bak.version")
+}
+
+/// Go back to the antumbra used before the last update: the previously active
+/// version when it is still installed, otherwise the binary the update replaced.
+/// Either way the current binary can be restored by rolling back again.
+pub async fn rollback(app: &AppHandle) -> Result<AntumbraUpdateResult> {
+    let previous = fs::read_to_string(previous_version_path(app)?)
+        .ok()
+        .map(|previous| previous.trim().to_string());
+    if let Some(previous) = previous {
+        let version = (!previous.is_empty()).then_some(previous);
+        let path = match &version {
+            Some(version) => get_versioned_antumbra_path(app, version)?,
+            None => get_default_antumbra_path(app)?,
+        };
+        if path.exists() {
+            set_active_version(app, version.as_deref())?;
+            let version = version.unwrap_or_else(|| "default".to_string());
+            log::info!("Rolled antumbra back to {}", version);
+            return Ok(AntumbraUpdateResult { version, path: path.display().to_string() });
+        }
+    }
+
+    let target_path = get_antumbra_updatable_path(app)?;
+    let backup = backup_path(&target_path);
+    if !backup.exists() {
+        anyhow::bail!("No previous antumbra version to roll back to");
+    }
+
+    let version = fs::read_to_string(backup_version_path(&target_path))
+        .ok()
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty());
+
+    // Stage a copy so the backup survives until the swap succeeds
+    let temp_path = target_path.with_extension("download");
+    fs::copy(&backup, &temp_path).context("Failed to stage previous antumbra binary")?;
+    install_binary(&target_path, &temp_path, version.as_deref()).await?;
+
+    let version = version.unwrap_or_else(|| "unknown".to_string());
+    log::info!("Rolled antumbra back to {}", version);
+    Ok(AntumbraUpdateResult { version, path: target_path.display().to_string() })
 }
 
 fn emit_progress(app: &AppHandle, status: &str, bytes: u64, total: u64, attempt: u32, max: u32, message: &str) {
@@ -248,10 +654,132 @@ fn emit_retry_message(app: &AppHandle, attempt: u32, max: u32, delay_ms: u64, re
         0,
         attempt,
         max,
-        &format!("{}. Retrying in {}s...", reason, delay_ms / 1000),
+        &t_with("update.retrying", &[("reason", &reason), ("seconds", &(delay_ms / 1000))]),
     );
 }
 
+/// Returned when the user aborts an update download
+#[derive(Debug, thiserror::Error)]
+#[error("Download cancelled")]
+pub struct DownloadCancelled;
+
+/// The cancellation flag of the download in progress, if any
+static ACTIVE_DOWNLOAD: OnceLock<Mutex<Option<CancelToken>>> = OnceLock::new();
+
+/// Lets `cancel_download` stop a running update download
+#[derive(Clone, Default)]
+struct CancelToken(Arc<CancelState>);
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    /// Create a token and make it the one `cancel_download` targets
+    fn register() -> ActiveDownload {
+        let token = Self::default();
+        if let Ok(mut active) = ACTIVE_DOWNLOAD.get_or_init(|| Mutex::new(None)).lock() {
+            *active = Some(token.clone());
+        }
+        ActiveDownload { token }
+    }
+
+    fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.0.cancelled.load(Ordering::SeqCst) {
+            return Err(DownloadCancelled.into());
+        }
+        Ok(())
+    }
+
+    /// Resolves once the token is cancelled
+    async fn cancelled(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            if self.0.cancelled.load(Ordering::SeqCst) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Sleep for `duration`, waking early with an error on cancellation
+    async fn sleep(&self, duration: Duration) -> Result<()> {
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => self.check(),
+            _ = self.cancelled() => Err(DownloadCancelled.into()),
+        }
+    }
+}
+
+/// Clears the registered token when the download ends
+struct ActiveDownload {
+    token: CancelToken,
+}
+
+impl Drop for ActiveDownload {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE_DOWNLOAD.get_or_init(|| Mutex::new(None)).lock() {
+            if active.as_ref().is_some_and(|token| Arc::ptr_eq(&token.0, &self.token.0)) {
+                *active = None;
+            }
+        }
+    }
+}
+
+/// Abort the update download in progress. Returns false when none is running.
+pub fn cancel_download() -> bool {
+    let active = ACTIVE_DOWNLOAD
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .ok()
+        .and_then(|mut active| active.take());
+    match active {
+        Some(token) => {
+            log::info!("Cancelling antumbra download");
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Keeps the average download rate under the configured cap by pausing
+/// between chunks, so an update doesn't saturate a tethered connection
+struct Throttle {
+    bytes_per_sec: u64,
+    started: Instant,
+    bytes: u64,
+}
+
+impl Throttle {
+    fn from_settings() -> Option<Self> {
+        let kbps = load_settings().ok()?.download_speed_limit_kbps.filter(|&kbps| kbps > 0)?;
+        Some(Self::new(kbps * 1024))
+    }
+
+    fn new(bytes_per_sec: u64) -> Self {
+        Self { bytes_per_sec, started: Instant::now(), bytes: 0 }
+    }
+
+    /// How long to pause after `len` more bytes to stay under the cap
+    fn delay_after(&mut self, len: u64) -> Duration {
+        self.bytes += len;
+        self.delay_at(self.started.elapsed())
+    }
+
+    fn delay_at(&self, elapsed: Duration) -> Duration {
+        let target = Duration::from_secs_f64(self.bytes as f64 / self.bytes_per_sec as f64);
+        target.saturating_sub(elapsed)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum DownloadMethod {
     AsyncStreaming,
@@ -262,21 +790,47 @@ enum DownloadMethod {
     PowerShell,
 }
 
-fn build_download_methods(attempt: u32, max: u32) -> Vec<DownloadMethod> {
+fn build_download_methods<'a>(
+    attempt: u32,
+    max: u32,
+    url: &'a str,
+    mirrors: &'a [String],
+) -> Vec<(DownloadMethod, &'a str)> {
+    // Mirrors come before the system fallbacks, which only retry the primary URL
+    let mut methods = vec![(DownloadMethod::AsyncStreaming, url)];
+    methods.extend(mirrors.iter().map(|mirror| (DownloadMethod::AsyncStreaming, mirror.as_str())));
+
     if attempt < max {
-        return vec![DownloadMethod::AsyncStreaming];
+        return methods;
     }
 
-    let mut methods = vec![DownloadMethod::AsyncStreaming, DownloadMethod::Blocking];
+    methods.push((DownloadMethod::Blocking, url));
 
     #[cfg(unix)]
-    methods.push(DownloadMethod::Curl);
+    methods.push((DownloadMethod::Curl, url));
     #[cfg(windows)]
-    methods.push(DownloadMethod::PowerShell);
+    methods.push((DownloadMethod::PowerShell, url));
 
     methods
 }
 
+/// Expand the configured mirror templates for one release asset
+fn mirror_urls(tag: &str, asset_name: &str) -> Vec<String> {
+    let templates = load_settings().map(|settings| settings.update_mirrors).unwrap_or_default();
+    let version = tag.trim_start_matches('v');
+    templates
+        .iter()
+        .map(|template| template.trim())
+        .filter(|template| !template.is_empty())
+        .map(|template| {
+            template
+                .replace("{tag}", tag)
+                .replace("{version}", version)
+                .replace("{asset}", asset_name)
+        })
+        .collect()
+}
+
 async fn try_download_method(
     app: &AppHandle,
     method: DownloadMethod,
@@ -284,7 +838,8 @@ async fn try_download_method(
     temp_path: &Path,
     attempt: u32,
     max_attempts: u32,
-) -> Result<u64> {
+    cancel: &CancelToken,
+) -> Result<(u64, Option<String>)> {
     match method {
         DownloadMethod::AsyncStreaming => {
             emit_progress(
@@ -294,9 +849,9 @@ async fn try_download_method(
                 0,
                 attempt,
                 max_attempts,
-                &format!("Download attempt {}/{}...", attempt, max_attempts),
+                &t_with("update.attempt", &[("attempt", &attempt), ("max", &max_attempts)]),
             );
-            try_download_async_streaming(app, url, temp_path).await
+            try_download_async_streaming(app, url, temp_path, cancel).await
         }
         DownloadMethod::Blocking => {
             emit_progress(
@@ -306,10 +861,10 @@ async fn try_download_method(
                 0,
                 attempt,
                 max_attempts,
-                "Trying alternative download method...",
+                &t("update.fallback_blocking"),
             );
             try_download_blocking(url, temp_path)?;
-            Ok(0)
+            Ok((0, None))
         }
         #[cfg(unix)]
         DownloadMethod::Curl => {
@@ -320,10 +875,10 @@ async fn try_download_method(
                 0,
                 attempt,
                 max_attempts,
-                "Trying system download...",
+                &t("update.fallback_system"),
             );
             try_download_curl(url, temp_path)?;
-            Ok(0)
+            Ok((0, None))
         }
         #[cfg(windows)]
         DownloadMethod::PowerShell => {
@@ -334,10 +889,10 @@ async fn try_download_method(
                 0,
                 attempt,
                 max_attempts,
-                "Trying system download...",
+                &t("update.fallback_system"),
             );
             try_download_powershell(url, temp_path)?;
-            Ok(0)
+            Ok((0, None))
         }
     }
 }
@@ -345,42 +900,63 @@ async fn try_download_method(
 async fn download_file_with_retry_and_progress(
     app: &AppHandle,
     url: &str,
+    mirrors: &[String],
     temp_path: &Path,
     expected_checksum: &str,
+    cancel: &CancelToken,
 ) -> Result<()> {
-    const MAX_RETRIES: u32 = 3;
+    let max_retries = load_settings().map(|settings| settings.download_retries).unwrap_or(3).max(1);
 
-    'attempts: for attempt in 1..=MAX_RETRIES {
-        // Clean temp file before attempt
-        if temp_path.exists() {
-            let _ = fs::remove_file(temp_path);
-        }
+    // Start from scratch; a partial file left by a failed streaming attempt
+    // below is kept so the next attempt can resume it
+    cleanup_temp_file(temp_path);
+    let mut partial_source: Option<&str> = None;
 
-        let methods = build_download_methods(attempt, MAX_RETRIES);
+    'attempts: for attempt in 1..=max_retries {
+        let methods = build_download_methods(attempt, max_retries, url, mirrors);
 
-        for method in methods {
-            let result = try_download_method(app, method, url, temp_path, attempt, MAX_RETRIES).await;
+        for (method, source) in methods {
+            cancel.check()?;
+
+            // Only resume a partial file from the server that produced it
+            if partial_source.is_some_and(|partial| partial != source) {
+                cleanup_temp_file(temp_path);
+            }
+            partial_source = None;
+            if source != url {
+                log::info!("Trying mirror {}", source);
+            }
+
+            let result =
+                try_download_method(app, method, source, temp_path, attempt, max_retries, cancel)
+                    .await;
             match result {
-                Ok(total_bytes) => {
+                Ok((total_bytes, streamed_checksum)) => {
                     emit_progress(
                         app,
                         "verifying",
                         total_bytes,
                         total_bytes,
                         attempt,
-                        MAX_RETRIES,
-                        "Verifying download checksum...",
+                        max_retries,
+                        &t("update.verifying_checksum"),
                     );
 
-                    if verify_file_checksum(temp_path, expected_checksum)? {
+                    // Streamed downloads were hashed as they were written;
+                    // the fallbacks are hashed from disk
+                    let verified = match streamed_checksum {
+                        Some(actual) => checksum_matches(&actual, expected_checksum),
+                        None => verify_file_checksum(temp_path, expected_checksum)?,
+                    };
+                    if verified {
                         emit_progress(
                             app,
                             "completed",
                             total_bytes,
                             total_bytes,
                             attempt,
-                            MAX_RETRIES,
-                            "Download successful and verified!",
+                            max_retries,
+                            &t("update.verified"),
                         );
                         return Ok(());
                     }
@@ -388,10 +964,11 @@ async fn download_file_with_retry_and_progress(
                     log::warn!("Checksum mismatch on attempt {}", attempt);
                     cleanup_temp_file(temp_path);
 
-                    if attempt < MAX_RETRIES {
+                    if attempt < max_retries {
                         let delay = attempt as u64 * 1000;
-                        emit_retry_message(app, attempt, MAX_RETRIES, delay, "Checksum mismatch");
-                        tokio::time::sleep(Duration::from_millis(delay)).await;
+                        let reason = t("update.reason.checksum_mismatch");
+                        emit_retry_message(app, attempt, max_retries, delay, &reason);
+                        cancel.sleep(Duration::from_millis(delay)).await?;
                         continue 'attempts;
                     }
 
@@ -400,71 +977,126 @@ async fn download_file_with_retry_and_progress(
                         attempt
                     ));
                 }
+                Err(err) if err.is::<DownloadCancelled>() => return Err(err),
                 Err(err) => {
                     log::error!("Download method failed on attempt {}: {}", attempt, err);
-                    cleanup_temp_file(temp_path);
+                    if matches!(method, DownloadMethod::AsyncStreaming) {
+                        partial_source = Some(source);
+                    } else {
+                        cleanup_temp_file(temp_path);
+                    }
                 }
             }
         }
 
-        if attempt < MAX_RETRIES {
+        if attempt < max_retries {
             let delay = attempt as u64 * 2000; // 2s, 4s
-            emit_retry_message(app, attempt, MAX_RETRIES, delay, "Download failed");
-            tokio::time::sleep(Duration::from_millis(delay)).await;
+            let reason = t("update.reason.download_failed");
+            emit_retry_message(app, attempt, max_retries, delay, &reason);
+            cancel.sleep(Duration::from_millis(delay)).await?;
         }
     }
 
     Err(anyhow::anyhow!(
         "Failed to download after {} attempts and all fallbacks",
-        MAX_RETRIES
+        max_retries
     ))
 }
 
-async fn try_download_async_streaming(app: &AppHandle, url: &str, temp_path: &Path) -> Result<u64> {
+async fn try_download_async_streaming(
+    app: &AppHandle,
+    url: &str,
+    temp_path: &Path,
+    cancel: &CancelToken,
+) -> Result<(u64, Option<String>)> {
     use futures_util::StreamExt;
     
-    // Client with proper configuration for streaming
-    let client = reqwest::Client::builder()
-        .read_timeout(Duration::from_secs(30))        // Per-read timeout (CRITICAL!)
-        .connect_timeout(Duration::from_secs(10))     // Connection timeout
+    // Shared client settings include the per-read timeout that catches stalled streams
+    let client = http::client_builder()
         .redirect(reqwest::redirect::Policy::limited(10)) // Follow redirects
         .build()
         .context("Failed to create HTTP client")?;
     
-    log::info!("Starting async download from: {}", url);
+    // Resume a partial file left by an earlier failed attempt
+    let resume_from = fs::metadata(temp_path).map(|meta| meta.len()).unwrap_or(0);
+    log::info!("Starting async download from: {} (resuming at byte {})", url, resume_from);
     
-    let response = client
+    let mut request = client
         .get(url)
-        .header("User-Agent", "penumbra-wrapper/1.0")
-        .header("Accept", "application/octet-stream")   // Required for GitHub
-        .send()
-        .await
-        .context("Failed to send download request")?;
+        .header("Accept", "application/octet-stream");   // Required for GitHub
+    if resume_from > 0 {
+        request = request.header("Range", format!("bytes={}-", resume_from));
+    }
+    let response = request.send().await.context("Failed to send download request")?;
     
     let status = response.status();
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && resume_from > 0 {
+        // Nothing left to fetch; the checksum decides whether the file is whole
+        log::info!("Server reports the partial download is already complete");
+        return Ok((resume_from, None));
+    }
     if !status.is_success() {
         return Err(anyhow::anyhow!("HTTP error {}: {}", status, status.canonical_reason().unwrap_or("Unknown")));
     }
     
-    let total_bytes = response.content_length().unwrap_or(0);
+    // A 200 instead of 206 means the server ignored the Range header
+    let resumed = resume_from > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT;
+    let offset = if resumed { resume_from } else { 0 };
+    let total_bytes = response.content_length().map(|len| len + offset).unwrap_or(0);
     log::info!("Content-Length: {} bytes ({:.2} MB)", total_bytes, total_bytes as f64 / 1_048_576.0);
     
     // Create file with 64KB buffer (optimal for 1-2MB files on Windows)
-    let file = File::create(temp_path)
-        .await
-        .context("Failed to create temp file")?;
+    let file = if resumed {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(temp_path)
+            .await
+            .context("Failed to reopen partial download")?
+    } else {
+        File::create(temp_path)
+            .await
+            .context("Failed to create temp file")?
+    };
     let mut writer = BufWriter::with_capacity(64 * 1024, file);
+
+    // Hash while writing so verifying needs no second pass over the file; a
+    // resumed download first hashes the part already on disk
+    let mut hasher = Sha256::new();
+    if resumed {
+        let partial = fs::File::open(temp_path).context("Failed to read partial download")?;
+        std::io::copy(&mut partial.take(offset), &mut hasher)
+            .context("Failed to hash partial download")?;
+    }
     
     let mut stream = response.bytes_stream();
-    let mut downloaded: u64 = 0;
+    let mut downloaded: u64 = offset;
     let mut last_progress_emit = Instant::now();
+    let mut throttle = Throttle::from_settings();
     
     loop {
         // CRITICAL: Per-chunk timeout to detect hangs
-        match tokio::time::timeout(Duration::from_secs(30), stream.next()).await {
+        let next = tokio::select! {
+            next = tokio::time::timeout(Duration::from_secs(30), stream.next()) => next,
+            _ = cancel.cancelled() => {
+                let _ = writer.flush().await;
+                return Err(DownloadCancelled.into());
+            }
+        };
+        match next {
             Ok(Some(Ok(chunk))) => {
                 writer.write_all(&chunk).await.context("Failed to write chunk")?;
+                hasher.update(&chunk);
                 downloaded += chunk.len() as u64;
+
+                if let Some(throttle) = &mut throttle {
+                    let delay = throttle.delay_after(chunk.len() as u64);
+                    if !delay.is_zero() {
+                        if let Err(err) = cancel.sleep(delay).await {
+                            let _ = writer.flush().await;
+                            return Err(err);
+                        }
+                    }
+                }
                 
                 // Emit progress every 100ms or every 256KB
                 let now = Instant::now();
@@ -477,12 +1109,15 @@ async fn try_download_async_streaming(app: &AppHandle, url: &str, temp_path: &Pa
                         0.0
                     };
                     
-                    emit_progress(app, "downloading", downloaded, total_bytes, 1, 3, 
-                        &format!("Downloading... {:.1}%", percentage));
+                    let percent = format!("{:.1}", percentage);
+                    emit_progress(app, "downloading", downloaded, total_bytes, 1, 3,
+                        &t_with("update.downloading", &[("percent", &percent)]));
                     last_progress_emit = now;
                 }
             }
             Ok(Some(Err(e))) => {
+                // Keep what arrived so the retry can resume from it
+                let _ = writer.flush().await;
                 return Err(anyhow::anyhow!("Stream error: {}", e));
             }
             Ok(None) => {
@@ -490,6 +1125,7 @@ async fn try_download_async_streaming(app: &AppHandle, url: &str, temp_path: &Pa
                 break;
             }
             Err(_) => {
+                let _ = writer.flush().await;
                 return Err(anyhow::anyhow!("Download stalled - no data received for 30 seconds"));
             }
         }
@@ -500,21 +1136,19 @@ async fn try_download_async_streaming(app: &AppHandle, url: &str, temp_path: &Pa
     drop(writer);
     
     log::info!("Downloaded {} bytes successfully", downloaded);
-    Ok(downloaded)
+    Ok((downloaded, Some(hex::encode(hasher.finalize()))))
 }
 
 fn try_download_blocking(url: &str, temp_path: &Path) -> Result<()> {
     log::info!("Using blocking reqwest for download");
     
-    let client = reqwest::blocking::Client::builder()
+    let client = http::blocking_client_builder()
         .timeout(Duration::from_secs(60))  // Total timeout for small files
-        .connect_timeout(Duration::from_secs(10))
         .redirect(reqwest::redirect::Policy::limited(10))
         .build()?;
     
     let mut response = client
         .get(url)
-        .header("User-Agent", "penumbra-wrapper/1.0")
         .header("Accept", "application/octet-stream")
         .send()?;
     
@@ -536,16 +1170,24 @@ fn try_download_blocking(url: &str, temp_path: &Path) -> Result<()> {
 #[cfg(windows)]
 fn try_download_powershell(url: &str, temp_path: &Path) -> Result<()> {
     log::info!("Using PowerShell for download");
-    
+
+    let network = http::NetworkSettings::load();
+    let proxy_arg = network
+        .proxy
+        .as_ref()
+        .map(|proxy| format!(" -Proxy {}", ps_quote(proxy)))
+        .unwrap_or_default();
     let output = std::process::Command::new("powershell")
         .args(&[
             "-NoProfile",
             "-ExecutionPolicy", "Bypass",
             "-Command",
             &format!(
-                "Invoke-WebRequest -Uri '{}' -OutFile '{}' -UseBasicParsing",
-                url,
-                temp_path.display()
+                "Invoke-WebRequest -Uri {} -OutFile {} -UseBasicParsing -UserAgent {}{}",
+                ps_quote(url),
+                ps_quote(&temp_path.display().to_string()),
+                ps_quote(&network.user_agent),
+                proxy_arg
             ),
         ])
         .output()?;
@@ -557,11 +1199,43 @@ fn try_download_powershell(url: &str, temp_path: &Path) -> Result<()> {
     }
 }
 
+/// Single-quote `value` for a PowerShell command line. Quotes inside are
+/// doubled, including the typographic ones PowerShell also treats as quotes.
+#[cfg(any(windows, test))]
+fn ps_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
 #[cfg(unix)]
 fn try_download_curl(url: &str, temp_path: &Path) -> Result<()> {
     log::info!("Using curl for download");
-    
-    let output = std::process::Command::new("curl")
+
+    let network = http::NetworkSettings::load();
+    let mut command = std::process::Command::new("curl");
+    command.args(["--user-agent", &network.user_agent]);
+    command.args(["--connect-timeout", &network.connect_timeout.as_secs().to_string()]);
+    if let Some(proxy) = &network.proxy {
+        command.args(["--proxy", proxy]);
+    }
+    if let Some(path) = &network.ca_certificate_path {
+        command.args(["--cacert", path]);
+    }
+    if network.accept_invalid_certs {
+        command.arg("--insecure");
+    }
+    if let Some(limit) = Throttle::from_settings() {
+        command.args(["--limit-rate", &limit.bytes_per_sec.to_string()]);
+    }
+    let output = command
         .args(&[
             "-L",  // Follow redirects
             "-o", temp_path.to_str().unwrap(),
@@ -588,19 +1262,32 @@ fn cleanup_temp_file(temp_path: &Path) {
 
 fn verify_file_checksum(path: &Path, expected: &str) -> Result<bool> {
     let actual = compute_file_checksum(path)?;
+    Ok(checksum_matches(&actual, expected))
+}
+
+fn checksum_matches(actual: &str, expected: &str) -> bool {
     let matches = actual.to_lowercase() == expected.trim().to_lowercase();
-    
     if !matches {
         log::error!("Checksum mismatch: expected {}, got {}", expected, actual);
     }
-    
-    Ok(matches)
+    matches
 }
 
-/// Safely replace binary with Windows-specific handling for file locks and atomic operations
-async fn safe_replace_binary(target_path: &Path, temp_path: &Path) -> Result<()> {
+/// Safely replace binary with Windows-specific handling for file locks and atomic operations.
+/// Returns whether the previous binary was kept as a backup.
+async fn safe_replace_binary(target_path: &Path, temp_path: &Path) -> Result<bool> {
     log::info!("Starting safe binary replacement: {:?} -> {:?}", temp_path, target_path);
 
+    // Keep the current binary around for rollback
+    let backed_up = target_path.exists()
+        && match fs::copy(target_path, backup_path(target_path)) {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("Failed to back up current antumbra binary: {}", e);
+                false
+            }
+        };
+
     // Atomic replacement with Windows-specific retry logic
     #[cfg(windows)]
     {
@@ -613,7 +1300,7 @@ async fn safe_replace_binary(target_path: &Path, temp_path: &Path) -> Result<()>
     }
 
     log::info!("Successfully replaced antumbra binary");
-    Ok(())
+    Ok(backed_up)
 }
 
 #[cfg(windows)]
@@ -632,7 +1319,7 @@ async fn replace_binary_with_retry(temp_path: &Path, target_path: &Path) -> Resu
                         log::warn!("File locked (attempt {}/5), retrying in 2 seconds...", attempt + 1);
                         
                         // Try to kill any running antumbra process
-                        if let Err(kill_err) = crate::services::antumbra::kill_current_process() {
+                        if let Err(kill_err) = crate::services::antumbra::kill_all_processes() {
                             log::warn!("Failed to kill antumbra process: {}", kill_err);
                         }
                         
@@ -661,30 +1348,143 @@ async fn replace_binary_with_retry(temp_path: &Path, target_path: &Path) -> Resu
     unreachable!()
 }
 
-async fn fetch_latest_release() -> Result<ReleaseInfo> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get("https://api.github.com/repos/rdndds/penumbra/releases/latest")
-        .header("User-Agent", "penumbra-wrapper")
-        .send()
+/// The newest release on the configured update channel. GitHub's
+/// `/releases/latest` never returns pre-releases, so that channel scans the list.
+/// A cached response younger than `max_age` is used without asking GitHub.
+async fn fetch_latest_release(max_age: Duration) -> Result<ReleaseInfo> {
+    let channel = load_settings().map(|settings| settings.update_channel).unwrap_or_default();
+    if channel == UpdateChannel::Stable {
+        return fetch_github_json(&format!("{}/latest", RELEASES_API), max_age)
+            .await
+            .context("Failed to fetch latest release");
+    }
+
+    let releases: Vec<ReleaseInfo> =
+        fetch_github_json(&format!("{}?per_page=30", RELEASES_API), max_age)
+            .await
+            .context("Failed to fetch releases")?;
+    // Report an unsupported platform rather than "no matching release"
+    asset_candidates()?;
+    releases
+        .into_iter()
+        .find(|release| select_asset(release).is_ok())
+        .context("No release provides an antumbra binary for this platform")
+}
+
+async fn fetch_release_by_tag(tag: &str) -> Result<ReleaseInfo> {
+    fetch_github_json(&format!("{}/tags/{}", RELEASES_API, tag), update_check_interval())
         .await
-        .context("Failed to fetch latest release")?;
+        .with_context(|| format!("Failed to fetch antumbra release {}", tag))
+}
 
-    let release = response
-        .error_for_status()
-        .context("GitHub API returned an error status")?
-        .json::<ReleaseInfo>()
+/// How long a cached GitHub response is trusted without revalidation
+fn update_check_interval() -> Duration {
+    let minutes = load_settings().map(|settings| settings.update_check_interval_minutes);
+    Duration::from_secs(minutes.unwrap_or(60) * 60)
+}
+
+/// A GitHub API response kept in `release_cache.json`, keyed by URL
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CachedResponse {
+    etag: Option<String>,
+    fetched_at: i64,
+    body: serde_json::Value,
+}
+
+fn release_cache_path() -> Result<PathBuf> {
+    Ok(crate::services::config::get_config_dir()?.join("release_cache.json"))
+}
+
+fn load_release_cache() -> HashMap<String, CachedResponse> {
+    release_cache_path()
+        .and_then(|path| Ok(fs::read_to_string(path)?))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn store_cached_response(url: &str, response: CachedResponse) {
+    let mut cache = load_release_cache();
+    cache.insert(url.to_string(), response);
+    let result =
+        release_cache_path().and_then(|path| Ok(fs::write(path, serde_json::to_string(&cache)?)?));
+    if let Err(e) = result {
+        warn!("Failed to write release cache: {}", e);
+    }
+}
+
+/// GET a GitHub API URL, answering from the cache while it is younger than
+/// `max_age` and revalidating with the stored ETag after that. Conditional
+/// requests answered with 304 don't count against the rate limit.
+async fn fetch_github_json<T: serde::de::DeserializeOwned>(
+    url: &str,
+    max_age: Duration,
+) -> Result<T> {
+    let now = chrono::Utc::now().timestamp();
+    let cached = load_release_cache().remove(url);
+    if let Some(entry) = &cached {
+        let age = Duration::from_secs(now.saturating_sub(entry.fetched_at).max(0) as u64);
+        if age < max_age {
+            log::debug!("Using cached GitHub response for {} ({}s old)", url, age.as_secs());
+            return serde_json::from_value(entry.body.clone())
+                .context("Failed to parse release JSON");
+        }
+    }
+
+    let client = http::client_builder().build().context("Failed to create HTTP client")?;
+    let mut request = client.get(url);
+    if let Some(etag) = cached.as_ref().and_then(|entry| entry.etag.as_deref()) {
+        request = request.header("If-None-Match", etag);
+    }
+    let response = http::with_github_auth(request)
+        .send()
         .await
-        .context("Failed to parse release JSON")?;
+        .context("Failed to reach the GitHub API")?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        if let Some(mut entry) = cached {
+            entry.fetched_at = now;
+            store_cached_response(url, entry.clone());
+            return serde_json::from_value(entry.body).context("Failed to parse release JSON");
+        }
+    }
 
-    Ok(release)
+    let response = response.error_for_status().context("GitHub API returned an error status")?;
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body =
+        response.json::<serde_json::Value>().await.context("Failed to parse release JSON")?;
+    let value = serde_json::from_value(body.clone()).context("Failed to parse release JSON")?;
+    store_cached_response(url, CachedResponse { etag, fetched_at: now, body });
+
+    Ok(value)
 }
 
-async fn find_asset_and_checksum(release: &ReleaseInfo) -> Result<(String, String, String)> {
-    let asset_name = select_asset_name()?;
-    let asset = release.assets.iter().find(|asset| asset.name == asset_name).cloned();
+/// Recent antumbra releases, newest first
+pub async fn list_releases() -> Result<Vec<AntumbraRelease>> {
+    // Always revalidate; an unchanged list costs nothing thanks to the ETag
+    let releases: Vec<ReleaseInfo> =
+        fetch_github_json(&format!("{}?per_page=30", RELEASES_API), Duration::ZERO)
+            .await
+            .context("Failed to list antumbra releases")?;
+    Ok(releases
+        .into_iter()
+        .map(|release| AntumbraRelease {
+            has_asset: select_asset(&release).is_ok(),
+            tag: release.tag_name,
+            name: release.name,
+            published_at: release.published_at,
+            prerelease: release.prerelease,
+        })
+        .collect())
+}
 
-    let asset = asset.context("Matching antumbra release asset not found")?;
+async fn find_asset_and_checksum(release: &ReleaseInfo) -> Result<(String, String, String)> {
+    let asset = select_asset(release)?.clone();
+    let asset_name = asset.name.clone();
 
     let checksum_asset = release
         .assets
@@ -707,23 +1507,107 @@ async fn find_asset_and_checksum(release: &ReleaseInfo) -> Result<(String, Strin
     Ok((asset.name, asset.browser_download_url, checksum))
 }
 
-fn select_asset_name() -> Result<String> {
+/// Download URL of the detached `<asset>.minisig` signature, if the release has one
+fn signature_url(release: &ReleaseInfo, asset_name: &str) -> Option<String> {
+    let signature_name = format!("{}.minisig", asset_name);
+    release
+        .assets
+        .iter()
+        .find(|asset| asset.name == signature_name)
+        .map(|asset| asset.browser_download_url.clone())
+}
+
+/// Check the detached signature of a downloaded asset against the embedded
+/// release key. The checksum alone comes from the same release and can't
+/// catch a compromised release pipeline.
+async fn verify_release_signature(
+    tag: &str,
+    asset_name: &str,
+    signature_url: Option<&str>,
+    path: &Path,
+) -> Result<()> {
+    let required = load_settings().map(|settings| settings.require_signed_updates).unwrap_or(false);
+
+    let Some(key) = RELEASE_SIGNING_KEY else {
+        if required {
+            anyhow::bail!("Signed updates are required but this build has no release signing key");
+        }
+        log::warn!("No release signing key embedded, skipping signature verification");
+        return Ok(());
+    };
+
+    let Some(signature_url) = signature_url else {
+        if required {
+            anyhow::bail!("Release {} has no signature for {}", tag, asset_name);
+        }
+        log::warn!("Release {} is unsigned, skipping signature verification", tag);
+        return Ok(());
+    };
+
+    let signature_bytes = download_bytes(signature_url).await?;
+    let signature_text =
+        String::from_utf8(signature_bytes).context("Release signature was not valid UTF-8")?;
+    let public_key = minisign_verify::PublicKey::from_base64(key)
+        .map_err(|e| anyhow::anyhow!("Invalid embedded signing key: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&signature_text)
+        .map_err(|e| anyhow::anyhow!("Invalid release signature: {}", e))?;
+    let data = fs::read(path).context("Failed to read downloaded asset for verification")?;
+
+    public_key
+        .verify(&data, &signature, false)
+        .map_err(|e| anyhow::anyhow!("Release signature verification failed: {}", e))?;
+    log::info!("Verified minisign signature of {}", asset_name);
+    Ok(())
+}
+
+/// Release asset names usable on this platform, best first. ARM Windows and
+/// macOS can fall back to the x86_64 build through emulation.
+fn asset_candidates() -> Result<&'static [&'static str]> {
     if cfg!(target_os = "linux") && cfg!(target_arch = "x86_64") {
-        Ok("antumbra-linux-x86_64".to_string())
+        Ok(&["antumbra-linux-x86_64"])
+    } else if cfg!(target_os = "linux") && cfg!(target_arch = "aarch64") {
+        Ok(&["antumbra-linux-aarch64"])
     } else if cfg!(target_os = "windows") && cfg!(target_arch = "x86_64") {
-        Ok("antumbra.exe".to_string())
-    } else if cfg!(target_os = "macos") {
-        anyhow::bail!("Antumbra updates are not available for macOS yet")
+        Ok(&["antumbra.exe"])
+    } else if cfg!(target_os = "windows") && cfg!(target_arch = "aarch64") {
+        Ok(&["antumbra-windows-aarch64.exe", "antumbra.exe"])
+    } else if cfg!(target_os = "macos") && cfg!(target_arch = "x86_64") {
+        Ok(&["antumbra-macos-x86_64"])
+    } else if cfg!(target_os = "macos") && cfg!(target_arch = "aarch64") {
+        Ok(&["antumbra-macos-aarch64", "antumbra-macos-x86_64"])
     } else {
-        anyhow::bail!("Antumbra updates are not available for this platform")
+        anyhow::bail!(
+            "Antumbra updates are not available for this platform ({}/{})",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    }
+}
+
+/// The best asset of `release` for this platform
+fn select_asset(release: &ReleaseInfo) -> Result<&ReleaseAsset> {
+    let candidates = asset_candidates()?;
+    let asset = candidates
+        .iter()
+        .find_map(|name| release.assets.iter().find(|asset| asset.name == *name))
+        .with_context(|| {
+            format!(
+                "Release {} has no antumbra build for {}/{}",
+                release.tag_name,
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            )
+        })?;
+    if asset.name != candidates[0] {
+        log::warn!("No native antumbra build in {}, using {}", release.tag_name, asset.name);
     }
+    Ok(asset)
 }
 
 async fn download_bytes(url: &str) -> Result<Vec<u8>> {
-    let client = reqwest::Client::new();
+    let client = http::client_builder().build().context("Failed to create HTTP client")?;
     let response = client
         .get(url)
-        .header("User-Agent", "penumbra-wrapper")
         .send()
         .await
         .context("Failed to download update asset")?;
@@ -738,12 +1622,61 @@ async fn download_bytes(url: &str) -> Result<Vec<u8>> {
     Ok(bytes.to_vec())
 }
 
+/// Checksum of the installed binary, rehashed only when its size or
+/// modification time differ from the cached entry
+fn installed_checksum(path: &Path) -> Result<String> {
+    let metadata = fs::metadata(path).context("Failed to read antumbra binary metadata")?;
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_millis() as u64)
+        .unwrap_or(0);
+    let mut key = BinaryChecksum {
+        path: path.display().to_string(),
+        size: metadata.len(),
+        modified_ms,
+        sha256: String::new(),
+    };
+
+    let cached = load_settings().ok().and_then(|settings| settings.binary_checksum);
+    if let Some(sha256) = cached_checksum(cached.as_ref(), &key) {
+        return Ok(sha256.to_string());
+    }
+
+    key.sha256 = compute_file_checksum(path)?;
+    let sha256 = key.sha256.clone();
+    // Without a modification time a replaced binary could look unchanged
+    if modified_ms > 0 {
+        let saved = modify_settings(|settings| {
+            settings.binary_checksum = Some(key);
+            Ok(())
+        });
+        if let Err(err) = saved {
+            warn!("Failed to cache antumbra checksum: {}", err);
+        }
+    }
+    Ok(sha256)
+}
+
+fn cached_checksum<'a>(
+    cached: Option<&'a BinaryChecksum>,
+    key: &BinaryChecksum,
+) -> Option<&'a str> {
+    cached
+        .filter(|cached| {
+            cached.path == key.path
+                && cached.size == key.size
+                && cached.modified_ms == key.modified_ms
+        })
+        .map(|cached| cached.sha256.as_str())
+}
+
 fn compute_file_checksum(path: &Path) -> Result<String> {
-    let data = fs::read(path).context("Failed to read antumbra binary for checksum")?;
+    let mut file = fs::File::open(path).context("Failed to read antumbra binary for checksum")?;
     let mut hasher = Sha256::new();
-    hasher.update(&data);
-    let digest = hasher.finalize();
-    Ok(hex::encode(digest))
+    std::io::copy(&mut file, &mut hasher).context("Failed to read antumbra binary for checksum")?;
+    Ok(hex::encode(hasher.finalize()))
 }
 
 fn parse_checksum(contents: &str, asset_name: &str) -> Option<String> {
@@ -857,3 +1790,44 @@ fn normalize_version(version: &str) -> Option<String> {
     let token = version.split_whitespace().find(|part| part.chars().any(|c| c.is_ascii_digit()))?;
     Some(token.trim_start_matches('v').to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_checksum_requires_unchanged_file() {
+        let cached = BinaryChecksum {
+            path: "/bin/antumbra".into(),
+            size: 100,
+            modified_ms: 1_000,
+            sha256: "abc".into(),
+        };
+        let key = BinaryChecksum { sha256: String::new(), ..cached.clone() };
+        assert_eq!(cached_checksum(Some(&cached), &key), Some("abc"));
+        assert_eq!(
+            cached_checksum(Some(&cached), &BinaryChecksum { size: 101, ..key.clone() }),
+            None
+        );
+        assert_eq!(
+            cached_checksum(Some(&cached), &BinaryChecksum { modified_ms: 2_000, ..key.clone() }),
+            None
+        );
+        assert_eq!(cached_checksum(None, &key), None);
+    }
+
+    #[test]
+    fn throttle_delays_only_when_ahead_of_the_cap() {
+        let mut throttle = Throttle::new(1024);
+        throttle.bytes = 2048;
+        assert_eq!(throttle.delay_at(Duration::from_millis(500)), Duration::from_millis(1500));
+        assert_eq!(throttle.delay_at(Duration::from_secs(3)), Duration::ZERO);
+    }
+
+    #[test]
+    fn powershell_quoting_doubles_quotes() {
+        assert_eq!(ps_quote("https://example.com/a.zip"), "'https://example.com/a.zip'");
+        assert_eq!(ps_quote("C:\\O'Brien\\antumbra"), "'C:\\O''Brien\\antumbra'");
+        assert_eq!(ps_quote("a\u{2019}; calc"), "'a\u{2019}\u{2019}; calc'");
+    }
+}
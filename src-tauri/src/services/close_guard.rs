@@ -0,0 +1,103 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Closing the window while antumbra writes to a device can brick it, so a
+//! close request with operations running is held until the frontend confirms
+//! (or `CONFIRM_TIMEOUT` passes), and only then are they cancelled gracefully.
+
+use crate::models::CloseRequestedWhileBusyEvent;
+use crate::services::antumbra::kill_all_processes;
+use crate::services::{events, mock_executor, operation_queue, operation_registry};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, CloseRequestApi, Manager, Window};
+
+/// Close anyway when the frontend does not answer in time
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Label Tauri gives the window from tauri.conf.json
+const MAIN_WINDOW: &str = "main";
+
+/// Set once closing was confirmed, so the repeated close request goes through
+static CONFIRMED: AtomicBool = AtomicBool::new(false);
+
+/// The label of the window waiting to close and its timeout task
+static PENDING: Mutex<Option<(String, JoinHandle<()>)>> = Mutex::new(None);
+
+/// `WindowEvent::CloseRequested` handler
+pub fn on_close_requested(window: &Window, api: &CloseRequestApi) {
+    // Secondary windows such as a detached console close without stopping anything
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+
+    let operations = operation_registry::list();
+    if CONFIRMED.load(Ordering::SeqCst) || operations.is_empty() {
+        let _ = kill_all_processes();
+        return;
+    }
+
+    api.prevent_close();
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    log::warn!(
+        "Close requested while {} operation(s) run; asking for confirmation",
+        operations.len()
+    );
+
+    let (timeout_app, timeout_label) = (app.clone(), label.clone());
+    let timeout = tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(CONFIRM_TIMEOUT).await;
+        log::warn!("No answer to the close confirmation, closing anyway");
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.take();
+        }
+        close(timeout_app, timeout_label).await;
+    });
+    if let Ok(mut pending) = PENDING.lock() {
+        if let Some((_, previous)) = pending.replace((label, timeout)) {
+            previous.abort();
+        }
+    }
+
+    let event =
+        CloseRequestedWhileBusyEvent { operations, timeout_secs: CONFIRM_TIMEOUT.as_secs() };
+    let _ = events::emit(&app, "app:close-requested-while-busy", event);
+}
+
+/// Answer a held close request: cancel the operations and close, or keep
+/// the window open. Returns false when no close request was pending.
+pub async fn confirm(app: AppHandle, proceed: bool) -> bool {
+    let Some((label, timeout)) = PENDING.lock().ok().and_then(|mut pending| pending.take()) else {
+        return false;
+    };
+    timeout.abort();
+    if proceed {
+        close(app, label).await;
+    } else {
+        log::info!("Close cancelled; operations keep running");
+    }
+    true
+}
+
+/// Drain the queue, stop running operations gracefully, then close the window
+async fn close(app: AppHandle, label: String) {
+    CONFIRMED.store(true, Ordering::SeqCst);
+
+    operation_queue::drain_waiting(&app);
+    mock_executor::cancel_all();
+    if let Ok(Err(err)) = tokio::task::spawn_blocking(kill_all_processes).await {
+        log::warn!("Failed to cancel operations before closing: {:#}", err);
+    }
+
+    match app.get_webview_window(&label) {
+        Some(window) => {
+            let _ = window.close();
+        }
+        None => app.exit(0),
+    }
+}
@@ -0,0 +1,269 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::antumbra::AntumbraCommandInfo;
+use crate::services::config::{get_config_dir, write_atomic};
+use crate::services::{operation_stats, usage_metrics};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime};
+
+/// Number of antumbra invocations kept in the history file
+const HISTORY_LIMIT: usize = 200;
+
+/// Oldest entry first, loaded from disk on first use
+static HISTORY: OnceLock<Mutex<VecDeque<AntumbraCommandInfo>>> = OnceLock::new();
+
+/// Bumped on every change; snapshots are numbered with it so a save that
+/// lost the race to a newer one doesn't overwrite it
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Generation of the snapshot last written, held while writing
+static SAVED_GENERATION: Mutex<u64> = Mutex::new(0);
+
+/// One page of the command history, newest entry first
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommandHistoryPage {
+    pub entries: Vec<AntumbraCommandInfo>,
+    pub total: usize,
+}
+
+fn history() -> &'static Mutex<VecDeque<AntumbraCommandInfo>> {
+    HISTORY.get_or_init(|| {
+        let entries = load().unwrap_or_else(|err| {
+            log::warn!("Failed to load command history: {:#}", err);
+            VecDeque::new()
+        });
+        Mutex::new(entries)
+    })
+}
+
+fn history_path() -> Result<PathBuf> {
+    Ok(get_config_dir()?.join("command_history.json"))
+}
+
+fn load() -> Result<VecDeque<AntumbraCommandInfo>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(VecDeque::new());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read command history {:?}", path))?;
+    serde_json::from_str(&contents).with_context(|| format!("Invalid command history {:?}", path))
+}
+
+/// Copy of the entries to save, taken while the history is locked
+struct Snapshot {
+    generation: u64,
+    entries: Vec<AntumbraCommandInfo>,
+}
+
+fn snapshot(entries: &VecDeque<AntumbraCommandInfo>) -> Snapshot {
+    Snapshot {
+        generation: GENERATION.fetch_add(1, Ordering::SeqCst) + 1,
+        entries: entries.iter().cloned().collect(),
+    }
+}
+
+/// Write `snapshot` unless a newer one was written already. Called without
+/// the history lock so disk I/O doesn't block other commands.
+fn save(snapshot: Snapshot) {
+    let Ok(mut saved) = SAVED_GENERATION.lock() else {
+        return;
+    };
+    if *saved >= snapshot.generation {
+        return;
+    }
+    let result = history_path().and_then(|path| {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_atomic(&path, serde_json::to_string_pretty(&snapshot.entries)?.as_bytes())
+            .with_context(|| format!("Failed to write command history {:?}", path))
+    });
+    match result {
+        Ok(()) => *saved = snapshot.generation,
+        Err(err) => log::warn!("Failed to save command history: {:#}", err),
+    }
+}
+
+/// Append `info`, evicting the oldest entries beyond the limit
+fn push(entries: &mut VecDeque<AntumbraCommandInfo>, info: AntumbraCommandInfo) {
+    entries.push_back(info);
+    while entries.len() > HISTORY_LIMIT {
+        entries.pop_front();
+    }
+}
+
+/// How a recorded command ended
+struct Outcome {
+    success: bool,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+    bytes: Option<u64>,
+}
+
+/// Fill in the outcome of the entry with `id`. False when it was evicted.
+fn fill_outcome(entries: &mut VecDeque<AntumbraCommandInfo>, id: &str, outcome: Outcome) -> bool {
+    let Some(entry) = entries.iter_mut().rev().find(|entry| entry.id == id) else {
+        return false;
+    };
+    entry.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    entry.duration_ms = Some(outcome.duration_ms);
+    entry.exit_code = outcome.exit_code;
+    entry.success = Some(outcome.success);
+    entry.bytes = outcome.bytes;
+    true
+}
+
+/// An in-flight history entry. Dropping it without `finish` records a failure.
+pub struct HistoryEntry {
+    id: String,
+    args: Vec<String>,
+    started: Instant,
+    started_at: SystemTime,
+    finished: bool,
+}
+
+impl HistoryEntry {
+    /// Record the outcome and duration of the command
+    pub fn finish(mut self, success: bool, exit_code: Option<i32>) {
+        self.finished = true;
+        self.complete(success, exit_code);
+    }
+
+    fn complete(&self, success: bool, exit_code: Option<i32>) {
+        let outcome = Outcome {
+            success,
+            exit_code,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            bytes: if success {
+                operation_stats::transferred_bytes(&self.args, self.started_at)
+            } else {
+                None
+            },
+        };
+        usage_metrics::record(self.args.first().map(String::as_str).unwrap_or_default(), success);
+
+        let snapshot = {
+            let Ok(mut entries) = history().lock() else {
+                return;
+            };
+            if !fill_outcome(&mut entries, &self.id, outcome) {
+                return;
+            }
+            snapshot(&entries)
+        };
+        save(snapshot);
+    }
+}
+
+impl Drop for HistoryEntry {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.complete(false, None);
+        }
+    }
+}
+
+/// Append a started command, evicting the oldest entries beyond the limit
+pub fn record(mut info: AntumbraCommandInfo) -> HistoryEntry {
+    let id = uuid::Uuid::new_v4().to_string();
+    info.id = id.clone();
+    let args = info.args.clone();
+    let snapshot = history().lock().ok().map(|mut entries| {
+        push(&mut entries, info);
+        snapshot(&entries)
+    });
+    if let Some(snapshot) = snapshot {
+        save(snapshot);
+    }
+    HistoryEntry {
+        id,
+        args,
+        started: Instant::now(),
+        started_at: SystemTime::now(),
+        finished: false,
+    }
+}
+
+/// The most recently started command
+pub fn last() -> Option<AntumbraCommandInfo> {
+    history().lock().ok().and_then(|entries| entries.back().cloned())
+}
+
+/// Every recorded command, oldest first
+pub fn all() -> Vec<AntumbraCommandInfo> {
+    history().lock().map(|entries| entries.iter().cloned().collect()).unwrap_or_default()
+}
+
+/// `limit` entries starting `offset` entries back from the newest
+pub fn page(offset: usize, limit: usize) -> CommandHistoryPage {
+    let Ok(entries) = history().lock() else {
+        return CommandHistoryPage { entries: Vec::new(), total: 0 };
+    };
+    CommandHistoryPage {
+        entries: entries.iter().rev().skip(offset).take(limit).cloned().collect(),
+        total: entries.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(id: &str, args: &[&str]) -> AntumbraCommandInfo {
+        AntumbraCommandInfo {
+            id: id.to_string(),
+            command: "antumbra".to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            working_dir: "/tmp".to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            operation_id: None,
+            finished_at: None,
+            duration_ms: None,
+            exit_code: None,
+            success: None,
+            bytes: None,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn push_keeps_only_the_newest_entries() {
+        let mut entries = VecDeque::new();
+        for i in 0..HISTORY_LIMIT + 5 {
+            push(&mut entries, info(&i.to_string(), &["read"]));
+        }
+        assert_eq!(entries.len(), HISTORY_LIMIT);
+        assert_eq!(entries.front().unwrap().id, "5");
+        assert_eq!(entries.back().unwrap().id, (HISTORY_LIMIT + 4).to_string());
+    }
+
+    #[test]
+    fn outcome_is_filled_into_the_recorded_entry() {
+        let mut entries = VecDeque::new();
+        push(&mut entries, info("a", &["read", "boot"]));
+        push(&mut entries, info("b", &["write", "boot"]));
+
+        let outcome =
+            Outcome { success: true, exit_code: Some(0), duration_ms: 42, bytes: Some(7) };
+        assert!(fill_outcome(&mut entries, "a", outcome));
+        let entry = &entries[0];
+        assert_eq!(entry.success, Some(true));
+        assert_eq!(entry.exit_code, Some(0));
+        assert_eq!(entry.duration_ms, Some(42));
+        assert_eq!(entry.bytes, Some(7));
+        assert!(entry.finished_at.is_some());
+        assert_eq!(entries[1].success, None);
+
+        let evicted = Outcome { success: false, exit_code: None, duration_ms: 1, bytes: None };
+        assert!(!fill_outcome(&mut entries, "gone", evicted));
+    }
+}
@@ -3,12 +3,108 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
-use anyhow::Result;
+use crate::services::paths;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Schema version written to config.json. Bump it together with a new entry
+/// in `MIGRATIONS` whenever a stored field is renamed or changes meaning.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Serializes read-modify-write cycles of the settings file
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Distinguishes temporary files of concurrent `write_atomic` calls
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// `MIGRATIONS[n]` upgrades a version `n` config to version `n + 1`
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v0_to_v1];
+
+/// Which antumbra releases update checks consider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum UpdateChannel {
+    #[default]
+    #[serde(rename = "stable")]
+    Stable,
+    /// Also offer pre-releases and nightlies
+    #[serde(rename = "pre-release")]
+    PreRelease,
+}
+
+/// Tool used for device operations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolBackend {
+    #[default]
+    Antumbra,
+    /// Experimental; only covers the common read, write, erase and reboot commands
+    Mtkclient,
+}
+
+/// What a recently used path was picked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecentItemKind {
+    Scatter,
+    Da,
+    Image,
+    OutputDir,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentItem {
+    pub kind: RecentItemKind,
+    pub path: String,
+    /// RFC 3339 timestamp of the last use
+    pub used_at: String,
+}
+
+/// SHA-256 of the installed antumbra binary, valid while its size and
+/// modification time are unchanged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinaryChecksum {
+    pub path: String,
+    pub size: u64,
+    /// Milliseconds since the Unix epoch
+    pub modified_ms: u64,
+    pub sha256: String,
+}
+
+/// A named selection of partitions, such as "modem set" or "boot chain"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionSet {
+    pub name: String,
+    pub partitions: Vec<String>,
+}
+
+/// Timeouts for one antumbra command that replace the global ones when set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandTimeouts {
+    #[serde(default)]
+    pub inactivity_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+}
+
+/// Effective time limits for one antumbra run; `None` means unlimited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperationLimits {
+    pub inactivity_timeout: Option<Duration>,
+    pub max_duration: Option<Duration>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
+    /// Schema version of the file this was loaded from; 0 predates versioning
+    #[serde(default)]
+    pub config_version: u32,
     #[serde(default)]
     pub da_path: Option<String>,
     #[serde(default)]
@@ -19,16 +115,262 @@ pub struct AppSettings {
     pub auto_check_updates: bool,
     #[serde(default)]
     pub antumbra_version: Option<String>,
+    /// Run antumbra attached to a pseudo-terminal so it prints live progress bars
+    #[serde(default)]
+    pub pty_mode: bool,
+    /// Suppress a repeated output line seen within this many ms (0 disables)
+    #[serde(default = "default_dedup_window_ms")]
+    pub output_dedup_window_ms: u64,
+    /// Apply output deduplication to stderr as well as stdout
+    #[serde(default)]
+    pub dedup_stderr: bool,
+    /// Replay canned output instead of running antumbra (no device needed)
+    #[serde(default)]
+    pub simulation_mode: bool,
+    /// Save the output and timing of every antumbra run to a session file
+    #[serde(default)]
+    pub record_sessions: bool,
+    /// Queue device operations behind a running one instead of rejecting them as busy
+    #[serde(default = "default_true")]
+    pub queue_operations: bool,
+    /// Output lines kept in memory per stream; older lines are dropped
+    #[serde(default = "default_output_buffer_lines")]
+    pub output_buffer_lines: usize,
+    /// Also write every output line to a file under the config directory. The
+    /// file is kept for a week when the buffer had to drop lines, else deleted.
+    #[serde(default)]
+    pub spill_output_to_disk: bool,
+    /// Proxy URL for GitHub requests; the system proxy is used when unset
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// GitHub personal access token sent with API requests to avoid rate limits
+    #[serde(default)]
+    pub github_token: Option<String>,
+    /// Release channel offered by update checks
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// Fallback download URL templates; {tag}, {version} and {asset} are substituted
+    #[serde(default)]
+    pub update_mirrors: Vec<String>,
+    /// Refuse antumbra releases that lack a valid minisign signature
+    #[serde(default)]
+    pub require_signed_updates: bool,
+    /// Installed version under bin/<version>/ to run; bin/antumbra when unset.
+    /// Managed by the version commands, not the settings form.
+    #[serde(default)]
+    pub active_antumbra_version: Option<String>,
+    /// Reuse the cached GitHub release response for this long before asking again
+    #[serde(default = "default_update_check_interval_minutes")]
+    pub update_check_interval_minutes: u64,
+    /// Run this antumbra binary instead of the managed one; disables in-app updates
+    #[serde(default)]
+    pub antumbra_path_override: Option<String>,
+    /// Cap antumbra update downloads at this many KB/s
+    #[serde(default)]
+    pub download_speed_limit_kbps: Option<u64>,
+    /// Recently used files and directories, newest first.
+    /// Managed by the recent item commands, not the settings form.
+    #[serde(default)]
+    pub recent_items: Vec<RecentItem>,
+    /// Saved partition selections for batch reads and flashes.
+    /// Managed by the partition set commands, not the settings form.
+    #[serde(default)]
+    pub partition_sets: Vec<PartitionSet>,
+    /// Checksum of the installed binary, so update checks skip rehashing it.
+    /// Managed by the update checker, not the settings form.
+    #[serde(default)]
+    pub binary_checksum: Option<BinaryChecksum>,
+    /// Kill antumbra after this many seconds without output (0 disables)
+    #[serde(default = "default_inactivity_timeout_secs")]
+    pub inactivity_timeout_secs: u64,
+    /// Kill antumbra after it has run this many seconds in total (0 disables)
+    #[serde(default)]
+    pub max_operation_duration_secs: u64,
+    /// How long a cancelled antumbra gets to close the USB session before it is killed
+    #[serde(default = "default_cancel_grace_period_secs")]
+    pub cancel_grace_period_secs: u64,
+    /// Attempts per antumbra update download before giving up
+    #[serde(default = "default_download_retries")]
+    pub download_retries: u32,
+    /// Per-command timeout overrides keyed by antumbra subcommand, e.g. "read-all"
+    #[serde(default)]
+    pub command_timeouts: BTreeMap<String, CommandTimeouts>,
+    /// Seconds to wait for an HTTP connection to open
+    #[serde(default = "default_http_connect_timeout_secs")]
+    pub http_connect_timeout_secs: u64,
+    /// Seconds an HTTP response may stall before the request fails
+    #[serde(default = "default_http_read_timeout_secs")]
+    pub http_read_timeout_secs: u64,
+    /// User-Agent sent with HTTP requests; penumbra-wrapper/<version> when unset
+    #[serde(default)]
+    pub http_user_agent: Option<String>,
+    /// Extra PEM root certificate to trust, e.g. for a TLS-intercepting proxy
+    #[serde(default)]
+    pub http_ca_certificate_path: Option<String>,
+    /// Skip TLS certificate verification. Unsafe; only for broken corporate proxies
+    #[serde(default)]
+    pub http_accept_invalid_certs: bool,
+    /// Language of backend messages such as error suggestions, e.g. "es".
+    /// The system language is used when unset.
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Sort outputs saved under `default_output_path` into
+    /// `<device>/<date>/<operation>` folders
+    #[serde(default)]
+    pub organize_output_dirs: bool,
+    /// Read-only mode: reject flash, erase, format and seccfg commands
+    #[serde(default)]
+    pub safe_mode: bool,
+    /// Redact serials, IMEIs, user names and tokens from exported logs and
+    /// diagnostics
+    #[serde(default = "default_true")]
+    pub redact_exports: bool,
+    /// Extra regular expressions redacted from exports
+    #[serde(default)]
+    pub redaction_patterns: Vec<String>,
+    /// Opt in to counting operation types and outcomes for the anonymous usage report
+    #[serde(default)]
+    pub usage_metrics_enabled: bool,
+    /// Where `upload_usage_metrics` posts the report; nothing is sent without it
+    #[serde(default)]
+    pub usage_metrics_endpoint: Option<String>,
+    /// Show a desktop notification when an operation finishes while the window is unfocused
+    #[serde(default = "default_true")]
+    pub notifications_enabled: bool,
+    /// Antumbra subcommands that trigger a notification
+    #[serde(default = "default_notification_operations")]
+    pub notification_operations: Vec<String>,
+    /// Keep the system awake while an antumbra operation runs
+    #[serde(default = "default_true")]
+    pub prevent_sleep: bool,
+    /// Tool that talks to the device; mtkclient is an experimental fallback for SoCs antumbra lacks
+    #[serde(default)]
+    pub backend: ToolBackend,
+    /// mtkclient's mtk script or executable; looked up on PATH when unset
+    #[serde(default)]
+    pub mtkclient_path: Option<String>,
+    /// Keep progress lines out of the console (they stay in the captured output)
+    /// and send parsed progress at a bounded rate
+    #[serde(default = "default_true")]
+    pub compact_progress_output: bool,
+}
+
+fn default_notification_operations() -> Vec<String> {
+    ["read-all", "download", "upload", "erase", "format"].map(String::from).to_vec()
+}
+
+fn default_output_buffer_lines() -> usize {
+    10_000
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_dedup_window_ms() -> u64 {
+    2000
+}
+
+fn default_update_check_interval_minutes() -> u64 {
+    60
+}
+
+fn default_inactivity_timeout_secs() -> u64 {
+    30
+}
+
+fn default_cancel_grace_period_secs() -> u64 {
+    3
+}
+
+fn default_download_retries() -> u32 {
+    3
+}
+
+fn default_http_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_http_read_timeout_secs() -> u64 {
+    30
+}
+
+/// A limit in seconds, with 0 meaning no limit
+fn limit(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+impl AppSettings {
+    /// Time limits for the antumbra subcommand `command`, honoring its override
+    pub fn operation_limits(&self, command: &str) -> OperationLimits {
+        let overrides = self.command_timeouts.get(command).cloned().unwrap_or_default();
+        OperationLimits {
+            inactivity_timeout: limit(
+                overrides.inactivity_timeout_secs.unwrap_or(self.inactivity_timeout_secs),
+            ),
+            max_duration: limit(
+                overrides.max_duration_secs.unwrap_or(self.max_operation_duration_secs),
+            ),
+        }
+    }
+
+    pub fn cancel_grace_period(&self) -> Duration {
+        Duration::from_secs(self.cancel_grace_period_secs)
+    }
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            config_version: CONFIG_VERSION,
             da_path: None,
             preloader_path: None,
             default_output_path: None,
             auto_check_updates: true,
             antumbra_version: None,
+            pty_mode: false,
+            output_dedup_window_ms: default_dedup_window_ms(),
+            dedup_stderr: false,
+            simulation_mode: false,
+            record_sessions: false,
+            queue_operations: true,
+            output_buffer_lines: default_output_buffer_lines(),
+            spill_output_to_disk: false,
+            proxy_url: None,
+            github_token: None,
+            update_channel: UpdateChannel::Stable,
+            update_mirrors: Vec::new(),
+            require_signed_updates: false,
+            active_antumbra_version: None,
+            update_check_interval_minutes: default_update_check_interval_minutes(),
+            antumbra_path_override: None,
+            download_speed_limit_kbps: None,
+            recent_items: Vec::new(),
+            partition_sets: Vec::new(),
+            binary_checksum: None,
+            inactivity_timeout_secs: default_inactivity_timeout_secs(),
+            max_operation_duration_secs: 0,
+            cancel_grace_period_secs: default_cancel_grace_period_secs(),
+            download_retries: default_download_retries(),
+            command_timeouts: BTreeMap::new(),
+            http_connect_timeout_secs: default_http_connect_timeout_secs(),
+            http_read_timeout_secs: default_http_read_timeout_secs(),
+            http_user_agent: None,
+            http_ca_certificate_path: None,
+            http_accept_invalid_certs: false,
+            locale: None,
+            organize_output_dirs: false,
+            safe_mode: false,
+            redact_exports: true,
+            redaction_patterns: Vec::new(),
+            usage_metrics_enabled: false,
+            usage_metrics_endpoint: None,
+            notifications_enabled: true,
+            notification_operations: default_notification_operations(),
+            prevent_sleep: true,
+            backend: ToolBackend::Antumbra,
+            mtkclient_path: None,
+            compact_progress_output: true,
         }
     }
 }
@@ -40,11 +382,82 @@ pub fn load_settings() -> Result<AppSettings> {
         return Ok(AppSettings::default());
     }
 
-    let contents = std::fs::read_to_string(&config_path)?;
-    let settings: AppSettings = serde_json::from_str(&contents)?;
+    let (settings, migrated) = match read_settings_file(&config_path) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            // A damaged config would otherwise fail every load from now on
+            let backup_path = backup_path(&config_path);
+            log::error!("{:#}, trying backup {:?}", err, backup_path);
+            let (settings, _) = read_settings_file(&backup_path).map_err(|_| err)?;
+            log::warn!("Restored settings from backup {:?}", backup_path);
+            (settings, true)
+        }
+    };
+
+    // save_settings keeps the replaced file as the backup
+    if migrated {
+        if let Err(e) = save_settings(&settings) {
+            log::warn!("Failed to save migrated config: {}", e);
+        }
+    }
     Ok(settings)
 }
 
+/// Parse and migrate one settings file. Returns whether it needs saving back.
+fn read_settings_file(path: &Path) -> Result<(AppSettings, bool)> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {:?}", path))?;
+    let mut value: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid config file {:?}", path))?;
+    let migrated = migrate(&mut value)?;
+    let settings =
+        serde_json::from_value(value).with_context(|| format!("Invalid config file {:?}", path))?;
+    Ok((settings, migrated))
+}
+
+fn backup_path(config_path: &Path) -> PathBuf {
+    config_path.with_extension("json.bak")
+}
+
+/// Upgrade a raw config object to `CONFIG_VERSION` in place. Returns whether
+/// anything was migrated. Files from a newer build are left untouched.
+fn migrate(value: &mut Value) -> Result<bool> {
+    let object = value.as_object_mut().context("Config file is not a JSON object")?;
+    let version = object.get("config_version").and_then(Value::as_u64).unwrap_or(0) as u32;
+
+    if version > CONFIG_VERSION {
+        log::warn!(
+            "Config version {} is newer than supported version {}, loading as is",
+            version,
+            CONFIG_VERSION
+        );
+        return Ok(false);
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        log::info!("Migrating config from version {} to {}", from, from + 1);
+        migration(object);
+    }
+    object.insert("config_version".to_string(), Value::from(CONFIG_VERSION));
+    Ok(version < CONFIG_VERSION)
+}
+
+/// Unversioned configs deserialized a missing `auto_check_updates` as false,
+/// unlike fresh installs; give them the documented default instead
+fn migrate_v0_to_v1(object: &mut Map<String, Value>) {
+    object.entry("auto_check_updates").or_insert(Value::Bool(true));
+}
+
+/// Load the settings, apply `change` and save them, without another caller
+/// modifying the file in between
+pub fn modify_settings<T>(change: impl FnOnce(&mut AppSettings) -> Result<T>) -> Result<T> {
+    let _guard = SETTINGS_LOCK.lock();
+    let mut settings = load_settings()?;
+    let result = change(&mut settings)?;
+    save_settings(&settings)?;
+    Ok(result)
+}
+
 pub fn save_settings(settings: &AppSettings) -> Result<()> {
     let config_path = get_config_path()?;
 
@@ -52,23 +465,131 @@ pub fn save_settings(settings: &AppSettings) -> Result<()> {
         std::fs::create_dir_all(parent)?;
     }
 
-    let contents = serde_json::to_string_pretty(settings)?;
-    std::fs::write(&config_path, contents)?;
+    // Settings from the frontend don't carry the version; always stamp the current one
+    let mut settings = settings.clone();
+    settings.config_version = CONFIG_VERSION;
+    let contents = serde_json::to_string_pretty(&settings)?;
+
+    // Keep the last good config; a damaged one must not replace a good backup
+    let current_is_valid = std::fs::read_to_string(&config_path)
+        .is_ok_and(|current| serde_json::from_str::<Value>(&current).is_ok());
+    if current_is_valid {
+        if let Err(e) = std::fs::copy(&config_path, backup_path(&config_path)) {
+            log::warn!("Failed to back up config: {}", e);
+        }
+    }
+
+    write_atomic(&config_path, contents.as_bytes())
+}
+
+/// Write to a temporary file next to `path`, flush it to disk and rename it
+/// over `path`, so a crash leaves either the old or the new file, never half of one
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let temp_path = path.with_extension(format!("tmp-{}-{}", std::process::id(), counter));
+    let result = (|| -> Result<()> {
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result.with_context(|| format!("Failed to write {:?}", path))?;
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = std::fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
     Ok(())
 }
 
 pub fn get_config_path() -> Result<PathBuf> {
-    let config_dir = dirs::config_dir()
-        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
-
-    Ok(config_dir.join("penumbra-wrapper").join("config.json"))
+    paths::config_file()
 }
 
-/// Get the configuration directory (reserved for future features)
-#[allow(dead_code)]
+/// Get the configuration directory
 pub fn get_config_dir() -> Result<PathBuf> {
-    let config_dir = dirs::config_dir()
-        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+    paths::config_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_unversioned_config() {
+        let mut value = serde_json::json!({ "da_path": "/tmp/da.bin" });
+        assert!(migrate(&mut value).unwrap());
 
-    Ok(config_dir.join("penumbra-wrapper"))
+        let settings: AppSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.config_version, CONFIG_VERSION);
+        assert!(settings.auto_check_updates);
+        assert_eq!(settings.da_path.as_deref(), Some("/tmp/da.bin"));
+    }
+
+    #[test]
+    fn command_timeouts_override_global_limits() {
+        let mut settings = AppSettings::default();
+        settings.command_timeouts.insert(
+            "read-all".to_string(),
+            CommandTimeouts { inactivity_timeout_secs: Some(0), max_duration_secs: Some(7200) },
+        );
+
+        let read_all = settings.operation_limits("read-all");
+        assert_eq!(read_all.inactivity_timeout, None);
+        assert_eq!(read_all.max_duration, Some(Duration::from_secs(7200)));
+
+        let erase = settings.operation_limits("erase");
+        assert_eq!(erase.inactivity_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(erase.max_duration, None);
+    }
+
+    #[test]
+    fn atomic_write_replaces_file() {
+        let path =
+            std::env::temp_dir().join(format!("penumbra-config-{}.json", std::process::id()));
+        std::fs::write(&path, "old").unwrap();
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn concurrent_atomic_writes_use_separate_temp_files() {
+        let dir = std::env::temp_dir().join(format!("penumbra-config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+
+        let writers: Vec<_> = (0..8)
+            .map(|idx| {
+                let path = path.clone();
+                std::thread::spawn(move || write_atomic(&path, format!("{}", idx).as_bytes()))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.parse::<u32>().is_ok_and(|idx| idx < 8));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn leaves_current_and_newer_configs_alone() {
+        let mut current = serde_json::json!({ "config_version": CONFIG_VERSION });
+        assert!(!migrate(&mut current).unwrap());
+
+        let mut newer = serde_json::json!({ "config_version": CONFIG_VERSION + 1 });
+        assert!(!migrate(&mut newer).unwrap());
+        assert_eq!(newer["config_version"], CONFIG_VERSION + 1);
+    }
 }
@@ -0,0 +1,56 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::config::{get_config_path, load_settings};
+use crate::services::{events, i18n};
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Poll `config.json` and emit `settings:changed` with the new `AppSettings`
+/// whenever its contents change, whether saved by a window or edited by hand
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Ok(path) = get_config_path() else {
+            log::warn!("Config directory unknown, not watching settings");
+            return;
+        };
+        let stamp = |path: &std::path::Path| -> Option<(u64, Option<SystemTime>)> {
+            let metadata = std::fs::metadata(path).ok()?;
+            Some((metadata.len(), metadata.modified().ok()))
+        };
+
+        let mut last_stamp = stamp(&path);
+        let mut last_contents = std::fs::read_to_string(&path).ok();
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let current_stamp = stamp(&path);
+            if current_stamp == last_stamp {
+                continue;
+            }
+            last_stamp = current_stamp;
+
+            let contents = std::fs::read_to_string(&path).ok();
+            if contents == last_contents {
+                continue;
+            }
+
+            // A half-written hand edit is retried on the next change
+            match load_settings() {
+                Ok(settings) => {
+                    last_contents = contents;
+                    i18n::reload();
+                    log::info!("Settings file changed, notifying windows");
+                    let _ = events::emit(&app, "settings:changed", settings);
+                }
+                Err(err) => log::warn!("Ignoring invalid settings file change: {:#}", err),
+            }
+        }
+    });
+}
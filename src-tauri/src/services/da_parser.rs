@@ -0,0 +1,125 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::error::AppError;
+use crate::models::da::{DaEntry, DaFile};
+use std::fs;
+
+const DA_HEADER_MAGIC: &[u8] = b"MTK_DOWNLOAD_AGENT";
+const DA_ID_OFFSET: usize = 0x20;
+const DA_ID_LEN: usize = 0x40;
+const DA_VERSION_OFFSET: usize = 0x60;
+const DA_COUNT_OFFSET: usize = 0x68;
+const DA_ENTRIES_OFFSET: usize = 0x6C;
+const DA_ENTRY_MAGIC: u16 = 0xDADA;
+// Legacy (v3) containers use 0xD8-byte entries, V5/V6 use 0xDC
+const DA_ENTRY_SIZES: [usize; 2] = [0xDC, 0xD8];
+// Sanity limit; real containers carry a few dozen SoCs at most
+const DA_MAX_ENTRIES: u32 = 512;
+
+pub struct DaParser;
+
+impl DaParser {
+    /// Parse a DA container and list the SoC hardware codes it supports
+    pub fn parse(file_path: &str) -> Result<DaFile, AppError> {
+        let data = fs::read(file_path)
+            .map_err(|e| AppError::io(format!("Failed to read DA file: {}", e)))?;
+        Self::parse_bytes(&data, file_path)
+    }
+
+    fn parse_bytes(data: &[u8], file_path: &str) -> Result<DaFile, AppError> {
+        if data.len() < DA_ENTRIES_OFFSET || !data.starts_with(DA_HEADER_MAGIC) {
+            return Err(AppError::Parse("Not a MediaTek DA file (missing header)".to_string()));
+        }
+
+        let identifier = String::from_utf8_lossy(&data[DA_ID_OFFSET..DA_ID_OFFSET + DA_ID_LEN])
+            .trim_end_matches('\0')
+            .trim()
+            .to_string();
+        let version = read_u32(data, DA_VERSION_OFFSET).unwrap_or(0);
+        let count = read_u32(data, DA_COUNT_OFFSET).unwrap_or(0);
+
+        if count == 0 || count > DA_MAX_ENTRIES {
+            return Err(AppError::Parse(format!("Invalid DA entry count: {}", count)));
+        }
+
+        let entry_size = Self::detect_entry_size(data, count as usize).ok_or_else(|| {
+            AppError::Parse("Unsupported DA layout (entry magic not found)".to_string())
+        })?;
+
+        let mut entries = Vec::with_capacity(count as usize);
+        for i in 0..count as usize {
+            let offset = DA_ENTRIES_OFFSET + i * entry_size;
+            let field = |index: usize| read_u16(data, offset + index * 2).unwrap_or(0);
+            if field(0) != DA_ENTRY_MAGIC {
+                log::warn!("DA entry {} has invalid magic, stopping", i);
+                break;
+            }
+
+            entries.push(DaEntry {
+                hw_code: format!("{:#06x}", field(1)),
+                hw_sub_code: format!("{:#06x}", field(2)),
+                hw_version: format!("{:#06x}", field(3)),
+                sw_version: format!("{:#06x}", field(4)),
+            });
+        }
+
+        Ok(DaFile { identifier, version, entries, file_path: file_path.to_string() })
+    }
+
+    /// Pick the entry stride whose every entry starts with the DADA magic
+    fn detect_entry_size(data: &[u8], count: usize) -> Option<usize> {
+        DA_ENTRY_SIZES.iter().copied().find(|size| {
+            (0..count).all(|i| read_u16(data, DA_ENTRIES_OFFSET + i * size) == Some(DA_ENTRY_MAGIC))
+        })
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_da(entry_size: usize, hw_codes: &[u16]) -> Vec<u8> {
+        let mut data = vec![0u8; DA_ENTRIES_OFFSET + entry_size * hw_codes.len()];
+        data[..DA_HEADER_MAGIC.len()].copy_from_slice(DA_HEADER_MAGIC);
+        let id = b"MTK_AllInOne_DA_v3";
+        data[DA_ID_OFFSET..DA_ID_OFFSET + id.len()].copy_from_slice(id);
+        data[DA_VERSION_OFFSET..DA_VERSION_OFFSET + 4].copy_from_slice(&4u32.to_le_bytes());
+        data[DA_COUNT_OFFSET..DA_COUNT_OFFSET + 4]
+            .copy_from_slice(&(hw_codes.len() as u32).to_le_bytes());
+        for (i, code) in hw_codes.iter().enumerate() {
+            let offset = DA_ENTRIES_OFFSET + i * entry_size;
+            data[offset..offset + 2].copy_from_slice(&DA_ENTRY_MAGIC.to_le_bytes());
+            data[offset + 2..offset + 4].copy_from_slice(&code.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_da_entries() {
+        for entry_size in DA_ENTRY_SIZES {
+            let data = build_da(entry_size, &[0x0766, 0x0989]);
+            let da = DaParser::parse_bytes(&data, "da.bin").unwrap();
+            assert_eq!(da.identifier, "MTK_AllInOne_DA_v3");
+            assert_eq!(da.version, 4);
+            assert_eq!(da.entries.len(), 2);
+            assert_eq!(da.entries[0].hw_code, "0x0766");
+            assert_eq!(da.entries[1].hw_code, "0x0989");
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_da_file() {
+        assert!(DaParser::parse_bytes(&[0u8; 256], "random.bin").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Capacity of the volume holding a path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskSpace {
+    /// The existing directory that was measured
+    pub path: String,
+    pub total_bytes: u64,
+    /// Available to the current user
+    pub free_bytes: u64,
+}
+
+/// Space on the volume containing `path`. The path does not have to exist
+/// yet: the nearest existing ancestor is measured, so an output folder can be
+/// checked before it is created.
+pub fn query(path: &Path) -> Result<DiskSpace> {
+    let existing = nearest_existing(path)
+        .with_context(|| format!("No existing parent directory for {}", path.display()))?;
+    let (total_bytes, free_bytes) = volume_space(&existing)
+        .with_context(|| format!("Failed to query disk space for {}", existing.display()))?;
+    Ok(DiskSpace { path: existing.display().to_string(), total_bytes, free_bytes })
+}
+
+fn nearest_existing(path: &Path) -> Option<PathBuf> {
+    let absolute = std::path::absolute(path).ok()?;
+    absolute.ancestors().find(|ancestor| ancestor.exists()).map(Path::to_path_buf)
+}
+
+#[cfg(unix)]
+fn volume_space(path: &Path) -> Result<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let fragment = stat.f_frsize as u64;
+    Ok((stat.f_blocks as u64 * fragment, stat.f_bavail as u64 * fragment))
+}
+
+#[cfg(windows)]
+fn volume_space(path: &Path) -> Result<(u64, u64)> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetDiskFreeSpaceExW;
+    use winapi::um::winnt::ULARGE_INTEGER;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut free: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    let mut total: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    let ok =
+        unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut free, &mut total, std::ptr::null_mut()) };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(unsafe { (*total.QuadPart(), *free.QuadPart()) })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn volume_space(_path: &Path) -> Result<(u64, u64)> {
+    anyhow::bail!("Disk space is not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_nearest_existing_parent() {
+        let missing = std::env::temp_dir().join("penumbra-disk-space-test/not/created/yet");
+        let space = query(&missing).unwrap();
+        assert_eq!(PathBuf::from(&space.path), std::path::absolute(std::env::temp_dir()).unwrap());
+        assert!(space.total_bytes >= space.free_bytes);
+    }
+}
@@ -0,0 +1,114 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! The last output and progress events of each running operation, so a
+//! reloaded frontend can refill its console instead of starting blank.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Events kept per operation; older ones are dropped first
+const CAPACITY: usize = 500;
+
+/// Events worth replaying; completion and cancellation are covered by
+/// `list_running_operations`
+const REPLAYED_EVENTS: &[&str] =
+    &["operation:output", "operation:output_batch", "operation:progress"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayedEvent {
+    /// Increases with every recorded event; pass the last one seen as `since`
+    pub seq: u64,
+    pub event: String,
+    pub payload: Value,
+}
+
+#[derive(Default)]
+struct EventBuffer {
+    next_seq: u64,
+    events: VecDeque<ReplayedEvent>,
+}
+
+impl EventBuffer {
+    fn push(&mut self, event: &str, payload: Value) {
+        if self.events.len() == CAPACITY {
+            self.events.pop_front();
+        }
+        self.next_seq += 1;
+        self.events.push_back(ReplayedEvent {
+            seq: self.next_seq,
+            event: event.to_string(),
+            payload,
+        });
+    }
+
+    fn since(&self, since: u64) -> Vec<ReplayedEvent> {
+        self.events.iter().filter(|event| event.seq > since).cloned().collect()
+    }
+}
+
+static BUFFERS: Mutex<Option<HashMap<String, EventBuffer>>> = Mutex::new(None);
+
+/// Called for every emitted event; keeps the replayable ones
+pub fn record(event: &str, payload: &Value) {
+    if !REPLAYED_EVENTS.contains(&event) {
+        return;
+    }
+    let Some(operation_id) = payload.get("operation_id").and_then(Value::as_str) else {
+        return;
+    };
+    if let Ok(mut buffers) = BUFFERS.lock() {
+        buffers
+            .get_or_insert_with(HashMap::new)
+            .entry(operation_id.to_string())
+            .or_default()
+            .push(event, payload.clone());
+    }
+}
+
+/// Events of `operation_id` recorded after `since`, oldest first
+pub fn replay(operation_id: &str, since: Option<u64>) -> Vec<ReplayedEvent> {
+    let Ok(buffers) = BUFFERS.lock() else {
+        return Vec::new();
+    };
+    buffers
+        .as_ref()
+        .and_then(|buffers| buffers.get(operation_id))
+        .map(|buffer| buffer.since(since.unwrap_or(0)))
+        .unwrap_or_default()
+}
+
+/// Drop the buffer of a finished operation
+pub fn forget(operation_id: &str) {
+    if let Ok(mut buffers) = BUFFERS.lock() {
+        if let Some(buffers) = buffers.as_mut() {
+            buffers.remove(operation_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_the_newest_events() {
+        let mut buffer = EventBuffer::default();
+        for i in 0..CAPACITY + 10 {
+            buffer.push("operation:output", json!({ "line": i }));
+        }
+
+        let events = buffer.since(0);
+        assert_eq!(events.len(), CAPACITY);
+        assert_eq!(events[0].seq, 11);
+        assert_eq!(events[0].payload["line"], 10);
+
+        let tail = buffer.since(CAPACITY as u64 + 8);
+        assert_eq!(tail.iter().map(|event| event.seq).collect::<Vec<_>>(), [509, 510]);
+    }
+}
@@ -0,0 +1,134 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::SCHEMA_VERSION;
+use crate::services::{event_replay, structured_log};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Operation IDs each subscribed window wants, by window label; `None`
+/// means every operation. Windows that never subscribed get everything.
+static SUBSCRIPTIONS: Mutex<Option<HashMap<String, Option<HashSet<String>>>>> = Mutex::new(None);
+
+/// Emit `event` with the schema version, the session ID, and the current
+/// operation ID when the payload lacks one, added to object payloads. Events
+/// of an operation only reach the windows interested in it.
+pub fn emit<S: Serialize>(app: &AppHandle, event: &str, payload: S) -> tauri::Result<()> {
+    let mut payload = serde_json::to_value(payload)?;
+    tag(&mut payload, structured_log::session_id(), structured_log::current_operation());
+    event_replay::record(event, &payload);
+
+    let operation_id = payload.get("operation_id").and_then(Value::as_str);
+    let targets = SUBSCRIPTIONS.lock().ok().and_then(|subscriptions| {
+        let subscriptions = subscriptions.as_ref().filter(|s| !s.is_empty())?;
+        let labels = app.webview_windows().into_keys();
+        Some(route(subscriptions, labels, operation_id?))
+    });
+    match targets {
+        Some(labels) => {
+            for label in labels {
+                app.emit_to(label.as_str(), event, &payload)?;
+            }
+            Ok(())
+        }
+        None => app.emit(event, payload),
+    }
+}
+
+/// Only send operation events to `label` from now on: those of
+/// `operation_ids`, or of every operation when `None`
+pub fn subscribe(label: &str, operation_ids: Option<Vec<String>>) {
+    if let Ok(mut subscriptions) = SUBSCRIPTIONS.lock() {
+        let operation_ids = operation_ids.map(|ids| ids.into_iter().collect());
+        subscriptions.get_or_insert_with(HashMap::new).insert(label.to_string(), operation_ids);
+    }
+}
+
+/// Stop sending operation events to `label` at all; the window can still
+/// catch up with `replay_operation_events`
+pub fn unsubscribe(label: &str) {
+    subscribe(label, Some(Vec::new()));
+}
+
+/// Forget a closed window, which gets everything again if it is reopened
+pub fn forget_window(label: &str) {
+    if let Ok(mut subscriptions) = SUBSCRIPTIONS.lock() {
+        if let Some(subscriptions) = subscriptions.as_mut() {
+            subscriptions.remove(label);
+        }
+    }
+}
+
+/// Windows among `labels` that should receive events of `operation_id`
+fn route(
+    subscriptions: &HashMap<String, Option<HashSet<String>>>,
+    labels: impl IntoIterator<Item = String>,
+    operation_id: &str,
+) -> Vec<String> {
+    labels
+        .into_iter()
+        .filter(|label| match subscriptions.get(label) {
+            Some(Some(operation_ids)) => operation_ids.contains(operation_id),
+            Some(None) | None => true,
+        })
+        .collect()
+}
+
+fn tag(payload: &mut Value, session_id: &str, operation_id: Option<String>) {
+    let Value::Object(fields) = payload else {
+        return;
+    };
+    fields.entry("schema_version").or_insert_with(|| SCHEMA_VERSION.into());
+    fields.entry("session_id").or_insert_with(|| session_id.into());
+    if let Some(operation_id) = operation_id {
+        if !matches!(fields.get("operation_id"), Some(Value::String(_))) {
+            fields.insert("operation_id".into(), operation_id.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tags_object_payloads_only() {
+        let mut payload = json!({ "operation_id": "op-1", "line": "hi" });
+        tag(&mut payload, "abcd1234", Some("op-2".into()));
+        assert_eq!(
+            payload,
+            json!({
+                "operation_id": "op-1",
+                "line": "hi",
+                "session_id": "abcd1234",
+                "schema_version": SCHEMA_VERSION,
+            })
+        );
+
+        let mut payload = json!({ "operation_id": null });
+        tag(&mut payload, "abcd1234", Some("op-2".into()));
+        assert_eq!(payload["operation_id"], "op-2");
+
+        let mut payload = json!(["a", "b"]);
+        tag(&mut payload, "abcd1234", None);
+        assert_eq!(payload, json!(["a", "b"]));
+    }
+
+    #[test]
+    fn routes_operation_events_to_subscribed_windows() {
+        let subscriptions = HashMap::from([
+            ("console".to_string(), Some(HashSet::from(["op-1".to_string()]))),
+            ("monitor".to_string(), None),
+        ]);
+        let labels = || ["main", "console", "monitor"].map(String::from);
+
+        assert_eq!(route(&subscriptions, labels(), "op-1"), ["main", "console", "monitor"]);
+        assert_eq!(route(&subscriptions, labels(), "op-2"), ["main", "monitor"]);
+    }
+}
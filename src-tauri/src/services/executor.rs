@@ -0,0 +1,48 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::Partition;
+use crate::services::antumbra::AntumbraExecutor;
+use crate::services::config::{load_settings, ToolBackend};
+use crate::services::mock_executor::MockExecutor;
+use crate::services::mtkclient::MtkClientExecutor;
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use tauri::AppHandle;
+
+/// Runs antumbra commands and streams their output as operation events
+pub trait CommandExecutor: Send + Sync {
+    /// Execute a command, emitting `operation:*` events, and return its stdout
+    fn execute_streaming(
+        &self,
+        app: AppHandle,
+        operation_id: String,
+        args: Vec<String>,
+    ) -> BoxFuture<'_, Result<String>>;
+
+    fn get_version(&self) -> Result<String>;
+
+    /// Partitions from the output of `pgpt`, for backends that print them
+    /// differently than antumbra; `None` uses antumbra's format
+    fn parse_partitions(&self, _output: &str) -> Option<Vec<Partition>> {
+        None
+    }
+}
+
+/// Pick the executor for device commands: the configured backend, or
+/// canned output when `simulation_mode` is enabled
+pub fn create_executor(app: &AppHandle) -> Result<Box<dyn CommandExecutor>> {
+    let settings = load_settings().unwrap_or_default();
+    if settings.simulation_mode {
+        log::info!("Simulation mode enabled, using mock executor");
+        return Ok(Box::new(MockExecutor::new()));
+    }
+    match settings.backend {
+        ToolBackend::Antumbra => Ok(Box::new(AntumbraExecutor::new(app)?)),
+        ToolBackend::Mtkclient => {
+            Ok(Box::new(MtkClientExecutor::new(settings.mtkclient_path.as_deref())?))
+        }
+    }
+}
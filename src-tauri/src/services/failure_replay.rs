@@ -0,0 +1,196 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Re-run the last failed antumbra command with verbose logging and collect
+//! everything support asks for into one troubleshooting log.
+
+use crate::services::antumbra::{AntumbraCommandInfo, AntumbraExecutor};
+use crate::services::{command_history, operation_registry, paths, redaction, structured_log};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::AppHandle;
+
+/// Environment added to the replayed process for maximum detail
+const VERBOSE_ENV: &[(&str, &str)] = &[("RUST_LOG", "trace"), ("RUST_BACKTRACE", "full")];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayReport {
+    /// The history entry that was replayed
+    pub original: AntumbraCommandInfo,
+    pub dry_run: bool,
+    pub command_line: String,
+    /// The troubleshooting log that was written
+    pub log_path: String,
+    pub exit_code: Option<i32>,
+    pub success: Option<bool>,
+    pub duration_ms: Option<u64>,
+}
+
+/// Most recent failed command in the history
+pub fn last_failure() -> Option<AntumbraCommandInfo> {
+    command_history::all().into_iter().rev().find(|entry| entry.success == Some(false))
+}
+
+/// Replaying a device-modifying command honors safe mode
+pub fn is_write(entry: &AntumbraCommandInfo) -> bool {
+    operation_registry::writes_device(&entry.args)
+}
+
+/// Replay `entry` with `VERBOSE_ENV`, or with `dry_run` only describe what
+/// would run, and write the troubleshooting log
+pub async fn replay(
+    app: &AppHandle,
+    entry: AntumbraCommandInfo,
+    dry_run: bool,
+) -> Result<ReplayReport> {
+    let mut log = header(&entry, dry_run);
+    let mut report = ReplayReport {
+        command_line: command_line(&entry),
+        original: entry,
+        dry_run,
+        log_path: String::new(),
+        exit_code: None,
+        success: None,
+        duration_ms: None,
+    };
+
+    if !dry_run {
+        let operation_id = format!("replay-{}", uuid::Uuid::new_v4());
+        let antumbra_log = paths::antumbra_log_file().ok();
+        let antumbra_log_offset = antumbra_log.as_deref().map(file_len).unwrap_or(0);
+
+        // Queued, registered and cancellable like any other operation
+        log::info!("Replaying failed command: {}", report.command_line);
+        let entry = &report.original;
+        let executor = AntumbraExecutor::with_binary(
+            PathBuf::from(&entry.command),
+            PathBuf::from(&entry.working_dir),
+        )
+        .with_envs(VERBOSE_ENV);
+        let started = Instant::now();
+        let output = executor.execute_captured(app.clone(), operation_id, entry.args.clone()).await;
+        report.duration_ms = Some(started.elapsed().as_millis() as u64);
+
+        match output {
+            Ok(output) => {
+                report.exit_code = output.exit_code;
+                report.success = Some(output.success);
+                log.push_str(&format!(
+                    "\n== Exit code: {:?} after {} ms ==\n",
+                    report.exit_code,
+                    report.duration_ms.unwrap_or_default()
+                ));
+                section(&mut log, "stdout", &output.stdout);
+                section(&mut log, "stderr", &output.stderr);
+            }
+            Err(err) => log.push_str(&format!("\n== Replay failed: {:#} ==\n", err)),
+        }
+        if let Some(path) = antumbra_log {
+            section(&mut log, "antumbra.log", &read_from(&path, antumbra_log_offset));
+        }
+    }
+
+    let path = log_path();
+    std::fs::write(&path, redaction::redact_for_export(&log))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    log::info!("Wrote troubleshooting log {}", path.display());
+    report.log_path = path.display().to_string();
+    Ok(report)
+}
+
+fn header(entry: &AntumbraCommandInfo, dry_run: bool) -> String {
+    let mut log = format!(
+        "penumbra-wrapper {} troubleshooting log\nGenerated: {}\nSession: {}\nOS: {} {}\n",
+        env!("CARGO_PKG_VERSION"),
+        chrono::Utc::now().to_rfc3339(),
+        structured_log::session_id(),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    log.push_str(&format!("Mode: {}\n", if dry_run { "dry run (not executed)" } else { "replay" }));
+    log.push_str(&format!("Command: {}\n", command_line(entry)));
+    log.push_str(&format!("Working directory: {}\n", entry.working_dir));
+    let env: Vec<String> =
+        VERBOSE_ENV.iter().map(|(key, value)| format!("{key}={value}")).collect();
+    log.push_str(&format!("Environment: {}\n", env.join(" ")));
+    section(&mut log, "Original run", &serde_json::to_string_pretty(entry).unwrap_or_default());
+    log
+}
+
+fn section(log: &mut String, title: &str, contents: &str) {
+    log.push_str(&format!("\n===== {} =====\n", title));
+    log.push_str(contents);
+    if !log.ends_with('\n') {
+        log.push('\n');
+    }
+}
+
+fn command_line(entry: &AntumbraCommandInfo) -> String {
+    std::iter::once(&entry.command)
+        .chain(&entry.args)
+        .map(|arg| if arg.contains(' ') { format!("\"{}\"", arg) } else { arg.clone() })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn log_path() -> PathBuf {
+    let name = format!("replay-{}.log", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    paths::wrapper_log_file().with_file_name(name)
+}
+
+fn file_len(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0)
+}
+
+/// Everything appended to `path` after `offset`
+fn read_from(path: &Path, offset: u64) -> String {
+    let mut contents = Vec::new();
+    let read = std::fs::File::open(path).and_then(|mut file| {
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_to_end(&mut contents)
+    });
+    match read {
+        Ok(_) => String::from_utf8_lossy(&contents).into_owned(),
+        Err(err) => format!("(unavailable: {})", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(args: &[&str], success: Option<bool>) -> AntumbraCommandInfo {
+        AntumbraCommandInfo {
+            id: String::new(),
+            command: "/opt/antumbra".into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            working_dir: "/opt".into(),
+            started_at: "2025-01-01T10:00:00+00:00".into(),
+            operation_id: None,
+            finished_at: None,
+            duration_ms: None,
+            exit_code: None,
+            success,
+            bytes: None,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn dry_run_header_describes_command() {
+        let failed = entry(&["upload", "boot", "/out/my boot.img"], Some(false));
+        assert!(!is_write(&failed));
+        assert!(is_write(&entry(&["erase", "userdata"], None)));
+
+        let log = header(&failed, true);
+        assert!(log.contains("Command: /opt/antumbra upload boot \"/out/my boot.img\"\n"));
+        assert!(log.contains("Mode: dry run (not executed)\n"));
+        assert!(log.contains("Environment: RUST_LOG=trace RUST_BACKTRACE=full\n"));
+        assert!(log.contains("===== Original run ====="));
+    }
+}
@@ -0,0 +1,114 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! The platform `fastboot` binary, when installed, for steps that follow a
+//! BROM flash such as `fastboot flashing unlock` or `fastboot -w`.
+
+use crate::services::antumbra::AntumbraExecutor;
+use crate::services::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FastbootCliStatus {
+    pub path: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Post-flash steps the wrapper knows how to run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FastbootStep {
+    FlashingUnlock,
+    FlashingLock,
+    /// `fastboot -w`: erase userdata and cache
+    Wipe,
+    Reboot,
+    RebootBootloader,
+}
+
+impl FastbootStep {
+    /// Unlocking, locking and wiping erase user data, so safe mode blocks them
+    pub fn is_write(self) -> bool {
+        !matches!(self, FastbootStep::Reboot | FastbootStep::RebootBootloader)
+    }
+
+    /// Arguments for `fastboot [-s <serial>] <step>`
+    pub fn args(self, serial: Option<&str>) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(serial) = serial {
+            args.extend(["-s".to_string(), serial.to_string()]);
+        }
+        let step: &[&str] = match self {
+            FastbootStep::FlashingUnlock => &["flashing", "unlock"],
+            FastbootStep::FlashingLock => &["flashing", "lock"],
+            FastbootStep::Wipe => &["-w"],
+            FastbootStep::Reboot => &["reboot"],
+            FastbootStep::RebootBootloader => &["reboot", "bootloader"],
+        };
+        args.extend(step.iter().map(|arg| arg.to_string()));
+        args
+    }
+}
+
+/// `fastboot` from PATH, if installed
+pub fn find() -> Option<PathBuf> {
+    let name = if cfg!(windows) { "fastboot.exe" } else { "fastboot" };
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(name)).find(|candidate| candidate.is_file())
+}
+
+/// `fastboot` from PATH, or an error telling the user to install it
+pub fn binary() -> Result<PathBuf> {
+    find().context("fastboot was not found on PATH; install Android platform-tools")
+}
+
+pub fn status() -> FastbootCliStatus {
+    let path = find();
+    let version = path.as_ref().and_then(|fastboot| {
+        let mut command = std::process::Command::new(fastboot);
+        #[cfg(windows)]
+        std::os::windows::process::CommandExt::creation_flags(&mut command, CREATE_NO_WINDOW);
+        let output = command.arg("--version").output().ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout.lines().next().map(|line| line.trim().to_string())
+    });
+    FastbootCliStatus { path: path.map(|path| path.to_string_lossy().to_string()), version }
+}
+
+/// Run `step`, streaming fastboot's output as operation events. fastboot
+/// waits for the device itself, so this can be started before it reboots
+/// into fastboot mode.
+pub async fn run(
+    app: AppHandle,
+    operation_id: String,
+    step: FastbootStep,
+    serial: Option<&str>,
+) -> Result<String> {
+    let executor = AntumbraExecutor::with_binary(binary()?, paths::config_dir()?);
+    executor.execute_streaming(app, operation_id, step.args(serial)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_step_arguments() {
+        assert_eq!(FastbootStep::Wipe.args(None), ["-w"]);
+        assert_eq!(
+            FastbootStep::FlashingUnlock.args(Some("0123")),
+            ["-s", "0123", "flashing", "unlock"]
+        );
+        assert!(FastbootStep::FlashingLock.is_write());
+        assert!(!FastbootStep::RebootBootloader.is_write());
+    }
+}
@@ -0,0 +1,219 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Checksums of local files, e.g. to check a downloaded firmware image
+//! against the hash its vendor published before flashing it.
+
+use crate::models::HashProgressEvent;
+use crate::services::events;
+use anyhow::{Context, Result};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Minimum time between two `hash:progress` events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha256,
+    Md5,
+    Crc32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHash {
+    pub path: String,
+    pub algorithm: HashAlgorithm,
+    /// Lowercase hex
+    pub hash: String,
+    pub size: u64,
+    pub duration_ms: u64,
+    /// Whether `hash` equals the expected one, when one was given
+    pub matches: Option<bool>,
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Md5(Md5),
+    Crc32(crc32fast::Hasher),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+            HashAlgorithm::Crc32 => Hasher::Crc32(crc32fast::Hasher::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Md5(hasher) => hasher.update(data),
+            Hasher::Crc32(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finish(self) -> String {
+        match self {
+            Hasher::Sha256(hasher) => hex::encode(hasher.finalize()),
+            Hasher::Md5(hasher) => hex::encode(hasher.finalize()),
+            Hasher::Crc32(hasher) => format!("{:08x}", hasher.finalize()),
+        }
+    }
+}
+
+/// Hash everything `reader` yields, calling `on_progress` with the bytes read
+/// so far after every chunk
+fn hash_reader(
+    mut reader: impl Read,
+    algorithm: HashAlgorithm,
+    mut on_progress: impl FnMut(u64),
+) -> Result<String> {
+    let mut hasher = Hasher::new(algorithm);
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut hashed = 0u64;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        hasher.update(&buffer[..read]);
+        hashed += read as u64;
+        on_progress(hashed);
+    }
+    Ok(hasher.finish())
+}
+
+/// Hash `path`, emitting `hash:progress` while it is read. Blocks, so run it
+/// on a blocking thread.
+pub fn hash_file(
+    app: &AppHandle,
+    operation_id: &str,
+    path: &Path,
+    algorithm: HashAlgorithm,
+    expected: Option<&str>,
+) -> Result<FileHash> {
+    let started = Instant::now();
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let total_bytes = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+    let progress = |bytes_hashed: u64| HashProgressEvent {
+        operation_id: operation_id.to_string(),
+        path: path.display().to_string(),
+        bytes_hashed,
+        total_bytes,
+        percentage: if total_bytes == 0 {
+            100.0
+        } else {
+            (bytes_hashed as f64 / total_bytes as f64 * 100.0) as f32
+        },
+    };
+    let mut last_emit = Instant::now();
+    let hash = hash_reader(file, algorithm, |bytes_hashed| {
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            last_emit = Instant::now();
+            let _ = events::emit(app, "hash:progress", progress(bytes_hashed));
+        }
+    })
+    .with_context(|| format!("Failed to read {}", path.display()))?;
+    let _ = events::emit(app, "hash:progress", progress(total_bytes));
+
+    let matches = expected.map(|expected| normalize(expected) == hash);
+    Ok(FileHash {
+        path: path.display().to_string(),
+        algorithm,
+        hash,
+        size: total_bytes,
+        duration_ms: started.elapsed().as_millis() as u64,
+        matches,
+    })
+}
+
+/// Hash `path` without reporting progress
+pub fn hash_path(path: &Path, algorithm: HashAlgorithm) -> Result<String> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    hash_reader(file, algorithm, |_| {})
+        .with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// Hash every file of `paths` with at most `concurrency` read at once.
+/// Results are in the order of `paths`.
+pub async fn hash_paths(
+    paths: Vec<String>,
+    algorithm: HashAlgorithm,
+    concurrency: usize,
+) -> Vec<Result<String>> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+    for (index, path) in paths.iter().cloned().enumerate() {
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result =
+                tokio::task::spawn_blocking(move || hash_path(Path::new(&path), algorithm)).await;
+            (index, result.unwrap_or_else(|err| Err(err.into())))
+        });
+    }
+
+    let mut results: Vec<Result<String>> =
+        paths.iter().map(|path| Err(anyhow::anyhow!("{} was not hashed", path))).collect();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, result)) = joined {
+            results[index] = result;
+        }
+    }
+    results
+}
+
+/// Published hashes come in upper case, with `0x` or spaces between bytes
+fn normalize(hash: &str) -> String {
+    let hash = hash.trim();
+    let hash = hash.strip_prefix("0x").or_else(|| hash.strip_prefix("0X")).unwrap_or(hash);
+    hash.chars().filter(|c| !c.is_whitespace() && *c != ':').collect::<String>().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_with_each_algorithm() {
+        let hash = |algorithm| hash_reader(&b"abc"[..], algorithm, |_| {}).unwrap();
+        assert_eq!(
+            hash(HashAlgorithm::Sha256),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(hash(HashAlgorithm::Md5), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hash(HashAlgorithm::Crc32), "352441c2");
+    }
+
+    #[test]
+    fn reports_progress_and_normalizes_expected_hashes() {
+        let data = vec![0u8; CHUNK_SIZE + 10];
+        let mut progress = Vec::new();
+        hash_reader(&data[..], HashAlgorithm::Crc32, |bytes| progress.push(bytes)).unwrap();
+        assert_eq!(progress, [CHUNK_SIZE as u64, CHUNK_SIZE as u64 + 10]);
+
+        assert_eq!(normalize(" 0x352441C2 "), "352441c2");
+        assert_eq!(normalize("90:01:50 98"), "90015098");
+    }
+}
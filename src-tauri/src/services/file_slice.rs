@@ -0,0 +1,69 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Byte ranges of local files for the hex viewer, e.g. to look inside a
+//! partition dump without a separate tool.
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Largest slice returned at once; the viewer pages through bigger files
+pub const MAX_SLICE_LENGTH: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSlice {
+    pub offset: u64,
+    /// Bytes actually returned; less than asked for near the end of the file
+    pub length: u64,
+    pub file_size: u64,
+    /// Base64 of the bytes
+    pub data: String,
+}
+
+/// Read up to `length` bytes of `path` starting at `offset`
+pub fn read_slice(path: &Path, offset: u64, length: u64) -> Result<FileSlice> {
+    if length > MAX_SLICE_LENGTH {
+        anyhow::bail!("Slices are limited to {} bytes", MAX_SLICE_LENGTH);
+    }
+    let mut file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let file_size = file.metadata()?.len();
+    if offset > file_size {
+        anyhow::bail!("Offset {} is past the end of the file ({} bytes)", offset, file_size);
+    }
+
+    file.seek(SeekFrom::Start(offset))?;
+    let mut data = Vec::with_capacity(length.min(file_size - offset) as usize);
+    file.take(length)
+        .read_to_end(&mut data)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(FileSlice { offset, length: data.len() as u64, file_size, data: STANDARD.encode(&data) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_slices_within_the_file() {
+        let path = std::env::temp_dir().join(format!("file-slice-{}.bin", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let slice = read_slice(&path, 2, 4).unwrap();
+        assert_eq!((slice.length, slice.file_size), (4, 10));
+        assert_eq!(STANDARD.decode(&slice.data).unwrap(), b"2345");
+
+        let tail = read_slice(&path, 8, 16).unwrap();
+        assert_eq!(STANDARD.decode(&tail.data).unwrap(), b"89");
+        assert_eq!(read_slice(&path, 10, 4).unwrap().length, 0);
+        assert!(read_slice(&path, 11, 4).is_err());
+        assert!(read_slice(&path, 0, MAX_SLICE_LENGTH + 1).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,123 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Shared HTTP configuration. Every request to GitHub or a mirror goes
+//! through these builders so proxy, timeouts, user agent and TLS options
+//! behave the same everywhere.
+
+use crate::services::config::{load_settings, AppSettings};
+use crate::services::secrets;
+use std::time::Duration;
+
+/// Network options from settings, resolved once per client
+#[derive(Debug, Clone)]
+pub struct NetworkSettings {
+    pub proxy: Option<String>,
+    pub user_agent: String,
+    pub connect_timeout: Duration,
+    pub read_timeout: Duration,
+    pub ca_certificate_path: Option<String>,
+    pub accept_invalid_certs: bool,
+}
+
+impl NetworkSettings {
+    pub fn load() -> Self {
+        Self::from_settings(&load_settings().unwrap_or_default())
+    }
+
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        let non_empty = |value: &Option<String>| {
+            value.as_deref().map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+        };
+        Self {
+            proxy: non_empty(&settings.proxy_url),
+            user_agent: non_empty(&settings.http_user_agent).unwrap_or_else(default_user_agent),
+            connect_timeout: Duration::from_secs(settings.http_connect_timeout_secs.max(1)),
+            read_timeout: Duration::from_secs(settings.http_read_timeout_secs.max(1)),
+            ca_certificate_path: non_empty(&settings.http_ca_certificate_path),
+            accept_invalid_certs: settings.http_accept_invalid_certs,
+        }
+    }
+
+    fn explicit_proxy(&self) -> Option<reqwest::Proxy> {
+        let url = self.proxy.as_deref()?;
+        match reqwest::Proxy::all(url) {
+            Ok(proxy) => Some(proxy),
+            Err(err) => {
+                log::warn!("Ignoring invalid proxy URL {:?}: {}", url, err);
+                None
+            }
+        }
+    }
+
+    fn ca_certificate(&self) -> Option<reqwest::Certificate> {
+        let path = self.ca_certificate_path.as_deref()?;
+        let certificate = std::fs::read(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|pem| Ok(reqwest::Certificate::from_pem(&pem)?));
+        match certificate {
+            Ok(certificate) => Some(certificate),
+            Err(err) => {
+                log::warn!("Ignoring CA certificate {:?}: {}", path, err);
+                None
+            }
+        }
+    }
+}
+
+pub fn default_user_agent() -> String {
+    format!("penumbra-wrapper/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Async client builder with the configured network options. Without an
+/// explicit proxy, reqwest picks up the system proxy (HTTP_PROXY/HTTPS_PROXY,
+/// or the OS settings).
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let network = NetworkSettings::load();
+    let mut builder = reqwest::Client::builder()
+        .user_agent(network.user_agent.clone())
+        .connect_timeout(network.connect_timeout)
+        .read_timeout(network.read_timeout);
+    if let Some(proxy) = network.explicit_proxy() {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(certificate) = network.ca_certificate() {
+        builder = builder.add_root_certificate(certificate);
+    }
+    if network.accept_invalid_certs {
+        log::warn!("TLS certificate verification is disabled in settings");
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    builder
+}
+
+/// Blocking counterpart of `client_builder`. Blocking clients have no
+/// per-read timeout, so callers set an overall `timeout` themselves.
+pub fn blocking_client_builder() -> reqwest::blocking::ClientBuilder {
+    let network = NetworkSettings::load();
+    let mut builder = reqwest::blocking::Client::builder()
+        .user_agent(network.user_agent.clone())
+        .connect_timeout(network.connect_timeout);
+    if let Some(proxy) = network.explicit_proxy() {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(certificate) = network.ca_certificate() {
+        builder = builder.add_root_certificate(certificate);
+    }
+    if network.accept_invalid_certs {
+        log::warn!("TLS certificate verification is disabled in settings");
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    builder
+}
+
+/// Authenticate a GitHub API request with the configured token, if any.
+/// Unauthenticated requests share a small per-IP rate limit.
+pub fn with_github_auth(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match secrets::github_token() {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
@@ -0,0 +1,152 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Message catalog for user-facing backend text. Catalogs are flat JSON maps
+//! in `src-tauri/locales/`, with `{name}` placeholders filled by `t_with`.
+
+use crate::services::config::load_settings;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+const FALLBACK_LOCALE: &str = "en";
+
+/// Bundled catalogs; English must contain every key
+const CATALOG_SOURCES: &[(&str, &str)] = &[
+    ("en", include_str!("../../locales/en.json")),
+    ("es", include_str!("../../locales/es.json")),
+    ("ru", include_str!("../../locales/ru.json")),
+];
+
+type Catalog = HashMap<String, String>;
+
+static CATALOGS: OnceLock<HashMap<&'static str, Catalog>> = OnceLock::new();
+
+/// The resolved locale, refreshed by `reload` when settings change
+static LOCALE: RwLock<Option<&'static str>> = RwLock::new(None);
+
+fn catalogs() -> &'static HashMap<&'static str, Catalog> {
+    CATALOGS.get_or_init(|| {
+        CATALOG_SOURCES
+            .iter()
+            .filter_map(|(code, source)| match serde_json::from_str(source) {
+                Ok(catalog) => Some((*code, catalog)),
+                Err(err) => {
+                    log::error!("Invalid {} message catalog: {}", code, err);
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+/// Locale codes with a bundled catalog
+pub fn available_locales() -> Vec<&'static str> {
+    CATALOG_SOURCES.iter().map(|(code, _)| *code).collect()
+}
+
+/// Re-read the `locale` setting, falling back to the system language
+pub fn reload() {
+    let locale = resolve_locale();
+    if let Ok(mut current) = LOCALE.write() {
+        *current = Some(locale);
+    }
+}
+
+fn current_locale() -> &'static str {
+    // Tests assert on English text regardless of the developer's settings
+    if cfg!(test) {
+        return FALLBACK_LOCALE;
+    }
+    if let Some(locale) = LOCALE.read().ok().and_then(|current| *current) {
+        return locale;
+    }
+    reload();
+    LOCALE.read().ok().and_then(|current| *current).unwrap_or(FALLBACK_LOCALE)
+}
+
+fn resolve_locale() -> &'static str {
+    let configured = load_settings()
+        .ok()
+        .and_then(|settings| settings.locale)
+        .filter(|locale| !locale.trim().is_empty());
+    configured
+        .or_else(system_locale)
+        .and_then(|locale| match_locale(&locale))
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+/// Map "es_ES.UTF-8", "pt-BR" or "ru" to a bundled catalog: the full tag first,
+/// then the language alone
+fn match_locale(requested: &str) -> Option<&'static str> {
+    let tag =
+        requested.split(['.', '@']).next().unwrap_or_default().replace('_', "-").to_lowercase();
+    let language = tag.split('-').next().unwrap_or_default().to_string();
+    [tag, language]
+        .into_iter()
+        .find_map(|candidate| available_locales().into_iter().find(|code| *code == candidate))
+}
+
+fn lookup(locale: &str, key: &str) -> String {
+    let catalogs = catalogs();
+    [locale, FALLBACK_LOCALE]
+        .iter()
+        .find_map(|code| catalogs.get(code).and_then(|catalog| catalog.get(key)))
+        .cloned()
+        .unwrap_or_else(|| {
+            log::debug!("Missing message catalog key {}", key);
+            key.to_string()
+        })
+}
+
+/// The message for `key` in the current locale
+pub fn t(key: &str) -> String {
+    lookup(current_locale(), key)
+}
+
+/// The message for `key` with each `{name}` placeholder replaced
+pub fn t_with(key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    let mut message = t(key);
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogs_cover_every_english_key() {
+        let english = &catalogs()[FALLBACK_LOCALE];
+        for code in available_locales() {
+            let catalog = catalogs().get(code).expect("catalog parses");
+            for key in english.keys() {
+                assert!(catalog.contains_key(key), "{} is missing {}", code, key);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_system_style_locales() {
+        assert_eq!(match_locale("es_ES.UTF-8"), Some("es"));
+        assert_eq!(match_locale("ru"), Some("ru"));
+        assert_eq!(match_locale("xx_YY"), None);
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        let message = t_with("update.attempt", &[("attempt", &2), ("max", &3)]);
+        assert_eq!(message, "Download attempt 2/3...");
+        assert_eq!(lookup("es", "update.cancelled"), "Descarga cancelada");
+    }
+}
@@ -0,0 +1,189 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use serde::Serialize;
+
+// Scores, highest wins. Exact matches keep the historical priority order.
+const SCORE_SCATTER_FILE_NAME: u32 = 100;
+const SCORE_EXACT_IMG: u32 = 90;
+const SCORE_EXACT_BIN: u32 = 85;
+const SCORE_NORMALIZED: u32 = 70;
+const SCORE_SLOTLESS: u32 = 60;
+const SCORE_NUMBERED_SLOT: u32 = 50;
+const SCORE_NUMBERED: u32 = 30;
+
+const IMAGE_EXTENSIONS: [&str; 2] = [".img", ".bin"];
+// Vendor decorations between the partition name and the extension
+const STEM_SUFFIXES: [&str; 15] = [
+    ".ext4",
+    ".sparse",
+    ".raw",
+    ".signed",
+    "-verified",
+    "_verified",
+    "-signed",
+    "_signed",
+    "-sign",
+    "_sign",
+    "-sparse",
+    "_sparse",
+    "-raw",
+    "_raw",
+    "_unsparse",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageCandidate {
+    pub file: String, // Relative to the scatter directory, e.g. "images/boot.img"
+    pub score: u32,
+}
+
+pub struct ImageMatcher;
+
+impl ImageMatcher {
+    /// Rank every file that could belong to `partition_name`, best first.
+    /// Ties keep the order of `files`.
+    pub fn candidates(
+        partition_name: &str,
+        scatter_file_name: Option<&str>,
+        files: &[String],
+    ) -> Vec<ImageCandidate> {
+        let mut candidates: Vec<ImageCandidate> = files
+            .iter()
+            .filter_map(|file| {
+                Self::score(partition_name, scatter_file_name, file)
+                    .map(|score| ImageCandidate { file: file.clone(), score })
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.score.cmp(&a.score));
+        candidates
+    }
+
+    /// True when the two best candidates share the top score
+    pub fn is_ambiguous(candidates: &[ImageCandidate]) -> bool {
+        matches!(candidates, [first, second, ..] if first.score == second.score)
+    }
+
+    /// Score how well `file` matches a partition (case-insensitive)
+    pub fn score(partition_name: &str, scatter_file_name: Option<&str>, file: &str) -> Option<u32> {
+        let partition = partition_name.to_lowercase();
+        let file_lower = file.to_lowercase();
+        let base = file_lower.rsplit('/').next().unwrap_or(&file_lower);
+
+        if let Some(sf_name) = scatter_file_name.map(str::to_lowercase) {
+            if !sf_name.is_empty() && sf_name != "none" && base == sf_name {
+                return Some(SCORE_SCATTER_FILE_NAME);
+            }
+        }
+
+        if base == format!("{}.img", partition) {
+            return Some(SCORE_EXACT_IMG);
+        }
+        if base == format!("{}.bin", partition) {
+            return Some(SCORE_EXACT_BIN);
+        }
+
+        let stem = IMAGE_EXTENSIONS.iter().find_map(|ext| base.strip_suffix(ext))?;
+        let stem = Self::normalize_stem(stem);
+        if stem.is_empty() {
+            return None;
+        }
+
+        if stem == partition {
+            return Some(SCORE_NORMALIZED);
+        }
+
+        let (partition_base, partition_slot) = Self::split_slot(&partition);
+        if partition_slot.is_some() && stem == partition_base {
+            return Some(SCORE_SLOTLESS);
+        }
+
+        // Numbered images such as tee1.img/tee2.img map to tee_a/tee_b
+        let digits_start = stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        if digits_start == stem.len() {
+            return None;
+        }
+        let stem_base = stem[..digits_start].trim_end_matches(['_', '-']);
+        if stem_base != partition_base {
+            return None;
+        }
+
+        let number = &stem[digits_start..];
+        match partition_slot {
+            Some(slot) if Self::slot_for_number(number) == Some(slot) => Some(SCORE_NUMBERED_SLOT),
+            Some(_) => None,
+            None => Some(SCORE_NUMBERED),
+        }
+    }
+
+    fn normalize_stem(stem: &str) -> &str {
+        let mut stem = stem;
+        while let Some(stripped) = STEM_SUFFIXES.iter().find_map(|suffix| stem.strip_suffix(suffix))
+        {
+            stem = stripped;
+        }
+        stem
+    }
+
+    fn split_slot(partition: &str) -> (&str, Option<char>) {
+        if let Some(base) = partition.strip_suffix("_a") {
+            (base, Some('a'))
+        } else if let Some(base) = partition.strip_suffix("_b") {
+            (base, Some('b'))
+        } else {
+            (partition, None)
+        }
+    }
+
+    fn slot_for_number(number: &str) -> Option<char> {
+        match number {
+            "1" => Some('a'),
+            "2" => Some('b'),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_naming() {
+        assert_eq!(ImageMatcher::score("system", None, "system.ext4.img"), Some(SCORE_NORMALIZED));
+        assert_eq!(ImageMatcher::score("boot", None, "boot-verified.img"), Some(SCORE_NORMALIZED));
+        assert_eq!(
+            ImageMatcher::score("userdata", None, "images/userdata_sparse.img"),
+            Some(SCORE_NORMALIZED)
+        );
+        assert_eq!(ImageMatcher::score("tee_a", None, "tee1.img"), Some(SCORE_NUMBERED_SLOT));
+        assert_eq!(ImageMatcher::score("tee_b", None, "tee1.img"), None);
+        assert_eq!(ImageMatcher::score("vbmeta_a", None, "vbmeta.img"), Some(SCORE_SLOTLESS));
+        assert_eq!(ImageMatcher::score("boot", None, "bootloader.img"), None);
+    }
+
+    #[test]
+    fn test_exact_matches_win() {
+        let files = vec![
+            "boot-verified.img".to_string(),
+            "boot.bin".to_string(),
+            "images/boot.img".to_string(),
+        ];
+        let candidates = ImageMatcher::candidates("boot", None, &files);
+        assert_eq!(candidates[0].file, "images/boot.img");
+        assert!(!ImageMatcher::is_ambiguous(&candidates));
+
+        let candidates = ImageMatcher::candidates("boot", Some("boot-verified.img"), &files);
+        assert_eq!(candidates[0].file, "boot-verified.img");
+    }
+
+    #[test]
+    fn test_ambiguous_candidates() {
+        let files = vec!["system.ext4.img".to_string(), "system_sparse.img".to_string()];
+        let candidates = ImageMatcher::candidates("system", None, &files);
+        assert_eq!(candidates.len(), 2);
+        assert!(ImageMatcher::is_ambiguous(&candidates));
+    }
+}
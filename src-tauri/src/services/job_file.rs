@@ -0,0 +1,395 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Declarative jobs: a YAML or JSON list of backups, flashes and reboots run
+//! one after another through the operation queue, e.g.
+//!
+//! ```yaml
+//! name: Restore stock boot
+//! da: da.bin
+//! skip: [userdata]
+//! steps:
+//!   - action: backup
+//!     output_dir: backup
+//!   - action: flash
+//!     partition: boot
+//!     image: boot.img
+//!   - action: reboot
+//!     mode: fastboot
+//!   - action: fastboot
+//!     step: wipe
+//!   - action: fastboot
+//!     step: reboot
+//!     when: always
+//! ```
+//!
+//! Relative paths are resolved against the job file's folder.
+
+use crate::services::events;
+use crate::services::executor::create_executor;
+use crate::services::fastboot_cli::{self, FastbootStep};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobFile {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub da: String,
+    #[serde(default)]
+    pub preloader: Option<String>,
+    /// Partitions no step may touch; also skipped by backups
+    #[serde(default)]
+    pub skip: Vec<String>,
+    pub steps: Vec<JobStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStep {
+    #[serde(flatten)]
+    pub action: JobAction,
+    #[serde(default)]
+    pub when: StepCondition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum JobAction {
+    /// Read every partition into `output_dir`
+    Backup {
+        output_dir: String,
+        #[serde(default)]
+        skip: Vec<String>,
+    },
+    Read {
+        partition: String,
+        output: String,
+    },
+    Flash {
+        partition: String,
+        image: String,
+    },
+    Erase {
+        partition: String,
+    },
+    Reboot {
+        #[serde(default = "default_reboot_mode")]
+        mode: String,
+    },
+    /// A post-flash step run with the platform fastboot binary
+    Fastboot {
+        step: FastbootStep,
+        #[serde(default)]
+        serial: Option<String>,
+    },
+}
+
+fn default_reboot_mode() -> String {
+    "normal".to_string()
+}
+
+/// When a step runs, given how the earlier ones went
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepCondition {
+    /// Only while every earlier step succeeded
+    #[default]
+    Success,
+    /// Only after an earlier step failed, e.g. to reboot out of a bad flash
+    Failure,
+    Always,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+/// Sent on `job:step` when a step starts and when it ends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStepEvent {
+    pub job_id: String,
+    pub index: usize,
+    pub action: String,
+    pub partition: Option<String>,
+    pub operation_id: Option<String>,
+    pub status: StepStatus,
+    pub error: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobReport {
+    pub job_id: String,
+    pub name: Option<String>,
+    pub steps: Vec<JobStepEvent>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub duration_ms: u64,
+}
+
+impl JobAction {
+    fn name(&self) -> &'static str {
+        match self {
+            JobAction::Backup { .. } => "backup",
+            JobAction::Read { .. } => "read",
+            JobAction::Flash { .. } => "flash",
+            JobAction::Erase { .. } => "erase",
+            JobAction::Reboot { .. } => "reboot",
+            JobAction::Fastboot { .. } => "fastboot",
+        }
+    }
+
+    fn partition(&self) -> Option<&str> {
+        match self {
+            JobAction::Read { partition, .. }
+            | JobAction::Flash { partition, .. }
+            | JobAction::Erase { partition } => Some(partition),
+            _ => None,
+        }
+    }
+
+    /// Whether the step writes to the device, so safe mode must block it
+    pub fn is_write(&self) -> bool {
+        match self {
+            JobAction::Flash { .. } | JobAction::Erase { .. } => true,
+            JobAction::Fastboot { step, .. } => step.is_write(),
+            _ => false,
+        }
+    }
+}
+
+impl JobFile {
+    /// Parse a job and resolve its relative paths against `path`'s folder
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read job file {}", path.display()))?;
+        let base = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&content, base)
+    }
+
+    fn parse(content: &str, base: &Path) -> Result<Self> {
+        // JSON is valid YAML, so one parser covers both formats
+        let mut job: JobFile = serde_yaml::from_str(content).context("Invalid job file")?;
+        if job.steps.is_empty() {
+            anyhow::bail!("Job file has no steps");
+        }
+
+        let resolve = |path: &mut String| *path = resolve_path(base, path);
+        resolve(&mut job.da);
+        if let Some(preloader) = job.preloader.as_mut() {
+            resolve(preloader);
+        }
+        for step in &mut job.steps {
+            match &mut step.action {
+                JobAction::Backup { output_dir, .. } => resolve(output_dir),
+                JobAction::Read { output, .. } => resolve(output),
+                JobAction::Flash { image, .. } => resolve(image),
+                _ => {}
+            }
+        }
+        Ok(job)
+    }
+
+    pub fn has_writes(&self) -> bool {
+        self.steps.iter().any(|step| step.action.is_write())
+    }
+
+    /// Antumbra arguments for `action`, built like the matching commands do
+    fn args(&self, action: &JobAction) -> Vec<String> {
+        let mut args: Vec<String> = match action {
+            JobAction::Backup { output_dir, .. } => vec!["read-all".into(), output_dir.clone()],
+            JobAction::Read { partition, output } => {
+                vec!["upload".into(), partition.clone(), output.clone()]
+            }
+            JobAction::Flash { partition, image } => {
+                vec!["download".into(), partition.clone(), image.clone()]
+            }
+            JobAction::Erase { partition } => vec!["erase".into(), partition.clone()],
+            JobAction::Reboot { mode } => vec!["reboot".into(), mode.clone()],
+            JobAction::Fastboot { step, serial } => return step.args(serial.as_deref()),
+        };
+
+        args.push("-d".into());
+        args.push(self.da.clone());
+        if let Some(preloader) = &self.preloader {
+            args.push("-p".into());
+            args.push(preloader.clone());
+        }
+        if let JobAction::Backup { skip, .. } = action {
+            for partition in self.skip.iter().chain(skip) {
+                args.push("--skip".into());
+                args.push(partition.clone());
+            }
+        }
+        args
+    }
+}
+
+fn resolve_path(base: &Path, path: &str) -> String {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return path.to_string_lossy().to_string();
+    }
+    base.join(path).to_string_lossy().to_string()
+}
+
+fn should_run(condition: StepCondition, any_failed: bool) -> bool {
+    match condition {
+        StepCondition::Success => !any_failed,
+        StepCondition::Failure => any_failed,
+        StepCondition::Always => true,
+    }
+}
+
+/// Run every step of `job` in order, emitting `job:step` events
+pub async fn run(app: &AppHandle, job_id: &str, job: &JobFile) -> Result<JobReport> {
+    let started = Instant::now();
+    let executor = create_executor(app)?;
+    let mut steps = Vec::with_capacity(job.steps.len());
+    let mut any_failed = false;
+
+    for (index, step) in job.steps.iter().enumerate() {
+        let partition = step.action.partition();
+        let mut event = JobStepEvent {
+            job_id: job_id.to_string(),
+            index,
+            action: step.action.name().to_string(),
+            partition: partition.map(str::to_string),
+            operation_id: None,
+            status: StepStatus::Skipped,
+            error: None,
+            duration_ms: None,
+        };
+
+        let skipped = partition.is_some_and(|partition| job.skip.iter().any(|p| p == partition));
+        if skipped || !should_run(step.when, any_failed) {
+            let _ = events::emit(app, "job:step", &event);
+            steps.push(event);
+            continue;
+        }
+
+        let operation_id = Uuid::new_v4().to_string();
+        event.operation_id = Some(operation_id.clone());
+        event.status = StepStatus::Running;
+        let _ = events::emit(app, "job:step", &event);
+        log::info!(
+            "Job {} step {}: {} (operation_id: {})",
+            job_id,
+            index + 1,
+            step.action.name(),
+            operation_id
+        );
+
+        let step_started = Instant::now();
+        let result = match &step.action {
+            JobAction::Fastboot { step, serial } => {
+                fastboot_cli::run(app.clone(), operation_id, *step, serial.as_deref()).await
+            }
+            action => executor.execute_streaming(app.clone(), operation_id, job.args(action)).await,
+        };
+        match result {
+            Ok(_) => event.status = StepStatus::Succeeded,
+            Err(err) => {
+                log::warn!("Job {} step {} failed: {:#}", job_id, index + 1, err);
+                any_failed = true;
+                event.status = StepStatus::Failed;
+                event.error = Some(format!("{:#}", err));
+            }
+        }
+        event.duration_ms = Some(step_started.elapsed().as_millis() as u64);
+        let _ = events::emit(app, "job:step", &event);
+        steps.push(event);
+    }
+
+    let count = |status| steps.iter().filter(|step| step.status == status).count();
+    Ok(JobReport {
+        job_id: job_id.to_string(),
+        name: job.name.clone(),
+        succeeded: count(StepStatus::Succeeded),
+        failed: count(StepStatus::Failed),
+        skipped: count(StepStatus::Skipped),
+        duration_ms: started.elapsed().as_millis() as u64,
+        steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOB: &str = r#"
+name: Restore stock boot
+da: da.bin
+skip: [userdata]
+steps:
+  - action: backup
+    output_dir: /backups/device
+    skip: [cache]
+  - action: flash
+    partition: boot
+    image: images/boot.img
+  - action: reboot
+    when: always
+"#;
+
+    #[test]
+    fn parses_yaml_and_resolves_paths() {
+        let job = JobFile::parse(JOB, Path::new("/jobs")).unwrap();
+        assert_eq!(job.da, Path::new("/jobs").join("da.bin").to_string_lossy());
+        assert_eq!(job.steps[2].when, StepCondition::Always);
+        assert_eq!(job.steps[2].action, JobAction::Reboot { mode: "normal".into() });
+        assert!(job.has_writes());
+
+        assert_eq!(
+            job.args(&job.steps[0].action),
+            ["read-all", "/backups/device", "-d", &job.da, "--skip", "userdata", "--skip", "cache"]
+        );
+        assert_eq!(
+            job.args(&job.steps[1].action)[..3],
+            ["download", "boot", &*Path::new("/jobs").join("images/boot.img").to_string_lossy()]
+        );
+    }
+
+    #[test]
+    fn parses_json() {
+        let job = JobFile::parse(
+            r#"{"da": "/da.bin", "steps": [{"action": "erase", "partition": "frp"}]}"#,
+            Path::new("/"),
+        )
+        .unwrap();
+        assert_eq!(job.args(&job.steps[0].action), ["erase", "frp", "-d", "/da.bin"]);
+        assert!(JobFile::parse(r#"{"da": "/da.bin", "steps": []}"#, Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn parses_fastboot_steps() {
+        let job = JobFile::parse(
+            "da: /da.bin\nsteps:\n  - action: fastboot\n    step: flashing_unlock\n",
+            Path::new("/"),
+        )
+        .unwrap();
+        assert!(job.has_writes());
+        assert_eq!(job.args(&job.steps[0].action), ["flashing", "unlock"]);
+    }
+
+    #[test]
+    fn conditions_follow_earlier_failures() {
+        assert!(should_run(StepCondition::Success, false));
+        assert!(!should_run(StepCondition::Success, true));
+        assert!(should_run(StepCondition::Failure, true));
+        assert!(!should_run(StepCondition::Failure, false));
+        assert!(should_run(StepCondition::Always, true));
+    }
+}
@@ -0,0 +1,124 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Scatter files handed to the app by the OS file association, either as a
+//! launch argument or (on macOS) through an "open file" event.
+
+use crate::models::scatter::OpenScatterEvent;
+use crate::services::config::RecentItemKind;
+use crate::services::events;
+use crate::services::recent_items;
+use crate::services::scatter_parser::ScatterParser;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// Launch file waiting for the frontend to start listening
+static PENDING: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Set once the frontend asked for the launch file; later opens emit directly
+static FRONTEND_READY: AtomicBool = AtomicBool::new(false);
+
+/// Launch options that take a value, so the value is not mistaken for a file
+const VALUE_OPTIONS: &[&str] = &["--config-dir"];
+
+/// Remember a scatter file passed on the command line
+pub fn init(args: impl IntoIterator<Item = String>) {
+    let path = scatter_arg(args).filter(|path| path.is_file());
+    if let Some(path) = &path {
+        log::info!("Launched with scatter file {}", path.display());
+    }
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = path;
+    }
+}
+
+/// Open the launch file, if any, once the frontend listens for
+/// `app:open-scatter`. Returns whether there was one.
+pub fn open_pending(app: &AppHandle) -> bool {
+    FRONTEND_READY.store(true, Ordering::SeqCst);
+    let Some(path) = PENDING.lock().ok().and_then(|mut pending| pending.take()) else {
+        return false;
+    };
+    open(app, &path);
+    true
+}
+
+/// Open `path` now, or hold it until the frontend is ready
+#[cfg(target_os = "macos")]
+pub fn open_when_ready(app: &AppHandle, path: PathBuf) {
+    if !FRONTEND_READY.load(Ordering::SeqCst) {
+        if let Ok(mut pending) = PENDING.lock() {
+            *pending = Some(path);
+            return;
+        }
+    }
+    open(app, &path);
+}
+
+/// Parse `path` and emit `app:open-scatter` with the result
+pub fn open(app: &AppHandle, path: &Path) {
+    let path_str = path.display().to_string();
+    let event = match ScatterParser::parse(&path_str, None) {
+        Ok(scatter) => {
+            recent_items::record(RecentItemKind::Scatter, &path_str);
+            OpenScatterEvent { path: path_str, scatter: Some(scatter), error: None }
+        }
+        Err(err) => {
+            log::warn!("Failed to parse opened scatter file {}: {}", path_str, err);
+            OpenScatterEvent { path: path_str, scatter: None, error: Some(err.to_string()) }
+        }
+    };
+    let _ = events::emit(app, "app:open-scatter", event);
+}
+
+/// First argument after the program name that looks like a scatter file
+fn scatter_arg(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            args.next();
+            continue;
+        }
+        if arg.starts_with('-') {
+            continue;
+        }
+        let path = PathBuf::from(arg);
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+        if extension.eq_ignore_ascii_case("txt") || extension.eq_ignore_ascii_case("xml") {
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_scatter_argument() {
+        assert_eq!(
+            scatter_arg(args(&[
+                "penumbra",
+                "--config-dir",
+                "/cfg.txt",
+                "MT6765_Android_scatter.txt"
+            ])),
+            Some(PathBuf::from("MT6765_Android_scatter.txt"))
+        );
+        assert_eq!(
+            scatter_arg(args(&["penumbra", "--config-dir=/cfg", "/fw/MT6893_Android_scatter.XML"])),
+            Some(PathBuf::from("/fw/MT6893_Android_scatter.XML"))
+        );
+        assert_eq!(scatter_arg(args(&["penumbra", "boot.img"])), None);
+        assert_eq!(scatter_arg(args(&["/opt/penumbra.txt"])), None);
+    }
+}
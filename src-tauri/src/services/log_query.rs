@@ -0,0 +1,267 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::log_rotation::{self, MAX_LOG_FILES};
+use crate::services::paths;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+
+/// Filters for `query_log`; every field is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogQuery {
+    /// Levels to keep, case-insensitive ("error", "warn", ...)
+    pub levels: Vec<String>,
+    /// Case-insensitive substring, or a regex when `regex` is set
+    pub search: Option<String>,
+    pub regex: bool,
+    /// RFC 3339 bounds, inclusive
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Also search the rotated segments, not only the active log
+    pub include_rotated: bool,
+    /// Only records of this app run, see `structured_log::session_id`
+    pub session_id: Option<String>,
+    pub operation_id: Option<String>,
+    pub offset: usize,
+    /// Page size, 200 when unset
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogLine {
+    pub timestamp: Option<String>,
+    pub level: Option<String>,
+    pub session_id: Option<String>,
+    pub operation_id: Option<String>,
+    /// Continuation lines of a multi-line record are kept in the message
+    pub message: String,
+}
+
+/// One page of matching log lines, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogQueryPage {
+    pub entries: Vec<LogLine>,
+    /// Matches across all pages
+    pub total: usize,
+}
+
+const DEFAULT_PAGE_SIZE: usize = 200;
+
+/// Run `query` against the wrapper log (and, when asked, its rotated segments)
+pub fn query(query: &LogQuery) -> Result<LogQueryPage> {
+    let log_path = paths::wrapper_log_file();
+    let segments = if query.include_rotated { MAX_LOG_FILES } else { 1 };
+
+    // Segments are listed newest first; parse oldest first so lines stay in order
+    let mut lines = Vec::new();
+    for segment in log_rotation::list_segments(&log_path, segments).iter().rev() {
+        let bytes = std::fs::read(&segment.path)
+            .with_context(|| format!("Failed to read {}", segment.path))?;
+        lines.extend(parse(&String::from_utf8_lossy(&bytes)));
+    }
+
+    filter_page(lines, query)
+}
+
+fn filter_page(lines: Vec<LogLine>, query: &LogQuery) -> Result<LogQueryPage> {
+    let search = match query.search.as_deref().filter(|s| !s.is_empty()) {
+        Some(pattern) => {
+            let pattern = if query.regex { pattern.to_string() } else { regex::escape(pattern) };
+            let matcher = RegexBuilder::new(&pattern)
+                .case_insensitive(true)
+                .build()
+                .with_context(|| format!("Invalid search pattern: {}", pattern))?;
+            Some(matcher)
+        }
+        None => None,
+    };
+    let since = parse_bound(query.since.as_deref())?;
+    let until = parse_bound(query.until.as_deref())?;
+    let levels: Vec<String> = query.levels.iter().map(|level| level.to_uppercase()).collect();
+
+    let matches: Vec<LogLine> = lines
+        .into_iter()
+        .rev()
+        .filter(|line| {
+            if !levels.is_empty() {
+                let level = line.level.as_deref().unwrap_or_default();
+                if !levels.iter().any(|wanted| wanted == level) {
+                    return false;
+                }
+            }
+            if !matches_id(query.session_id.as_deref(), line.session_id.as_deref())
+                || !matches_id(query.operation_id.as_deref(), line.operation_id.as_deref())
+            {
+                return false;
+            }
+            if since.is_some() || until.is_some() {
+                let Some(time) = line.timestamp.as_deref().and_then(parse_time) else {
+                    return false;
+                };
+                if since.is_some_and(|since| time < since)
+                    || until.is_some_and(|until| time > until)
+                {
+                    return false;
+                }
+            }
+            search.as_ref().map_or(true, |search| search.is_match(&line.message))
+        })
+        .collect();
+
+    let total = matches.len();
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    let entries = matches.into_iter().skip(query.offset).take(limit).collect();
+    Ok(LogQueryPage { entries, total })
+}
+
+/// An unset or empty filter matches everything
+fn matches_id(wanted: Option<&str>, actual: Option<&str>) -> bool {
+    match wanted.filter(|wanted| !wanted.is_empty()) {
+        Some(wanted) => actual == Some(wanted),
+        None => true,
+    }
+}
+
+fn parse_bound(value: Option<&str>) -> Result<Option<DateTime<Utc>>> {
+    match value.filter(|value| !value.is_empty()) {
+        Some(value) => parse_time(value)
+            .map(Some)
+            .ok_or_else(|| anyhow::anyhow!("Invalid timestamp: {}", value)),
+        None => Ok(None),
+    }
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|time| time.with_timezone(&Utc))
+}
+
+/// Split `<rfc3339> [LEVEL] [session/operation] message` records; lines
+/// without that prefix continue the previous record. The context block is
+/// optional so logs written before it existed still parse.
+fn parse(text: &str) -> Vec<LogLine> {
+    let mut lines: Vec<LogLine> = Vec::new();
+    for raw in text.lines() {
+        match parse_record(raw) {
+            Some(line) => lines.push(line),
+            None => match lines.last_mut() {
+                Some(previous) => {
+                    previous.message.push('\n');
+                    previous.message.push_str(raw);
+                }
+                None => lines.push(LogLine {
+                    timestamp: None,
+                    level: None,
+                    session_id: None,
+                    operation_id: None,
+                    message: raw.into(),
+                }),
+            },
+        }
+    }
+    lines
+}
+
+fn parse_record(raw: &str) -> Option<LogLine> {
+    let (timestamp, rest) = raw.split_once(' ')?;
+    parse_time(timestamp)?;
+    let rest = rest.strip_prefix('[')?;
+    let (level, message) =
+        rest.split_once("] ").or_else(|| rest.strip_suffix(']').map(|l| (l, "")))?;
+    let (session_id, operation_id, message) = match split_context(message) {
+        Some((session, operation, message)) => (Some(session), operation, message),
+        None => (None, None, message),
+    };
+    Some(LogLine {
+        timestamp: Some(timestamp.to_string()),
+        level: Some(level.to_string()),
+        session_id: session_id.map(str::to_string),
+        operation_id: operation_id.map(str::to_string),
+        message: message.to_string(),
+    })
+}
+
+/// `[session] rest` or `[session/operation] rest`, where session is the
+/// 8 hex digits of `structured_log::session_id`
+fn split_context(message: &str) -> Option<(&str, Option<&str>, &str)> {
+    let rest = message.strip_prefix('[')?;
+    let (context, message) =
+        rest.split_once("] ").or_else(|| rest.strip_suffix(']').map(|c| (c, "")))?;
+    let (session, operation) = match context.split_once('/') {
+        Some((session, operation)) => (session, Some(operation)),
+        None => (context, None),
+    };
+    let is_session = session.len() == 8 && session.chars().all(|c| c.is_ascii_hexdigit());
+    (is_session && !context.contains(' ')).then_some((session, operation, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+2025-01-01T10:00:00+00:00 [INFO] PenumbraWrapper starting...
+2025-01-01T10:00:05+00:00 [WARN] Failed to lock output batch
+2025-01-01T10:01:00+00:00 [ERROR] Antumbra failed: boom
+stack line
+2025-01-01T10:02:00+00:00 [INFO] Reading partition 'boot'
+";
+
+    const TAGGED: &str = "\
+2025-01-01T10:00:00+00:00 [INFO] [0a1b2c3d] PenumbraWrapper starting...
+2025-01-01T10:00:05+00:00 [INFO] [0a1b2c3d/op-1] Executing antumbra
+2025-01-01T10:00:06+00:00 [WARN] [0a1b2c3d/op-2] [retry] Executing antumbra
+2025-01-01T10:00:07+00:00 [INFO] [not a session] message
+";
+
+    #[test]
+    fn parses_multiline_records() {
+        let lines = parse(SAMPLE);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2].level.as_deref(), Some("ERROR"));
+        assert_eq!(lines[2].message, "Antumbra failed: boom\nstack line");
+    }
+
+    #[test]
+    fn filters_and_pages_newest_first() {
+        let query = LogQuery { levels: vec!["info".into()], ..Default::default() };
+        let page = filter_page(parse(SAMPLE), &query).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.entries[0].message, "Reading partition 'boot'");
+
+        let query = LogQuery {
+            search: Some("fail.*(boom|batch)".into()),
+            regex: true,
+            since: Some("2025-01-01T10:00:30+00:00".into()),
+            ..Default::default()
+        };
+        let page = filter_page(parse(SAMPLE), &query).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].level.as_deref(), Some("ERROR"));
+
+        let query = LogQuery { offset: 1, limit: Some(1), ..Default::default() };
+        let page = filter_page(parse(SAMPLE), &query).unwrap();
+        assert_eq!(page.total, 4);
+        assert_eq!(page.entries[0].level.as_deref(), Some("ERROR"));
+    }
+
+    #[test]
+    fn parses_session_and_operation_context() {
+        let lines = parse(TAGGED);
+        assert_eq!(lines[0].session_id.as_deref(), Some("0a1b2c3d"));
+        assert_eq!(lines[0].operation_id, None);
+        assert_eq!(lines[2].operation_id.as_deref(), Some("op-2"));
+        assert_eq!(lines[2].message, "[retry] Executing antumbra");
+        assert_eq!(lines[3].session_id, None);
+        assert_eq!(lines[3].message, "[not a session] message");
+
+        let query = LogQuery { operation_id: Some("op-1".into()), ..Default::default() };
+        let page = filter_page(parse(TAGGED), &query).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].message, "Executing antumbra");
+    }
+}
@@ -0,0 +1,150 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Rotate the active log once it would grow past this size
+pub const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Active log plus rotated segments kept on disk
+pub const MAX_LOG_FILES: usize = 5;
+/// Rotated segments older than this are deleted at startup
+pub const MAX_LOG_AGE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFileInfo {
+    pub path: String,
+    pub size: u64,
+    /// RFC 3339, when the filesystem reports it
+    pub modified: Option<String>,
+    /// `true` for the file currently being written
+    pub active: bool,
+}
+
+/// Append-only log file that shifts itself to `<name>.1`, `<name>.2`, ...
+/// when it reaches `max_bytes`, keeping at most `max_files` files in total
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, max_bytes, max_files: max_files.max(1), file, size })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let _ = std::fs::remove_file(segment_path(&self.path, self.max_files - 1));
+        for index in (1..self.max_files - 1).rev() {
+            let from = segment_path(&self.path, index);
+            if from.exists() {
+                std::fs::rename(&from, segment_path(&self.path, index + 1))?;
+            }
+        }
+        if self.max_files > 1 {
+            std::fs::rename(&self.path, segment_path(&self.path, 1))?;
+        }
+        self.file = OpenOptions::new().create(true).write(true).truncate(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            // Keep logging into the current file if rotation fails
+            if let Err(err) = self.rotate() {
+                eprintln!("Failed to rotate {}: {}", self.path.display(), err);
+            }
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn segment_path(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// Delete rotated segments of `path` last modified more than `max_age` ago
+pub fn prune_old_segments(path: &Path, max_files: usize, max_age: Duration) {
+    let now = SystemTime::now();
+    for index in 1..max_files {
+        let segment = segment_path(path, index);
+        let Ok(modified) = std::fs::metadata(&segment).and_then(|m| m.modified()) else {
+            continue;
+        };
+        if now.duration_since(modified).map(|age| age > max_age).unwrap_or(false) {
+            let _ = std::fs::remove_file(&segment);
+        }
+    }
+}
+
+/// The active log and its rotated segments, newest first
+pub fn list_segments(path: &Path, max_files: usize) -> Vec<LogFileInfo> {
+    (0..max_files)
+        .filter_map(|index| {
+            let segment = segment_path(path, index);
+            let metadata = std::fs::metadata(&segment).ok()?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339());
+            Some(LogFileInfo {
+                path: segment.display().to_string(),
+                size: metadata.len(),
+                modified,
+                active: index == 0,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_and_caps_segments() {
+        let dir = std::env::temp_dir().join(format!("log-rotation-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.log");
+
+        let mut log = RotatingFile::open(&path, 10, 3).unwrap();
+        for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+            log.write_all(line.as_bytes()).unwrap();
+        }
+        log.flush().unwrap();
+
+        let segments = list_segments(&path, 3);
+        assert_eq!(segments.len(), 3);
+        assert!(segments[0].active);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "dddddddd\n");
+        assert_eq!(std::fs::read_to_string(segment_path(&path, 2)).unwrap(), "bbbbbbbb\n");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,179 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::{AntumbraLogEvent, LogTailEvent};
+use crate::services::{events, paths};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::task::JoinHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static CURRENT_TAIL: OnceLock<Mutex<Option<JoinHandle<()>>>> = OnceLock::new();
+
+/// Follow the wrapper log from its current end, emitting `log:tail` with the
+/// complete lines appended since the last poll. Replaces any previous tail.
+///
+/// Nothing in here may log: every record would be appended to the file being
+/// tailed and come straight back as another event.
+pub fn start(app: AppHandle) {
+    stop();
+
+    let reader = LineReader::new(paths::wrapper_log_file(), move |lines| {
+        let _ = events::emit(&app, "log:tail", LogTailEvent { lines });
+    });
+    let task = spawn_poller(Arc::new(Mutex::new(reader)));
+
+    let store = CURRENT_TAIL.get_or_init(|| Mutex::new(None));
+    if let Ok(mut guard) = store.lock() {
+        *guard = Some(task);
+    }
+}
+
+/// Follows antumbra.log for the lifetime of one operation. Dropping it stops
+/// the watcher after a last read, so lines written just before antumbra
+/// exited still go out.
+pub struct AntumbraLogFollower {
+    task: Option<JoinHandle<()>>,
+    reader: Option<Arc<Mutex<LineReader>>>,
+}
+
+impl Drop for AntumbraLogFollower {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        if let Some(Ok(mut reader)) = self.reader.as_ref().map(|reader| reader.lock()) {
+            reader.poll();
+        }
+    }
+}
+
+/// Emit `antumbra:log` for lines antumbra appends to its own log while
+/// `operation_id` runs. Antumbra writes details there that never reach
+/// stdout/stderr.
+pub fn follow_antumbra_log(app: AppHandle, operation_id: &str) -> AntumbraLogFollower {
+    let Ok(path) = paths::antumbra_log_file() else {
+        return AntumbraLogFollower { task: None, reader: None };
+    };
+    let operation_id = operation_id.to_string();
+    let reader = Arc::new(Mutex::new(LineReader::new(path, move |lines| {
+        let event = AntumbraLogEvent { operation_id: operation_id.clone(), lines };
+        let _ = events::emit(&app, "antumbra:log", event);
+    })));
+    let task = spawn_poller(reader.clone());
+    AntumbraLogFollower { task: Some(task), reader: Some(reader) }
+}
+
+/// Reads a growing file from where it last stopped and hands complete new
+/// lines to a callback
+struct LineReader {
+    path: PathBuf,
+    offset: u64,
+    partial: String,
+    on_lines: Box<dyn FnMut(Vec<String>) + Send>,
+}
+
+impl LineReader {
+    /// Starts at the current end of `path`
+    fn new(path: PathBuf, on_lines: impl FnMut(Vec<String>) + Send + 'static) -> Self {
+        let offset = file_len(&path);
+        Self { path, offset, partial: String::new(), on_lines: Box::new(on_lines) }
+    }
+
+    fn poll(&mut self) {
+        let len = file_len(&self.path);
+        if len < self.offset {
+            // Rotated or truncated: the new file starts from scratch
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if len == self.offset {
+            return;
+        }
+
+        let Some(chunk) = read_from(&self.path, self.offset) else {
+            return;
+        };
+        self.offset += chunk.len() as u64;
+        self.partial.push_str(&String::from_utf8_lossy(&chunk));
+
+        let Some(end) = self.partial.rfind('\n') else {
+            return;
+        };
+        let lines: Vec<String> = self.partial[..end]
+            .lines()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect();
+        self.partial.drain(..=end);
+
+        if !lines.is_empty() {
+            (self.on_lines)(lines);
+        }
+    }
+}
+
+fn spawn_poller(reader: Arc<Mutex<LineReader>>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Ok(mut reader) = reader.lock() {
+                reader.poll();
+            }
+        }
+    })
+}
+
+/// Stop following the wrapper log, if a tail is running
+pub fn stop() {
+    let store = CURRENT_TAIL.get_or_init(|| Mutex::new(None));
+    if let Ok(mut guard) = store.lock() {
+        if let Some(task) = guard.take() {
+            task.abort();
+        }
+    }
+}
+
+fn file_len(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0)
+}
+
+fn read_from(path: &Path, offset: u64) -> Option<Vec<u8>> {
+    let mut file = std::fs::File::open(path).ok()?;
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut chunk = Vec::new();
+    file.read_to_end(&mut chunk).ok()?;
+    Some(chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn reader_emits_only_complete_new_lines() {
+        let path = std::env::temp_dir().join(format!("log-tail-{}.log", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "before\n").unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let mut reader = LineReader::new(path.clone(), move |lines| {
+            sink.lock().unwrap().extend(lines);
+        });
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "first\r\nsec").unwrap();
+        reader.poll();
+        write!(file, "ond\n").unwrap();
+        reader.poll();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(*seen.lock().unwrap(), vec!["first".to_string(), "second".to_string()]);
+    }
+}
@@ -0,0 +1,278 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::OperationCompleteEvent;
+use crate::services::antumbra::{acquire_device, emit_stream_line, StreamState};
+use crate::services::config::{load_settings, AppSettings};
+use crate::services::events;
+use crate::services::executor::CommandExecutor;
+use crate::services::operation_registry;
+use crate::services::output_buffer::OutputBuffer;
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::collections::HashSet;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::AppHandle;
+
+const PREFIX: &str = "Antumbra ✦  ";
+const LINE_DELAY: Duration = Duration::from_millis(150);
+const PROGRESS_DELAY: Duration = Duration::from_millis(250);
+
+/// Simulated operations that are still running, and those asked to stop
+static RUNNING: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+static CANCELLED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+/// Replays canned antumbra output with realistic timing, no device or binary needed
+#[derive(Debug, Default)]
+pub struct MockExecutor;
+
+impl MockExecutor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn run(&self, app: AppHandle, operation_id: String, args: Vec<String>) -> Result<String> {
+        let settings = load_settings().unwrap_or_default();
+        let _ticket = acquire_device(&app, &operation_id, &args, &settings).await?;
+        log::info!("Simulating antumbra with args: {:?}", args);
+
+        play(&app, &settings, &operation_id, &args, script(&args), true).await
+    }
+}
+
+/// One line of canned output and the delay before it is printed
+pub(crate) struct ScriptLine {
+    pub delay: Duration,
+    pub line: String,
+    pub is_stderr: bool,
+    /// Ended by a bare '\r', i.e. a progress bar redrawn in place
+    pub redraw: bool,
+}
+
+/// Feed `lines` through the regular output pipeline as if antumbra printed them,
+/// then report completion. The playback can be stopped with `cancel`.
+pub(crate) async fn play(
+    app: &AppHandle,
+    settings: &AppSettings,
+    operation_id: &str,
+    args: &[String],
+    lines: Vec<ScriptLine>,
+    success: bool,
+) -> Result<String> {
+    let _running = RunningGuard::new(operation_id);
+    let _registration = operation_registry::register(operation_id, args, None, true);
+    let state = StreamState::new(operation_id, args, settings, Arc::new(AtomicU64::new(0)));
+    let batcher = state.batcher.clone();
+    let flush_task = batcher.spawn_flusher(app, operation_id);
+    let stdout_lines =
+        Arc::new(Mutex::new(OutputBuffer::from_settings(settings, operation_id, "stdout")));
+    let stderr_lines =
+        Arc::new(Mutex::new(OutputBuffer::from_settings(settings, operation_id, "stderr")));
+
+    let mut cancelled = false;
+    for script_line in lines {
+        tokio::time::sleep(script_line.delay).await;
+        if take_cancelled(operation_id) {
+            cancelled = true;
+            break;
+        }
+        let storage = if script_line.is_stderr { &stderr_lines } else { &stdout_lines };
+        emit_stream_line(
+            app,
+            operation_id,
+            script_line.is_stderr,
+            storage,
+            &state,
+            script_line.line,
+            script_line.redraw,
+        );
+    }
+
+    if let Some(progress) = state.flush_progress() {
+        let _ = events::emit(app, "operation:progress", progress);
+    }
+    drop(flush_task);
+    batcher.flush(app, operation_id);
+
+    let error = if cancelled {
+        Some("Operation cancelled".to_string())
+    } else if !success {
+        Some(stderr_lines.lock().map(|mut lines| lines.finish()).unwrap_or_default())
+    } else {
+        None
+    };
+    let complete_event = OperationCompleteEvent {
+        operation_id: operation_id.to_string(),
+        success: error.is_none(),
+        error: error.clone(),
+    };
+    let _ = events::emit(app, "operation:complete", complete_event);
+
+    if let Some(error) = error {
+        anyhow::bail!(error);
+    }
+
+    let output = stdout_lines.lock().map(|mut lines| lines.finish()).unwrap_or_default();
+    Ok(output)
+}
+
+impl CommandExecutor for MockExecutor {
+    fn execute_streaming(
+        &self,
+        app: AppHandle,
+        operation_id: String,
+        args: Vec<String>,
+    ) -> BoxFuture<'_, Result<String>> {
+        Box::pin(self.run(app, operation_id, args))
+    }
+
+    fn get_version(&self) -> Result<String> {
+        Ok("antumbra 0.0.0-simulated".to_string())
+    }
+}
+
+/// Ask a simulated operation to stop. Returns false when it isn't running.
+pub fn cancel(operation_id: &str) -> bool {
+    let running = RUNNING
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+        .map(|running| running.contains(operation_id))
+        .unwrap_or(false);
+    if running {
+        if let Ok(mut cancelled) = CANCELLED.get_or_init(|| Mutex::new(HashSet::new())).lock() {
+            cancelled.insert(operation_id.to_string());
+        }
+    }
+    running
+}
+
+/// Ask every simulated operation to stop
+pub fn cancel_all() {
+    let running: Vec<String> = RUNNING
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+        .map(|running| running.iter().cloned().collect())
+        .unwrap_or_default();
+    for operation_id in running {
+        cancel(&operation_id);
+    }
+}
+
+fn take_cancelled(operation_id: &str) -> bool {
+    CANCELLED
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+        .map(|mut cancelled| cancelled.remove(operation_id))
+        .unwrap_or(false)
+}
+
+struct RunningGuard {
+    operation_id: String,
+}
+
+impl RunningGuard {
+    fn new(operation_id: &str) -> Self {
+        if let Ok(mut running) = RUNNING.get_or_init(|| Mutex::new(HashSet::new())).lock() {
+            running.insert(operation_id.to_string());
+        }
+        Self { operation_id: operation_id.to_string() }
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        if let Ok(mut running) = RUNNING.get_or_init(|| Mutex::new(HashSet::new())).lock() {
+            running.remove(&self.operation_id);
+        }
+        take_cancelled(&self.operation_id);
+    }
+}
+
+/// Canned output for an antumbra invocation, with the delay before each line
+fn script(args: &[String]) -> Vec<ScriptLine> {
+    let command = args.first().map(String::as_str).unwrap_or_default();
+    let partition = args.get(1).map(String::as_str).unwrap_or("unknown");
+
+    let mut lines = vec![
+        "Waiting for MTK device...".to_string(),
+        "Found MTK port: USB 0E8D:2000".to_string(),
+        "Handshake completed".to_string(),
+        "DA loaded successfully".to_string(),
+    ];
+    let mut progress = Vec::new();
+
+    match command {
+        "pgpt" => {
+            lines.push("Partition Table:".to_string());
+            for (name, addr, size, human) in [
+                ("preloader", "0x00000000", "0x00400000", "4 MiB"),
+                ("boot_para", "0x00008000", "0x01A00000", "26 MiB"),
+                ("boot_a", "0x25100000", "0x02000000", "32 MiB"),
+                ("boot_b", "0x27100000", "0x02000000", "32 MiB"),
+                ("super", "0x43800000", "0x1FA120000", "7.9 GiB"),
+                ("userdata", "0x250800000", "0x39447FB000", "229.1 GiB"),
+            ] {
+                lines.push(format!(
+                    "Name: {:<22} Addr: {:<17} Size: {} ({})",
+                    name, addr, size, human
+                ));
+            }
+        }
+        "download" | "upload" => {
+            let verb = if command == "download" { "Writing" } else { "Reading" };
+            for step in 0..=10u64 {
+                progress.push(format!(
+                    "{} {}: {:.1} MiB / 32.0 MiB ({}%)",
+                    verb,
+                    partition,
+                    step as f64 * 3.2,
+                    step * 10
+                ));
+            }
+        }
+        "erase" | "format" => lines.push(format!("Erasing {}...", partition)),
+        _ => {}
+    }
+
+    // The bar is redrawn in place until its final state
+    let last_progress = progress.len().saturating_sub(1);
+    let line = |delay, text: &str, redraw| ScriptLine {
+        delay,
+        line: format!("{}{}", PREFIX, text),
+        is_stderr: false,
+        redraw,
+    };
+    let mut script: Vec<ScriptLine> =
+        lines.iter().map(|text| line(LINE_DELAY, text, false)).collect();
+    script.extend(
+        progress
+            .iter()
+            .enumerate()
+            .map(|(idx, text)| line(PROGRESS_DELAY, text, idx < last_progress)),
+    );
+    script.push(line(LINE_DELAY, "Operation completed successfully", false));
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_matches_command() {
+        let pgpt = script(&["pgpt".to_string()]);
+        assert_eq!(pgpt.iter().filter(|line| line.line.contains("Name:")).count(), 6);
+
+        let read = script(&["upload".to_string(), "boot_a".to_string()]);
+        let done = read
+            .iter()
+            .find(|line| line.line.ends_with("Reading boot_a: 32.0 MiB / 32.0 MiB (100%)"))
+            .unwrap();
+        assert!(!done.redraw);
+        assert!(read.iter().any(|line| line.redraw));
+    }
+}
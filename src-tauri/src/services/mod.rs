@@ -3,7 +3,58 @@
     SPDX-FileCopyrightText: 2025 Shomy
 */
 
+pub mod adb_cli;
 pub mod antumbra;
+pub mod antumbra_progress;
 pub mod antumbra_update;
+pub mod close_guard;
+pub mod command_history;
 pub mod config;
+pub mod config_watcher;
+pub mod da_parser;
+pub mod disk_space;
+pub mod event_replay;
+pub mod events;
+pub mod executor;
+pub mod failure_replay;
+pub mod fastboot_cli;
+pub mod file_hash;
+pub mod file_slice;
+pub mod http;
+pub mod i18n;
+pub mod job_file;
+pub mod launch_file;
+pub mod image_matcher;
+pub mod log_query;
+pub mod log_rotation;
+pub mod log_tail;
+pub mod mock_executor;
+pub mod mtkclient;
+pub mod network_diagnostics;
+pub mod notifications;
+pub mod operation_queue;
+pub mod operation_registry;
+pub mod operation_report;
+pub mod operation_stats;
+pub mod partition_sets;
+pub mod partition_table;
+pub mod output_buffer;
+pub mod output_layout;
+pub mod paths;
+pub mod plugins;
+pub mod preloader_library;
+pub mod recent_items;
+pub mod redaction;
+pub mod resource_monitor;
 pub mod scatter_parser;
+pub mod secrets;
+pub mod self_test;
+pub mod scatter_watcher;
+pub mod scatter_writer;
+pub mod session_recorder;
+pub mod session_script;
+pub mod sleep_inhibitor;
+pub mod structured_log;
+pub mod usage_metrics;
+pub mod usb_drivers;
+pub mod workspace;
@@ -0,0 +1,183 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Experimental mtkclient backend for SoCs antumbra does not support yet.
+//! Commands keep antumbra's vocabulary everywhere else in the app; they are
+//! translated here, and mtkclient's partition table is parsed back.
+
+use crate::models::Partition;
+use crate::services::antumbra::AntumbraExecutor;
+use crate::services::executor::CommandExecutor;
+use crate::services::paths;
+use anyhow::{Context, Result};
+use futures_util::future::BoxFuture;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+pub struct MtkClientExecutor {
+    runner: AntumbraExecutor,
+}
+
+impl MtkClientExecutor {
+    /// Use `mtkclient_path`, or `mtk` from PATH when it is unset
+    pub fn new(mtkclient_path: Option<&str>) -> Result<Self> {
+        let binary = PathBuf::from(mtkclient_path.filter(|path| !path.is_empty()).unwrap_or("mtk"));
+        let working_dir = paths::config_dir()?;
+        std::fs::create_dir_all(&working_dir).context("Failed to create config directory")?;
+        log::info!("Using experimental mtkclient backend: {:?}", binary);
+        Ok(Self { runner: AntumbraExecutor::with_binary(binary, working_dir) })
+    }
+}
+
+impl CommandExecutor for MtkClientExecutor {
+    fn execute_streaming(
+        &self,
+        app: AppHandle,
+        operation_id: String,
+        args: Vec<String>,
+    ) -> BoxFuture<'_, Result<String>> {
+        Box::pin(async move {
+            let args = build_args(&args)?;
+            self.runner.execute_streaming(app, operation_id, args).await
+        })
+    }
+
+    fn get_version(&self) -> Result<String> {
+        Ok("mtkclient (experimental backend)".to_string())
+    }
+
+    fn parse_partitions(&self, output: &str) -> Option<Vec<Partition>> {
+        Some(parse_printgpt(output))
+    }
+}
+
+/// Translate antumbra arguments into the matching mtkclient invocation
+fn build_args(args: &[String]) -> Result<Vec<String>> {
+    let mut positional = Vec::new();
+    let mut connection = Vec::new();
+    let mut skip = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-d" => connection.extend(["--loader".to_string(), next_value(&mut iter, arg)?]),
+            "-p" => connection.extend(["--preloader".to_string(), next_value(&mut iter, arg)?]),
+            "--skip" => skip.push(next_value(&mut iter, arg)?),
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    let mut mtk: Vec<String> = match positional.as_slice() {
+        ["pgpt"] => vec!["printgpt".into()],
+        ["upload", partition, output] => {
+            vec!["r".into(), partition.to_string(), output.to_string()]
+        }
+        ["download", partition, image] => {
+            vec!["w".into(), partition.to_string(), image.to_string()]
+        }
+        ["erase", partition] => vec!["e".into(), partition.to_string()],
+        ["read-all", output_dir] => {
+            let mut mtk = vec!["rl".into(), output_dir.to_string()];
+            if !skip.is_empty() {
+                mtk.extend(["--skip".to_string(), skip.join(",")]);
+            }
+            mtk
+        }
+        ["reboot", "normal"] => vec!["reset".into()],
+        ["seccfg", action @ ("unlock" | "lock")] => {
+            vec!["da".into(), "seccfg".into(), action.to_string()]
+        }
+        _ => anyhow::bail!(
+            "'{}' is not supported by the mtkclient backend",
+            positional.first().copied().unwrap_or_default()
+        ),
+    };
+    mtk.extend(connection);
+    Ok(mtk)
+}
+
+fn next_value<'a>(iter: &mut impl Iterator<Item = &'a String>, flag: &str) -> Result<String> {
+    iter.next().cloned().with_context(|| format!("Missing value for {}", flag))
+}
+
+/// Parse `mtk printgpt` lines such as
+/// `boot_a:     Offset 0x0000000025100000, Length 0x0000000002000000, Flags ...`
+fn parse_printgpt(output: &str) -> Vec<Partition> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.trim().split_once(':')?;
+            let start = field(rest, "Offset")?;
+            let size = field(rest, "Length")?;
+            let bytes = u64::from_str_radix(size.trim_start_matches("0x"), 16).ok();
+            Some(Partition {
+                name: name.trim().to_string(),
+                start: start.to_string(),
+                size: size.to_string(),
+                display_size: bytes.map(human_size),
+            })
+        })
+        .collect()
+}
+
+fn field<'a>(rest: &'a str, name: &str) -> Option<&'a str> {
+    rest.split(',').find_map(|part| part.trim().strip_prefix(name)).map(str::trim)
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if size.fract() == 0.0 {
+        format!("{} {}", size, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn translates_antumbra_arguments() {
+        assert_eq!(
+            build_args(&args(&["download", "boot", "boot.img", "-d", "da.bin", "-p", "pl.bin"]))
+                .unwrap(),
+            ["w", "boot", "boot.img", "--loader", "da.bin", "--preloader", "pl.bin"]
+        );
+        assert_eq!(
+            build_args(&args(&["read-all", "out", "-d", "da.bin", "--skip", "a", "--skip", "b"]))
+                .unwrap(),
+            ["rl", "out", "--skip", "a,b", "--loader", "da.bin"]
+        );
+        assert!(build_args(&args(&["format", "userdata", "-d", "da.bin"])).is_err());
+        assert!(build_args(&args(&["reboot", "fastboot", "-d", "da.bin"])).is_err());
+    }
+
+    #[test]
+    fn parses_printgpt_output() {
+        let output = "\
+GPT Table:
+-------------
+preloader:           Offset 0x0000000000000000, Length 0x0000000000400000, Flags 0x00000000
+super:               Offset 0x0000000043800000, Length 0x00000001FA120000, Flags 0x00000000
+Total disk size:0x0000003a39ffc000, sectors:0x0000000001d1cffe0
+";
+        let partitions = parse_printgpt(output);
+        assert_eq!(partitions.len(), 2);
+        assert_eq!(partitions[0].name, "preloader");
+        assert_eq!(partitions[0].display_size.as_deref(), Some("4 MiB"));
+        assert_eq!(partitions[1].start, "0x0000000043800000");
+        assert_eq!(partitions[1].display_size.as_deref(), Some("7.9 GiB"));
+    }
+}
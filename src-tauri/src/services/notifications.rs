@@ -0,0 +1,81 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Native desktop notifications for operations that finish while nobody is
+//! looking at the window.
+
+use crate::services::config::{load_settings, AppSettings};
+use crate::services::i18n::t_with;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Quicker operations finish before anyone switches away
+const MIN_DURATION: Duration = Duration::from_secs(10);
+
+/// Notify that antumbra `command` finished, unless the window has focus, the
+/// run was short, or the user turned notifications off for this operation
+pub fn operation_finished(app: &AppHandle, command: &str, error: Option<&str>, elapsed: Duration) {
+    let settings = load_settings().unwrap_or_default();
+    if !should_notify(&settings, command, elapsed) || window_focused(app) {
+        return;
+    }
+
+    let key = if error.is_some() { "notification.failed" } else { "notification.completed" };
+    let title = t_with(key, &[("operation", &command)]);
+    let body = match error {
+        Some(error) => first_line(error).to_string(),
+        None => t_with("notification.duration", &[("duration", &format_duration(elapsed))]),
+    };
+    if let Err(err) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show notification: {}", err);
+    }
+}
+
+fn should_notify(settings: &AppSettings, command: &str, elapsed: Duration) -> bool {
+    settings.notifications_enabled
+        && elapsed >= MIN_DURATION
+        && settings.notification_operations.iter().any(|operation| operation == command)
+}
+
+fn window_focused(app: &AppHandle) -> bool {
+    app.webview_windows().values().any(|window| window.is_focused().unwrap_or(false))
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default()
+}
+
+fn format_duration(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notifies_only_long_enabled_operations() {
+        let mut settings = AppSettings::default();
+        assert!(should_notify(&settings, "read-all", Duration::from_secs(1800)));
+        assert!(!should_notify(&settings, "read-all", Duration::from_secs(3)));
+        assert!(!should_notify(&settings, "reboot", Duration::from_secs(60)));
+
+        settings.notification_operations.retain(|operation| operation != "read-all");
+        assert!(!should_notify(&settings, "read-all", Duration::from_secs(1800)));
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 5s");
+        assert_eq!(format_duration(Duration::from_secs(5400)), "1h 30m");
+    }
+}
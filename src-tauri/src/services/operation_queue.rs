@@ -0,0 +1,511 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::QueuePositionEvent;
+use crate::services::events;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+use tokio::sync::Notify;
+
+/// Only one antumbra process can talk to the device at a time, so
+/// device-touching operations wait here for their turn.
+struct OperationQueue {
+    state: Mutex<QueueState>,
+    notify: Notify,
+}
+
+#[derive(Default)]
+struct QueueState {
+    active: Option<QueuedOperation>,
+    waiting: VecDeque<QueuedOperation>,
+    /// Operation whose pause holds the queue; it and everything queued behind
+    /// it wait until it resumes, even after it has finished running
+    paused_by: Option<String>,
+}
+
+impl QueueState {
+    /// Index into `waiting` from which operations are held by the pause. A
+    /// pause at the running or an already finished operation holds them all.
+    fn held_from(&self) -> Option<usize> {
+        let paused_by = self.paused_by.as_deref()?;
+        Some(self.waiting.iter().position(|op| op.operation_id == paused_by).unwrap_or(0))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueuedOperation {
+    pub operation_id: String,
+    pub label: String,
+    pub queued_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueuedOperationInfo {
+    pub operation_id: String,
+    pub label: String,
+    pub queued_at: String,
+    pub position: usize, // 0 = running
+    pub paused: bool,
+}
+
+static QUEUE: OnceLock<OperationQueue> = OnceLock::new();
+
+fn queue() -> &'static OperationQueue {
+    QUEUE.get_or_init(OperationQueue::new)
+}
+
+/// Holds the device slot; the next queued operation starts when dropped
+pub struct QueueTicket {
+    queue: &'static OperationQueue,
+    app: Option<AppHandle>,
+    operation_id: String,
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.queue.state.lock() {
+            if state.active.as_ref().is_some_and(|op| op.operation_id == self.operation_id) {
+                state.active = None;
+            }
+        }
+        self.queue.notify.notify_waiters();
+        self.queue.emit_positions(self.app.as_ref());
+    }
+}
+
+/// Takes a waiting operation out of the queue when its `acquire` future is
+/// dropped before it got the device, e.g. because the command was aborted
+struct WaitingGuard<'a> {
+    queue: &'a OperationQueue,
+    app: Option<&'a AppHandle>,
+    operation_id: &'a str,
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        if self.queue.remove_waiting(self.operation_id) {
+            self.queue.emit_positions(self.app);
+        }
+    }
+}
+
+impl OperationQueue {
+    fn new() -> Self {
+        Self { state: Mutex::new(QueueState::default()), notify: Notify::new() }
+    }
+
+    fn lock(&self) -> anyhow::Result<std::sync::MutexGuard<'_, QueueState>> {
+        self.state.lock().map_err(|_| anyhow::anyhow!("Operation queue poisoned"))
+    }
+
+    async fn acquire(
+        &'static self,
+        app: Option<&AppHandle>,
+        operation_id: &str,
+        label: &str,
+    ) -> anyhow::Result<QueueTicket> {
+        self.lock()?.waiting.push_back(QueuedOperation {
+            operation_id: operation_id.to_string(),
+            label: label.to_string(),
+            queued_at: chrono::Utc::now().to_rfc3339(),
+        });
+        let _waiting = WaitingGuard { queue: self, app, operation_id };
+        self.emit_positions(app);
+
+        loop {
+            // Register interest before checking so a release in between is not missed
+            let notified = self.notify.notified();
+            {
+                let mut state = self.lock()?;
+
+                if !state.waiting.iter().any(|op| op.operation_id == operation_id) {
+                    anyhow::bail!("Operation cancelled while queued");
+                }
+
+                let is_next =
+                    state.waiting.front().is_some_and(|op| op.operation_id == operation_id);
+                let held = state.held_from() == Some(0);
+                if state.active.is_none() && !held && is_next {
+                    state.active = state.waiting.pop_front();
+                    drop(state);
+                    self.emit_positions(app);
+                    return Ok(QueueTicket {
+                        queue: self,
+                        app: app.cloned(),
+                        operation_id: operation_id.to_string(),
+                    });
+                }
+            }
+
+            log::debug!("Operation {} waiting for device", operation_id);
+            notified.await;
+        }
+    }
+
+    /// Drop `operation_id` from the waiting list. Returns false if it isn't there.
+    fn remove_waiting(&self, operation_id: &str) -> bool {
+        let removed = match self.state.lock() {
+            Ok(mut state) => {
+                let before = state.waiting.len();
+                state.waiting.retain(|op| op.operation_id != operation_id);
+                if state.paused_by.as_deref() == Some(operation_id) {
+                    state.paused_by = None;
+                }
+                state.waiting.len() != before
+            }
+            Err(_) => false,
+        };
+        if removed {
+            self.notify.notify_waiters();
+        }
+        removed
+    }
+
+    /// The running operation (position 0) followed by waiting ones in order
+    fn list(&self) -> Vec<QueuedOperationInfo> {
+        let Ok(state) = self.state.lock() else {
+            return Vec::new();
+        };
+
+        let held_from = state.held_from();
+        let active = state.active.iter().map(|op| {
+            let paused = state.paused_by.as_deref() == Some(op.operation_id.as_str());
+            (op, 0, paused)
+        });
+        let waiting = state
+            .waiting
+            .iter()
+            .enumerate()
+            .map(|(i, op)| (op, i + 1, held_from.is_some_and(|held_from| i >= held_from)));
+        active
+            .chain(waiting)
+            .map(|(op, position, paused)| QueuedOperationInfo {
+                operation_id: op.operation_id.clone(),
+                label: op.label.clone(),
+                queued_at: op.queued_at.clone(),
+                position,
+                paused,
+            })
+            .collect()
+    }
+
+    fn emit_positions(&self, app: Option<&AppHandle>) {
+        let Some(app) = app else {
+            return;
+        };
+        let operations = self.list();
+        let queue_length = operations.iter().filter(|op| op.position > 0).count();
+        for op in operations {
+            let event = QueuePositionEvent {
+                operation_id: op.operation_id,
+                position: op.position,
+                queue_length,
+                paused: op.paused,
+            };
+            let _ = events::emit(app, "queue:position", event);
+        }
+    }
+}
+
+/// Wait until `operation_id` may use the device.
+/// Fails if the operation is cancelled while still queued; dropping the
+/// returned future before then leaves the queue as well.
+pub async fn acquire(
+    app: &AppHandle,
+    operation_id: &str,
+    label: &str,
+) -> anyhow::Result<QueueTicket> {
+    queue().acquire(Some(app), operation_id, label).await
+}
+
+/// Returned by `try_acquire` when another operation holds or is waiting for the device
+#[derive(Debug, thiserror::Error)]
+#[error("Device busy: operation {running_operation_id} is still running")]
+pub struct DeviceBusy {
+    pub running_operation_id: String,
+}
+
+/// Take the device slot only if it is free right now
+pub fn try_acquire(
+    app: &AppHandle,
+    operation_id: &str,
+    label: &str,
+) -> anyhow::Result<QueueTicket> {
+    let queue = queue();
+    {
+        let mut state = queue.lock()?;
+        if let Some(busy) = state.active.as_ref().or(state.waiting.front()) {
+            return Err(DeviceBusy { running_operation_id: busy.operation_id.clone() }.into());
+        }
+        if let Some(paused_by) = &state.paused_by {
+            return Err(DeviceBusy { running_operation_id: paused_by.clone() }.into());
+        }
+        state.active = Some(QueuedOperation {
+            operation_id: operation_id.to_string(),
+            label: label.to_string(),
+            queued_at: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+    queue.emit_positions(Some(app));
+    Ok(QueueTicket { queue, app: Some(app.clone()), operation_id: operation_id.to_string() })
+}
+
+/// Remove a waiting operation from the queue. Returns false if it isn't queued.
+pub fn cancel_queued(app: &AppHandle, operation_id: &str) -> bool {
+    let queue = queue();
+    let removed = queue.remove_waiting(operation_id);
+    if removed {
+        log::info!("Removed queued operation {}", operation_id);
+        queue.emit_positions(Some(app));
+    }
+    removed
+}
+
+/// Remove every waiting operation at once, so none of them can start while
+/// the running one is being cancelled. Returns the removed operations.
+pub fn drain_waiting(app: &AppHandle) -> Vec<QueuedOperation> {
+    let queue = queue();
+    let drained: Vec<QueuedOperation> = match queue.state.lock() {
+        Ok(mut state) => {
+            state.paused_by = None;
+            state.waiting.drain(..).collect()
+        }
+        Err(_) => Vec::new(),
+    };
+
+    if !drained.is_empty() {
+        log::info!("Removed {} queued operation(s)", drained.len());
+        queue.notify.notify_waiters();
+        queue.emit_positions(Some(app));
+    }
+    drained
+}
+
+/// Hold the queue at `operation_id` until `resume`: a queued operation and
+/// everything behind it wait, while operations ahead of it still run. Pausing
+/// the running operation lets its invocation finish and holds everything
+/// queued. Only `resume` or cancelling the operation ends the hold. Fails if
+/// the operation is neither running nor queued.
+pub fn pause(app: &AppHandle, operation_id: &str) -> anyhow::Result<()> {
+    let queue = queue();
+    queue.pause(operation_id)?;
+    log::info!("Paused queue at operation {}", operation_id);
+    queue.emit_positions(Some(app));
+    Ok(())
+}
+
+/// Drop the hold of a cancelled operation, or with `None` of any operation
+/// that is no longer queued. Returns false if there was nothing to release.
+pub fn release_pause(app: &AppHandle, operation_id: Option<&str>) -> bool {
+    let queue = queue();
+    let released = queue.release_pause(operation_id);
+    if released {
+        log::info!("Released queue pause of cancelled operation");
+        queue.emit_positions(Some(app));
+    }
+    released
+}
+
+/// Release a queue held by `pause`
+pub fn resume(app: &AppHandle, operation_id: &str) -> anyhow::Result<()> {
+    let queue = queue();
+    queue.resume(operation_id)?;
+    log::info!("Resumed queue at operation {}", operation_id);
+    queue.emit_positions(Some(app));
+    Ok(())
+}
+
+impl OperationQueue {
+    fn pause(&self, operation_id: &str) -> anyhow::Result<()> {
+        let mut state = self.lock()?;
+        let mut known = state.active.iter().chain(state.waiting.iter());
+        if !known.any(|op| op.operation_id == operation_id) {
+            anyhow::bail!("Operation {} is not running or queued", operation_id);
+        }
+        if let Some(paused_by) = &state.paused_by {
+            anyhow::bail!("Queue is already paused by operation {}", paused_by);
+        }
+        state.paused_by = Some(operation_id.to_string());
+        Ok(())
+    }
+
+    fn release_pause(&self, operation_id: Option<&str>) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let matches = match (operation_id, state.paused_by.as_deref()) {
+            (Some(operation_id), Some(paused_by)) => operation_id == paused_by,
+            (None, Some(paused_by)) => !state.waiting.iter().any(|op| op.operation_id == paused_by),
+            (_, None) => false,
+        };
+        if matches {
+            state.paused_by = None;
+            drop(state);
+            self.notify.notify_waiters();
+        }
+        matches
+    }
+
+    fn resume(&self, operation_id: &str) -> anyhow::Result<()> {
+        {
+            let mut state = self.lock()?;
+            if state.paused_by.as_deref() != Some(operation_id) {
+                anyhow::bail!("Operation {} is not paused", operation_id);
+            }
+            state.paused_by = None;
+        }
+        self.notify.notify_waiters();
+        Ok(())
+    }
+}
+
+/// The running operation (position 0) followed by waiting ones in order
+pub fn list_queued() -> Vec<QueuedOperationInfo> {
+    queue().list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::poll;
+    use std::task::Poll;
+
+    /// A queue of its own per test, so tests don't share the global one
+    fn test_queue() -> &'static OperationQueue {
+        Box::leak(Box::new(OperationQueue::new()))
+    }
+
+    fn ids(queue: &OperationQueue) -> Vec<String> {
+        queue.list().into_iter().map(|op| op.operation_id).collect()
+    }
+
+    fn ready<T>(poll: Poll<T>) -> T {
+        match poll {
+            Poll::Ready(value) => value,
+            Poll::Pending => panic!("operation is still waiting"),
+        }
+    }
+
+    #[tokio::test]
+    async fn grants_the_device_in_fifo_order() {
+        let queue = test_queue();
+        let first = queue.acquire(None, "a", "read").await.unwrap();
+        let mut second = Box::pin(queue.acquire(None, "b", "write"));
+        let mut third = Box::pin(queue.acquire(None, "c", "erase"));
+        assert!(poll!(&mut second).is_pending());
+        assert!(poll!(&mut third).is_pending());
+        assert_eq!(ids(queue), ["a", "b", "c"]);
+
+        drop(first);
+        assert!(poll!(&mut third).is_pending());
+        let second = ready(poll!(&mut second)).unwrap();
+        assert_eq!(ids(queue), ["b", "c"]);
+
+        drop(second);
+        let _third = ready(poll!(&mut third)).unwrap();
+        assert_eq!(ids(queue), ["c"]);
+    }
+
+    #[tokio::test]
+    async fn dropped_or_cancelled_waiters_leave_the_queue() {
+        let queue = test_queue();
+        let first = queue.acquire(None, "a", "read").await.unwrap();
+        let mut second = Box::pin(queue.acquire(None, "b", "write"));
+        let mut third = Box::pin(queue.acquire(None, "c", "erase"));
+        let mut fourth = Box::pin(queue.acquire(None, "d", "format"));
+        assert!(poll!(&mut second).is_pending());
+        assert!(poll!(&mut third).is_pending());
+        assert!(poll!(&mut fourth).is_pending());
+
+        drop(second);
+        assert_eq!(ids(queue), ["a", "c", "d"]);
+        assert!(queue.remove_waiting("c"));
+        assert!(ready(poll!(&mut third)).is_err());
+        assert_eq!(ids(queue), ["a", "d"]);
+
+        drop(first);
+        let _fourth = ready(poll!(&mut fourth)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn released_ticket_frees_the_device() {
+        let queue = test_queue();
+        let ticket = queue.acquire(None, "a", "read").await.unwrap();
+        assert_eq!(queue.list()[0].position, 0);
+        drop(ticket);
+        assert!(queue.list().is_empty());
+
+        let mut next = Box::pin(queue.acquire(None, "b", "write"));
+        let _next = ready(poll!(&mut next)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn paused_queue_holds_until_resume() {
+        let queue = test_queue();
+        let first = queue.acquire(None, "a", "write").await.unwrap();
+        let mut second = Box::pin(queue.acquire(None, "b", "write"));
+        assert!(poll!(&mut second).is_pending());
+        queue.pause("b").unwrap();
+        let paused: Vec<bool> = queue.list().iter().map(|op| op.paused).collect();
+        assert_eq!(paused, [false, true]);
+
+        drop(first);
+        assert!(poll!(&mut second).is_pending());
+        assert!(queue.resume("a").is_err());
+        queue.resume("b").unwrap();
+        let _second = ready(poll!(&mut second)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn operations_ahead_of_the_pause_still_run() {
+        let queue = test_queue();
+        let first = queue.acquire(None, "a", "write").await.unwrap();
+        let mut second = Box::pin(queue.acquire(None, "b", "write"));
+        let mut third = Box::pin(queue.acquire(None, "c", "write"));
+        assert!(poll!(&mut second).is_pending());
+        assert!(poll!(&mut third).is_pending());
+        queue.pause("c").unwrap();
+
+        drop(first);
+        let second = ready(poll!(&mut second)).unwrap();
+        drop(second);
+        assert!(poll!(&mut third).is_pending());
+        queue.resume("c").unwrap();
+        let _third = ready(poll!(&mut third)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn pause_outlives_the_running_operation() {
+        let queue = test_queue();
+        let first = queue.acquire(None, "a", "write").await.unwrap();
+        queue.pause("a").unwrap();
+        assert!(queue.pause("a").is_err());
+        let mut next = Box::pin(queue.acquire(None, "b", "write"));
+        assert!(poll!(&mut next).is_pending());
+        drop(first);
+
+        assert!(poll!(&mut next).is_pending());
+        assert!(queue.list()[0].paused);
+        queue.resume("a").unwrap();
+        let _next = ready(poll!(&mut next)).unwrap();
+        assert!(!queue.list()[0].paused);
+    }
+
+    #[tokio::test]
+    async fn cancelling_the_paused_operation_releases_the_hold() {
+        let queue = test_queue();
+        let first = queue.acquire(None, "a", "write").await.unwrap();
+        queue.pause("a").unwrap();
+        let mut next = Box::pin(queue.acquire(None, "b", "write"));
+        drop(first);
+        assert!(poll!(&mut next).is_pending());
+
+        assert!(!queue.release_pause(Some("b")));
+        assert!(queue.release_pause(Some("a")));
+        let _next = ready(poll!(&mut next)).unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::event_replay;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+
+/// An operation the backend is currently executing
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct RunningOperation {
+    pub operation_id: String,
+    pub command: String, // antumbra subcommand, e.g. "download"
+    pub partition: Option<String>,
+    pub started_at: String,
+    pub pid: Option<u32>,
+    pub simulated: bool,
+}
+
+/// Antumbra subcommands that modify the device, so safe mode must block them
+const WRITE_COMMANDS: &[&str] = &["download", "erase", "format", "seccfg"];
+
+/// Whether antumbra run with `args` writes to the device
+pub fn writes_device(args: &[String]) -> bool {
+    args.first().is_some_and(|command| WRITE_COMMANDS.contains(&command.as_str()))
+}
+
+static RUNNING: OnceLock<Mutex<HashMap<String, RunningOperation>>> = OnceLock::new();
+
+fn running() -> &'static Mutex<HashMap<String, RunningOperation>> {
+    RUNNING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Running operations the user asked to stop, so their failure reads as a cancel
+static CANCEL_REQUESTED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Removes the operation from the registry when dropped
+pub struct Registration {
+    operation_id: String,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Ok(mut running) = running().lock() {
+            running.remove(&self.operation_id);
+        }
+        if let Ok(mut requested) = CANCEL_REQUESTED.lock() {
+            if let Some(requested) = requested.as_mut() {
+                requested.remove(&self.operation_id);
+            }
+        }
+        event_replay::forget(&self.operation_id);
+    }
+}
+
+/// Record an in-flight operation started with antumbra `args`
+pub fn register(
+    operation_id: &str,
+    args: &[String],
+    pid: Option<u32>,
+    simulated: bool,
+) -> Registration {
+    let command = args.first().cloned().unwrap_or_default();
+    let partition = match command.as_str() {
+        "download" | "upload" | "erase" | "format" => args.get(1).cloned(),
+        _ => None,
+    };
+    let operation = RunningOperation {
+        operation_id: operation_id.to_string(),
+        command,
+        partition,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        pid,
+        simulated,
+    };
+    if let Ok(mut running) = running().lock() {
+        running.insert(operation_id.to_string(), operation);
+    }
+    Registration { operation_id: operation_id.to_string() }
+}
+
+/// In-flight operations, oldest first
+pub fn list() -> Vec<RunningOperation> {
+    let mut operations: Vec<RunningOperation> =
+        running().lock().map(|running| running.values().cloned().collect()).unwrap_or_default();
+    operations.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    operations
+}
+
+/// Remember that `operation_id` is being stopped on request
+pub fn mark_cancelled(operation_id: &str) {
+    let is_running = running().lock().is_ok_and(|running| running.contains_key(operation_id));
+    if !is_running {
+        return;
+    }
+    if let Ok(mut requested) = CANCEL_REQUESTED.lock() {
+        requested.get_or_insert_with(HashSet::new).insert(operation_id.to_string());
+    }
+}
+
+/// Whether `mark_cancelled` was called for the still registered `operation_id`
+pub fn is_cancelled(operation_id: &str) -> bool {
+    CANCEL_REQUESTED
+        .lock()
+        .is_ok_and(|requested| requested.as_ref().is_some_and(|ids| ids.contains(operation_id)))
+}
@@ -0,0 +1,304 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::antumbra::AntumbraCommandInfo;
+use crate::services::{command_history, redaction};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Outcome of one partition within a reported operation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PartitionResult {
+    pub operation_id: String,
+    /// Antumbra subcommand, e.g. "download"
+    pub operation: String,
+    pub partition: Option<String>,
+    /// Flashed image or written dump
+    pub file: Option<String>,
+    pub success: Option<bool>,
+    pub exit_code: Option<i32>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub bytes: Option<u64>,
+    /// SHA-256 of `file` at export time
+    pub sha256: Option<String>,
+    pub command_line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationReport {
+    pub generated_at: String,
+    pub app_version: String,
+    /// Whatever the frontend knows about the device: label, chipset, ...
+    pub device: BTreeMap<String, String>,
+    pub results: Vec<PartitionResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub total_duration_ms: u64,
+}
+
+/// Build a report for `operation_ids` from the command history, hashing the
+/// files involved. A read-all is expanded into one result per dump it wrote.
+pub fn build(operation_ids: &[String], device: BTreeMap<String, String>) -> OperationReport {
+    let entries: Vec<AntumbraCommandInfo> = command_history::all()
+        .into_iter()
+        .filter(|entry| entry.operation_id.as_ref().is_some_and(|id| operation_ids.contains(id)))
+        .collect();
+
+    let mut results = results_from(&entries);
+    for result in &mut results {
+        result.sha256 = result.file.as_deref().and_then(|file| sha256_file(Path::new(file)).ok());
+    }
+
+    let succeeded = results.iter().filter(|result| result.success == Some(true)).count();
+    OperationReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        device,
+        failed: results.len() - succeeded,
+        succeeded,
+        total_duration_ms: entries.iter().filter_map(|entry| entry.duration_ms).sum(),
+        results,
+    }
+}
+
+/// Write `report` as HTML when `destination` ends in .html/.htm, else as JSON.
+/// Paths, serials and the like are redacted unless `redact_exports` is off.
+pub fn write(report: &OperationReport, destination: &Path) -> Result<()> {
+    let report = &redacted(report);
+    let html = destination
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+    let contents = if html { render_html(report) } else { serde_json::to_string_pretty(report)? };
+    std::fs::write(destination, contents)
+        .with_context(|| format!("Failed to write {}", destination.display()))
+}
+
+fn redacted(report: &OperationReport) -> OperationReport {
+    let mut report = report.clone();
+    for value in report.device.values_mut() {
+        *value = redaction::redact_for_export(value);
+    }
+    for result in &mut report.results {
+        result.file = result.file.as_deref().map(redaction::redact_for_export);
+        result.command_line = redaction::redact_for_export(&result.command_line);
+    }
+    report
+}
+
+fn results_from(entries: &[AntumbraCommandInfo]) -> Vec<PartitionResult> {
+    let mut results = Vec::new();
+    for entry in entries {
+        let operation = entry.args.first().cloned().unwrap_or_default();
+        let result = PartitionResult {
+            operation_id: entry.operation_id.clone().unwrap_or_default(),
+            operation: operation.clone(),
+            partition: None,
+            file: None,
+            success: entry.success,
+            exit_code: entry.exit_code,
+            started_at: entry.started_at.clone(),
+            finished_at: entry.finished_at.clone(),
+            duration_ms: entry.duration_ms,
+            bytes: entry.bytes,
+            sha256: None,
+            command_line: command_line(entry),
+        };
+        match operation.as_str() {
+            "download" | "upload" => results.push(PartitionResult {
+                partition: entry.args.get(1).cloned(),
+                file: entry.args.get(2).cloned(),
+                ..result
+            }),
+            "read-all" => {
+                let dumps = entry.args.get(1).map(|dir| dumps_since(dir, &entry.started_at));
+                match dumps.filter(|dumps| !dumps.is_empty()) {
+                    Some(dumps) => results.extend(dumps.into_iter().map(|(path, size)| {
+                        PartitionResult {
+                            partition: Path::new(&path)
+                                .file_stem()
+                                .map(|stem| stem.to_string_lossy().into_owned()),
+                            file: Some(path),
+                            bytes: Some(size),
+                            duration_ms: None,
+                            ..result.clone()
+                        }
+                    })),
+                    None => results.push(result),
+                }
+            }
+            "erase" | "format" => {
+                results.push(PartitionResult { partition: entry.args.get(1).cloned(), ..result })
+            }
+            _ => results.push(result),
+        }
+    }
+    results
+}
+
+/// Files in `dir` modified at or after `started_at`, sorted by name
+fn dumps_since(dir: &str, started_at: &str) -> Vec<(String, u64)> {
+    let Ok(started) = chrono::DateTime::parse_from_rfc3339(started_at) else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dumps: Vec<(String, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|metadata| metadata.is_file())?;
+            let modified: chrono::DateTime<chrono::Utc> = metadata.modified().ok()?.into();
+            (modified >= started).then(|| (entry.path().display().to_string(), metadata.len()))
+        })
+        .collect();
+    dumps.sort();
+    dumps
+}
+
+fn command_line(entry: &AntumbraCommandInfo) -> String {
+    std::iter::once(&entry.command)
+        .chain(&entry.args)
+        .map(|arg| quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote(arg: &str) -> String {
+    if arg.contains(char::is_whitespace) {
+        format!("\"{}\"", arg)
+    } else {
+        arg.to_string()
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn render_html(report: &OperationReport) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Operation report</title>\n\
+         <style>body{font-family:sans-serif;margin:2em}table{border-collapse:collapse}\
+         td,th{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+         code{font-size:85%;word-break:break-all}.ok{color:#080}.fail{color:#b00}</style>\n\
+         </head><body>\n<h1>Operation report</h1>\n",
+    );
+    html.push_str(&format!(
+        "<p>Generated {} by penumbra-wrapper {}. {} succeeded, {} failed, {:.1}s total.</p>\n",
+        escape(&report.generated_at),
+        escape(&report.app_version),
+        report.succeeded,
+        report.failed,
+        report.total_duration_ms as f64 / 1000.0
+    ));
+
+    if !report.device.is_empty() {
+        html.push_str("<h2>Device</h2>\n<table>\n");
+        for (key, value) in &report.device {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                escape(key),
+                escape(value)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str(
+        "<h2>Results</h2>\n<table>\n<tr><th>Partition</th><th>Operation</th><th>Result</th>\
+         <th>Duration</th><th>Size</th><th>SHA-256</th><th>Command</th></tr>\n",
+    );
+    for result in &report.results {
+        let (class, status) = match result.success {
+            Some(true) => ("ok", "OK".to_string()),
+            Some(false) => ("fail", format!("Failed ({})", result.exit_code.unwrap_or(-1))),
+            None => ("fail", "Interrupted".to_string()),
+        };
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td>\
+             <td><code>{}</code></td><td><code>{}</code></td></tr>\n",
+            escape(result.partition.as_deref().unwrap_or("-")),
+            escape(&result.operation),
+            class,
+            status,
+            result.duration_ms.map(|ms| format!("{:.1}s", ms as f64 / 1000.0)).unwrap_or_default(),
+            result.bytes.map(|bytes| bytes.to_string()).unwrap_or_default(),
+            escape(result.sha256.as_deref().unwrap_or("")),
+            escape(&result.command_line),
+        ));
+    }
+    html.push_str("</table>\n</body></html>\n");
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(args: &[&str], success: bool) -> AntumbraCommandInfo {
+        AntumbraCommandInfo {
+            id: String::new(),
+            command: "antumbra".into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            working_dir: String::new(),
+            started_at: "2025-01-01T10:00:00+00:00".into(),
+            operation_id: Some("op-1".into()),
+            finished_at: Some("2025-01-01T10:00:05+00:00".into()),
+            duration_ms: Some(5_000),
+            exit_code: Some(if success { 0 } else { 1 }),
+            success: Some(success),
+            bytes: None,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn maps_history_to_partition_results() {
+        let entries = vec![
+            entry(&["download", "boot_a", "/fw/my boot.img", "-d", "da.bin"], true),
+            entry(&["erase", "userdata", "-d", "da.bin"], false),
+        ];
+        let results = results_from(&entries);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].partition.as_deref(), Some("boot_a"));
+        assert_eq!(results[0].file.as_deref(), Some("/fw/my boot.img"));
+        assert_eq!(
+            results[0].command_line,
+            "antumbra download boot_a \"/fw/my boot.img\" -d da.bin"
+        );
+        assert_eq!(results[1].partition.as_deref(), Some("userdata"));
+        assert_eq!(results[1].success, Some(false));
+    }
+
+    #[test]
+    fn html_escapes_values() {
+        let report = OperationReport {
+            generated_at: "now".into(),
+            app_version: "1.0".into(),
+            device: BTreeMap::from([("label".into(), "<Pixel & co>".into())]),
+            results: results_from(&[entry(&["download", "boot", "a\"b.img"], true)]),
+            succeeded: 1,
+            failed: 0,
+            total_duration_ms: 5_000,
+        };
+        let html = render_html(&report);
+        assert!(html.contains("&lt;Pixel &amp; co&gt;"));
+        assert!(html.contains("a&quot;b.img"));
+        assert!(!html.contains("<Pixel"));
+    }
+}
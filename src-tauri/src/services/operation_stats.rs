@@ -0,0 +1,191 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::antumbra::AntumbraCommandInfo;
+use crate::services::command_history;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Aggregated history for one antumbra subcommand
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OperationStats {
+    /// Antumbra subcommand, e.g. "download" or "read-all"
+    pub operation: String,
+    pub runs: usize,
+    pub successes: usize,
+    /// Over successful runs only
+    pub average_duration_ms: Option<u64>,
+    pub total_bytes: u64,
+    /// Over successful runs that recorded a byte count
+    pub average_bytes_per_sec: Option<f64>,
+    pub ms_per_gb: Option<u64>,
+}
+
+/// Statistics for every subcommand in the command history
+pub fn statistics() -> Vec<OperationStats> {
+    compute(&command_history::all())
+}
+
+/// Expected duration of a new run of `args`: from the historical throughput
+/// when the size is known up front (flashing an image), else the average
+/// duration of the subcommand
+pub fn estimate_duration_ms(args: &[String]) -> Option<u64> {
+    let command = args.first()?;
+    let stats = statistics().into_iter().find(|stats| &stats.operation == command)?;
+    let size = match command.as_str() {
+        "download" => args.get(2).and_then(|image| file_size(Path::new(image))),
+        _ => None,
+    };
+    match (size, stats.average_bytes_per_sec) {
+        (Some(size), Some(rate)) if rate > 0.0 => Some((size as f64 / rate * 1000.0) as u64),
+        _ => stats.average_duration_ms,
+    }
+}
+
+/// Historical throughput of `command`, for ETAs before a run has its own rate
+pub fn average_bytes_per_sec(command: &str) -> Option<f64> {
+    statistics().into_iter().find(|stats| stats.operation == command)?.average_bytes_per_sec
+}
+
+pub fn compute(entries: &[AntumbraCommandInfo]) -> Vec<OperationStats> {
+    #[derive(Default)]
+    struct Totals {
+        runs: usize,
+        successes: usize,
+        duration_ms: u64,
+        total_bytes: u64,
+        timed_bytes: u64,
+        timed_ms: u64,
+    }
+
+    let mut grouped: BTreeMap<&str, Totals> = BTreeMap::new();
+    for entry in entries {
+        let Some(command) = entry.args.first() else {
+            continue;
+        };
+        // Still running, or interrupted before it could be recorded
+        if entry.finished_at.is_none() {
+            continue;
+        }
+        let totals = grouped.entry(command).or_default();
+        totals.runs += 1;
+        if entry.success != Some(true) {
+            continue;
+        }
+        let duration_ms = entry.duration_ms.unwrap_or_default();
+        totals.successes += 1;
+        totals.duration_ms += duration_ms;
+        if let Some(bytes) = entry.bytes.filter(|bytes| *bytes > 0) {
+            totals.total_bytes += bytes;
+            if duration_ms > 0 {
+                totals.timed_bytes += bytes;
+                totals.timed_ms += duration_ms;
+            }
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|(operation, totals)| {
+            let timed = totals.timed_ms > 0;
+            OperationStats {
+                operation: operation.to_string(),
+                runs: totals.runs,
+                successes: totals.successes,
+                average_duration_ms: (totals.successes > 0)
+                    .then(|| totals.duration_ms / totals.successes as u64),
+                total_bytes: totals.total_bytes,
+                average_bytes_per_sec: timed
+                    .then(|| totals.timed_bytes as f64 / (totals.timed_ms as f64 / 1000.0)),
+                ms_per_gb: timed.then(|| {
+                    (totals.timed_ms as f64 / totals.timed_bytes as f64 * BYTES_PER_GB) as u64
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Bytes moved by a finished run of `args`, measured from the files it used:
+/// the flashed image, the dumped partition, or the dumps read-all wrote
+/// since `started`
+pub fn transferred_bytes(args: &[String], started: SystemTime) -> Option<u64> {
+    match args.first()?.as_str() {
+        "download" | "upload" => file_size(Path::new(args.get(2)?)),
+        "read-all" => {
+            let entries = std::fs::read_dir(args.get(1)?).ok()?;
+            let total = entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|metadata| metadata.is_file())
+                .filter(|metadata| metadata.modified().is_ok_and(|modified| modified >= started))
+                .map(|metadata| metadata.len())
+                .sum();
+            Some(total)
+        }
+        _ => None,
+    }
+}
+
+fn file_size(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok().filter(|metadata| metadata.is_file()).map(|m| m.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(
+        args: &[&str],
+        duration_ms: u64,
+        bytes: Option<u64>,
+        success: bool,
+    ) -> AntumbraCommandInfo {
+        AntumbraCommandInfo {
+            id: String::new(),
+            command: "antumbra".into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            working_dir: String::new(),
+            started_at: String::new(),
+            operation_id: None,
+            finished_at: Some(String::new()),
+            duration_ms: Some(duration_ms),
+            exit_code: Some(if success { 0 } else { 1 }),
+            success: Some(success),
+            bytes,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_successful_runs_per_subcommand() {
+        const GB: u64 = 1024 * 1024 * 1024;
+        let entries = vec![
+            run(&["download", "boot", "boot.img"], 2_000, Some(64 * 1024 * 1024), true),
+            run(&["download", "super", "super.img"], 30_000, Some(GB), true),
+            run(&["download", "vbmeta", "vbmeta.img"], 100, None, false),
+            run(&["read-all", "/out"], 600_000, Some(2 * GB), true),
+            run(&["erase", "userdata"], 500, None, true),
+        ];
+        let stats = compute(&entries);
+        assert_eq!(stats.len(), 3);
+
+        let download = stats.iter().find(|stats| stats.operation == "download").unwrap();
+        assert_eq!((download.runs, download.successes), (3, 2));
+        assert_eq!(download.average_duration_ms, Some(16_000));
+        let rate = download.average_bytes_per_sec.unwrap();
+        assert!((rate - (GB + 64 * 1024 * 1024) as f64 / 32.0).abs() < 1.0);
+
+        let read_all = stats.iter().find(|stats| stats.operation == "read-all").unwrap();
+        assert_eq!(read_all.ms_per_gb, Some(300_000));
+
+        let erase = stats.iter().find(|stats| stats.operation == "erase").unwrap();
+        assert_eq!(erase.average_bytes_per_sec, None);
+        assert_eq!(erase.average_duration_ms, Some(500));
+    }
+}
@@ -0,0 +1,206 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::config::{get_config_dir, AppSettings};
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Spill files kept for a failure's full output are deleted after this long
+const SPILL_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Keeps the most recent output lines of a stream in memory, optionally
+/// writing every line to a spill file so nothing is lost for long operations
+pub struct OutputBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+    dropped: usize,
+    spill: Option<(PathBuf, BufWriter<File>)>,
+}
+
+impl OutputBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self { lines: VecDeque::new(), capacity: capacity.max(1), dropped: 0, spill: None }
+    }
+
+    pub fn with_spill(capacity: usize, path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create output spill file {:?}", path))?;
+        let mut buffer = Self::new(capacity);
+        buffer.spill = Some((path.to_path_buf(), BufWriter::new(file)));
+        Ok(buffer)
+    }
+
+    /// Build a buffer for one stream of `operation_id` using the configured
+    /// cap, spilling to `<config>/output/` when enabled
+    pub fn from_settings(settings: &AppSettings, operation_id: &str, stream: &str) -> Self {
+        let capacity = settings.output_buffer_lines;
+        if !settings.spill_output_to_disk {
+            return Self::new(capacity);
+        }
+
+        let path = spill_dir().map(|dir| dir.join(format!("{}_{}.log", operation_id, stream)));
+        match path.and_then(|path| Self::with_spill(capacity, &path)) {
+            Ok(buffer) => buffer,
+            Err(err) => {
+                log::warn!("Output spill disabled: {:#}", err);
+                Self::new(capacity)
+            }
+        }
+    }
+
+    pub fn push(&mut self, line: String) {
+        if let Some((path, writer)) = &mut self.spill {
+            if let Err(err) = writeln!(writer, "{}", line) {
+                log::warn!("Failed to write output spill file {:?}: {}", path, err);
+                self.spill = None;
+            }
+        }
+
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Flush the spill file and join the retained tail. When lines were
+    /// evicted the tail is prefixed with a note saying where to find them.
+    pub fn finish(&mut self) -> String {
+        let mut spill_path = None;
+        if let Some((path, writer)) = &mut self.spill {
+            if let Err(err) = writer.flush() {
+                log::warn!("Failed to flush output spill file {:?}: {}", path, err);
+            }
+            spill_path = Some(path.clone());
+        }
+
+        let tail = self.lines.iter().map(String::as_str).collect::<Vec<_>>().join("\n");
+        if self.dropped == 0 {
+            return tail;
+        }
+
+        let note = match spill_path {
+            Some(path) => format!(
+                "[{} earlier lines omitted, full output in {}]",
+                self.dropped,
+                path.display()
+            ),
+            None => format!("[{} earlier lines omitted]", self.dropped),
+        };
+        format!("{}\n{}", note, tail)
+    }
+}
+
+/// A spill file is only worth keeping when `finish` pointed at it for the
+/// evicted lines; otherwise the output it holds was returned in full
+impl Drop for OutputBuffer {
+    fn drop(&mut self) {
+        if self.dropped > 0 {
+            return;
+        }
+        if let Some((path, writer)) = self.spill.take() {
+            drop(writer);
+            if let Err(err) = std::fs::remove_file(&path) {
+                log::warn!("Failed to remove output spill file {:?}: {}", path, err);
+            }
+        }
+    }
+}
+
+fn spill_dir() -> Result<PathBuf> {
+    Ok(get_config_dir()?.join("output"))
+}
+
+/// Delete spill files left by earlier runs once they are `SPILL_MAX_AGE` old
+pub fn prune_spill_files() {
+    if let Ok(dir) = spill_dir() {
+        prune_dir(&dir, SPILL_MAX_AGE, SystemTime::now());
+    }
+}
+
+fn prune_dir(dir: &Path, max_age: Duration, now: SystemTime) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else {
+            continue;
+        };
+        if now.duration_since(modified).map(|age| age > max_age).unwrap_or(false) {
+            if let Err(err) = std::fs::remove_file(entry.path()) {
+                log::warn!("Failed to remove output spill file {:?}: {}", entry.path(), err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_tail() {
+        let mut buffer = OutputBuffer::new(2);
+        buffer.push("one".to_string());
+        buffer.push("two".to_string());
+        assert_eq!(buffer.finish(), "one\ntwo");
+
+        buffer.push("three".to_string());
+        assert_eq!(buffer.finish(), "[1 earlier lines omitted]\ntwo\nthree");
+    }
+
+    #[test]
+    fn spills_every_line() {
+        let path =
+            std::env::temp_dir().join(format!("penumbra-output-buffer-{}.log", std::process::id()));
+        let mut buffer = OutputBuffer::with_spill(1, &path).unwrap();
+        buffer.push("one".to_string());
+        buffer.push("two".to_string());
+        let tail = buffer.finish();
+
+        assert!(tail.ends_with("\ntwo"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+
+        // The note points at the file, so it outlives the buffer
+        drop(buffer);
+        assert!(path.exists());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn drop_removes_a_spill_file_nothing_points_at() {
+        let path = std::env::temp_dir()
+            .join(format!("penumbra-output-buffer-{}.log", uuid::Uuid::new_v4()));
+        let mut buffer = OutputBuffer::with_spill(10, &path).unwrap();
+        buffer.push("one".to_string());
+        assert_eq!(buffer.finish(), "one");
+
+        drop(buffer);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn prune_removes_only_old_files() {
+        let dir = std::env::temp_dir().join(format!("output-prune-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("op_stdout.log");
+        std::fs::write(&path, "output").unwrap();
+
+        let hour = Duration::from_secs(60 * 60);
+        prune_dir(&dir, hour, SystemTime::now());
+        assert!(path.exists());
+
+        prune_dir(&dir, hour, SystemTime::now() + 2 * hour);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,100 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::OperationOutputPathEvent;
+use crate::services::config::load_settings;
+use crate::services::events;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const UNKNOWN_DEVICE: &str = "unknown-device";
+
+/// Place `target` under `<default_output_path>/<device>/<date>/<operation>`
+/// when `organize_output_dirs` is on and `target` lives in the default output
+/// directory. Creates the folder, emits `operation:output_path` with the
+/// resolved location and returns it; anything else is returned unchanged.
+/// `target_is_dir` tells a directory target (read-all) from an output file.
+pub fn resolve(
+    app: &AppHandle,
+    operation_id: &str,
+    target: &str,
+    target_is_dir: bool,
+    operation: &str,
+    device: Option<&str>,
+) -> Result<String> {
+    let settings = load_settings()?;
+    let base = match settings.default_output_path {
+        Some(base) if settings.organize_output_dirs => base,
+        _ => return Ok(target.to_string()),
+    };
+
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let Some(resolved) = organize(Path::new(&base), Path::new(target), device, &date, operation)
+    else {
+        return Ok(target.to_string());
+    };
+
+    let folder = if target_is_dir {
+        resolved.clone()
+    } else {
+        resolved.parent().map(Path::to_path_buf).unwrap_or_else(|| resolved.clone())
+    };
+    std::fs::create_dir_all(&folder)
+        .with_context(|| format!("Failed to create output folder {}", folder.display()))?;
+
+    let path = resolved.to_string_lossy().to_string();
+    let event = OperationOutputPathEvent {
+        operation_id: operation_id.to_string(),
+        path: path.clone(),
+        folder: folder.to_string_lossy().to_string(),
+    };
+    let _ = events::emit(app, "operation:output_path", event);
+    Ok(path)
+}
+
+fn organize(
+    base: &Path,
+    target: &Path,
+    device: Option<&str>,
+    date: &str,
+    operation: &str,
+) -> Option<PathBuf> {
+    let relative = target.strip_prefix(base).ok()?;
+    let device = device.map(sanitize).filter(|label| !label.is_empty());
+    Some(
+        base.join(device.as_deref().unwrap_or(UNKNOWN_DEVICE))
+            .join(date)
+            .join(sanitize(operation))
+            .join(relative),
+    )
+}
+
+fn sanitize(label: &str) -> String {
+    label
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn organizes_only_default_output_targets() {
+        let base = Path::new("/backups");
+        assert_eq!(
+            organize(base, Path::new("/backups/boot.img"), Some("Pixel 7"), "2025-01-02", "read"),
+            Some(PathBuf::from("/backups/Pixel_7/2025-01-02/read/boot.img"))
+        );
+        assert_eq!(
+            organize(base, base, None, "2025-01-02", "read-all"),
+            Some(PathBuf::from("/backups/unknown-device/2025-01-02/read-all"))
+        );
+        assert_eq!(organize(base, Path::new("/elsewhere/boot.img"), None, "d", "read"), None);
+    }
+}
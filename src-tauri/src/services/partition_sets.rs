@@ -0,0 +1,83 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::config::{load_settings, modify_settings, PartitionSet};
+use anyhow::Result;
+
+pub fn list() -> Result<Vec<PartitionSet>> {
+    Ok(load_settings()?.partition_sets)
+}
+
+/// Create the set `name`, or replace the partitions of an existing one
+pub fn save(name: &str, partitions: Vec<String>) -> Result<PartitionSet> {
+    let name = validate_name(name)?;
+    let set = PartitionSet { name: name.to_string(), partitions: dedup(partitions) };
+    modify_settings(|settings| {
+        match settings.partition_sets.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = set.clone(),
+            None => settings.partition_sets.push(set.clone()),
+        }
+        Ok(set)
+    })
+}
+
+pub fn rename(name: &str, new_name: &str) -> Result<PartitionSet> {
+    let new_name = validate_name(new_name)?;
+    modify_settings(|settings| {
+        if name != new_name && settings.partition_sets.iter().any(|set| set.name == new_name) {
+            anyhow::bail!("A partition set named '{}' already exists", new_name);
+        }
+        let set = settings
+            .partition_sets
+            .iter_mut()
+            .find(|set| set.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Partition set '{}' not found", name))?;
+        set.name = new_name.to_string();
+        Ok(set.clone())
+    })
+}
+
+pub fn delete(name: &str) -> Result<()> {
+    modify_settings(|settings| {
+        let before = settings.partition_sets.len();
+        settings.partition_sets.retain(|set| set.name != name);
+        if settings.partition_sets.len() == before {
+            anyhow::bail!("Partition set '{}' not found", name);
+        }
+        Ok(())
+    })
+}
+
+fn validate_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Partition set name cannot be empty");
+    }
+    Ok(name)
+}
+
+/// Drop blanks and repeats while keeping the user's order
+fn dedup(partitions: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::with_capacity(partitions.len());
+    for partition in partitions {
+        let partition = partition.trim();
+        if !partition.is_empty() && !unique.iter().any(|existing| existing == partition) {
+            unique.push(partition.to_string());
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_keeps_order() {
+        let partitions = ["md1img", " boot_a", "", "md1img", "vbmeta_a"];
+        let partitions = partitions.iter().map(|p| p.to_string()).collect();
+        assert_eq!(dedup(partitions), vec!["md1img", "boot_a", "vbmeta_a"]);
+    }
+}
@@ -0,0 +1,138 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::Partition;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// The partition table from the last successful `pgpt`
+static CACHED: Mutex<Option<Vec<Partition>>> = Mutex::new(None);
+
+pub fn remember(partitions: &[Partition]) {
+    if let Ok(mut cached) = CACHED.lock() {
+        *cached = Some(partitions.to_vec());
+    }
+}
+
+pub fn cached() -> Option<Vec<Partition>> {
+    CACHED.lock().ok().and_then(|cached| cached.clone())
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Guess the format from the file extension, defaulting to CSV
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+/// One exported row; sizes and offsets are also given in bytes so exports
+/// from different devices can be compared in a spreadsheet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportedPartition {
+    name: String,
+    start: String,
+    start_bytes: Option<u64>,
+    size: String,
+    size_bytes: Option<u64>,
+    display_size: Option<String>,
+}
+
+impl From<&Partition> for ExportedPartition {
+    fn from(partition: &Partition) -> Self {
+        Self {
+            name: partition.name.clone(),
+            start: partition.start.clone(),
+            start_bytes: parse_hex(&partition.start),
+            size: partition.size.clone(),
+            size_bytes: parse_hex(&partition.size),
+            display_size: partition.display_size.clone(),
+        }
+    }
+}
+
+fn parse_hex(value: &str) -> Option<u64> {
+    let digits = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X"))?;
+    u64::from_str_radix(digits, 16).ok()
+}
+
+/// Write `partitions` to `path` as CSV or JSON
+pub fn export(path: &Path, format: ExportFormat, partitions: &[Partition]) -> Result<()> {
+    let rows: Vec<ExportedPartition> = partitions.iter().map(ExportedPartition::from).collect();
+    let content = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&rows)?,
+        ExportFormat::Csv => to_csv(&rows),
+    };
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write partition list to {}", path.display()))
+}
+
+fn to_csv(rows: &[ExportedPartition]) -> String {
+    let mut csv = String::from("name,start,start_bytes,size,size_bytes,display_size\n");
+    for row in rows {
+        let fields = [
+            csv_field(&row.name),
+            csv_field(&row.start),
+            row.start_bytes.map(|bytes| bytes.to_string()).unwrap_or_default(),
+            csv_field(&row.size),
+            row.size_bytes.map(|bytes| bytes.to_string()).unwrap_or_default(),
+            csv_field(row.display_size.as_deref().unwrap_or_default()),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_csv_with_byte_columns() {
+        let partitions = [
+            Partition {
+                name: "preloader".into(),
+                start: "0x0".into(),
+                size: "0x400000".into(),
+                display_size: Some("4 MiB".into()),
+            },
+            Partition {
+                name: "odd,name".into(),
+                start: "bogus".into(),
+                size: "0x1000".into(),
+                display_size: None,
+            },
+        ];
+        let rows: Vec<ExportedPartition> = partitions.iter().map(ExportedPartition::from).collect();
+        assert_eq!(
+            to_csv(&rows),
+            "name,start,start_bytes,size,size_bytes,display_size\n\
+             preloader,0x0,0,0x400000,4194304,4 MiB\n\
+             \"odd,name\",bogus,,0x1000,4096,\n"
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("table.JSON")), ExportFormat::Json);
+        assert_eq!(ExportFormat::from_path(Path::new("table.txt")), ExportFormat::Csv);
+    }
+}
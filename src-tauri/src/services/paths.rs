@@ -0,0 +1,171 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+/// Environment variable that relocates every config, log and binary file
+pub const CONFIG_DIR_ENV: &str = "PENUMBRA_CONFIG_DIR";
+
+/// Config directory chosen at launch, if any
+static CONFIG_DIR_OVERRIDE: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// The one directory holding settings, logs and the managed antumbra binaries
+pub fn config_dir() -> Result<PathBuf> {
+    if let Some(dir) = config_dir_override() {
+        return Ok(dir.to_path_buf());
+    }
+
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+
+    Ok(config_dir.join("penumbra-wrapper"))
+}
+
+pub fn config_file() -> Result<PathBuf> {
+    Ok(config_dir()?.join("config.json"))
+}
+
+/// The wrapper's own log, falling back to the temp directory so logging
+/// still works when the config directory cannot be determined
+pub fn wrapper_log_file() -> PathBuf {
+    config_dir()
+        .unwrap_or_else(|_| std::env::temp_dir().join("penumbra-wrapper"))
+        .join("penumbra-wrapper.log")
+}
+
+/// Log written by antumbra itself, which runs with the config directory as cwd
+pub fn antumbra_log_file() -> Result<PathBuf> {
+    Ok(config_dir()?.join("antumbra.log"))
+}
+
+pub fn bin_dir() -> Result<PathBuf> {
+    Ok(config_dir()?.join("bin"))
+}
+
+/// One folder per installed plugin, each with a plugin.json manifest
+pub fn plugins_dir() -> Result<PathBuf> {
+    Ok(config_dir()?.join("plugins"))
+}
+
+/// Use `--config-dir <path>` from the launch arguments, or `PENUMBRA_CONFIG_DIR`,
+/// instead of the platform config directories. Call before anything reads settings.
+pub fn init_config_dir_override(args: impl IntoIterator<Item = String>) {
+    let dir = parse_config_dir_arg(args).or_else(|| {
+        std::env::var_os(CONFIG_DIR_ENV).filter(|value| !value.is_empty()).map(PathBuf::from)
+    });
+    let _ = CONFIG_DIR_OVERRIDE.set(dir);
+}
+
+fn config_dir_override() -> Option<&'static Path> {
+    CONFIG_DIR_OVERRIDE
+        .get_or_init(|| std::env::var_os(CONFIG_DIR_ENV).map(PathBuf::from))
+        .as_deref()
+        .filter(|dir| !dir.as_os_str().is_empty())
+}
+
+fn parse_config_dir_arg(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config-dir" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(dir) = arg.strip_prefix("--config-dir=") {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    None
+}
+
+/// Older builds kept antumbra's binaries and log under Tauri's
+/// `app_config_dir()`, which is not the settings directory on every platform.
+/// Move whatever is left there into `config_dir()`; files that already exist
+/// at the destination win.
+pub fn migrate_legacy_files(app: &AppHandle) {
+    if config_dir_override().is_some() {
+        return;
+    }
+    let (Ok(legacy), Ok(target)) = (app.path().app_config_dir(), config_dir()) else {
+        return;
+    };
+    if legacy == target || !legacy.is_dir() {
+        return;
+    }
+
+    match move_dir_contents(&legacy, &target) {
+        Ok(0) => {}
+        Ok(moved) => {
+            log::info!("Moved {} item(s) from {} to {}", moved, legacy.display(), target.display());
+            let _ = std::fs::remove_dir(&legacy);
+        }
+        Err(err) => log::warn!("Failed to migrate {}: {:#}", legacy.display(), err),
+    }
+}
+
+fn move_dir_contents(from: &Path, to: &Path) -> Result<usize> {
+    std::fs::create_dir_all(to).with_context(|| format!("Failed to create {}", to.display()))?;
+
+    let mut moved = 0;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let dest = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            moved += move_dir_contents(&source, &dest)?;
+            let _ = std::fs::remove_dir(&source);
+            continue;
+        }
+        if dest.exists() {
+            continue;
+        }
+        if std::fs::rename(&source, &dest).is_err() {
+            // Different filesystems: copy, then drop the original
+            std::fs::copy(&source, &dest)
+                .with_context(|| format!("Failed to copy {}", source.display()))?;
+            let _ = std::fs::remove_file(&source);
+        }
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_config_dir_flag() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_config_dir_arg(args(&["app", "--config-dir", "/tmp/profile"])),
+            Some(PathBuf::from("/tmp/profile"))
+        );
+        assert_eq!(
+            parse_config_dir_arg(args(&["app", "--config-dir=/tmp/b"])),
+            Some(PathBuf::from("/tmp/b"))
+        );
+        assert_eq!(parse_config_dir_arg(args(&["app"])), None);
+    }
+
+    #[test]
+    fn moves_files_without_overwriting() {
+        let root = std::env::temp_dir().join(format!("paths-test-{}", uuid::Uuid::new_v4()));
+        let (from, to) = (root.join("old"), root.join("new"));
+        std::fs::create_dir_all(from.join("bin")).unwrap();
+        std::fs::create_dir_all(&to).unwrap();
+        std::fs::write(from.join("bin").join("antumbra"), b"old").unwrap();
+        std::fs::write(from.join("antumbra.log"), b"old").unwrap();
+        std::fs::write(to.join("antumbra.log"), b"new").unwrap();
+
+        assert_eq!(move_dir_contents(&from, &to).unwrap(), 1);
+        assert_eq!(std::fs::read(to.join("bin").join("antumbra")).unwrap(), b"old");
+        assert_eq!(std::fs::read(to.join("antumbra.log")).unwrap(), b"new");
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,270 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Plugins add tools (image converters, vendor unlockers, ...) without
+//! rebuilding the app. Each lives in its own folder under `plugins/` with a
+//! plugin.json manifest naming executables shipped next to it:
+//!
+//! ```json
+//! {
+//!   "id": "sparse-tools",
+//!   "name": "Sparse image tools",
+//!   "tools": [{
+//!     "id": "unsparse",
+//!     "name": "Convert sparse image",
+//!     "executable": "bin/simg2img",
+//!     "args": ["{input}", "{output}"],
+//!     "writes_device": false,
+//!     "params": [
+//!       { "name": "input", "label": "Sparse image", "kind": "file" },
+//!       { "name": "output", "label": "Raw image", "kind": "save_file" }
+//!     ]
+//!   }]
+//! }
+//! ```
+//!
+//! Only native executables are supported; WASM modules are not loaded.
+
+use crate::services::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "plugin.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub tools: Vec<PluginTool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginTool {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Relative to the plugin folder
+    pub executable: String,
+    /// `{param}` placeholders are replaced with the given values. An argument
+    /// naming an optional parameter that was left empty is dropped, together
+    /// with a flag right before it (`"--label", "{label}"`).
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub params: Vec<PluginParam>,
+    /// Blocked in safe mode, like the built-in write operations. Assumed
+    /// unless the manifest says otherwise.
+    #[serde(default = "default_true")]
+    pub writes_device: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginParam {
+    pub name: String,
+    pub label: String,
+    #[serde(default)]
+    pub kind: ParamKind,
+    #[serde(default = "default_true")]
+    pub required: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// How the frontend asks for a parameter
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    #[default]
+    Text,
+    File,
+    SaveFile,
+    Directory,
+}
+
+/// A tool together with the plugin providing it, as listed to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginToolInfo {
+    pub plugin_id: String,
+    pub plugin_name: String,
+    #[serde(flatten)]
+    pub tool: PluginTool,
+}
+
+/// A tool ready to run: the executable, its folder and the filled-in arguments
+pub struct PreparedTool {
+    pub executable: PathBuf,
+    pub working_dir: PathBuf,
+    pub args: Vec<String>,
+    pub writes_device: bool,
+}
+
+/// Every readable plugin manifest, with the folder it came from. Broken
+/// manifests are logged and skipped so one bad plugin can't hide the rest.
+fn load_all() -> Vec<(PathBuf, PluginManifest)> {
+    let Ok(dir) = paths::plugins_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut plugins: Vec<(PathBuf, PluginManifest)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(MANIFEST_FILE).is_file())
+        .filter_map(|path| match load_manifest(&path) {
+            Ok(manifest) => Some((path, manifest)),
+            Err(err) => {
+                log::warn!("Skipping plugin {}: {:#}", path.display(), err);
+                None
+            }
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.1.id.cmp(&b.1.id));
+    plugins
+}
+
+fn load_manifest(folder: &Path) -> Result<PluginManifest> {
+    let path = folder.join(MANIFEST_FILE);
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+}
+
+/// Tools of every installed plugin
+pub fn list_tools() -> Vec<PluginToolInfo> {
+    load_all()
+        .into_iter()
+        .flat_map(|(_, manifest)| {
+            let (plugin_id, plugin_name) = (manifest.id, manifest.name);
+            manifest.tools.into_iter().map(move |tool| PluginToolInfo {
+                plugin_id: plugin_id.clone(),
+                plugin_name: plugin_name.clone(),
+                tool,
+            })
+        })
+        .collect()
+}
+
+/// Look up `tool_id` of `plugin_id` and fill in its arguments from `params`
+pub fn prepare(
+    plugin_id: &str,
+    tool_id: &str,
+    params: &HashMap<String, String>,
+) -> Result<PreparedTool> {
+    let (folder, manifest) = load_all()
+        .into_iter()
+        .find(|(_, manifest)| manifest.id == plugin_id)
+        .with_context(|| format!("Plugin {} is not installed", plugin_id))?;
+    let tool = manifest
+        .tools
+        .iter()
+        .find(|tool| tool.id == tool_id)
+        .with_context(|| format!("Plugin {} has no tool {}", plugin_id, tool_id))?;
+
+    // Keep manifests from pointing at arbitrary binaries elsewhere on disk
+    let folder = folder.canonicalize().context("Failed to resolve plugin folder")?;
+    let executable = folder
+        .join(&tool.executable)
+        .canonicalize()
+        .with_context(|| format!("Plugin executable not found: {}", tool.executable))?;
+    if !executable.starts_with(&folder) {
+        anyhow::bail!("Plugin executable must be inside the plugin folder");
+    }
+
+    Ok(PreparedTool {
+        executable,
+        working_dir: folder,
+        args: fill_args(tool, params)?,
+        writes_device: tool.writes_device,
+    })
+}
+
+fn fill_args(tool: &PluginTool, params: &HashMap<String, String>) -> Result<Vec<String>> {
+    let value = |param: &PluginParam| params.get(&param.name).filter(|value| !value.is_empty());
+    if let Some(missing) = tool.params.iter().find(|param| param.required && value(param).is_none())
+    {
+        anyhow::bail!("{} is required", missing.label);
+    }
+
+    let mut args = Vec::with_capacity(tool.args.len());
+    // Whether the last argument is a bare flag that belongs to the next one
+    let mut after_flag = false;
+    'args: for template in &tool.args {
+        let mut arg = template.clone();
+        let mut has_placeholder = false;
+        for param in &tool.params {
+            let placeholder = format!("{{{}}}", param.name);
+            if !arg.contains(&placeholder) {
+                continue;
+            }
+            has_placeholder = true;
+            match value(param) {
+                Some(value) => arg = arg.replace(&placeholder, value),
+                None => {
+                    if after_flag {
+                        args.pop();
+                    }
+                    after_flag = false;
+                    continue 'args;
+                }
+            }
+        }
+        after_flag = !has_placeholder && template.starts_with('-');
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool() -> PluginTool {
+        serde_json::from_str(
+            r#"{
+                "id": "unsparse",
+                "name": "Convert sparse image",
+                "executable": "bin/simg2img",
+                "args": ["{input}", "--out={output}", "--label", "{label}"],
+                "params": [
+                    { "name": "input", "label": "Sparse image", "kind": "file" },
+                    { "name": "output", "label": "Raw image", "kind": "save_file" },
+                    { "name": "label", "label": "Label", "required": false }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fills_placeholders_and_drops_empty_optionals() {
+        let params = HashMap::from([
+            ("input".to_string(), "a.img".to_string()),
+            ("output".to_string(), "b.img".to_string()),
+        ]);
+        assert_eq!(fill_args(&tool(), &params).unwrap(), ["a.img", "--out=b.img"]);
+
+        let mut labelled = params.clone();
+        labelled.insert("label".to_string(), "userdata".to_string());
+        assert_eq!(
+            fill_args(&tool(), &labelled).unwrap(),
+            ["a.img", "--out=b.img", "--label", "userdata"]
+        );
+        assert!(tool().writes_device);
+
+        let params = HashMap::from([("input".to_string(), "a.img".to_string())]);
+        let err = fill_args(&tool(), &params).unwrap_err();
+        assert_eq!(err.to_string(), "Raw image is required");
+    }
+}
@@ -0,0 +1,144 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::preloader::{PreloaderEntry, PreloaderMetadata};
+use crate::services::config::get_config_dir;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes changes to the library index
+static LOCK: Mutex<()> = Mutex::new(());
+
+pub fn library_dir() -> Result<PathBuf> {
+    Ok(get_config_dir()?.join("preloaders"))
+}
+
+fn index_path() -> Result<PathBuf> {
+    Ok(library_dir()?.join("index.json"))
+}
+
+fn load_index() -> Result<Vec<PreloaderEntry>> {
+    let path = index_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read preloader library {:?}", path))?;
+    serde_json::from_str(&contents).with_context(|| format!("Invalid preloader library {:?}", path))
+}
+
+fn save_index(entries: &[PreloaderEntry]) -> Result<()> {
+    let path = index_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(entries)?)
+        .with_context(|| format!("Failed to write preloader library {:?}", path))
+}
+
+/// Every stored preloader, optionally only those whose model or chipset
+/// contains `query` (case-insensitive)
+pub fn list(query: Option<&str>) -> Result<Vec<PreloaderEntry>> {
+    let entries = load_index()?;
+    let Some(query) = query.map(str::to_lowercase).filter(|query| !query.trim().is_empty()) else {
+        return Ok(entries);
+    };
+    Ok(entries.into_iter().filter(|entry| matches_query(&entry.metadata, &query)).collect())
+}
+
+fn matches_query(metadata: &PreloaderMetadata, query: &str) -> bool {
+    [&metadata.model, &metadata.chipset]
+        .into_iter()
+        .flatten()
+        .any(|value| value.to_lowercase().contains(query))
+}
+
+/// Copy `source` into the library. Importing a file that is already stored
+/// returns the existing entry with its metadata updated.
+pub fn import(source: &Path, metadata: PreloaderMetadata) -> Result<PreloaderEntry> {
+    let data = std::fs::read(source).with_context(|| format!("Failed to read {:?}", source))?;
+    let sha256 = hex::encode(Sha256::digest(&data));
+
+    let _guard = LOCK.lock();
+    let mut entries = load_index()?;
+    if let Some(existing) = entries.iter_mut().find(|entry| entry.sha256 == sha256) {
+        existing.metadata = metadata;
+        let entry = existing.clone();
+        save_index(&entries)?;
+        return Ok(entry);
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let path = library_dir()?.join(format!("{}.bin", id));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, &data)
+        .with_context(|| format!("Failed to store preloader {:?}", path))?;
+
+    let entry = PreloaderEntry {
+        id,
+        file_name: source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.display().to_string(),
+        sha256,
+        size: data.len() as u64,
+        imported_at: chrono::Utc::now().to_rfc3339(),
+        metadata,
+    };
+    entries.push(entry.clone());
+    save_index(&entries)?;
+    Ok(entry)
+}
+
+pub fn update(id: &str, metadata: PreloaderMetadata) -> Result<PreloaderEntry> {
+    let _guard = LOCK.lock();
+    let mut entries = load_index()?;
+    let entry = entries
+        .iter_mut()
+        .find(|entry| entry.id == id)
+        .with_context(|| format!("Preloader {} not found in library", id))?;
+    entry.metadata = metadata;
+    let entry = entry.clone();
+    save_index(&entries)?;
+    Ok(entry)
+}
+
+/// Drop a preloader from the library and delete its stored copy
+pub fn remove(id: &str) -> Result<()> {
+    let _guard = LOCK.lock();
+    let mut entries = load_index()?;
+    let index = entries
+        .iter()
+        .position(|entry| entry.id == id)
+        .with_context(|| format!("Preloader {} not found in library", id))?;
+    let entry = entries.remove(index);
+    save_index(&entries)?;
+    if let Err(err) = std::fs::remove_file(&entry.path) {
+        log::warn!("Failed to delete stored preloader {}: {}", entry.path, err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_matches_model_or_chipset() {
+        let metadata = PreloaderMetadata {
+            model: Some("Redmi Note 12".to_string()),
+            chipset: Some("MT6789".to_string()),
+            ..Default::default()
+        };
+        assert!(matches_query(&metadata, "note 12"));
+        assert!(matches_query(&metadata, "mt6789"));
+        assert!(!matches_query(&metadata, "mt6833"));
+    }
+}
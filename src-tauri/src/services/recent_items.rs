@@ -0,0 +1,79 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::config::{load_settings, modify_settings, RecentItem, RecentItemKind};
+use anyhow::Result;
+
+/// Paths remembered per kind
+const RECENT_LIMIT: usize = 10;
+
+/// Move `path` to the front of the recent `kind` list. Failures are only
+/// logged since remembering a path must never fail the operation using it.
+pub fn record(kind: RecentItemKind, path: &str) {
+    if path.trim().is_empty() {
+        return;
+    }
+    let result = modify_settings(|settings| {
+        push(&mut settings.recent_items, kind, path, chrono::Utc::now().to_rfc3339());
+        Ok(())
+    });
+    if let Err(err) = result {
+        log::warn!("Failed to remember recent {:?} {}: {:#}", kind, path, err);
+    }
+}
+
+/// Recent items of `kind`, or of every kind when `None`, newest first
+pub fn list(kind: Option<RecentItemKind>) -> Vec<RecentItem> {
+    let settings = load_settings().unwrap_or_default();
+    settings
+        .recent_items
+        .into_iter()
+        .filter(|item| kind.is_none_or(|kind| item.kind == kind))
+        .collect()
+}
+
+/// Forget recent items of `kind`, or all of them when `None`
+pub fn clear(kind: Option<RecentItemKind>) -> Result<()> {
+    modify_settings(|settings| {
+        settings.recent_items.retain(|item| kind.is_some_and(|kind| item.kind != kind));
+        Ok(())
+    })
+}
+
+fn push(items: &mut Vec<RecentItem>, kind: RecentItemKind, path: &str, used_at: String) {
+    items.retain(|item| !(item.kind == kind && item.path == path));
+    items.insert(0, RecentItem { kind, path: path.to_string(), used_at });
+
+    let mut kept = 0;
+    items.retain(|item| {
+        if item.kind != kind {
+            return true;
+        }
+        kept += 1;
+        kept <= RECENT_LIMIT
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_newest_unique_paths_per_kind() {
+        let mut items = Vec::new();
+        push(&mut items, RecentItemKind::Da, "/da.bin", "t0".to_string());
+        for i in 0..=RECENT_LIMIT {
+            push(&mut items, RecentItemKind::Image, &format!("/img{}", i), format!("t{}", i));
+        }
+        push(&mut items, RecentItemKind::Image, "/img5", "later".to_string());
+
+        let images: Vec<_> = items.iter().filter(|i| i.kind == RecentItemKind::Image).collect();
+        assert_eq!(images.len(), RECENT_LIMIT);
+        assert_eq!(images[0].path, "/img5");
+        assert_eq!(images[0].used_at, "later");
+        assert!(!images.iter().any(|item| item.path == "/img0"));
+        assert!(items.iter().any(|item| item.kind == RecentItemKind::Da));
+    }
+}
@@ -0,0 +1,129 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! CPU and memory sampling of a running antumbra process. A process that
+//! stops using CPU while the device is still attached, or whose memory keeps
+//! growing, has usually wedged long before the inactivity timeout fires.
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Time between two samples
+pub const SAMPLE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Below this CPU percentage a sample counts as idle
+const IDLE_CPU_PERCENT: f32 = 0.5;
+/// Consecutive idle samples before warning, 30s at `SAMPLE_INTERVAL`
+const IDLE_SAMPLES_WARNING: u32 = 15;
+/// Warn once memory exceeds both this and `MEMORY_GROWTH_FACTOR` times the
+/// first sample
+const MEMORY_WARNING_BYTES: u64 = 512 * 1024 * 1024;
+const MEMORY_GROWTH_FACTOR: u64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceSample {
+    /// Percentage of one core; can exceed 100 on multi-threaded work
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub peak_memory_bytes: u64,
+}
+
+pub struct ResourceMonitor {
+    system: System,
+    pid: Pid,
+    samples: u32,
+    idle_samples: u32,
+    baseline_memory: u64,
+    peak_memory: u64,
+}
+
+impl ResourceMonitor {
+    pub fn new(pid: u32) -> Self {
+        Self {
+            system: System::new(),
+            pid: Pid::from_u32(pid),
+            samples: 0,
+            idle_samples: 0,
+            baseline_memory: 0,
+            peak_memory: 0,
+        }
+    }
+
+    /// Refresh the process; None once it is gone
+    pub fn sample(&mut self) -> Option<ResourceSample> {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[self.pid]),
+            ProcessRefreshKind::new().with_cpu().with_memory(),
+        );
+        let process = self.system.process(self.pid)?;
+        let (cpu_percent, memory_bytes) = (process.cpu_usage(), process.memory());
+        self.record(cpu_percent, memory_bytes);
+        Some(ResourceSample { cpu_percent, memory_bytes, peak_memory_bytes: self.peak_memory })
+    }
+
+    fn record(&mut self, cpu_percent: f32, memory_bytes: u64) {
+        // CPU usage is a delta between refreshes, so the first one always reads 0
+        if self.samples == 0 {
+            self.baseline_memory = memory_bytes;
+        } else if cpu_percent < IDLE_CPU_PERCENT {
+            self.idle_samples += 1;
+        } else {
+            self.idle_samples = 0;
+        }
+        self.samples += 1;
+        self.peak_memory = self.peak_memory.max(memory_bytes);
+    }
+
+    /// Why the process looks wedged, if it does
+    pub fn warning(&self, current_memory: u64) -> Option<String> {
+        if self.idle_samples >= IDLE_SAMPLES_WARNING {
+            let idle_secs = self.idle_samples as u64 * SAMPLE_INTERVAL.as_secs();
+            return Some(format!("antumbra has used no CPU for {}s", idle_secs));
+        }
+        if current_memory > MEMORY_WARNING_BYTES
+            && current_memory > self.baseline_memory.saturating_mul(MEMORY_GROWTH_FACTOR)
+        {
+            return Some(format!(
+                "antumbra memory grew from {} MiB to {} MiB",
+                self.baseline_memory / (1024 * 1024),
+                current_memory / (1024 * 1024)
+            ));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn warns_on_idle_cpu_and_memory_growth() {
+        let mut monitor = ResourceMonitor::new(std::process::id());
+        monitor.record(0.0, 20 * MIB);
+        for _ in 0..IDLE_SAMPLES_WARNING - 1 {
+            monitor.record(0.1, 20 * MIB);
+        }
+        assert_eq!(monitor.warning(20 * MIB), None);
+
+        monitor.record(0.0, 20 * MIB);
+        assert_eq!(monitor.warning(20 * MIB).unwrap(), "antumbra has used no CPU for 30s");
+
+        monitor.record(35.0, 600 * MIB);
+        assert_eq!(
+            monitor.warning(600 * MIB).unwrap(),
+            "antumbra memory grew from 20 MiB to 600 MiB"
+        );
+        assert_eq!(monitor.peak_memory, 600 * MIB);
+    }
+
+    #[test]
+    fn samples_own_process() {
+        let mut monitor = ResourceMonitor::new(std::process::id());
+        let sample = monitor.sample().unwrap();
+        assert!(sample.memory_bytes > 0);
+    }
+}
@@ -14,27 +14,123 @@ pub struct ScatterParser;
 
 impl ScatterParser {
     /// Parse scatter file - auto-detects format (XML or TXT/YAML)
-    pub fn parse(file_path: &str) -> Result<ScatterFile, AppError> {
+    ///
+    /// `storage_type` selects which storage section to load when the scatter
+    /// describes several (e.g. EMMC and UFS variants of the same model).
+    /// When `None`, UFS is preferred, then EMMC, then the first section found.
+    pub fn parse(file_path: &str, storage_type: Option<&str>) -> Result<ScatterFile, AppError> {
         let content = fs::read_to_string(file_path)
             .map_err(|e| AppError::io(format!("Failed to read scatter file: {}", e)))?;
 
         // Auto-detect format
         let trimmed = content.trim();
         if trimmed.starts_with('<') || trimmed.starts_with("<?xml") {
-            Self::parse_xml(&content, file_path)
+            Self::parse_xml(&content, file_path, storage_type)
         } else {
-            Self::parse_txt(&content, file_path)
+            Self::parse_txt(&content, file_path, storage_type)
         }
     }
 
-    /// Parse XML format scatter file
-    fn parse_xml(content: &str, file_path: &str) -> Result<ScatterFile, AppError> {
+    /// Pick the storage section to parse from the sections present in the file
+    fn select_target_storage(
+        available: &[String],
+        requested: Option<&str>,
+    ) -> Result<String, AppError> {
+        if let Some(requested) = requested.map(str::trim).filter(|r| !r.is_empty()) {
+            // Old-format scatters have no sections; the request is checked against
+            // the general storage field once it is parsed
+            if available.is_empty() {
+                return Ok(requested.to_uppercase());
+            }
+            return available
+                .iter()
+                .find(|st| st.eq_ignore_ascii_case(requested))
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::Parse(format!(
+                        "Storage type {} not found in scatter file (available: {})",
+                        requested,
+                        available.join(", ")
+                    ))
+                });
+        }
+
+        let preferred = ["UFS", "EMMC"]
+            .iter()
+            .find_map(|p| available.iter().find(|st| st.as_str() == *p))
+            .or_else(|| available.first());
+        Ok(preferred.cloned().unwrap_or_else(|| "EMMC".to_string()))
+    }
+
+    /// A scatter without storage_type sections describes the single layout
+    /// named by its general `storage` field, so a requested storage type must
+    /// match that field (which may carry an `HW_STORAGE_` prefix)
+    fn check_sectionless_storage(
+        storage_type: &str,
+        requested: Option<&str>,
+    ) -> Result<(), AppError> {
+        let Some(requested) = requested.map(str::trim).filter(|r| !r.is_empty()) else {
+            return Ok(());
+        };
+        let general = storage_type.strip_prefix("HW_STORAGE_").unwrap_or(storage_type);
+        if general.eq_ignore_ascii_case(requested) || storage_type.eq_ignore_ascii_case(requested) {
+            return Ok(());
+        }
+        let found = if storage_type.is_empty() { "none" } else { storage_type };
+        Err(AppError::Parse(format!(
+            "Storage type {} not found in scatter file (available: {})",
+            requested, found
+        )))
+    }
+
+    /// Collect the names of all storage_type sections in an XML scatter
+    fn collect_xml_storage_types(content: &str) -> Result<Vec<String>, AppError> {
         let mut reader = Reader::from_str(content);
         reader.config_mut().trim_text(true);
 
-        // First pass: detect if UFS storage_type exists
-        let has_ufs = content.contains("<storage_type name=\"UFS\">");
-        let target_storage = if has_ufs { "UFS" } else { "EMMC" };
+        let mut storage_types = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"storage_type" => {
+                    let st = e
+                        .attributes()
+                        .filter_map(|a| a.ok())
+                        .find(|attr| attr.key.as_ref() == b"name")
+                        .and_then(|attr| String::from_utf8(attr.value.to_vec()).ok())
+                        .unwrap_or_default();
+                    if !st.is_empty() && !storage_types.contains(&st) {
+                        storage_types.push(st);
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(AppError::Parse(format!("XML parse error: {}", e)));
+                }
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(storage_types)
+    }
+
+    /// Parse XML format scatter file
+    pub(crate) fn parse_xml(
+        content: &str,
+        file_path: &str,
+        requested_storage: Option<&str>,
+    ) -> Result<ScatterFile, AppError> {
+        // First pass: find every storage_type section and pick the target
+        let available_storage_types = Self::collect_xml_storage_types(content)?;
+        let target_storage =
+            Self::select_target_storage(&available_storage_types, requested_storage)?;
+        let target_storage = target_storage.as_str();
+
+        let mut reader = Reader::from_str(content);
+        reader.config_mut().trim_text(true);
 
         let mut platform = String::new();
         let mut project = String::new();
@@ -173,17 +269,33 @@ impl ScatterParser {
             buf.clear();
         }
 
+        if available_storage_types.is_empty() {
+            Self::check_sectionless_storage(&storage_type, requested_storage)?;
+        }
+        let available_storage_types = if available_storage_types.is_empty()
+            && !storage_type.is_empty()
+        {
+            vec![storage_type.clone()]
+        } else {
+            available_storage_types
+        };
+
         Ok(ScatterFile {
             platform,
             project,
             storage_type,
+            available_storage_types,
             partitions,
             file_path: file_path.to_string(),
         })
     }
 
     /// Parse TXT/YAML format scatter file
-    fn parse_txt(content: &str, file_path: &str) -> Result<ScatterFile, AppError> {
+    fn parse_txt(
+        content: &str,
+        file_path: &str,
+        requested_storage: Option<&str>,
+    ) -> Result<ScatterFile, AppError> {
         use serde::Deserialize;
 
         // Try parsing as a single YAML array (newer format: - general: ... - storage_type: ... - partition_index: ...)
@@ -202,17 +314,20 @@ impl ScatterParser {
             return Err(AppError::Parse("Empty YAML file".to_string()));
         }
 
-        // First pass: detect if UFS storage_type exists
-        let has_ufs = docs.iter().any(|doc| {
+        // First pass: find every storage_type section and pick the target
+        let mut available_storage_types: Vec<String> = Vec::new();
+        for doc in &docs {
             if let Value::Mapping(map) = doc {
                 if let Some(Value::String(st)) = map.get("storage_type") {
-                    return st == "UFS";
+                    if !available_storage_types.contains(st) {
+                        available_storage_types.push(st.clone());
+                    }
                 }
             }
-            false
-        });
+        }
 
-        let target_storage = if has_ufs { "UFS" } else { "EMMC" };
+        let target_storage =
+            Self::select_target_storage(&available_storage_types, requested_storage)?;
 
         let has_storage_type_sections = docs.iter().any(|doc| {
             if let Value::Mapping(map) = doc {
@@ -222,7 +337,13 @@ impl ScatterParser {
             }
         });
 
-        Self::process_yaml_docs(docs, file_path, target_storage, has_storage_type_sections)
+        Self::process_yaml_docs(
+            docs,
+            file_path,
+            &target_storage,
+            has_storage_type_sections,
+            available_storage_types,
+        )
     }
 
     /// Process YAML documents from either format
@@ -231,6 +352,7 @@ impl ScatterParser {
         file_path: &str,
         target_storage: &str,
         has_storage_type_sections: bool,
+        available_storage_types: Vec<String>,
     ) -> Result<ScatterFile, AppError> {
         let mut platform = String::new();
         let mut project = String::new();
@@ -369,10 +491,22 @@ impl ScatterParser {
             }
         }
 
+        if available_storage_types.is_empty() {
+            Self::check_sectionless_storage(&storage_type, requested_storage)?;
+        }
+        let available_storage_types = if available_storage_types.is_empty()
+            && !storage_type.is_empty()
+        {
+            vec![storage_type.clone()]
+        } else {
+            available_storage_types
+        };
+
         Ok(ScatterFile {
             platform,
             project,
             storage_type,
+            available_storage_types,
             partitions,
             file_path: file_path.to_string(),
         })
@@ -395,3 +529,76 @@ impl ScatterParser {
         map.get(key).and_then(|v| v.as_bool())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTI_STORAGE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<root>
+  <general>
+    <platform>MT6781</platform>
+    <project>test</project>
+  </general>
+  <storage_type name="EMMC">
+    <partition_index name="SYS0">
+      <partition_name>preloader</partition_name>
+      <is_download>true</is_download>
+      <region>EMMC_BOOT1</region>
+    </partition_index>
+  </storage_type>
+  <storage_type name="UFS">
+    <partition_index name="SYS0">
+      <partition_name>preloader_ufs</partition_name>
+      <is_download>true</is_download>
+      <region>UFS_LU0</region>
+    </partition_index>
+  </storage_type>
+</root>"#;
+
+    #[test]
+    fn test_xml_prefers_ufs_by_default() {
+        let scatter = ScatterParser::parse_xml(MULTI_STORAGE_XML, "scatter.xml", None).unwrap();
+        assert_eq!(scatter.storage_type, "UFS");
+        assert_eq!(scatter.available_storage_types, vec!["EMMC", "UFS"]);
+        assert_eq!(scatter.partitions[0].partition_name, "preloader_ufs");
+    }
+
+    #[test]
+    fn test_xml_respects_requested_storage() {
+        let scatter =
+            ScatterParser::parse_xml(MULTI_STORAGE_XML, "scatter.xml", Some("emmc")).unwrap();
+        assert_eq!(scatter.storage_type, "EMMC");
+        assert_eq!(scatter.partitions.len(), 1);
+        assert_eq!(scatter.partitions[0].partition_name, "preloader");
+
+        assert!(ScatterParser::parse_xml(MULTI_STORAGE_XML, "scatter.xml", Some("NAND")).is_err());
+    }
+
+    #[test]
+    fn test_sectionless_scatter_checks_general_storage() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<root>
+  <general>
+    <platform>MT6765</platform>
+    <storage>EMMC</storage>
+  </general>
+  <partition_index name="SYS0">
+    <partition_name>preloader</partition_name>
+    <is_download>true</is_download>
+    <region>EMMC_BOOT1</region>
+  </partition_index>
+</root>"#;
+        let scatter = ScatterParser::parse_xml(xml, "scatter.xml", Some("emmc")).unwrap();
+        assert_eq!(scatter.storage_type, "EMMC");
+        assert_eq!(scatter.partitions.len(), 1);
+        assert!(ScatterParser::parse_xml(xml, "scatter.xml", Some("UFS")).is_err());
+
+        let prefixed = xml.replace(">EMMC</storage>", ">HW_STORAGE_EMMC</storage>");
+        assert!(ScatterParser::parse_xml(&prefixed, "scatter.xml", Some("EMMC")).is_ok());
+
+        let unnamed = xml.replace("    <storage>EMMC</storage>\n", "");
+        assert!(ScatterParser::parse_xml(&unnamed, "scatter.xml", None).is_ok());
+        assert!(ScatterParser::parse_xml(&unnamed, "scatter.xml", Some("EMMC")).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::scatter::ScatterChangedEvent;
+use crate::services::events;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+use tokio::task::JoinHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+struct ScatterWatch {
+    scatter_path: String,
+    task: JoinHandle<()>,
+}
+
+static CURRENT_WATCH: OnceLock<Mutex<Option<ScatterWatch>>> = OnceLock::new();
+
+type Snapshot = HashMap<String, (u64, Option<SystemTime>)>;
+
+/// Watch the scatter file and the images next to it, replacing any previous watch.
+/// Emits `scatter:changed` once the directory has settled after a change.
+pub fn watch_scatter(app: AppHandle, scatter_path: String) -> anyhow::Result<()> {
+    let scatter_file = PathBuf::from(&scatter_path);
+    let scatter_dir = scatter_file
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| anyhow::anyhow!("Invalid scatter path: {}", scatter_path))?;
+
+    stop_watching();
+
+    let path_for_task = scatter_path.clone();
+    let task = tokio::spawn(async move {
+        let mut baseline = snapshot_async(&scatter_dir, &scatter_file).await;
+        let mut last_seen = baseline.clone();
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let current = snapshot_async(&scatter_dir, &scatter_file).await;
+
+            // Wait for the directory to settle (e.g. an archive still extracting)
+            if current != last_seen {
+                last_seen = current;
+                continue;
+            }
+            if current == baseline {
+                continue;
+            }
+
+            let event = diff_snapshots(&path_for_task, &scatter_file, &baseline, &current);
+            log::info!(
+                "Scatter directory changed ({} file(s), scatter changed: {})",
+                event.changed_files.len(),
+                event.scatter_changed
+            );
+            let _ = events::emit(&app, "scatter:changed", event);
+            baseline = current;
+        }
+    });
+
+    log::info!("Watching scatter file: {}", scatter_path);
+    let store = CURRENT_WATCH.get_or_init(|| Mutex::new(None));
+    if let Ok(mut guard) = store.lock() {
+        *guard = Some(ScatterWatch { scatter_path, task });
+    }
+    Ok(())
+}
+
+/// Stop the active scatter watch, if any
+pub fn stop_watching() {
+    let store = CURRENT_WATCH.get_or_init(|| Mutex::new(None));
+    if let Ok(mut guard) = store.lock() {
+        if let Some(watch) = guard.take() {
+            watch.task.abort();
+            log::info!("Stopped watching scatter file: {}", watch.scatter_path);
+        }
+    }
+}
+
+/// `snapshot` on the blocking pool, since large image directories take a while to stat
+async fn snapshot_async(scatter_dir: &Path, scatter_file: &Path) -> Snapshot {
+    let (dir, file) = (scatter_dir.to_path_buf(), scatter_file.to_path_buf());
+    tokio::task::spawn_blocking(move || snapshot(&dir, &file)).await.unwrap_or_else(|err| {
+        log::warn!("Scatter directory scan failed: {}", err);
+        Snapshot::new()
+    })
+}
+
+/// Record size and mtime of the scatter and every file in its directory and images/
+fn snapshot(scatter_dir: &Path, scatter_file: &Path) -> Snapshot {
+    let mut files = Snapshot::new();
+    for dir in [scatter_dir.to_path_buf(), scatter_dir.join("images")] {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() {
+                    files.insert(
+                        entry.path().display().to_string(),
+                        (metadata.len(), metadata.modified().ok()),
+                    );
+                }
+            }
+        }
+    }
+
+    // Make sure a deleted scatter shows up as a change
+    files.entry(scatter_file.display().to_string()).or_insert((0, None));
+    files
+}
+
+fn diff_snapshots(
+    scatter_path: &str,
+    scatter_file: &Path,
+    before: &Snapshot,
+    after: &Snapshot,
+) -> ScatterChangedEvent {
+    let mut changed_files: Vec<String> = after
+        .iter()
+        .filter(|(path, meta)| before.get(*path) != Some(*meta))
+        .map(|(path, _)| path.clone())
+        .chain(before.keys().filter(|path| !after.contains_key(*path)).cloned())
+        .collect();
+    changed_files.sort();
+
+    let scatter_key = scatter_file.display().to_string();
+    ScatterChangedEvent {
+        scatter_path: scatter_path.to_string(),
+        scatter_changed: changed_files.contains(&scatter_key),
+        changed_files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_added_modified_and_removed_files() {
+        let dir = std::env::temp_dir().join(format!("scatter-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("images")).unwrap();
+        let scatter = dir.join("MT6781_Android_scatter.txt");
+        std::fs::write(&scatter, "- general: MTK_PLATFORM_CFG").unwrap();
+        std::fs::write(dir.join("boot.img"), "boot").unwrap();
+        let before = snapshot(&dir, &scatter);
+
+        std::fs::write(dir.join("boot.img"), "new boot").unwrap();
+        std::fs::write(dir.join("images").join("vbmeta.img"), "vbmeta").unwrap();
+        let after = snapshot(&dir, &scatter);
+        let event = diff_snapshots("scatter", &scatter, &before, &after);
+        assert!(!event.scatter_changed);
+        assert_eq!(
+            event.changed_files,
+            vec![
+                dir.join("boot.img").display().to_string(),
+                dir.join("images").join("vbmeta.img").display().to_string(),
+            ]
+        );
+
+        std::fs::remove_file(&scatter).unwrap();
+        let removed = snapshot(&dir, &scatter);
+        let event = diff_snapshots("scatter", &scatter, &after, &removed);
+        assert!(event.scatter_changed);
+        assert_eq!(event.changed_files, vec![scatter.display().to_string()]);
+
+        assert!(diff_snapshots("scatter", &scatter, &removed, &removed).changed_files.is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
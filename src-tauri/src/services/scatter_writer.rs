@@ -0,0 +1,133 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::error::AppError;
+use crate::models::scatter::{ScatterFile, ScatterPartition};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use std::fs;
+use std::io::Cursor;
+
+pub struct ScatterWriter;
+
+impl ScatterWriter {
+    /// Serialize a scatter file to the XML layout understood by `ScatterParser`.
+    /// Only the loaded storage section is written.
+    pub fn to_xml(scatter: &ScatterFile) -> Result<String, AppError> {
+        let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+
+        Self::write(&mut writer, Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        Self::write(&mut writer, Event::Start(BytesStart::new("root")))?;
+
+        Self::write(&mut writer, Event::Start(BytesStart::new("general")))?;
+        Self::write_field(&mut writer, "platform", &scatter.platform)?;
+        Self::write_field(&mut writer, "project", &scatter.project)?;
+        Self::write_field(&mut writer, "storage", &scatter.storage_type)?;
+        Self::write(&mut writer, Event::End(BytesEnd::new("general")))?;
+
+        let mut storage_start = BytesStart::new("storage_type");
+        storage_start.push_attribute(("name", scatter.storage_type.as_str()));
+        Self::write(&mut writer, Event::Start(storage_start))?;
+        for partition in &scatter.partitions {
+            Self::write_partition(&mut writer, partition)?;
+        }
+        Self::write(&mut writer, Event::End(BytesEnd::new("storage_type")))?;
+
+        Self::write(&mut writer, Event::End(BytesEnd::new("root")))?;
+
+        String::from_utf8(writer.into_inner().into_inner())
+            .map_err(|e| AppError::Parse(format!("Generated scatter is not valid UTF-8: {}", e)))
+    }
+
+    /// Write the scatter as XML to `output_path`
+    pub fn save(scatter: &ScatterFile, output_path: &str) -> Result<(), AppError> {
+        let xml = Self::to_xml(scatter)?;
+        fs::write(output_path, xml)
+            .map_err(|e| AppError::io(format!("Failed to write scatter file: {}", e)))
+    }
+
+    fn write_partition(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        partition: &ScatterPartition,
+    ) -> Result<(), AppError> {
+        let mut start = BytesStart::new("partition_index");
+        start.push_attribute(("name", partition.index.as_str()));
+        Self::write(writer, Event::Start(start))?;
+
+        Self::write_field(writer, "partition_name", &partition.partition_name)?;
+        Self::write_field(writer, "file_name", partition.file_name.as_deref().unwrap_or("NONE"))?;
+        Self::write_field(
+            writer,
+            "is_download",
+            if partition.is_download { "true" } else { "false" },
+        )?;
+        Self::write_field(writer, "type", &partition.partition_type)?;
+        Self::write_field(writer, "linear_start_addr", &partition.linear_start_addr)?;
+        Self::write_field(writer, "physical_start_addr", &partition.physical_start_addr)?;
+        Self::write_field(writer, "partition_size", &partition.partition_size)?;
+        Self::write_field(writer, "region", &partition.region)?;
+        Self::write_field(writer, "storage", &partition.storage)?;
+        Self::write_field(writer, "operation_type", &partition.operation_type)?;
+
+        Self::write(writer, Event::End(BytesEnd::new("partition_index")))
+    }
+
+    fn write_field(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        tag: &str,
+        value: &str,
+    ) -> Result<(), AppError> {
+        Self::write(writer, Event::Start(BytesStart::new(tag)))?;
+        Self::write(writer, Event::Text(BytesText::new(value)))?;
+        Self::write(writer, Event::End(BytesEnd::new(tag)))
+    }
+
+    fn write(writer: &mut Writer<Cursor<Vec<u8>>>, event: Event) -> Result<(), AppError> {
+        writer
+            .write_event(event)
+            .map_err(|e| AppError::io(format!("Failed to serialize scatter file: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::scatter_parser::ScatterParser;
+
+    #[test]
+    fn test_xml_round_trip() {
+        let mut scatter = ScatterFile {
+            platform: "MT6781".to_string(),
+            project: "x670_h814".to_string(),
+            storage_type: "EMMC".to_string(),
+            available_storage_types: vec!["EMMC".to_string()],
+            partitions: vec![ScatterPartition {
+                index: "SYS0".to_string(),
+                partition_name: "boot".to_string(),
+                file_name: Some("boot & co.img".to_string()),
+                is_download: true,
+                partition_type: "NORMAL_ROM".to_string(),
+                linear_start_addr: "0x0".to_string(),
+                physical_start_addr: "0x0".to_string(),
+                partition_size: "0x2000000".to_string(),
+                region: "EMMC_USER".to_string(),
+                storage: "HW_STORAGE_EMMC".to_string(),
+                operation_type: "UPDATE".to_string(),
+            }],
+            file_path: String::new(),
+        };
+        scatter.partition_mut("boot").unwrap().is_download = false;
+
+        let xml = ScatterWriter::to_xml(&scatter).unwrap();
+        let parsed = ScatterParser::parse_xml(&xml, "out.xml", None).unwrap();
+
+        assert_eq!(parsed.platform, "MT6781");
+        assert_eq!(parsed.storage_type, "EMMC");
+        assert_eq!(parsed.partitions.len(), 1);
+        assert_eq!(parsed.partitions[0].file_name.as_deref(), Some("boot & co.img"));
+        assert!(!parsed.partitions[0].is_download);
+        assert_eq!(parsed.partitions[0].partition_size, "0x2000000");
+    }
+}
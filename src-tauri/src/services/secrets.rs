@@ -0,0 +1,121 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::config::{load_settings, modify_settings};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Keyring service name all secrets are stored under
+const KEYRING_SERVICE: &str = "penumbra-wrapper";
+
+/// Sensitive values kept in the OS keyring instead of config.json
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKey {
+    GithubToken,
+    AuthPassphrase,
+    BackupPassphrase,
+}
+
+impl SecretKey {
+    fn account(self) -> &'static str {
+        match self {
+            SecretKey::GithubToken => "github_token",
+            SecretKey::AuthPassphrase => "auth_passphrase",
+            SecretKey::BackupPassphrase => "backup_passphrase",
+        }
+    }
+}
+
+fn entry(key: SecretKey) -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, key.account()).context("OS keyring unavailable")
+}
+
+/// The stored secret, or `None` when it was never set
+pub fn get(key: SecretKey) -> Result<Option<String>> {
+    match entry(key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => {
+            Err(err).with_context(|| format!("Failed to read {} from keyring", key.account()))
+        }
+    }
+}
+
+/// Store `value`, or forget the secret when it is blank
+pub fn set(key: SecretKey, value: &str) -> Result<()> {
+    let value = value.trim();
+    if value.is_empty() {
+        return delete(key);
+    }
+    entry(key)?
+        .set_password(value)
+        .with_context(|| format!("Failed to store {} in keyring", key.account()))
+}
+
+pub fn delete(key: SecretKey) -> Result<()> {
+    match entry(key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => {
+            Err(err).with_context(|| format!("Failed to delete {} from keyring", key.account()))
+        }
+    }
+}
+
+/// The GitHub token from the keyring, falling back to config.json on systems
+/// without a keyring
+pub fn github_token() -> Option<String> {
+    let stored = get(SecretKey::GithubToken).unwrap_or_else(|err| {
+        log::debug!("{:#}", err);
+        None
+    });
+    stored
+        .or_else(|| load_settings().ok().and_then(|settings| settings.github_token))
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Store the GitHub token in the keyring, or in config.json on systems
+/// without one. A blank token forgets it in both places.
+pub fn set_github_token(token: &str) -> Result<()> {
+    let token = token.trim();
+    if let Err(err) = set(SecretKey::GithubToken, token) {
+        log::warn!("{:#}; keeping the GitHub token in config.json", err);
+        return modify_settings(|settings| {
+            settings.github_token = Some(token.to_string()).filter(|token| !token.is_empty());
+            Ok(())
+        });
+    }
+
+    // Drop a copy an earlier save without keyring left behind
+    if load_settings()?.github_token.is_some() {
+        modify_settings(|settings| {
+            settings.github_token = None;
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// Move secrets that older versions wrote to config.json into the keyring
+pub fn migrate_plaintext_secrets() {
+    let Some(token) = load_settings().ok().and_then(|settings| settings.github_token) else {
+        return;
+    };
+    if let Err(err) = set(SecretKey::GithubToken, &token) {
+        log::warn!("{:#}; GitHub token stays in config.json", err);
+        return;
+    }
+    // The second save replaces config.json.bak, which still held the token
+    let cleared = modify_settings(|settings| {
+        settings.github_token = None;
+        Ok(())
+    })
+    .and_then(|()| modify_settings(|_| Ok(())));
+    match cleared {
+        Ok(()) => log::info!("Moved GitHub token from config.json to the OS keyring"),
+        Err(err) => log::warn!("Failed to remove GitHub token from config.json: {:#}", err),
+    }
+}
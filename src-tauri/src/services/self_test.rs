@@ -0,0 +1,140 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::services::antumbra::{self, AntumbraExecutor};
+use crate::services::config::load_settings;
+use crate::services::paths;
+use crate::services::scatter_parser::ScatterParser;
+use crate::services::session_recorder;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Parsed by the scatter check; bundled so the check needs no user files
+const SAMPLE_SCATTER: &str = include_str!("../../samples/self_test_scatter.txt");
+const SAMPLE_PARTITIONS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub passed: bool,
+    /// What was found, or why the check failed
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// Everything short of talking to a device: locate the binary, run
+/// `--version`, round-trip a file in each configured directory and parse the
+/// bundled scatter. Blocking; run it off the async runtime.
+pub fn run(app: &AppHandle) -> SelfTestReport {
+    let mut checks = Vec::new();
+
+    let binary = match antumbra::get_existing_antumbra_path(app) {
+        Ok(Some(path)) => Ok(path.display().to_string()),
+        Ok(None) => Err(anyhow::anyhow!("antumbra is not installed")),
+        Err(err) => Err(err),
+    };
+    let found = binary.is_ok();
+    checks.push(check("Locate antumbra binary", binary));
+
+    if found {
+        let version = AntumbraExecutor::new(app).and_then(|executor| executor.get_version());
+        checks.push(check("Run antumbra --version", version));
+    } else {
+        checks.push(skipped("Run antumbra --version", "no binary to run"));
+    }
+
+    for (name, dir) in directories() {
+        let result = dir.and_then(|dir| round_trip(&dir).map(|_| dir.display().to_string()));
+        checks.push(check(&format!("Write and read {}", name), result));
+    }
+
+    checks.push(check("Parse sample scatter", parse_sample_scatter()));
+
+    SelfTestReport { passed: checks.iter().all(|check| check.passed), checks }
+}
+
+/// The directories the app writes to, by display name
+fn directories() -> Vec<(&'static str, Result<PathBuf>)> {
+    let mut dirs = vec![
+        ("config directory", paths::config_dir()),
+        ("antumbra binary directory", paths::bin_dir()),
+        (
+            "log directory",
+            paths::wrapper_log_file()
+                .parent()
+                .map(Path::to_path_buf)
+                .context("Log file has no parent directory"),
+        ),
+        ("session recordings", session_recorder::sessions_dir()),
+    ];
+    let settings = load_settings().unwrap_or_default();
+    if let Some(output) = settings.default_output_path.filter(|path| !path.is_empty()) {
+        dirs.push(("default output directory", Ok(PathBuf::from(output))));
+    }
+    dirs
+}
+
+/// Create `dir` if needed, then write, read back and remove a probe file
+fn round_trip(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+    let probe = dir.join(format!(".penumbra-self-test-{}", uuid::Uuid::new_v4()));
+    let contents = b"penumbra self-test";
+    let result = std::fs::write(&probe, contents)
+        .with_context(|| format!("Cannot write to {}", dir.display()))
+        .and_then(|_| std::fs::read(&probe).context("Cannot read the probe file back"))
+        .and_then(|read| {
+            anyhow::ensure!(read == contents, "Probe file came back different");
+            Ok(())
+        });
+    let _ = std::fs::remove_file(&probe);
+    result
+}
+
+fn parse_sample_scatter() -> Result<String> {
+    let path =
+        std::env::temp_dir().join(format!("penumbra-self-test-{}.txt", uuid::Uuid::new_v4()));
+    std::fs::write(&path, SAMPLE_SCATTER).context("Cannot write the sample scatter")?;
+    let parsed = ScatterParser::parse(&path.display().to_string(), None);
+    let _ = std::fs::remove_file(&path);
+
+    let scatter = parsed.map_err(|err| anyhow::anyhow!("{}", err))?;
+    anyhow::ensure!(
+        scatter.partitions.len() == SAMPLE_PARTITIONS,
+        "Expected {} partitions, parsed {}",
+        SAMPLE_PARTITIONS,
+        scatter.partitions.len()
+    );
+    Ok(format!("{} with {} partitions", scatter.platform, scatter.partitions.len()))
+}
+
+fn check(name: &str, result: Result<String>) -> SelfTestCheck {
+    match result {
+        Ok(detail) => SelfTestCheck { name: name.to_string(), passed: true, detail },
+        Err(err) => {
+            SelfTestCheck { name: name.to_string(), passed: false, detail: format!("{:#}", err) }
+        }
+    }
+}
+
+fn skipped(name: &str, reason: &str) -> SelfTestCheck {
+    SelfTestCheck { name: name.to_string(), passed: false, detail: format!("Skipped: {}", reason) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_scatter_parses() {
+        assert_eq!(parse_sample_scatter().unwrap(), "MT6765 with 3 partitions");
+    }
+}
@@ -0,0 +1,148 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::session::{RecordedSession, RecordedSessionInfo, SessionLine};
+use crate::models::SCHEMA_VERSION;
+use crate::services::config::{get_config_dir, load_settings, AppSettings};
+use crate::services::mock_executor::{self, ScriptLine};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// Captures the lines antumbra prints during one operation
+pub struct SessionRecorder {
+    started: Instant,
+    session: Mutex<RecordedSession>,
+}
+
+impl SessionRecorder {
+    /// Start recording when `record_sessions` is enabled in the settings
+    pub fn start_if_enabled(
+        settings: &AppSettings,
+        operation_id: &str,
+        args: &[String],
+    ) -> Option<Self> {
+        settings.record_sessions.then(|| Self {
+            started: Instant::now(),
+            session: Mutex::new(RecordedSession {
+                operation_id: operation_id.to_string(),
+                args: args.to_vec(),
+                started_at: Utc::now().to_rfc3339(),
+                exit_code: None,
+                lines: Vec::new(),
+                schema_version: SCHEMA_VERSION,
+            }),
+        })
+    }
+
+    pub fn record(&self, line: &str, is_stderr: bool, redraw: bool) {
+        let offset_ms = self.started.elapsed().as_millis() as u64;
+        if let Ok(mut session) = self.session.lock() {
+            let line = line.to_string();
+            session.lines.push(SessionLine { offset_ms, line, is_stderr, redraw });
+        }
+    }
+
+    /// Write the session file, returning its path
+    pub fn finish(&self, exit_code: Option<i32>) -> Result<PathBuf> {
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Session recorder lock poisoned"))?
+            .clone();
+        session.exit_code = exit_code;
+
+        let dir = sessions_dir()?;
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create sessions directory {:?}", dir))?;
+        let file_name =
+            format!("{}_{}.json", Utc::now().format("%Y%m%d_%H%M%S"), session.operation_id);
+        let path = dir.join(file_name);
+        std::fs::write(&path, serde_json::to_string_pretty(&session)?)
+            .with_context(|| format!("Failed to write session file {:?}", path))?;
+        log::info!("Recorded antumbra session to {:?}", path);
+        Ok(path)
+    }
+}
+
+pub fn sessions_dir() -> Result<PathBuf> {
+    Ok(get_config_dir()?.join("sessions"))
+}
+
+pub fn load_session(path: &Path) -> Result<RecordedSession> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read session file {:?}", path))?;
+    serde_json::from_str(&contents).with_context(|| format!("Invalid session file {:?}", path))
+}
+
+/// Recorded sessions, newest first
+pub fn list_sessions() -> Result<Vec<RecordedSessionInfo>> {
+    let dir = sessions_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in std::fs::read_dir(&dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        match load_session(&path) {
+            Ok(session) => sessions.push(RecordedSessionInfo {
+                path: path.to_string_lossy().to_string(),
+                operation_id: session.operation_id,
+                command: session.args.first().cloned().unwrap_or_default(),
+                started_at: session.started_at,
+                exit_code: session.exit_code,
+                line_count: session.lines.len(),
+            }),
+            Err(err) => log::warn!("Skipping session file: {:#}", err),
+        }
+    }
+
+    sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(sessions)
+}
+
+/// Feed a recorded session back through the event pipeline under `operation_id`.
+/// `speed` scales playback (2.0 replays twice as fast).
+pub async fn replay(
+    app: &AppHandle,
+    session: RecordedSession,
+    operation_id: &str,
+    speed: f64,
+) -> Result<String> {
+    let speed = if speed > 0.0 { speed } else { 1.0 };
+    log::info!(
+        "Replaying session {} ({} lines) as {}",
+        session.operation_id,
+        session.lines.len(),
+        operation_id
+    );
+
+    let mut previous = 0;
+    let lines = session
+        .lines
+        .into_iter()
+        .map(|line| {
+            let gap = line.offset_ms.saturating_sub(previous);
+            previous = line.offset_ms;
+            ScriptLine {
+                delay: Duration::from_millis((gap as f64 / speed) as u64),
+                line: line.line,
+                is_stderr: line.is_stderr,
+                redraw: line.redraw,
+            }
+        })
+        .collect();
+
+    let success = session.exit_code == Some(0);
+    let settings = load_settings().unwrap_or_default();
+    mock_executor::play(app, &settings, operation_id, &session.args, lines, success).await
+}
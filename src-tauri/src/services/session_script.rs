@@ -0,0 +1,199 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Turn the commands run during this app session into a standalone script,
+//! so a procedure done in the GUI can be repeated from a terminal.
+//!
+//! Unlike reports and diagnostics bundles the script is not redacted: it has
+//! to name the real files and working directories to be runnable. It is
+//! meant to stay on the machine that wrote it, which the script header says.
+
+use crate::services::antumbra::AntumbraCommandInfo;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptFormat {
+    /// POSIX shell
+    Sh,
+    /// Windows batch file
+    Batch,
+}
+
+impl ScriptFormat {
+    /// Guess the format from the file extension: .bat and .cmd are batch
+    /// files, anything else a shell script
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("bat") || ext.eq_ignore_ascii_case("cmd") => {
+                ScriptFormat::Batch
+            }
+            _ => ScriptFormat::Sh,
+        }
+    }
+}
+
+/// Operations run by `session_id`, oldest first. Version probes and other
+/// runs outside an operation are left out since they don't touch the device.
+pub fn session_commands(
+    history: &[AntumbraCommandInfo],
+    session_id: &str,
+) -> Vec<AntumbraCommandInfo> {
+    history
+        .iter()
+        .filter(|entry| entry.session_id.as_deref() == Some(session_id))
+        .filter(|entry| entry.operation_id.is_some())
+        .cloned()
+        .collect()
+}
+
+/// Write `commands` to `path` as a script that stops at the first failure
+pub fn export(path: &Path, format: ScriptFormat, commands: &[AntumbraCommandInfo]) -> Result<()> {
+    std::fs::write(path, render(format, commands))
+        .with_context(|| format!("Failed to write session script to {}", path.display()))?;
+
+    #[cfg(unix)]
+    if format == ScriptFormat::Sh {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make {} executable", path.display()))?;
+    }
+    Ok(())
+}
+
+fn render(format: ScriptFormat, commands: &[AntumbraCommandInfo]) -> String {
+    let mut script = String::new();
+    match format {
+        ScriptFormat::Sh => {
+            script.push_str("#!/bin/sh\n");
+            script.push_str("# Exported from penumbra-wrapper\n");
+            script.push_str("# Not redacted: contains local file paths, do not share\n");
+            script.push_str("set -e\n");
+        }
+        ScriptFormat::Batch => {
+            script.push_str("@echo off\r\n");
+            script.push_str("rem Exported from penumbra-wrapper\r\n");
+            script.push_str("rem Not redacted: contains local file paths, do not share\r\n");
+            script.push_str("setlocal\r\n");
+        }
+    }
+
+    let mut working_dir: Option<&str> = None;
+    for command in commands {
+        let outcome = match command.success {
+            Some(true) => "succeeded",
+            Some(false) => "failed",
+            None => "did not finish",
+        };
+        let line = |text: String| match format {
+            ScriptFormat::Sh => format!("{}\n", text),
+            ScriptFormat::Batch => format!("{}\r\n", text),
+        };
+
+        script.push_str(&line(String::new()));
+        let comment = format!("{} ({})", command.started_at, outcome);
+        script.push_str(&line(match format {
+            ScriptFormat::Sh => format!("# {}", comment),
+            ScriptFormat::Batch => format!("rem {}", comment),
+        }));
+
+        if working_dir != Some(command.working_dir.as_str()) {
+            working_dir = Some(&command.working_dir);
+            script.push_str(&line(match format {
+                ScriptFormat::Sh => format!("cd {}", sh_quote(&command.working_dir)),
+                ScriptFormat::Batch => format!("cd /d {}", batch_quote(&command.working_dir)),
+            }));
+        }
+
+        let invocation = std::iter::once(&command.command).chain(&command.args);
+        script.push_str(&line(match format {
+            ScriptFormat::Sh => invocation.map(|arg| sh_quote(arg)).collect::<Vec<_>>().join(" "),
+            ScriptFormat::Batch => {
+                let args = invocation.map(|arg| batch_quote(arg)).collect::<Vec<_>>().join(" ");
+                format!("{}\r\nif errorlevel 1 exit /b 1", args)
+            }
+        }));
+    }
+    script
+}
+
+/// Single-quote `arg` unless it only has characters the shell leaves alone
+fn sh_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=,+@%".contains(c));
+    if plain {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Double-quote `arg` unless it only has characters cmd.exe leaves alone.
+/// `%` is doubled so variables aren't expanded inside the batch file.
+fn batch_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./\\:=,+@".contains(c));
+    if plain {
+        return arg.to_string();
+    }
+    format!("\"{}\"", arg.replace('%', "%%").replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(args: &[&str], session_id: &str, operation_id: Option<&str>) -> AntumbraCommandInfo {
+        AntumbraCommandInfo {
+            id: String::new(),
+            command: "/opt/antumbra".to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            working_dir: "/home/user/.config/penumbra".to_string(),
+            started_at: "2025-01-01T00:00:00+00:00".to_string(),
+            operation_id: operation_id.map(str::to_string),
+            finished_at: None,
+            duration_ms: None,
+            exit_code: Some(0),
+            success: Some(true),
+            bytes: None,
+            session_id: Some(session_id.to_string()),
+        }
+    }
+
+    #[test]
+    fn keeps_operations_of_the_current_session() {
+        let history = vec![
+            entry(&["erase", "frp"], "old", Some("a")),
+            entry(&["--version"], "now", None),
+            entry(&["download", "boot", "boot.img"], "now", Some("b")),
+        ];
+        let commands = session_commands(&history, "now");
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].args[0], "download");
+    }
+
+    #[test]
+    fn quotes_arguments() {
+        assert_eq!(sh_quote("/tmp/boot.img"), "/tmp/boot.img");
+        assert_eq!(sh_quote("my boot's.img"), r"'my boot'\''s.img'");
+        assert_eq!(sh_quote(""), "''");
+        assert_eq!(batch_quote(r"C:\images\boot.img"), r"C:\images\boot.img");
+        assert_eq!(batch_quote(r"C:\My Images\100%.img"), r#""C:\My Images\100%%.img""#);
+    }
+
+    #[test]
+    fn renders_shell_script() {
+        let commands = vec![entry(&["download", "boot", "my boot.img"], "now", Some("b"))];
+        let script = render(ScriptFormat::Sh, &commands);
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("cd /home/user/.config/penumbra\n"));
+        assert!(script.contains("/opt/antumbra download boot 'my boot.img'\n"));
+
+        let script = render(ScriptFormat::Batch, &commands);
+        assert!(script.contains("cd /d /home/user/.config/penumbra\r\n"));
+        assert!(script.contains("\"my boot.img\"\r\nif errorlevel 1 exit /b 1\r\n"));
+    }
+}
@@ -0,0 +1,146 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Keeps the machine from suspending while antumbra talks to a device; a
+//! laptop going to sleep mid-flash can leave the device unbootable. Guards
+//! are reference counted so overlapping operations share one inhibitor.
+
+use std::sync::Mutex;
+
+#[cfg(all(unix, not(target_os = "macos")))]
+const REASON: &str = "A device operation is running";
+
+struct State {
+    holders: usize,
+    inhibitor: Option<Inhibitor>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { holders: 0, inhibitor: None });
+
+/// Releases its hold on the inhibitor when dropped
+pub struct SleepGuard(());
+
+/// Block system sleep until the returned guard (and every other) is dropped
+pub fn acquire() -> SleepGuard {
+    if let Ok(mut state) = STATE.lock() {
+        state.holders += 1;
+        if state.holders == 1 {
+            state.inhibitor = Inhibitor::start()
+                .inspect(|_| log::info!("Preventing system sleep while operations run"))
+                .map_err(|err| log::warn!("Failed to prevent system sleep: {:#}", err))
+                .ok();
+        }
+    }
+    SleepGuard(())
+}
+
+impl Drop for SleepGuard {
+    fn drop(&mut self) {
+        if let Ok(mut state) = STATE.lock() {
+            state.holders = state.holders.saturating_sub(1);
+            if state.holders == 0 {
+                if let Some(inhibitor) = state.inhibitor.take() {
+                    inhibitor.stop();
+                    log::info!("Allowing system sleep again");
+                }
+            }
+        }
+    }
+}
+
+/// A helper process holding the inhibitor. It also watches our PID so a
+/// crash cannot leave the machine awake forever. It leads its own process
+/// group so stopping it also ends the `tail` that systemd-inhibit runs.
+#[cfg(unix)]
+struct Inhibitor(std::process::Child);
+
+#[cfg(unix)]
+impl Inhibitor {
+    fn start() -> anyhow::Result<Self> {
+        let pid = std::process::id().to_string();
+        #[cfg(target_os = "macos")]
+        let mut command = {
+            let mut command = std::process::Command::new("caffeinate");
+            command.args(["-i", "-w", pid.as_str()]);
+            command
+        };
+        #[cfg(not(target_os = "macos"))]
+        let mut command = {
+            let mut command = std::process::Command::new("systemd-inhibit");
+            command.args([
+                "--what=sleep:idle",
+                "--who=PenumbraWrapper",
+                format!("--why={}", REASON).as_str(),
+                "--mode=block",
+                "tail",
+                format!("--pid={}", pid).as_str(),
+                "-f",
+                "/dev/null",
+            ]);
+            command
+        };
+        use std::os::unix::process::CommandExt;
+        let child = command
+            .process_group(0)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+        Ok(Self(child))
+    }
+
+    fn stop(mut self) {
+        // A negative pid addresses the whole group
+        let pgid = -(self.0.id() as libc::pid_t);
+        if unsafe { libc::kill(pgid, libc::SIGTERM) } != 0 {
+            let _ = self.0.kill();
+        }
+        let _ = self.0.wait();
+    }
+}
+
+/// `SetThreadExecutionState` only lasts as long as the calling thread, so a
+/// dedicated thread holds it until told to stop.
+#[cfg(windows)]
+struct Inhibitor {
+    stop: std::sync::mpsc::Sender<()>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+#[cfg(windows)]
+impl Inhibitor {
+    fn start() -> anyhow::Result<Self> {
+        use winapi::um::winbase::SetThreadExecutionState;
+        use winapi::um::winnt::{ES_CONTINUOUS, ES_SYSTEM_REQUIRED};
+
+        let (stop, stopped) = std::sync::mpsc::channel::<()>();
+        let (started, result) = std::sync::mpsc::channel();
+        let thread = std::thread::spawn(move || {
+            let previous = unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+            let _ = started.send(previous != 0);
+            let _ = stopped.recv();
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+        });
+        anyhow::ensure!(result.recv().unwrap_or(false), "SetThreadExecutionState failed");
+        Ok(Self { stop, thread })
+    }
+
+    fn stop(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+struct Inhibitor;
+
+#[cfg(not(any(unix, windows)))]
+impl Inhibitor {
+    fn start() -> anyhow::Result<Self> {
+        anyhow::bail!("Sleep inhibition is not supported on this platform")
+    }
+
+    fn stop(self) {}
+}
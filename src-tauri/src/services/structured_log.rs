@@ -0,0 +1,105 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::LogEvent;
+use crate::services::events;
+use log::kv::{Error as KvError, Key, Value, VisitSource};
+use log::Record;
+use std::future::Future;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+/// Machine-readable companion to penumbra-wrapper.log, one JSON object per line
+pub const JSON_LOG_FILE: &str = "penumbra-wrapper.jsonl";
+
+/// Set once the Tauri app is up; records logged before that only reach the files
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Crates whose records never become `log:event`s. Emitting goes through
+/// them, so forwarding their own logging would feed back into itself.
+const SILENT_TARGETS: &[&str] = &["tauri", "wry", "tao"];
+
+static SESSION_ID: OnceLock<String> = OnceLock::new();
+
+tokio::task_local! {
+    static OPERATION_ID: String;
+}
+
+/// Short random ID for this app run, stamped on every log line and event so
+/// interleaved records from a long support session can be told apart
+pub fn session_id() -> &'static str {
+    SESSION_ID.get_or_init(|| uuid::Uuid::new_v4().simple().to_string()[..8].to_string())
+}
+
+/// Run `future` with `operation_id` attached to everything it logs or emits
+pub async fn in_operation<F: Future>(operation_id: String, future: F) -> F::Output {
+    OPERATION_ID.scope(operation_id, future).await
+}
+
+/// Operation of the enclosing `in_operation` scope, if any
+pub fn current_operation() -> Option<String> {
+    OPERATION_ID.try_with(Clone::clone).ok()
+}
+
+/// `[session]` or `[session/operation]` prefix for plain-text log lines
+pub fn context_prefix(record: &Record) -> String {
+    let mut event = LogEvent::default();
+    let _ = record.key_values().visit(&mut Fields(&mut event));
+    match event.operation_id.or_else(current_operation) {
+        Some(operation_id) => format!("[{}/{}]", session_id(), operation_id),
+        None => format!("[{}]", session_id()),
+    }
+}
+
+/// Start forwarding log records to the frontend as `log:event`
+pub fn attach(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+/// Build a `LogEvent` from a record. `operation_id` and `partition` come from
+/// the record's key-values: `log::info!(operation_id = id.as_str(); "...")`;
+/// without one, the operation of the enclosing `in_operation` scope is used.
+pub fn event_from(record: &Record, message: &std::fmt::Arguments) -> LogEvent {
+    let mut event = LogEvent {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        level: record.level().as_str().to_lowercase(),
+        module: record.module_path().unwrap_or_else(|| record.target()).to_string(),
+        message: message.to_string(),
+        session_id: session_id().to_string(),
+        operation_id: current_operation(),
+        partition_name: None,
+    };
+    let _ = record.key_values().visit(&mut Fields(&mut event));
+    event
+}
+
+/// One JSON line for the structured log file
+pub fn to_json(record: &Record, message: &std::fmt::Arguments) -> String {
+    serde_json::to_string(&event_from(record, message)).unwrap_or_default()
+}
+
+/// `fern::Output::call` sink that emits `log:event`
+pub fn emit(record: &Record) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    if SILENT_TARGETS.iter().any(|target| record.target().starts_with(target)) {
+        return;
+    }
+    let _ = events::emit(app, "log:event", event_from(record, record.args()));
+}
+
+struct Fields<'a>(&'a mut LogEvent);
+
+impl<'kvs> VisitSource<'kvs> for Fields<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), KvError> {
+        match key.as_str() {
+            "operation_id" => self.0.operation_id = Some(value.to_string()),
+            "partition" => self.0.partition_name = Some(value.to_string()),
+            _ => {}
+        }
+        Ok(())
+    }
+}
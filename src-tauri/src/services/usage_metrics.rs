@@ -0,0 +1,220 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+//! Opt-in, anonymous usage counters. Only aggregate counts per antumbra
+//! subcommand are kept, together with the app/antumbra versions and the OS:
+//! no partitions, paths, device identifiers or timestamps of single runs.
+//! Nothing is recorded unless `usage_metrics_enabled` is set, and nothing
+//! leaves the machine until the user uploads the report they can preview.
+
+use crate::services::config::{get_config_dir, load_settings};
+use crate::services::http;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// Subcommands that are counted; anything else is folded into "other" so
+/// free-form arguments can never end up in the report
+const KNOWN_OPERATIONS: &[&str] =
+    &["download", "upload", "read-all", "erase", "format", "pgpt", "seccfg", "reboot", "shutdown"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct OperationCounts {
+    pub successes: u64,
+    pub failures: u64,
+}
+
+/// Exactly what `upload` sends
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub app_version: String,
+    pub antumbra_version: Option<String>,
+    /// `std::env::consts::OS` and `ARCH`
+    pub os: String,
+    pub arch: String,
+    /// Day counting started, YYYY-MM-DD
+    pub since: String,
+    pub operations: BTreeMap<String, OperationCounts>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counters {
+    since: String,
+    operations: BTreeMap<String, OperationCounts>,
+}
+
+static COUNTERS: OnceLock<Mutex<Counters>> = OnceLock::new();
+
+fn counters() -> &'static Mutex<Counters> {
+    COUNTERS.get_or_init(|| {
+        let counters = load().unwrap_or_else(|err| {
+            log::warn!("Failed to load usage metrics: {:#}", err);
+            Counters::default()
+        });
+        Mutex::new(counters)
+    })
+}
+
+fn metrics_path() -> Result<PathBuf> {
+    Ok(get_config_dir()?.join("usage_metrics.json"))
+}
+
+fn load() -> Result<Counters> {
+    let path = metrics_path()?;
+    if !path.exists() {
+        return Ok(Counters::default());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read usage metrics {:?}", path))?;
+    serde_json::from_str(&contents).with_context(|| format!("Invalid usage metrics {:?}", path))
+}
+
+fn save(counters: &Counters) {
+    let result = metrics_path().and_then(|path| {
+        std::fs::write(&path, serde_json::to_string_pretty(counters)?)
+            .with_context(|| format!("Failed to write usage metrics {:?}", path))
+    });
+    if let Err(err) = result {
+        log::warn!("Failed to save usage metrics: {:#}", err);
+    }
+}
+
+/// Count one finished run of `command`, if the user opted in
+pub fn record(command: &str, success: bool) {
+    if !load_settings().unwrap_or_default().usage_metrics_enabled {
+        return;
+    }
+    let Ok(mut counters) = counters().lock() else {
+        return;
+    };
+    count(&mut counters, command, success, &chrono::Local::now().format("%Y-%m-%d").to_string());
+    save(&counters);
+}
+
+fn count(counters: &mut Counters, command: &str, success: bool, today: &str) {
+    if counters.since.is_empty() {
+        counters.since = today.to_string();
+    }
+    let key = if KNOWN_OPERATIONS.contains(&command) { command } else { "other" };
+    let entry = counters.operations.entry(key.to_string()).or_default();
+    if success {
+        entry.successes += 1;
+    } else {
+        entry.failures += 1;
+    }
+}
+
+/// The report as it would be uploaded right now
+pub fn preview() -> UsageReport {
+    let counters = counters().lock().map(|counters| counters.clone()).unwrap_or_default();
+    UsageReport {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        antumbra_version: load_settings().unwrap_or_default().antumbra_version,
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        since: counters.since,
+        operations: counters.operations,
+    }
+}
+
+/// Drop all counters, e.g. after opting out
+pub fn clear() {
+    if let Ok(mut counters) = counters().lock() {
+        *counters = Counters::default();
+        save(&counters);
+    }
+}
+
+/// Post the previewed report to `usage_metrics_endpoint` and drop the counts
+/// it sent. Refuses unless the user opted in and configured an endpoint.
+pub async fn upload() -> Result<UsageReport> {
+    let settings = load_settings().unwrap_or_default();
+    if !settings.usage_metrics_enabled {
+        anyhow::bail!("Usage metrics are disabled");
+    }
+    let endpoint = settings
+        .usage_metrics_endpoint
+        .filter(|endpoint| !endpoint.trim().is_empty())
+        .context("No usage metrics endpoint is configured")?;
+
+    let report = preview();
+    let client = http::client_builder().build().context("Failed to create HTTP client")?;
+    client
+        .post(&endpoint)
+        .json(&report)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to upload usage metrics to {}", endpoint))?;
+    log::info!("Uploaded usage metrics to {}", endpoint);
+    forget_sent(&report);
+    Ok(report)
+}
+
+/// Take the uploaded counts off the counters, keeping runs recorded while
+/// the upload was in flight for the next report
+fn forget_sent(report: &UsageReport) {
+    if let Ok(mut counters) = counters().lock() {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        subtract(&mut counters, &report.operations, &today);
+        save(&counters);
+    }
+}
+
+fn subtract(counters: &mut Counters, sent: &BTreeMap<String, OperationCounts>, today: &str) {
+    for (operation, sent) in sent {
+        if let Some(counts) = counters.operations.get_mut(operation) {
+            counts.successes = counts.successes.saturating_sub(sent.successes);
+            counts.failures = counts.failures.saturating_sub(sent.failures);
+        }
+    }
+    counters.operations.retain(|_, counts| *counts != OperationCounts::default());
+    // Whatever is left was counted during the upload
+    counters.since = if counters.operations.is_empty() { String::new() } else { today.to_string() };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_known_operations_only() {
+        let mut counters = Counters::default();
+        count(&mut counters, "download", true, "2025-01-01");
+        count(&mut counters, "download", false, "2025-01-02");
+        count(&mut counters, "/home/alice/custom", true, "2025-01-02");
+
+        assert_eq!(counters.since, "2025-01-01");
+        assert_eq!(
+            counters.operations.get("download"),
+            Some(&OperationCounts { successes: 1, failures: 1 })
+        );
+        assert_eq!(counters.operations.keys().collect::<Vec<_>>(), vec!["download", "other"]);
+    }
+
+    #[test]
+    fn upload_keeps_counts_recorded_meanwhile() {
+        let mut counters = Counters::default();
+        count(&mut counters, "download", true, "2025-01-01");
+        count(&mut counters, "erase", true, "2025-01-01");
+        let sent = counters.operations.clone();
+
+        count(&mut counters, "download", false, "2025-01-02");
+        subtract(&mut counters, &sent, "2025-01-02");
+        assert_eq!(counters.since, "2025-01-02");
+        assert_eq!(counters.operations.len(), 1);
+        assert_eq!(
+            counters.operations.get("download"),
+            Some(&OperationCounts { successes: 0, failures: 1 })
+        );
+
+        let sent = counters.operations.clone();
+        subtract(&mut counters, &sent, "2025-01-03");
+        assert!(counters.operations.is_empty());
+        assert!(counters.since.is_empty());
+    }
+}
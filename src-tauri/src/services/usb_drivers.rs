@@ -0,0 +1,351 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use serde::{Deserialize, Serialize};
+
+/// MediaTek's USB vendor id
+const MTK_VID: &str = "VID_0E8D";
+
+/// Driver stack seen for one MediaTek USB interface
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsbDriverInfo {
+    pub name: String,
+    pub instance_id: String,
+    /// "brom", "preloader", "da" or "unknown", from the USB product id
+    pub mode: String,
+    /// Bound driver service (usbser, WinUSB, libusb0, wdm_usb, ...), if any
+    pub service: Option<String>,
+    /// Device manager problem code, 0 when the device works
+    pub problem_code: u32,
+}
+
+/// What is installed on the machine, independent of what is plugged in
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsbDriverReport {
+    /// OS the report was gathered on; empty when there was nothing to scan
+    pub platform: String,
+    pub devices: Vec<UsbDriverInfo>,
+    /// Driver packages (`pnputil`) relevant to MediaTek devices (Windows)
+    pub driver_packages: Vec<String>,
+    /// Class-wide USB filters such as the libusb-win32 filter driver (Windows)
+    pub class_filters: Vec<String>,
+    /// udev rule files that mention the MediaTek vendor id (Linux)
+    pub udev_rules: Vec<String>,
+    /// ModemManager probes new serial devices and steals the BROM port (Linux)
+    pub modem_manager_running: bool,
+}
+
+fn mode_for(instance_id: &str) -> &'static str {
+    let upper = instance_id.to_uppercase();
+    if upper.contains("PID_0003") {
+        "brom"
+    } else if upper.contains("PID_2000") {
+        "preloader"
+    } else if upper.contains("PID_2001") {
+        "da"
+    } else {
+        "unknown"
+    }
+}
+
+fn is_legacy_vcom(service: &str) -> bool {
+    matches!(service.to_lowercase().as_str(), "wdm_usb" | "mtkcdcvcom" | "usbvcom")
+}
+
+fn is_libusb(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower.contains("libusb0") || lower.contains("libusbk") || lower.contains("libusb-win32")
+}
+
+/// Recommendations for known-bad driver setups
+pub fn findings(report: &UsbDriverReport) -> Vec<String> {
+    let mut findings = Vec::new();
+
+    if report.class_filters.iter().any(|filter| is_libusb(filter)) {
+        findings.push(
+            "The libusb-win32 class filter is installed for all USB devices. It intercepts the \
+             MediaTek preloader interface; remove it with the libusb-win32 filter wizard."
+                .to_string(),
+        );
+    }
+
+    if report.platform == "linux" {
+        if report.udev_rules.is_empty() {
+            findings.push(
+                "No udev rule grants access to MediaTek (0e8d) devices. Add one under \
+                 /etc/udev/rules.d, e.g. SUBSYSTEM==\"usb\", ATTR{idVendor}==\"0e8d\", \
+                 MODE=\"0666\", then reload udev."
+                    .to_string(),
+            );
+        }
+        if report.modem_manager_running {
+            findings.push(
+                "ModemManager is running and may grab the MediaTek serial port during the \
+                 handshake. Stop it or tag 0e8d devices with ID_MM_DEVICE_IGNORE."
+                    .to_string(),
+            );
+        }
+        return findings;
+    }
+    if report.platform != "windows" {
+        return findings;
+    }
+
+    for device in &report.devices {
+        match device.service.as_deref() {
+            None => findings.push(format!(
+                "{} ({}) has no driver. Install the MediaTek USB VCOM driver or bind \
+                 usbser to it.",
+                device.name, device.mode
+            )),
+            Some(_) if device.problem_code != 0 => findings.push(format!(
+                "{} ({}) reports device manager problem code {}. Reinstall its driver and \
+                 reconnect the device.",
+                device.name, device.mode, device.problem_code
+            )),
+            Some(service) if is_legacy_vcom(service) && device.mode == "brom" => {
+                findings.push(format!(
+                    "{} uses the legacy MTK VCOM driver ({}) in BROM mode, which is known to \
+                     drop the handshake. Update to a current MediaTek USB VCOM driver or usbser.",
+                    device.name, service
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    let services: Vec<String> = report
+        .devices
+        .iter()
+        .filter_map(|device| device.service.as_deref())
+        .map(str::to_lowercase)
+        .collect();
+    let has_serial = services.iter().any(|s| s == "usbser" || is_legacy_vcom(s));
+    if has_serial && services.iter().any(|s| is_libusb(s) || s == "winusb") {
+        findings.push(
+            "MediaTek interfaces are split between a serial driver and a libusb/WinUSB \
+             driver. Bind BROM and preloader to the same driver family."
+                .to_string(),
+        );
+    }
+
+    findings
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+#[cfg_attr(not(windows), allow(dead_code))]
+struct PnpEntity {
+    name: Option<String>,
+    device_id: String,
+    service: Option<String>,
+    config_manager_error_code: Option<u32>,
+}
+
+/// Parse `Get-CimInstance Win32_PnPEntity | ConvertTo-Json` output, which is
+/// a bare object instead of an array when only one device matches
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_pnp_entities(json: &str) -> Vec<UsbDriverInfo> {
+    let json = json.trim();
+    let entities: Vec<PnpEntity> = if json.starts_with('[') {
+        serde_json::from_str(json).unwrap_or_default()
+    } else {
+        serde_json::from_str(json).map(|entity| vec![entity]).unwrap_or_default()
+    };
+    entities
+        .into_iter()
+        .filter(|entity| entity.device_id.to_uppercase().contains(MTK_VID))
+        .map(|entity| UsbDriverInfo {
+            name: entity.name.unwrap_or_else(|| entity.device_id.clone()),
+            mode: mode_for(&entity.device_id).to_string(),
+            instance_id: entity.device_id,
+            service: entity.service.filter(|service| !service.is_empty()),
+            problem_code: entity.config_manager_error_code.unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Enumerate connected MediaTek USB interfaces, MediaTek-related driver
+/// packages and USB class filters
+#[cfg(windows)]
+pub fn scan() -> UsbDriverReport {
+    const PNP_QUERY: &str = "Get-CimInstance Win32_PnPEntity | \
+        Where-Object { $_.DeviceID -like 'USB\\VID_0E8D*' } | \
+        Select-Object Name,DeviceID,Service,ConfigManagerErrorCode | ConvertTo-Json -Compress";
+    const USB_CLASS_KEY: &str = "HKLM\\SYSTEM\\CurrentControlSet\\Control\\Class\\\
+        {36fc9e60-c465-11cf-8056-444553540000}";
+
+    let devices = run_hidden("powershell", &["-NoProfile", "-Command", PNP_QUERY])
+        .map(|output| parse_pnp_entities(&output))
+        .unwrap_or_default();
+
+    let driver_packages = run_hidden("pnputil", &["/enum-drivers"])
+        .map(|output| {
+            output
+                .lines()
+                .filter_map(|line| line.split_once(':').map(|(_, value)| value.trim()))
+                .filter(|value| {
+                    let lower = value.to_lowercase();
+                    lower.contains("mediatek")
+                        || lower.contains("vcom")
+                        || lower.contains("libusb")
+                        || lower.contains("usbdk")
+                })
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let class_filters = ["UpperFilters", "LowerFilters"]
+        .iter()
+        .filter_map(|value| run_hidden("reg", &["query", USB_CLASS_KEY, "/v", value]))
+        .flat_map(|output| {
+            output
+                .lines()
+                .filter(|line| line.contains("REG_MULTI_SZ"))
+                .filter_map(|line| line.split("REG_MULTI_SZ").nth(1))
+                .flat_map(|filters| filters.split("\\0"))
+                .map(|filter| filter.trim().to_string())
+                .filter(|filter| !filter.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    UsbDriverReport {
+        platform: "windows".to_string(),
+        devices,
+        driver_packages,
+        class_filters,
+        ..Default::default()
+    }
+}
+
+/// Connected MediaTek devices from sysfs, udev rules and ModemManager
+#[cfg(target_os = "linux")]
+pub fn scan() -> UsbDriverReport {
+    use std::path::Path;
+
+    let read = |path: &Path| std::fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+
+    let mut devices = Vec::new();
+    if let Ok(entries) = std::fs::read_dir("/sys/bus/usb/devices") {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if read(&path.join("idVendor")).as_deref() != Some("0e8d") {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let pid = read(&path.join("idProduct")).unwrap_or_default().to_uppercase();
+            // First interface's bound kernel driver (cdc_acm, usbfs, ...)
+            let service = std::fs::read_link(path.join(format!("{}:1.0", name)).join("driver"))
+                .ok()
+                .and_then(|link| link.file_name().map(|n| n.to_string_lossy().to_string()));
+            devices.push(UsbDriverInfo {
+                name: read(&path.join("product")).unwrap_or_else(|| name.clone()),
+                mode: mode_for(&format!("PID_{}", pid)).to_string(),
+                instance_id: name,
+                service,
+                problem_code: 0,
+            });
+        }
+    }
+
+    let udev_rules = ["/etc/udev/rules.d", "/lib/udev/rules.d", "/usr/lib/udev/rules.d"]
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            read(path).map(|rules| rules.to_lowercase().contains("0e8d")).unwrap_or(false)
+        })
+        .map(|path| path.display().to_string())
+        .collect();
+
+    let modem_manager_running = std::fs::read_dir("/proc")
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|entry| read(&entry.path().join("comm")).as_deref() == Some("ModemManager"))
+        })
+        .unwrap_or(false);
+
+    UsbDriverReport {
+        platform: "linux".to_string(),
+        devices,
+        udev_rules,
+        modem_manager_running,
+        ..Default::default()
+    }
+}
+
+/// macOS and the BSDs need no drivers for MediaTek devices
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn scan() -> UsbDriverReport {
+    UsbDriverReport::default()
+}
+
+#[cfg(windows)]
+fn run_hidden(program: &str, args: &[&str]) -> Option<String> {
+    use std::os::windows::process::CommandExt;
+
+    // CREATE_NO_WINDOW flag to hide console window
+    let output =
+        std::process::Command::new(program).args(args).creation_flags(0x08000000).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_and_multiple_entities() {
+        let single = r#"{"Name":"MediaTek USB Port","DeviceID":"USB\\VID_0E8D&PID_0003\\5&1","Service":"usbser","ConfigManagerErrorCode":0}"#;
+        let devices = parse_pnp_entities(single);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].mode, "brom");
+        assert_eq!(devices[0].service.as_deref(), Some("usbser"));
+
+        let many = r#"[{"Name":null,"DeviceID":"USB\\VID_0E8D&PID_2000\\6&2","Service":"","ConfigManagerErrorCode":28},
+                       {"Name":"Other","DeviceID":"USB\\VID_1234&PID_0001\\1","Service":"x","ConfigManagerErrorCode":0}]"#;
+        let devices = parse_pnp_entities(many);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].mode, "preloader");
+        assert_eq!(devices[0].service, None);
+    }
+
+    #[test]
+    fn flags_known_bad_setups() {
+        let device = |mode: &str, service: Option<&str>| UsbDriverInfo {
+            name: "MediaTek".into(),
+            instance_id: String::new(),
+            mode: mode.into(),
+            service: service.map(str::to_string),
+            problem_code: 0,
+        };
+
+        let healthy = UsbDriverReport {
+            platform: "windows".into(),
+            devices: vec![device("brom", Some("usbser")), device("preloader", Some("usbser"))],
+            ..Default::default()
+        };
+        assert!(findings(&healthy).is_empty());
+
+        let broken = UsbDriverReport {
+            platform: "windows".into(),
+            devices: vec![device("brom", Some("wdm_usb")), device("preloader", Some("WinUSB"))],
+            class_filters: vec!["libusb0".into()],
+            ..Default::default()
+        };
+        assert_eq!(findings(&broken).len(), 3);
+
+        let linux = UsbDriverReport {
+            platform: "linux".into(),
+            modem_manager_running: true,
+            ..Default::default()
+        };
+        assert_eq!(findings(&linux).len(), 2);
+    }
+}
@@ -0,0 +1,96 @@
+/*
+    SPDX-License-Identifier: AGPL-3.0-or-later
+    SPDX-FileCopyrightText: 2025 Shomy
+*/
+
+use crate::models::workspace::Workspace;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+pub const WORKSPACE_FILE: &str = "workspace.json";
+
+/// The workspace folder for `path`, which may name the folder or its `workspace.json`
+pub fn workspace_folder(path: &Path) -> PathBuf {
+    if path.file_name().is_some_and(|name| name == WORKSPACE_FILE) {
+        path.parent().map(Path::to_path_buf).unwrap_or_default()
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Read `workspace.json` from `folder`, resolving its paths against the folder
+pub fn load(folder: &Path) -> Result<Workspace> {
+    let path = folder.join(WORKSPACE_FILE);
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read workspace {:?}", path))?;
+    let workspace: Workspace = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid workspace file {:?}", path))?;
+    Ok(map_paths(workspace, |value| absolute(folder, value)))
+}
+
+/// Write `workspace` to `folder/workspace.json`, creating the folder if needed
+pub fn save(folder: &Path, workspace: Workspace) -> Result<()> {
+    std::fs::create_dir_all(folder)
+        .with_context(|| format!("Failed to create workspace folder {:?}", folder))?;
+    let mut workspace = map_paths(workspace, |value| relative(folder, value));
+    workspace.saved_at = Some(chrono::Utc::now().to_rfc3339());
+
+    let path = folder.join(WORKSPACE_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(&workspace)?)
+        .with_context(|| format!("Failed to write workspace {:?}", path))
+}
+
+fn map_paths(mut workspace: Workspace, convert: impl Fn(&str) -> String) -> Workspace {
+    for path in [
+        &mut workspace.scatter_path,
+        &mut workspace.da_path,
+        &mut workspace.preloader_path,
+        &mut workspace.backup_dir,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        *path = convert(path);
+    }
+    for image in workspace.images.values_mut() {
+        *image = convert(image);
+    }
+    workspace
+}
+
+fn absolute(folder: &Path, value: &str) -> String {
+    let path = Path::new(value);
+    if path.is_absolute() {
+        value.to_string()
+    } else {
+        folder.join(path).display().to_string()
+    }
+}
+
+/// Paths inside `folder` become relative; anything else is kept absolute
+fn relative(folder: &Path, value: &str) -> String {
+    match Path::new(value).strip_prefix(folder) {
+        Ok(rest) if !rest.as_os_str().is_empty() => rest.to_string_lossy().replace('\\', "/"),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_paths_inside_the_folder_relatively() {
+        let folder = std::env::temp_dir().join("penumbra-workspace");
+        let inside = folder.join("images").join("boot.img").display().to_string();
+        let outside = std::env::temp_dir().join("da.bin").display().to_string();
+
+        assert_eq!(relative(&folder, &inside), "images/boot.img");
+        assert_eq!(relative(&folder, &outside), outside);
+        assert_eq!(
+            absolute(&folder, "images/boot.img"),
+            folder.join("images/boot.img").display().to_string()
+        );
+        assert_eq!(workspace_folder(&folder.join(WORKSPACE_FILE)), folder);
+    }
+}
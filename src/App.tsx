@@ -10,6 +10,7 @@ import { LogPanel } from './components/LogPanel';
 import { ErrorBoundary } from './components/ErrorBoundary';
 import { UpdateAvailableModal } from './components/UpdateAvailableModal';
 import { useOperationStream } from './hooks/useOperationStream';
+import { useCloseGuard } from './hooks/useCloseGuard';
 import { useSettings } from './hooks/useSettings';
 import { ConfirmationProvider } from './hooks/confirmationProvider';
 import { DeviceApi } from './services/api/deviceApi';
@@ -18,6 +19,7 @@ import { useUpdateStore } from './store/updateStore';
 
 function AppContent() {
   useOperationStream();
+  useCloseGuard();
 
   const { isLoading: isSettingsLoading, error: settingsError } = useSettings();
   const autoCheckUpdates = useDeviceStore((state) => state.autoCheckUpdates);
@@ -94,7 +96,7 @@ function AppContent() {
       <UpdateAvailableModal
         isOpen={isUpdateModalOpen}
         onClose={() => setUpdateModalOpen(false)}
-        onDownload={downloadUpdate}
+        onDownload={() => downloadUpdate()}
         updateInfo={updateInfo}
         isDownloading={isDownloadingUpdate}
         downloadProgress={downloadProgress}
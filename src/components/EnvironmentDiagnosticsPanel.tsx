@@ -1,14 +1,16 @@
 import { useState } from 'react';
 import { AlertCircle, CheckCircle, Info, Terminal } from 'lucide-react';
 import { useDeviceStore } from '../store/deviceStore';
-import type { WindowsDiagnostics } from '../types';
+import type { EnvironmentDiagnostics, SelfTestReport } from '../types';
 import { DiagnosticsApi } from '../services/api/diagnosticsApi';
 
-export function WindowsDiagnosticsPanel() {
+export function EnvironmentDiagnosticsPanel() {
   const { isConnected, isConnecting } = useDeviceStore();
-  const [diagnostics, setDiagnostics] = useState<WindowsDiagnostics | null>(null);
+  const [diagnostics, setDiagnostics] = useState<EnvironmentDiagnostics | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [selfTest, setSelfTest] = useState<SelfTestReport | null>(null);
+  const [isSelfTesting, setIsSelfTesting] = useState(false);
 
   const runDiagnostics = async () => {
     if (isConnected || isConnecting) {
@@ -20,7 +22,7 @@ export function WindowsDiagnosticsPanel() {
     setError(null);
     
     try {
-      const result = await DiagnosticsApi.checkWindowsEnvironment();
+      const result = await DiagnosticsApi.checkEnvironment();
       setDiagnostics(result);
     } catch (err) {
       // Use the error parser to extract message from any error format
@@ -32,8 +34,23 @@ export function WindowsDiagnosticsPanel() {
     }
   };
 
+  const runSelfTest = async () => {
+    setIsSelfTesting(true);
+    setError(null);
+
+    try {
+      setSelfTest(await DiagnosticsApi.runSelfTest());
+    } catch (err) {
+      const { parseTauriError } = await import('../services/utils/errorParser');
+      setError(parseTauriError(err).message);
+    } finally {
+      setIsSelfTesting(false);
+    }
+  };
+
   const clearDiagnostics = () => {
     setDiagnostics(null);
+    setSelfTest(null);
     setError(null);
   };
 
@@ -52,9 +69,18 @@ export function WindowsDiagnosticsPanel() {
       <div className="flex items-center justify-between mb-6">
         <div className="flex items-center gap-2">
             <Terminal className="w-5 h-5 text-[var(--text-muted)]" />
-            <h3 className="text-lg font-medium text-[var(--text)]">Windows Environment Diagnostics</h3>
+            <h3 className="text-lg font-medium text-[var(--text)]">Environment Diagnostics</h3>
         </div>
         <div className="flex gap-2">
+          {!selfTest && (
+            <button
+              onClick={runSelfTest}
+              disabled={isSelfTesting}
+              className="px-4 py-2 bg-[var(--surface-alt)] hover:bg-[var(--surface-hover)] text-[var(--text)] rounded-md transition-colors disabled:opacity-50"
+            >
+              {isSelfTesting ? 'Testing...' : 'Self-Test'}
+            </button>
+          )}
           {!diagnostics && !isLoading && (
             <button
               onClick={runDiagnostics}
@@ -63,7 +89,7 @@ export function WindowsDiagnosticsPanel() {
               Run Diagnostics
             </button>
           )}
-          {diagnostics && (
+          {(diagnostics || selfTest) && (
             <button
               onClick={clearDiagnostics}
               className="px-4 py-2 bg-[var(--surface-alt)] hover:bg-[var(--surface-hover)] text-[var(--text)] rounded-md transition-colors"
@@ -91,6 +117,25 @@ export function WindowsDiagnosticsPanel() {
           </div>
       )}
 
+      {selfTest && (
+        <div className="mb-6">
+          <h4 className="text-md font-medium text-[var(--text)] mb-3">
+            Self-Test: {selfTest.passed ? 'All checks passed' : 'Some checks failed'}
+          </h4>
+          <div className="bg-[var(--surface-alt)] rounded-md p-4 space-y-2 text-sm">
+            {selfTest.checks.map((check) => (
+              <div key={check.name} className="flex items-start gap-2">
+                {getStatusIcon(check.passed)}
+                <div>
+                  <p className="text-[var(--text)] font-medium">{check.name}</p>
+                  <p className="text-[var(--text-muted)] font-mono break-all">{check.detail}</p>
+                </div>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
       {diagnostics && !isLoading && (
         <div className="space-y-6">
           {/* System Information */}
@@ -181,6 +226,44 @@ export function WindowsDiagnosticsPanel() {
             </div>
           </div>
 
+          {/* USB Drivers */}
+          {(diagnostics.usb_drivers.devices.length > 0 ||
+            diagnostics.usb_drivers.driver_packages.length > 0 ||
+            diagnostics.usb_drivers.udev_rules.length > 0) && (
+            <div>
+              <h4 className="text-md font-medium text-[var(--text)] mb-3">USB Drivers</h4>
+              <div className="bg-[var(--surface-alt)] rounded-md p-4">
+                <div className="space-y-3 text-sm">
+                  {diagnostics.usb_drivers.devices.map((device) => (
+                    <div key={device.instance_id} className="flex items-center gap-2">
+                      {getStatusIcon(!!device.service && device.problem_code === 0)}
+                      <span className="text-[var(--text)] font-medium">{device.name}</span>
+                      <span className="text-[var(--text-muted)]">
+                        {device.mode} · {device.service || 'no driver'}
+                      </span>
+                    </div>
+                  ))}
+                  {diagnostics.usb_drivers.udev_rules.length > 0 && (
+                    <div>
+                      <span className="text-[var(--text-muted)]">udev Rules:</span>
+                      <p className="text-[var(--text)] font-mono mt-1 break-all">
+                        {diagnostics.usb_drivers.udev_rules.join(', ')}
+                      </p>
+                    </div>
+                  )}
+                  {diagnostics.usb_drivers.driver_packages.length > 0 && (
+                    <div>
+                      <span className="text-[var(--text-muted)]">Installed Packages:</span>
+                      <p className="text-[var(--text)] font-mono mt-1 break-all">
+                        {diagnostics.usb_drivers.driver_packages.join(', ')}
+                      </p>
+                    </div>
+                  )}
+                </div>
+              </div>
+            </div>
+          )}
+
           {/* Recommendations */}
           {diagnostics.recommendations.length > 0 && (
             <div>
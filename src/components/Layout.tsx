@@ -6,8 +6,10 @@
 import { Outlet, NavLink } from 'react-router-dom';
 import { Terminal, Grid, FileStack, Wrench, Sun, Moon, Cpu } from 'lucide-react';
 import { useUIStore } from '../store/uiStore';
+import { useOpenScatter } from '../hooks/useOpenScatter';
 
 export function Layout() {
+  useOpenScatter();
   const toggleLogPanel = useUIStore((state) => state.toggleLogPanel);
   const theme = useUIStore((state) => state.theme);
   const toggleTheme = useUIStore((state) => state.toggleTheme);
@@ -0,0 +1,39 @@
+import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useConfirmation } from './useConfirmation';
+import { DeviceApi } from '../services/api/deviceApi';
+import type { CloseRequestedWhileBusyEvent } from '../types';
+
+/**
+ * Ask before closing the window while operations run. The backend holds the
+ * close until this answers, or closes anyway after the event's timeout.
+ */
+export const useCloseGuard = () => {
+  const { confirm } = useConfirmation();
+
+  useEffect(() => {
+    const unlisten = listen<CloseRequestedWhileBusyEvent>(
+      'app:close-requested-while-busy',
+      async (event) => {
+        const { operations, timeout_secs } = event.payload;
+        const names = operations
+          .map((op) => (op.partition ? `${op.command} (${op.partition})` : op.command))
+          .join(', ');
+        const proceed = await confirm({
+          title: 'Operations still running',
+          message:
+            `Closing now cancels: ${names}. Interrupting a write can leave the device ` +
+            `unbootable. The app closes automatically in ${timeout_secs} seconds.`,
+          variant: 'danger',
+          confirmText: 'Cancel and close',
+          cancelText: 'Keep running',
+        });
+        await DeviceApi.confirmCloseWhileBusy(proceed).catch(() => undefined);
+      }
+    );
+
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => undefined);
+    };
+  }, [confirm]);
+};
@@ -0,0 +1,40 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import type { BackendLogEvent } from '../types';
+
+const MAX_EVENTS = 1000;
+
+export interface LogEventFilter {
+  /** Only keep these levels ('error', 'warn', 'info', ...) */
+  levels?: string[];
+  operationId?: string;
+  partition?: string;
+}
+
+/**
+ * Collect the backend's `log:event` stream, newest last, keeping at most
+ * MAX_EVENTS entries that match the filter.
+ */
+export const useLogEvents = (filter: LogEventFilter = {}) => {
+  const [events, setEvents] = useState<BackendLogEvent[]>([]);
+  const { operationId, partition } = filter;
+  const levels = filter.levels?.join(',');
+
+  useEffect(() => {
+    const allowedLevels = levels ? levels.split(',') : null;
+    const matches = (event: BackendLogEvent) =>
+      (!allowedLevels || allowedLevels.includes(event.level)) &&
+      (!operationId || event.operation_id === operationId) &&
+      (!partition || event.partition_name === partition);
+
+    const unlisten = listen<BackendLogEvent>('log:event', (event) => {
+      if (!matches(event.payload)) return;
+      setEvents((current) => [...current, event.payload].slice(-MAX_EVENTS));
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => undefined);
+    };
+  }, [levels, operationId, partition]);
+
+  return { events, clear: () => setEvents([]) };
+};
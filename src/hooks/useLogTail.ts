@@ -0,0 +1,33 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { AntumbraApi } from '../services/api/antumbraApi';
+
+const MAX_LINES = 5000;
+
+interface LogTailEvent {
+  lines: string[];
+}
+
+/**
+ * Follow the wrapper log while mounted. Lines logged after mounting are
+ * appended as the backend writes them; older content comes from queryLog.
+ */
+export const useLogTail = (enabled = true) => {
+  const [lines, setLines] = useState<string[]>([]);
+
+  useEffect(() => {
+    if (!enabled) return;
+
+    const unlisten = listen<LogTailEvent>('log:tail', (event) => {
+      setLines((current) => [...current, ...event.payload.lines].slice(-MAX_LINES));
+    });
+    AntumbraApi.tailWrapperLog().catch(() => undefined);
+
+    return () => {
+      AntumbraApi.stopWrapperLogTail().catch(() => undefined);
+      unlisten.then((fn) => fn()).catch(() => undefined);
+    };
+  }, [enabled]);
+
+  return { lines, clear: () => setLines([]) };
+};
@@ -0,0 +1,49 @@
+import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useNavigate } from 'react-router-dom';
+import toast from 'react-hot-toast';
+import { useFlasherStore } from '../store/flasherStore';
+import { ScatterApi } from '../services/api/scatterApi';
+import type { OpenScatterEvent } from '../types';
+
+/**
+ * Load scatter files opened from the file manager into the flasher. Must be
+ * used inside the router.
+ */
+export const useOpenScatter = () => {
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    let isMounted = true;
+
+    const unlisten = listen<OpenScatterEvent>('app:open-scatter', async (event) => {
+      if (!isMounted) return;
+      const { path, scatter, error } = event.payload;
+      if (!scatter) {
+        toast.error(`Failed to open ${path}: ${error ?? 'unknown error'}`);
+        return;
+      }
+
+      const { setScatterFile, setSelectedPartitions, setPartitionImages } =
+        useFlasherStore.getState();
+      setScatterFile(scatter);
+      const detectedImages = await ScatterApi.detectImageFiles(path, scatter.partitions);
+      setPartitionImages(detectedImages);
+      setSelectedPartitions(
+        new Set(
+          scatter.partitions
+            .filter((p) => p.is_download && detectedImages.has(p.partition_name))
+            .map((p) => p.partition_name)
+        )
+      );
+      navigate('/flasher');
+      toast.success(`Opened ${scatter.platform} scatter file`);
+    });
+    unlisten.then(() => ScatterApi.openLaunchScatter()).catch(() => undefined);
+
+    return () => {
+      isMounted = false;
+      unlisten.then((fn) => fn()).catch(() => undefined);
+    };
+  }, [navigate]);
+};
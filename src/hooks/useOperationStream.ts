@@ -1,7 +1,8 @@
 import { useEffect } from 'react';
-import { listen } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import type { UnlistenFn } from '@tauri-apps/api/event';
 import { useOperationStore } from '../store/operationStore';
+import { DeviceApi } from '../services/api/deviceApi';
 import type { OperationProgressEvent } from '../types';
 
 interface OperationOutputEvent {
@@ -11,6 +12,30 @@ interface OperationOutputEvent {
   is_stderr: boolean;
 }
 
+interface OperationOutputBatchEvent {
+  operation_id: string;
+  lines: OperationOutputEvent[];
+}
+
+interface OperationOutputPathEvent {
+  operation_id: string;
+  path: string;
+  folder: string;
+}
+
+interface AntumbraLogEvent {
+  operation_id: string;
+  lines: string[];
+}
+
+interface OperationResourcesEvent {
+  operation_id: string;
+  cpu_percent: number;
+  memory_bytes: number;
+  peak_memory_bytes: number;
+  warning: string | null;
+}
+
 interface OperationCompleteEvent {
   operation_id: string;
   success: boolean;
@@ -18,42 +43,57 @@ interface OperationCompleteEvent {
 }
 
 export function useOperationStream() {
-  const { addLog, finishOperation, setIsStreaming, updateProgress } = useOperationStore();
+  const { addLog, finishOperation, setIsStreaming, updateProgress, setOutputPath } = useOperationStore();
 
   useEffect(() => {
     let unlistenOutput: UnlistenFn | null = null;
+    let unlistenOutputBatch: UnlistenFn | null = null;
     let unlistenComplete: UnlistenFn | null = null;
     let unlistenProgress: UnlistenFn | null = null;
+    let unlistenOutputPath: UnlistenFn | null = null;
+    let unlistenAntumbraLog: UnlistenFn | null = null;
+    let unlistenResources: UnlistenFn | null = null;
+    let lastResourceWarning: string | null = null;
     let isMounted = true;
 
+    const handleOutput = ({ line, timestamp, is_stderr }: OperationOutputEvent) => {
+      // Parse log level from line content
+      let level: 'info' | 'success' | 'error' | 'warning' = is_stderr ? 'error' : 'info';
+      const lowerLine = line.toLowerCase();
+        
+      if (lowerLine.includes('error') || lowerLine.includes('failed')) {
+        level = 'error';
+      } else if (lowerLine.includes('warning') || lowerLine.includes('warn')) {
+        level = 'warning';
+      } else if (lowerLine.includes('success') || lowerLine.includes('complete') || lowerLine.includes('found')) {
+        level = 'success';
+      }
+
+      addLog({
+        timestamp,
+        level,
+        message: line,
+      });
+    };
+
     const setupListeners = async () => {
+      // Scoped to this window so events routed to other windows are not received
+      const appWindow = getCurrentWebviewWindow();
+
       // Listen for operation output
-      unlistenOutput = await listen<OperationOutputEvent>('operation:output', (event) => {
+      unlistenOutput = await appWindow.listen<OperationOutputEvent>('operation:output', (event) => {
         if (!isMounted) return; // Guard against state updates after unmount
-        
-        const { line, timestamp, is_stderr } = event.payload;
-        
-        // Parse log level from line content
-        let level: 'info' | 'success' | 'error' | 'warning' = is_stderr ? 'error' : 'info';
-        const lowerLine = line.toLowerCase();
-        
-        if (lowerLine.includes('error') || lowerLine.includes('failed')) {
-          level = 'error';
-        } else if (lowerLine.includes('warning') || lowerLine.includes('warn')) {
-          level = 'warning';
-        } else if (lowerLine.includes('success') || lowerLine.includes('complete') || lowerLine.includes('found')) {
-          level = 'success';
-        }
+        handleOutput(event.payload);
+      });
 
-        addLog({
-          timestamp,
-          level,
-          message: line,
-        });
+      // Antumbra output arrives coalesced into batches
+      unlistenOutputBatch = await appWindow.listen<OperationOutputBatchEvent>('operation:output_batch', (event) => {
+        if (!isMounted) return;
+        event.payload.lines.forEach(handleOutput);
       });
 
       // Listen for operation completion
-      unlistenComplete = await listen<OperationCompleteEvent>('operation:complete', (event) => {
+      unlistenComplete = await appWindow.listen<OperationCompleteEvent>('operation:complete', (event) => {
         if (!isMounted) return; // Guard against state updates after unmount
         
         const { success, error } = event.payload;
@@ -63,13 +103,63 @@ export function useOperationStream() {
       });
 
       // Listen for operation progress
-      unlistenProgress = await listen<OperationProgressEvent>('operation:progress', (event) => {
+      unlistenProgress = await appWindow.listen<OperationProgressEvent>('operation:progress', (event) => {
         if (!isMounted) return;
         updateProgress(event.payload);
       });
+
+      // Organized output folders are only known once the backend resolves them
+      unlistenOutputPath = await appWindow.listen<OperationOutputPathEvent>('operation:output_path', (event) => {
+        if (!isMounted) return;
+        setOutputPath(event.payload.path);
+      });
+
+      // Details antumbra only writes to antumbra.log, never to stdout/stderr
+      unlistenAntumbraLog = await appWindow.listen<AntumbraLogEvent>('antumbra:log', (event) => {
+        if (!isMounted) return;
+        const timestamp = new Date().toISOString();
+        event.payload.lines.forEach((line) =>
+          handleOutput({
+            operation_id: event.payload.operation_id,
+            line: `[antumbra.log] ${line}`,
+            timestamp,
+            is_stderr: false,
+          })
+        );
+      });
+
+      // Only surface wedge warnings, once each, not every sample
+      unlistenResources = await appWindow.listen<OperationResourcesEvent>('operation:resources', (event) => {
+        if (!isMounted) return;
+        const { warning } = event.payload;
+        if (warning && warning !== lastResourceWarning) {
+          addLog({ timestamp: new Date().toISOString(), level: 'warning', message: warning });
+        }
+        lastResourceWarning = warning;
+      });
     };
 
-    setupListeners();
+    // After a reload, refill the console of operations that are still running
+    const replayRunning = async () => {
+      const running = await DeviceApi.listRunningOperations();
+      for (const { operation_id } of running) {
+        const events = await DeviceApi.replayOperationEvents(operation_id);
+        if (!isMounted) return;
+        for (const { event, payload } of events) {
+          if (event === 'operation:output') {
+            handleOutput(payload as OperationOutputEvent);
+          } else if (event === 'operation:output_batch') {
+            (payload as OperationOutputBatchEvent).lines.forEach(handleOutput);
+          } else if (event === 'operation:progress') {
+            updateProgress(payload as OperationProgressEvent);
+          }
+        }
+      }
+    };
+
+    setupListeners()
+      .then(replayRunning)
+      .catch(() => undefined);
 
     // Cleanup listeners on unmount
     return () => {
@@ -77,12 +167,24 @@ export function useOperationStream() {
       if (unlistenOutput) {
         unlistenOutput();
       }
+      if (unlistenOutputBatch) {
+        unlistenOutputBatch();
+      }
       if (unlistenComplete) {
         unlistenComplete();
       }
       if (unlistenProgress) {
         unlistenProgress();
       }
+      if (unlistenOutputPath) {
+        unlistenOutputPath();
+      }
+      if (unlistenAntumbraLog) {
+        unlistenAntumbraLog();
+      }
+      if (unlistenResources) {
+        unlistenResources();
+      }
     };
-  }, [addLog, finishOperation, setIsStreaming, updateProgress]);
+  }, [addLog, finishOperation, setIsStreaming, updateProgress, setOutputPath]);
 }
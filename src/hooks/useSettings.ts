@@ -1,4 +1,5 @@
 import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { useDeviceStore } from '../store/deviceStore';
 
 export const useSettings = () => {
@@ -13,5 +14,15 @@ export const useSettings = () => {
     }
   }, [loadSettings, isLoaded, isLoading, error]);
 
+  // Another window or a hand edit changed config.json; rehydrate from disk
+  useEffect(() => {
+    const unlisten = listen('settings:changed', () => {
+      loadSettings();
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => undefined);
+    };
+  }, [loadSettings]);
+
   return { isLoading, error };
 };
@@ -9,6 +9,9 @@ import { DialogType } from '../services/dialogs/fileDialogService';
 import { PartitionApi } from '../services/api/partitionApi';
 import { DeviceApi } from '../services/api/deviceApi';
 import { FastbootApi } from '../services/api/fastbootApi';
+import { FastbootToolsApi } from '../services/api/fastbootToolsApi';
+import { DiagnosticsApi } from '../services/api/diagnosticsApi';
+import { formatBytes } from '../services/utils/formatUtils';
 import { executeOperation } from '../services/operations/executeOperation';
 import { ConnectionWarning } from '../components/tools/ConnectionWarning';
 import { ReadAllSection } from '../components/tools/ReadAllSection';
@@ -56,6 +59,11 @@ export function Tools() {
 
     const skipList = Array.from(skipPartitions);
     const backupCount = partitions.length - skipList.length;
+    const requiredBytes = partitions
+      .filter((p) => !skipPartitions.has(p.name))
+      .reduce((sum, p) => sum + (parseInt(p.size, 16) || 0), 0);
+    const diskSpace = await DiagnosticsApi.getDiskSpace(outputDir).catch(() => null);
+    const lowOnSpace = diskSpace !== null && diskSpace.free_bytes < requiredBytes;
 
     const confirmed = await confirm({
       title: 'Backup All Partitions',
@@ -70,10 +78,16 @@ export function Tools() {
               Skipping {skipList.length} partitions: {skipList.join(', ')}
             </p>
           )}
+          {lowOnSpace && (
+            <p className="mt-2 font-semibold text-[var(--danger)]">
+              Not enough disk space: the backup needs {formatBytes(requiredBytes)} but only{' '}
+              {formatBytes(diskSpace.free_bytes)} is free.
+            </p>
+          )}
           <p className="mt-3">Continue?</p>
         </div>
       ),
-      variant: 'info',
+      variant: lowOnSpace ? 'danger' : 'info',
       confirmText: 'Backup',
     });
 
@@ -155,7 +169,7 @@ export function Tools() {
     setIsSeccfgRunning(true);
 
     try {
-      await executeOperation({
+      const { success } = await executeOperation({
         operation: `Seccfg ${action}`,
         type: 'write',
         partitionName: `seccfg-${action}`,
@@ -177,11 +191,42 @@ export function Tools() {
             operationId
           ),
       });
+      if (success && isUnlock) {
+        await offerFastbootUnlock();
+      }
     } finally {
       setIsSeccfgRunning(false);
     }
   };
 
+  // Many devices also need `fastboot flashing unlock` after seccfg is unlocked
+  const offerFastbootUnlock = async () => {
+    const fastboot = await FastbootToolsApi.cliStatus().catch(() => null);
+    if (!fastboot?.path) return;
+
+    const proceed = await confirm({
+      title: 'Finish unlock in fastboot?',
+      message:
+        'Some devices also require `fastboot flashing unlock`. Continue, then reboot the ' +
+        'device into fastboot mode; the command runs as soon as it shows up.',
+      variant: 'warning',
+      confirmText: 'Run fastboot unlock',
+      cancelText: 'Skip',
+    });
+    if (!proceed) return;
+
+    await executeOperation({
+      operation: 'Fastboot flashing unlock',
+      type: 'write',
+      partitionName: 'fastboot-unlock',
+      partitionSize: 'bootloader',
+      clearLogs: false,
+      successMessage: 'Fastboot unlock finished',
+      errorMessage: 'Fastboot unlock failed',
+      run: (operationId) => FastbootToolsApi.cliRun('flashing_unlock', null, operationId),
+    });
+  };
+
   const handleForceFastboot = async () => {
     if (isFastbootRunning) return;
 
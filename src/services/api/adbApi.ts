@@ -1,5 +1,13 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AdbListEntry, AdbRebootMode, AdbStatResult, AdbUsbDevice } from '../../types';
+import type {
+  AdbCliDevice,
+  AdbCliRebootTarget,
+  AdbCliStatus,
+  AdbListEntry,
+  AdbRebootMode,
+  AdbStatResult,
+  AdbUsbDevice,
+} from '../../types';
 
 export class AdbApi {
   static async listDevices(): Promise<AdbUsbDevice[]> {
@@ -79,4 +87,20 @@ export class AdbApi {
   static async authCheck(deviceId: string, operationId: string): Promise<void> {
     return invoke('adb_auth_check', { deviceId, operationId });
   }
+
+  static async cliStatus(): Promise<AdbCliStatus> {
+    return invoke('adb_cli_status');
+  }
+
+  static async cliDevices(): Promise<AdbCliDevice[]> {
+    return invoke('adb_cli_devices');
+  }
+
+  static async cliReboot(
+    serial: string,
+    target: AdbCliRebootTarget,
+    operationId: string
+  ): Promise<void> {
+    return invoke('adb_cli_reboot', { serial, target, operationId });
+  }
 }
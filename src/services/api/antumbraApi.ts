@@ -1,32 +1,102 @@
 import { invoke } from '@tauri-apps/api/core'
-import type { AntumbraCommandInfo, AntumbraUpdateInfo, AntumbraUpdateResult } from '../../types'
+import type {
+  AntumbraCommandInfo,
+  AntumbraRelease,
+  AntumbraUpdateInfo,
+  AntumbraUpdateResult,
+  CommandHistoryPage,
+  InstalledAntumbraVersion,
+  LogFileInfo,
+  LogQuery,
+  LogQueryPage,
+  OperationStats,
+} from '../../types'
 
 export class AntumbraApi {
   static async getUpdatablePath(): Promise<string> {
     return invoke('get_antumbra_updatable_path')
   }
 
-  static async checkUpdate(): Promise<AntumbraUpdateInfo> {
-    return invoke('check_antumbra_update')
+  /** `force` revalidates with GitHub instead of trusting a recent cached check */
+  static async checkUpdate(force = false): Promise<AntumbraUpdateInfo> {
+    return invoke('check_antumbra_update', { force })
   }
 
-  static async downloadUpdate(): Promise<AntumbraUpdateResult> {
-    return invoke('download_antumbra_update')
+  static async downloadUpdate(
+    tag?: string,
+    approved?: AntumbraUpdateInfo | null
+  ): Promise<AntumbraUpdateResult> {
+    return invoke('download_antumbra_update', { tag: tag ?? null, approved: approved ?? null })
+  }
+
+  static async cancelDownload(): Promise<boolean> {
+    return invoke('cancel_antumbra_download')
+  }
+
+  static async listReleases(): Promise<AntumbraRelease[]> {
+    return invoke('list_antumbra_releases')
+  }
+
+  static async rollbackUpdate(): Promise<AntumbraUpdateResult> {
+    return invoke('rollback_antumbra_update')
+  }
+
+  static async listInstalledVersions(): Promise<InstalledAntumbraVersion[]> {
+    return invoke('list_installed_antumbra_versions')
+  }
+
+  static async setActiveVersion(version: string | null): Promise<void> {
+    return invoke('set_active_antumbra_version', { version })
+  }
+
+  static async installFromFile(path: string, checksum?: string): Promise<AntumbraUpdateResult> {
+    return invoke('install_antumbra_from_file', { path, checksum: checksum ?? null })
   }
 
   static async getWrapperLogPath(): Promise<string> {
     return invoke('get_wrapper_log_path')
   }
 
-  static async readWrapperLog(): Promise<string> {
-    return invoke('read_wrapper_log')
+  /** Pass `redact` before sharing the log outside the app */
+  static async readWrapperLog(redact = false): Promise<string> {
+    return invoke('read_wrapper_log', { redact })
+  }
+
+  /** Write all wrapper log segments and antumbra.log, redacted, to one file */
+  static async exportLogs(destination: string): Promise<string> {
+    return invoke('export_logs', { destination })
+  }
+
+  static async getLogFiles(): Promise<LogFileInfo[]> {
+    return invoke('get_log_files')
+  }
+
+  static async queryLog(query: LogQuery): Promise<LogQueryPage> {
+    return invoke('query_log', { query })
+  }
+
+  /** Start emitting `log:tail` events with newly appended wrapper log lines */
+  static async tailWrapperLog(): Promise<void> {
+    return invoke('tail_wrapper_log')
   }
 
-  static async readAntumbraLog(): Promise<string> {
-    return invoke('read_antumbra_log')
+  static async stopWrapperLogTail(): Promise<void> {
+    return invoke('stop_wrapper_log_tail')
+  }
+
+  static async readAntumbraLog(redact = false): Promise<string> {
+    return invoke('read_antumbra_log', { redact })
   }
 
   static async getLastCommand(): Promise<AntumbraCommandInfo | null> {
     return invoke('get_last_antumbra_command')
   }
+
+  static async getCommandHistory(offset = 0, limit = 50): Promise<CommandHistoryPage> {
+    return invoke('get_command_history', { offset, limit })
+  }
+
+  static async getStatistics(): Promise<OperationStats[]> {
+    return invoke('get_statistics')
+  }
 }
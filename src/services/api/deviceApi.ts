@@ -1,13 +1,98 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { PartitionListResult } from '../../types';
+import type {
+  CancelAllResult,
+  PartitionListResult,
+  ReplayedEvent,
+  RunningOperation,
+} from '../../types';
 
 /**
  * Device API service - Handles device connection and control operations.
  * Centralizes all Tauri invoke calls related to device management.
  */
 export class DeviceApi {
-  static async cancelOperation(): Promise<void> {
-    return invoke('cancel_operation');
+  /**
+   * Cancel a running antumbra operation.
+   *
+   * @param operationId - Operation to cancel; cancels every running operation when omitted
+   */
+  static async cancelOperation(operationId?: string): Promise<void> {
+    return invoke('cancel_operation', { operationId: operationId || null });
+  }
+
+  /**
+   * Drain the operation queue and cancel every running operation at once.
+   *
+   * @returns The operation IDs that were running and queued
+   */
+  static async cancelAllOperations(): Promise<CancelAllResult> {
+    return invoke('cancel_all_operations');
+  }
+
+  /**
+   * Operations the backend is currently executing.
+   */
+  static async listRunningOperations(): Promise<RunningOperation[]> {
+    return invoke('list_running_operations');
+  }
+
+  /**
+   * Recent output and progress events of a running operation, to refill the
+   * console after a reload.
+   *
+   * @param operationId - Running operation
+   * @param since - Last `seq` already seen; replays everything buffered when omitted
+   */
+  static async replayOperationEvents(
+    operationId: string,
+    since?: number
+  ): Promise<ReplayedEvent[]> {
+    return invoke('replay_operation_events', { operationId, since: since ?? null });
+  }
+
+  /**
+   * Only receive events of the given operations in this window.
+   *
+   * @param operationIds - Operations to follow; follows every operation when omitted
+   */
+  static async subscribeOperationEvents(operationIds?: string[]): Promise<void> {
+    return invoke('subscribe_operation_events', { operationIds: operationIds ?? null });
+  }
+
+  /**
+   * Stop receiving operation events in this window.
+   */
+  static async unsubscribeOperationEvents(): Promise<void> {
+    return invoke('unsubscribe_operation_events');
+  }
+
+  /**
+   * Hold the queue at an operation until resumed or cancelled. The paused
+   * operation and everything queued behind it wait; pausing the running
+   * operation lets it finish and holds the rest of the queue.
+   *
+   * @param operationId - Running or queued operation to pause at
+   */
+  static async pauseOperation(operationId: string): Promise<void> {
+    return invoke('pause_operation', { operationId });
+  }
+
+  /**
+   * Release a queue held by `pauseOperation`.
+   *
+   * @param operationId - The operation the queue was paused at
+   */
+  static async resumeOperation(operationId: string): Promise<void> {
+    return invoke('resume_operation', { operationId });
+  }
+
+  /**
+   * Answer `app:close-requested-while-busy`.
+   *
+   * @param proceed - Cancel the running operations and close; keeps the window open when false
+   */
+  static async confirmCloseWhileBusy(proceed: boolean): Promise<void> {
+    return invoke('confirm_close_while_busy', { proceed });
   }
 
   /**
@@ -28,6 +113,32 @@ export class DeviceApi {
     });
   }
 
+  /**
+   * Export the partition table to CSV or JSON.
+   *
+   * @param path - Destination file; the format follows its extension unless given
+   * @param options.daPath - Needed when no table was listed yet or `refresh` is set
+   * @param options.refresh - Read a fresh table with pgpt instead of the last listed one
+   * @returns Number of partitions written
+   */
+  static async exportPartitionList(
+    path: string,
+    options: {
+      format?: 'csv' | 'json';
+      daPath?: string;
+      preloaderPath?: string;
+      refresh?: boolean;
+    } = {}
+  ): Promise<number> {
+    return invoke('export_partition_list', {
+      path,
+      format: options.format ?? null,
+      daPath: options.daPath || null,
+      preloaderPath: options.preloaderPath || null,
+      refresh: options.refresh ?? false,
+    });
+  }
+
   /**
    * Reboot device to the specified mode.
    * 
@@ -1,8 +1,85 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { WindowsDiagnostics } from '../../types';
+import type {
+  DiskSpace,
+  EnvironmentDiagnostics,
+  ErrorCodeInfo,
+  NetworkDiagnostics,
+  ReplayReport,
+  SchemaCatalog,
+  SelfTestReport,
+  UsageReport,
+} from '../../types';
 
 export class DiagnosticsApi {
-  static async checkWindowsEnvironment(): Promise<WindowsDiagnostics> {
-    return invoke('check_windows_environment');
+  static async checkEnvironment(): Promise<EnvironmentDiagnostics> {
+    return invoke('check_environment');
+  }
+
+  static async diagnoseNetwork(): Promise<NetworkDiagnostics> {
+    return invoke('diagnose_network');
+  }
+
+  /** Description and remediation steps for a code such as "PW-USB-001" */
+  static async lookupErrorCode(code: string): Promise<ErrorCodeInfo> {
+    return invoke('lookup_error_code', { code });
+  }
+
+  /** Non-device checks of the install: binary, directories, scatter parser */
+  static async runSelfTest(): Promise<SelfTestReport> {
+    return invoke('run_self_test');
+  }
+
+  /**
+   * Re-run the last failed antumbra command with verbose logging and write a
+   * troubleshooting log; `dryRun` only records what would run
+   */
+  static async replayLastFailure(dryRun = false): Promise<ReplayReport> {
+    return invoke('replay_last_failure', { dryRun });
+  }
+
+  /**
+   * Write a report of the given operations to `destination`: HTML when it
+   * ends in .html, JSON otherwise. `device` is free-form key/value info.
+   */
+  static async exportOperationReport(
+    operationIds: string[],
+    destination: string,
+    device?: Record<string, string>
+  ): Promise<string> {
+    return invoke('export_operation_report', { operationIds, destination, device: device ?? null });
+  }
+
+  /**
+   * Write this session's operations as a standalone antumbra script. The
+   * script is not redacted (it needs the real file paths to run), so callers
+   * should warn the user not to share it; use exportOperationReport for that.
+   */
+  static async exportSessionScript(
+    destination: string,
+    format?: 'sh' | 'batch'
+  ): Promise<number> {
+    return invoke('export_session_script', { destination, format: format ?? null });
+  }
+
+  /** JSON Schema of the backend's event and result payloads */
+  static async getSchemas(): Promise<SchemaCatalog> {
+    return invoke('get_schemas');
+  }
+
+  static async getDiskSpace(path: string): Promise<DiskSpace> {
+    return invoke('get_disk_space', { path });
+  }
+
+  /** Exactly what uploadUsageMetrics would send */
+  static async previewUsageMetrics(): Promise<UsageReport> {
+    return invoke('preview_usage_metrics');
+  }
+
+  static async uploadUsageMetrics(): Promise<UsageReport> {
+    return invoke('upload_usage_metrics');
+  }
+
+  static async clearUsageMetrics(): Promise<void> {
+    return invoke('clear_usage_metrics');
   }
 }
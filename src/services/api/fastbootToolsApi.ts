@@ -1,5 +1,11 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { FastbootDevice, FastbootRebootMode, FastbootSlot } from '../../types';
+import type {
+  FastbootCliStatus,
+  FastbootDevice,
+  FastbootRebootMode,
+  FastbootSlot,
+  FastbootStep,
+} from '../../types';
 
 export class FastbootToolsApi {
   static async listDevices(): Promise<FastbootDevice[]> {
@@ -77,4 +83,16 @@ export class FastbootToolsApi {
       operationId,
     });
   }
+
+  static async cliStatus(): Promise<FastbootCliStatus> {
+    return invoke('fastboot_cli_status');
+  }
+
+  static async cliRun(
+    step: FastbootStep,
+    serial: string | null,
+    operationId: string
+  ): Promise<void> {
+    return invoke('fastboot_cli_run', { step, serial, operationId });
+  }
 }
@@ -0,0 +1,44 @@
+import { invoke } from '@tauri-apps/api/core';
+import { v4 as uuidv4 } from 'uuid';
+import type { FileHash, FileSlice, HashAlgorithm } from '../../types';
+
+/**
+ * Files API service - Inspects local files such as downloaded firmware
+ * images and partition dumps.
+ */
+export class FilesApi {
+  /**
+   * Hash a file. Listen to `hash:progress` for progress.
+   *
+   * @param path - File to hash
+   * @param options.algorithm - Defaults to SHA-256
+   * @param options.expected - Published hash to compare against (case and `0x` ignored)
+   * @param options.operationId - ID carried by the progress events (auto-generated if not provided)
+   */
+  static async hashFile(
+    path: string,
+    options: { algorithm?: HashAlgorithm; expected?: string; operationId?: string } = {}
+  ): Promise<FileHash> {
+    return invoke('hash_file', {
+      path,
+      algorithm: options.algorithm ?? null,
+      expected: options.expected || null,
+      operationId: options.operationId || uuidv4(),
+    });
+  }
+
+  /**
+   * Read bytes of a file for the hex viewer.
+   *
+   * @param offset - First byte to read
+   * @param length - Bytes to read, at most 1 MiB
+   */
+  static async readFileSlice(path: string, offset: number, length: number): Promise<FileSlice> {
+    return invoke('read_file_slice', { path, offset, length });
+  }
+
+  /** Decode the base64 bytes of a slice */
+  static decodeSlice(slice: FileSlice): Uint8Array {
+    return Uint8Array.from(atob(slice.data), (c) => c.charCodeAt(0));
+  }
+}
@@ -0,0 +1,19 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { JobReport } from '../../types';
+
+/**
+ * Job API service - Runs declarative YAML/JSON job files (backups, flashes,
+ * reboots) step by step through the operation queue.
+ */
+export class JobApi {
+  /**
+   * Run a job file. Listen to `job:step` for per-step progress.
+   *
+   * @param path - Job file; relative paths inside it resolve against its folder
+   * @param jobId - Optional ID carried by the step events (auto-generated if not provided)
+   * @returns The outcome of every step
+   */
+  static async runJobFile(path: string, jobId?: string): Promise<JobReport> {
+    return invoke('run_job_file', { path, jobId: jobId ?? null });
+  }
+}
@@ -15,6 +15,8 @@ export interface ReadPartitionOptions {
   preloaderPath?: string;
   /** Optional operation ID for tracking (auto-generated if not provided) */
   operationId?: string;
+  /** Optional device name used for the organized output folder */
+  deviceLabel?: string;
 }
 
 /**
@@ -52,6 +54,7 @@ export class PartitionApi {
       outputPath: options.outputPath,
       preloaderPath: options.preloaderPath || null,
       operationId: options.operationId || uuidv4(),
+      deviceLabel: options.deviceLabel || null,
     });
   }
 
@@ -128,6 +131,7 @@ export class PartitionApi {
    * @param skipPartitions - Array of partition names to skip
    * @param preloaderPath - Optional path to preloader file
    * @param operationId - Optional operation ID for tracking (auto-generated if not provided)
+   * @param deviceLabel - Optional device name used for the organized output folder
    * @returns Promise resolving when all operations complete
    * @throws Error if any read operation fails
    */
@@ -136,7 +140,8 @@ export class PartitionApi {
     outputDir: string,
     skipPartitions: string[],
     preloaderPath?: string,
-    operationId?: string
+    operationId?: string,
+    deviceLabel?: string
   ): Promise<void> {
     return invoke('read_all_partitions', {
       daPath,
@@ -144,6 +149,7 @@ export class PartitionApi {
       skipPartitions,
       preloaderPath: preloaderPath || null,
       operationId: operationId || uuidv4(),
+      deviceLabel: deviceLabel || null,
     });
   }
 }
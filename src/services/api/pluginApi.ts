@@ -0,0 +1,37 @@
+import { invoke } from '@tauri-apps/api/core';
+import { v4 as uuidv4 } from 'uuid';
+import type { PluginToolInfo } from '../../types';
+
+/**
+ * Plugin API service - Lists and runs tools provided by installed plugins.
+ * Output streams through the usual `operation:*` events.
+ */
+export class PluginApi {
+  /**
+   * Tools of every installed plugin.
+   */
+  static async listTools(): Promise<PluginToolInfo[]> {
+    return invoke('list_plugin_tools');
+  }
+
+  /**
+   * Run a plugin tool.
+   *
+   * @param params - Values for the tool's parameters, keyed by name
+   * @param operationId - Optional operation ID for tracking (auto-generated if not provided)
+   * @returns The tool's stdout
+   */
+  static async runTool(
+    pluginId: string,
+    toolId: string,
+    params: Record<string, string>,
+    operationId?: string
+  ): Promise<string> {
+    return invoke('run_plugin_tool', {
+      pluginId,
+      toolId,
+      params,
+      operationId: operationId || uuidv4(),
+    });
+  }
+}
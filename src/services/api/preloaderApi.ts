@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { PreloaderEntry, PreloaderMetadata } from '../../types';
+
+/**
+ * Preloader API service - Manages the library of device-specific preloaders
+ * kept under the config directory.
+ */
+export class PreloaderApi {
+  /**
+   * List stored preloaders, optionally filtered by model or chipset.
+   */
+  static async listPreloaders(query?: string): Promise<PreloaderEntry[]> {
+    return invoke('list_preloaders', { query: query ?? null });
+  }
+
+  /**
+   * Copy a preloader file into the library. Re-importing the same file
+   * updates the metadata of the existing entry.
+   */
+  static async importPreloader(
+    filePath: string,
+    metadata: PreloaderMetadata
+  ): Promise<PreloaderEntry> {
+    return invoke('import_preloader', { filePath, metadata });
+  }
+
+  static async updatePreloader(id: string, metadata: PreloaderMetadata): Promise<PreloaderEntry> {
+    return invoke('update_preloader', { id, metadata });
+  }
+
+  static async removePreloader(id: string): Promise<void> {
+    return invoke('remove_preloader', { id });
+  }
+}
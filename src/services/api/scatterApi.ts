@@ -1,5 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { ScatterFile, ScatterPartition } from '../../types';
+import type { ImageMatch, ScatterFile, ScatterPartition } from '../../types';
 import { ErrorHandler } from '../utils/errorHandler';
 
 /**
@@ -18,6 +18,16 @@ export class ScatterApi {
     return invoke('parse_scatter_file', { filePath });
   }
 
+  /**
+   * Ask the backend to emit `app:open-scatter` for the scatter file the app
+   * was launched with. Call after listening for the event.
+   *
+   * @returns Whether a launch file was pending
+   */
+  static async openLaunchScatter(): Promise<boolean> {
+    return invoke('open_launch_scatter');
+  }
+
   /**
    * Auto-detect image files for scatter partitions.
    * Attempts to find matching .img files in the same directory as the scatter file.
@@ -44,4 +54,21 @@ export class ScatterApi {
       return new Map();
     }
   }
+
+  /**
+   * Every scored image candidate per partition, best first.
+   *
+   * @param options.hash - Also SHA-256 the best matches (reads every image; slow on big folders)
+   */
+  static async detectImageCandidates(
+    scatterPath: string,
+    partitions: ScatterPartition[],
+    options: { hash?: boolean } = {}
+  ): Promise<ImageMatch[]> {
+    return invoke('detect_image_candidates', {
+      scatterPath,
+      partitions,
+      hash: options.hash ?? null,
+    });
+  }
 }
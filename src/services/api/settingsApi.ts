@@ -1,5 +1,11 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AppSettings } from '../../types';
+import type {
+  AppSettings,
+  PartitionSet,
+  RecentItem,
+  RecentItemKind,
+  SecretKey,
+} from '../../types';
 
 export class SettingsApi {
   static async getSettings(): Promise<AppSettings> {
@@ -9,4 +15,46 @@ export class SettingsApi {
   static async updateSettings(settings: AppSettings): Promise<void> {
     return invoke('update_settings', { settings });
   }
+
+  /** Locales the backend can translate its messages into */
+  static async listLocales(): Promise<string[]> {
+    return invoke('list_locales');
+  }
+
+  static async getRecentItems(kind?: RecentItemKind): Promise<RecentItem[]> {
+    return invoke('get_recent_items', { kind: kind ?? null });
+  }
+
+  static async clearRecentItems(kind?: RecentItemKind): Promise<void> {
+    return invoke('clear_recent_items', { kind: kind ?? null });
+  }
+
+  static async listPartitionSets(): Promise<PartitionSet[]> {
+    return invoke('list_partition_sets');
+  }
+
+  static async savePartitionSet(name: string, partitions: string[]): Promise<PartitionSet> {
+    return invoke('save_partition_set', { name, partitions });
+  }
+
+  static async renamePartitionSet(name: string, newName: string): Promise<PartitionSet> {
+    return invoke('rename_partition_set', { name, newName });
+  }
+
+  static async deletePartitionSet(name: string): Promise<void> {
+    return invoke('delete_partition_set', { name });
+  }
+
+  // Secrets live in the OS keyring; their values are never sent back
+  static async hasSecret(key: SecretKey): Promise<boolean> {
+    return invoke('has_secret', { key });
+  }
+
+  static async setSecret(key: SecretKey, value: string): Promise<void> {
+    return invoke('set_secret', { key, value });
+  }
+
+  static async deleteSecret(key: SecretKey): Promise<void> {
+    return invoke('delete_secret', { key });
+  }
 }
@@ -0,0 +1,24 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { OpenedWorkspace, Workspace } from '../../types';
+
+/**
+ * Workspace API service - Opens and saves project folders holding a
+ * scatter file, images, DA and backups together with a workspace.json.
+ */
+export class WorkspaceApi {
+  /**
+   * Open a workspace folder (or its workspace.json).
+   *
+   * @returns The folder and its selections with absolute paths
+   */
+  static async openWorkspace(path: string): Promise<OpenedWorkspace> {
+    return invoke('open_workspace', { path });
+  }
+
+  /**
+   * Save the current selections to workspace.json in the given folder.
+   */
+  static async saveWorkspace(path: string, workspace: Workspace): Promise<void> {
+    return invoke('save_workspace', { path, workspace });
+  }
+}
@@ -73,7 +73,9 @@ export class ErrorHandler {
       useOperationStore.getState().addLog({
         timestamp: new Date().toISOString(),
         level: 'error',
-        message: `[${operation}] ${errorMessage}`,
+        message: parsedError.error_code
+          ? `[${operation}] ${errorMessage} (${parsedError.error_code})`
+          : `[${operation}] ${errorMessage}`,
       });
       
       // Also log suggestion to operation log if available
@@ -45,6 +45,7 @@ export function parseTauriError(error: unknown): AppError {
         category: parseCategory(err.category),
         suggestion: err.suggestion,
         code: err.code,
+        error_code: err.error_code,
         output: err.output,
       };
     }
@@ -1,5 +1,5 @@
 import { create } from 'zustand';
-import type { AppSettings } from '../types';
+import type { AppSettings, CommandTimeouts, ToolBackend, UpdateChannel } from '../types';
 import { SettingsApi } from '../services/api/settingsApi';
 import { ErrorHandler } from '../services/utils/errorHandler';
 
@@ -10,6 +10,46 @@ interface DeviceState {
   defaultOutputPath: string | null;
   antumbraVersion: string | null;
   autoCheckUpdates: boolean;
+  ptyMode: boolean;
+  outputDedupWindowMs: number;
+  dedupStderr: boolean;
+  simulationMode: boolean;
+  recordSessions: boolean;
+  queueOperations: boolean;
+  outputBufferLines: number;
+  spillOutputToDisk: boolean;
+  proxyUrl: string | null;
+  /** Whether a GitHub token is stored; the token itself stays in the backend */
+  hasGithubToken: boolean;
+  updateChannel: UpdateChannel;
+  updateMirrors: string[];
+  requireSignedUpdates: boolean;
+  updateCheckIntervalMinutes: number;
+  antumbraPathOverride: string | null;
+  downloadSpeedLimitKbps: number | null;
+  inactivityTimeoutSecs: number;
+  maxOperationDurationSecs: number;
+  cancelGracePeriodSecs: number;
+  downloadRetries: number;
+  commandTimeouts: Record<string, CommandTimeouts>;
+  httpConnectTimeoutSecs: number;
+  httpReadTimeoutSecs: number;
+  httpUserAgent: string | null;
+  httpCaCertificatePath: string | null;
+  httpAcceptInvalidCerts: boolean;
+  locale: string | null;
+  organizeOutputDirs: boolean;
+  safeMode: boolean;
+  redactExports: boolean;
+  redactionPatterns: string[];
+  usageMetricsEnabled: boolean;
+  usageMetricsEndpoint: string | null;
+  notificationsEnabled: boolean;
+  notificationOperations: string[];
+  preventSleep: boolean;
+  backend: ToolBackend;
+  mtkclientPath: string | null;
+  compactProgressOutput: boolean;
 
   // Settings hydration
   isSettingsLoading: boolean;
@@ -26,6 +66,10 @@ interface DeviceState {
   setPreloaderPath: (path: string | null) => Promise<void>;
   setDefaultOutputPath: (path: string | null) => Promise<void>;
   setAutoCheckUpdates: (enabled: boolean) => Promise<void>;
+  setPtyMode: (enabled: boolean) => Promise<void>;
+  setSimulationMode: (enabled: boolean) => Promise<void>;
+  /** Store the GitHub token, or forget it when null or blank */
+  setGithubToken: (token: string | null) => Promise<void>;
   updateSettings: (partial: Partial<DeviceState>) => Promise<void>;
   setConnecting: (connecting: boolean) => void;
   setConnected: (connected: boolean) => void;
@@ -37,11 +81,52 @@ interface DeviceState {
   saveSettings: (state?: DeviceState) => Promise<void>;
 }
 
+/** Antumbra subcommands that notify on completion unless configured otherwise */
+const DEFAULT_NOTIFICATION_OPERATIONS = ['read-all', 'download', 'upload', 'erase', 'format'];
+
 const buildSettings = (state: DeviceState): AppSettings => ({
   da_path: state.daPath || undefined,
   preloader_path: state.preloaderPath || undefined,
   default_output_path: state.defaultOutputPath || undefined,
   auto_check_updates: state.autoCheckUpdates,
+  pty_mode: state.ptyMode,
+  output_dedup_window_ms: state.outputDedupWindowMs,
+  dedup_stderr: state.dedupStderr,
+  simulation_mode: state.simulationMode,
+  record_sessions: state.recordSessions,
+  queue_operations: state.queueOperations,
+  output_buffer_lines: state.outputBufferLines,
+  spill_output_to_disk: state.spillOutputToDisk,
+  proxy_url: state.proxyUrl || undefined,
+  update_channel: state.updateChannel,
+  update_mirrors: state.updateMirrors,
+  require_signed_updates: state.requireSignedUpdates,
+  update_check_interval_minutes: state.updateCheckIntervalMinutes,
+  antumbra_path_override: state.antumbraPathOverride || undefined,
+  download_speed_limit_kbps: state.downloadSpeedLimitKbps ?? undefined,
+  inactivity_timeout_secs: state.inactivityTimeoutSecs,
+  max_operation_duration_secs: state.maxOperationDurationSecs,
+  cancel_grace_period_secs: state.cancelGracePeriodSecs,
+  download_retries: state.downloadRetries,
+  command_timeouts: state.commandTimeouts,
+  http_connect_timeout_secs: state.httpConnectTimeoutSecs,
+  http_read_timeout_secs: state.httpReadTimeoutSecs,
+  http_user_agent: state.httpUserAgent || undefined,
+  http_ca_certificate_path: state.httpCaCertificatePath || undefined,
+  http_accept_invalid_certs: state.httpAcceptInvalidCerts,
+  locale: state.locale || undefined,
+  organize_output_dirs: state.organizeOutputDirs,
+  safe_mode: state.safeMode,
+  redact_exports: state.redactExports,
+  redaction_patterns: state.redactionPatterns,
+  usage_metrics_enabled: state.usageMetricsEnabled,
+  usage_metrics_endpoint: state.usageMetricsEndpoint || undefined,
+  notifications_enabled: state.notificationsEnabled,
+  notification_operations: state.notificationOperations,
+  prevent_sleep: state.preventSleep,
+  backend: state.backend,
+  mtkclient_path: state.mtkclientPath || undefined,
+  compact_progress_output: state.compactProgressOutput,
   antumbra_version: state.antumbraVersion || undefined,
 });
 
@@ -52,6 +137,45 @@ export const useDeviceStore = create<DeviceState>((set, get) => ({
   defaultOutputPath: null,
   antumbraVersion: null,
   autoCheckUpdates: true,
+  ptyMode: false,
+  outputDedupWindowMs: 2000,
+  dedupStderr: false,
+  simulationMode: false,
+  recordSessions: false,
+  queueOperations: true,
+  outputBufferLines: 10000,
+  spillOutputToDisk: false,
+  proxyUrl: null,
+  hasGithubToken: false,
+  updateChannel: 'stable',
+  updateMirrors: [],
+  requireSignedUpdates: false,
+  updateCheckIntervalMinutes: 60,
+  antumbraPathOverride: null,
+  downloadSpeedLimitKbps: null,
+  inactivityTimeoutSecs: 30,
+  maxOperationDurationSecs: 0,
+  cancelGracePeriodSecs: 3,
+  downloadRetries: 3,
+  commandTimeouts: {},
+  httpConnectTimeoutSecs: 10,
+  httpReadTimeoutSecs: 30,
+  httpUserAgent: null,
+  httpCaCertificatePath: null,
+  httpAcceptInvalidCerts: false,
+  locale: null,
+  organizeOutputDirs: false,
+  safeMode: false,
+  redactExports: true,
+  redactionPatterns: [],
+  usageMetricsEnabled: false,
+  usageMetricsEndpoint: null,
+  notificationsEnabled: true,
+  notificationOperations: DEFAULT_NOTIFICATION_OPERATIONS,
+  preventSleep: true,
+  backend: 'antumbra',
+  mtkclientPath: null,
+  compactProgressOutput: true,
 
   // Connection State
   isConnecting: false,
@@ -80,6 +204,29 @@ export const useDeviceStore = create<DeviceState>((set, get) => ({
     await get().updateSettings({ autoCheckUpdates: enabled });
   },
 
+  setPtyMode: async (enabled) => {
+    await get().updateSettings({ ptyMode: enabled });
+  },
+
+  setSimulationMode: async (enabled) => {
+    await get().updateSettings({ simulationMode: enabled });
+  },
+
+  setGithubToken: async (token) => {
+    try {
+      if (token && token.trim()) {
+        await SettingsApi.setSecret('github_token', token);
+      } else {
+        await SettingsApi.deleteSecret('github_token');
+      }
+      set({ hasGithubToken: await SettingsApi.hasSecret('github_token') });
+    } catch (error) {
+      ErrorHandler.handle(error, 'Save GitHub token', {
+        addToOperationLog: false,
+      });
+    }
+  },
+
   updateSettings: async (partial) => {
     const state = get();
     const hasChanges = Object.keys(partial).some((key) => {
@@ -109,6 +256,7 @@ export const useDeviceStore = create<DeviceState>((set, get) => ({
     try {
       set({ isSettingsLoading: true, settingsError: null });
       const settings = await SettingsApi.getSettings();
+      const hasGithubToken = await SettingsApi.hasSecret('github_token').catch(() => false);
       
       // Auto-sync detected antumbra version if config version is null
       // This ensures version consistency between binary and config
@@ -123,6 +271,45 @@ export const useDeviceStore = create<DeviceState>((set, get) => ({
         defaultOutputPath: settings.default_output_path || null,
         antumbraVersion: settings.antumbra_version || null,
         autoCheckUpdates: settings.auto_check_updates,
+        ptyMode: settings.pty_mode ?? false,
+        outputDedupWindowMs: settings.output_dedup_window_ms ?? 2000,
+        dedupStderr: settings.dedup_stderr ?? false,
+        simulationMode: settings.simulation_mode ?? false,
+        recordSessions: settings.record_sessions ?? false,
+        queueOperations: settings.queue_operations ?? true,
+        outputBufferLines: settings.output_buffer_lines ?? 10000,
+        spillOutputToDisk: settings.spill_output_to_disk ?? false,
+        proxyUrl: settings.proxy_url || null,
+        hasGithubToken,
+        updateChannel: settings.update_channel ?? 'stable',
+        updateMirrors: settings.update_mirrors ?? [],
+        requireSignedUpdates: settings.require_signed_updates ?? false,
+        updateCheckIntervalMinutes: settings.update_check_interval_minutes ?? 60,
+        antumbraPathOverride: settings.antumbra_path_override || null,
+        downloadSpeedLimitKbps: settings.download_speed_limit_kbps ?? null,
+        inactivityTimeoutSecs: settings.inactivity_timeout_secs ?? 30,
+        maxOperationDurationSecs: settings.max_operation_duration_secs ?? 0,
+        cancelGracePeriodSecs: settings.cancel_grace_period_secs ?? 3,
+        downloadRetries: settings.download_retries ?? 3,
+        commandTimeouts: settings.command_timeouts ?? {},
+        httpConnectTimeoutSecs: settings.http_connect_timeout_secs ?? 10,
+        httpReadTimeoutSecs: settings.http_read_timeout_secs ?? 30,
+        httpUserAgent: settings.http_user_agent || null,
+        httpCaCertificatePath: settings.http_ca_certificate_path || null,
+        httpAcceptInvalidCerts: settings.http_accept_invalid_certs ?? false,
+        locale: settings.locale || null,
+        organizeOutputDirs: settings.organize_output_dirs ?? false,
+        safeMode: settings.safe_mode ?? false,
+        redactExports: settings.redact_exports ?? true,
+        redactionPatterns: settings.redaction_patterns ?? [],
+        usageMetricsEnabled: settings.usage_metrics_enabled ?? false,
+        usageMetricsEndpoint: settings.usage_metrics_endpoint || null,
+        notificationsEnabled: settings.notifications_enabled ?? true,
+        notificationOperations: settings.notification_operations ?? DEFAULT_NOTIFICATION_OPERATIONS,
+        preventSleep: settings.prevent_sleep ?? true,
+        backend: settings.backend ?? 'antumbra',
+        mtkclientPath: settings.mtkclient_path || null,
+        compactProgressOutput: settings.compact_progress_output ?? true,
         isSettingsLoaded: true,
       });
     } catch (error) {
@@ -19,6 +19,7 @@ interface OperationState {
   logs: LogEvent[];
   progress: FlashProgress | null;
   error: string | null;
+  outputPath: string | null;
   
   // Actions
   startOperation: (
@@ -30,6 +31,7 @@ interface OperationState {
   setOperationId: (operationId: string) => void;
   setIsStreaming: (isStreaming: boolean) => void;
   updateProgress: (progress: FlashProgress) => void;
+  setOutputPath: (outputPath: string) => void;
   addLog: (log: LogEvent) => void;
   clearLogs: () => void;
   finishOperation: (success: boolean, error?: string) => void;
@@ -50,6 +52,7 @@ export const useOperationStore = create<OperationState>((set, get) => ({
   logs: [],
   progress: null,
   error: null,
+  outputPath: null,
   
   // Actions
   startOperation: (type, partitionName, partitionSize, operationId) => {
@@ -62,6 +65,7 @@ export const useOperationStore = create<OperationState>((set, get) => ({
       progress: null,
       logs: state.logs, // Preserve existing logs
       error: null,
+      outputPath: null,
       operationId: operationId || null,
       isStreaming: !!operationId,
       startTime: Date.now(),
@@ -76,6 +80,8 @@ export const useOperationStore = create<OperationState>((set, get) => ({
   setIsStreaming: (isStreaming) => set({ isStreaming }),
   
   updateProgress: (progress) => set({ progress }),
+
+  setOutputPath: (outputPath) => set({ outputPath }),
   
   addLog: (log) => {
     const logs = get().logs;
@@ -123,6 +129,7 @@ export const useOperationStore = create<OperationState>((set, get) => ({
     progress: null,
     logs: [],
     error: null,
+    outputPath: null,
     operationId: null,
     isStreaming: false,
     startTime: null,
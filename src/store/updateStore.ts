@@ -6,6 +6,7 @@ import { ErrorHandler } from '../services/utils/errorHandler';
 import { WindowsErrorHandler } from '../services/utils/windowsErrorHandler';
 import { useOperationStore } from './operationStore';
 import type { AntumbraUpdateInfo, DownloadProgress } from '../types';
+import { ErrorType, type SerializedError } from '../types/errors';
 
 interface UpdateState {
   updateInfo: AntumbraUpdateInfo | null;
@@ -15,7 +16,8 @@ interface UpdateState {
   isUpdateModalOpen: boolean;
   setUpdateModalOpen: (open: boolean) => void;
   checkUpdate: (options?: { showToast?: boolean }) => Promise<void>;
-  downloadUpdate: () => Promise<void>;
+  downloadUpdate: (tag?: string) => Promise<void>;
+  cancelDownload: () => Promise<void>;
   startProgressListener: () => Promise<() => void>;
 }
 
@@ -23,7 +25,7 @@ let progressUnlisten: (() => void) | null = null;
 let listenerPromise: Promise<() => void> | null = null;
 let hasShownDownloadSuccess = false;
 
-export const useUpdateStore = create<UpdateState>((set) => ({
+export const useUpdateStore = create<UpdateState>((set, get) => ({
   updateInfo: null,
   isCheckingUpdate: false,
   isDownloadingUpdate: false,
@@ -37,7 +39,8 @@ export const useUpdateStore = create<UpdateState>((set) => ({
     set({ isCheckingUpdate: true });
 
     try {
-      const info = await AntumbraApi.checkUpdate();
+      // A user-initiated check (with toast) shouldn't be answered from the cache
+      const info = await AntumbraApi.checkUpdate(showToast);
       set({ updateInfo: info });
 
       if (!info.supported) {
@@ -65,17 +68,24 @@ export const useUpdateStore = create<UpdateState>((set) => ({
     }
   },
 
-  downloadUpdate: async () => {
+  downloadUpdate: async (tag) => {
     set({ isDownloadingUpdate: true, downloadProgress: null, isUpdateModalOpen: true });
     hasShownDownloadSuccess = false;
 
     try {
-      await AntumbraApi.downloadUpdate();
+      // Install exactly the release the user was shown, not whatever is latest now
+      const approved = tag ? null : get().updateInfo;
+      await AntumbraApi.downloadUpdate(tag, approved);
       set({ isUpdateModalOpen: false });
 
       const info = await AntumbraApi.checkUpdate();
       set({ updateInfo: info });
     } catch (error: unknown) {
+      if ((error as SerializedError | null)?.type === ErrorType.Cancelled) {
+        toast('Download cancelled');
+        return;
+      }
+
       const customMessage = WindowsErrorHandler.getErrorSuggestion(error);
 
       ErrorHandler.handle(error, 'Download antumbra update', {
@@ -100,6 +110,14 @@ export const useUpdateStore = create<UpdateState>((set) => ({
     }
   },
 
+  cancelDownload: async () => {
+    try {
+      await AntumbraApi.cancelDownload();
+    } catch (error: unknown) {
+      ErrorHandler.handle(error, 'Cancel antumbra download', { addToOperationLog: false });
+    }
+  },
+
   startProgressListener: async () => {
     if (progressUnlisten) return progressUnlisten;
     if (listenerPromise) return listenerPromise;
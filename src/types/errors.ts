@@ -28,8 +28,12 @@ export interface AppError {
   suggestion?: string;
   /** OS-specific error code (if applicable) */
   code?: number;
+  /** Stable catalog code, e.g. "PW-USB-001" */
+  error_code?: string;
   /** Command output (for command errors) */
   output?: string;
+  /** Operation holding the device (for busy errors) */
+  running_operation_id?: string;
 }
 
 /**
@@ -42,6 +46,7 @@ export interface SerializedError {
   category?: string;
   suggestion?: string;
   code?: number;
+  error_code?: string;
   output?: string;
   [key: string]: unknown;
 }
@@ -83,6 +88,11 @@ export const ErrorType = {
   Command: 'command',
   DeviceNotConnected: 'device_not_connected',
   Cancelled: 'cancelled',
+  Busy: 'busy',
+  AuthRequired: 'auth_required',
+  DaMismatch: 'da_mismatch',
+  HandshakeTimeout: 'handshake_timeout',
+  SafeMode: 'safe_mode',
   InvalidPartition: 'invalid_partition',
   Parse: 'parse',
   Update: 'update',
@@ -90,3 +100,14 @@ export const ErrorType = {
 } as const;
 
 export type ErrorType = typeof ErrorType[keyof typeof ErrorType];
+
+/**
+ * Catalog entry returned by lookup_error_code
+ */
+export interface ErrorCodeInfo {
+  code: string;
+  error_type: string;
+  title: string;
+  description: string;
+  remediation: string[];
+}
@@ -8,14 +8,22 @@ export interface Partition {
 export interface PartitionListResult {
   partitions: Partition[];
   operation_id: string;
+  schema_version: number;
 }
 
 export interface FlashProgress {
-  current: number;
-  total: number;
+  /** Bytes done and in total; null when antumbra only prints a percentage */
+  current: number | null;
+  total: number | null;
   percentage: number;
   partition_name: string;
   operation: 'read' | 'write';
+  operation_id?: string | null;
+  /** Smoothed over recent samples */
+  speed_bytes_per_sec?: number | null;
+  /** Since the previous progress event */
+  instant_speed_bytes_per_sec?: number | null;
+  eta_secs?: number | null;
 }
 
 export interface OperationProgressEvent {
@@ -26,6 +34,45 @@ export interface OperationProgressEvent {
   operation: 'read' | 'write';
 }
 
+/** Sent on `queue:position` whenever the operation queue changes */
+export interface QueuePositionEvent {
+  operation_id: string;
+  /** 0 = running, 1 = next in line */
+  position: number;
+  queue_length: number;
+  /** The queue is held by `pause_operation` */
+  paused: boolean;
+}
+
+/** An operation the backend is currently executing */
+export interface RunningOperation {
+  operation_id: string;
+  command: string;
+  partition: string | null;
+  started_at: string;
+  pid: number | null;
+  simulated: boolean;
+}
+
+/** A buffered event returned by `replay_operation_events` */
+export interface ReplayedEvent<T = unknown> {
+  seq: number;
+  event: string;
+  payload: T;
+}
+
+/** Sent when the window is closed while operations run */
+export interface CloseRequestedWhileBusyEvent {
+  operations: RunningOperation[];
+  timeout_secs: number;
+}
+
+/** Operations stopped by `cancel_all_operations` */
+export interface CancelAllResult {
+  running: string[];
+  queued: string[];
+}
+
 export interface LogEvent {
   id?: string;
   timestamp: string;
@@ -34,12 +81,97 @@ export interface LogEvent {
   partition_name?: string;
 }
 
+/** Backend log record streamed as `log:event` */
+export interface BackendLogEvent {
+  timestamp: string;
+  level: string;
+  module: string;
+  message: string;
+  session_id: string;
+  operation_id: string | null;
+  partition_name: string | null;
+}
+
+export type UpdateChannel = 'stable' | 'pre-release';
+
+export type ToolBackend = 'antumbra' | 'mtkclient';
+
+export type RecentItemKind = 'scatter' | 'da' | 'image' | 'output_dir';
+
+export interface RecentItem {
+  kind: RecentItemKind;
+  path: string;
+  used_at: string;
+}
+
+export type SecretKey = 'github_token' | 'auth_passphrase' | 'backup_passphrase';
+
+export interface BinaryChecksum {
+  path: string;
+  size: number;
+  modified_ms: number;
+  sha256: string;
+}
+
+export interface PartitionSet {
+  name: string;                     // "modem set", "boot chain"
+  partitions: string[];
+}
+
+export interface CommandTimeouts {
+  inactivity_timeout_secs?: number | null;
+  max_duration_secs?: number | null;
+}
+
 export interface AppSettings {
+  config_version?: number;
   da_path?: string;
   preloader_path?: string;
   default_output_path?: string;
   auto_check_updates: boolean;
   antumbra_version?: string;
+  pty_mode?: boolean;
+  output_dedup_window_ms?: number;
+  dedup_stderr?: boolean;
+  simulation_mode?: boolean;
+  record_sessions?: boolean;
+  queue_operations?: boolean;
+  output_buffer_lines?: number;
+  spill_output_to_disk?: boolean;
+  proxy_url?: string;
+  update_channel?: UpdateChannel;
+  update_mirrors?: string[];
+  require_signed_updates?: boolean;
+  active_antumbra_version?: string;
+  update_check_interval_minutes?: number;
+  antumbra_path_override?: string;
+  download_speed_limit_kbps?: number;
+  recent_items?: RecentItem[];
+  partition_sets?: PartitionSet[];
+  binary_checksum?: BinaryChecksum;
+  inactivity_timeout_secs?: number;
+  max_operation_duration_secs?: number;
+  cancel_grace_period_secs?: number;
+  download_retries?: number;
+  command_timeouts?: Record<string, CommandTimeouts>;
+  http_connect_timeout_secs?: number;
+  http_read_timeout_secs?: number;
+  http_user_agent?: string;
+  http_ca_certificate_path?: string;
+  http_accept_invalid_certs?: boolean;
+  locale?: string;
+  organize_output_dirs?: boolean;
+  safe_mode?: boolean;
+  redact_exports?: boolean;
+  redaction_patterns?: string[];
+  usage_metrics_enabled?: boolean;
+  usage_metrics_endpoint?: string;
+  notifications_enabled?: boolean;
+  notification_operations?: string[];
+  prevent_sleep?: boolean;
+  backend?: ToolBackend;
+  mtkclient_path?: string;
+  compact_progress_output?: boolean;
 }
 
 export interface AntumbraUpdateInfo {
@@ -52,13 +184,85 @@ export interface AntumbraUpdateInfo {
   asset_url: string | null;
   checksum: string | null;
   message: string | null;
+  latest_tag?: string | null;
+  signature_url?: string | null;
 }
 
 export interface AntumbraCommandInfo {
+  id: string;
   command: string;
   args: string[];
   working_dir: string;
   started_at: string;
+  operation_id: string | null;
+  finished_at: string | null;
+  duration_ms: number | null;
+  exit_code: number | null;
+  success: boolean | null;
+  bytes: number | null;
+  session_id: string | null;
+}
+
+export interface CommandHistoryPage {
+  entries: AntumbraCommandInfo[];
+  total: number;
+}
+
+export interface OperationStats {
+  operation: string;
+  runs: number;
+  successes: number;
+  average_duration_ms: number | null;
+  total_bytes: number;
+  average_bytes_per_sec: number | null;
+  ms_per_gb: number | null;
+}
+
+export interface LogFileInfo {
+  path: string;
+  size: number;
+  modified: string | null;
+  active: boolean;
+}
+
+export interface LogQuery {
+  levels?: string[];
+  search?: string | null;
+  regex?: boolean;
+  since?: string | null;
+  until?: string | null;
+  include_rotated?: boolean;
+  session_id?: string | null;
+  operation_id?: string | null;
+  offset?: number;
+  limit?: number | null;
+}
+
+export interface LogLine {
+  timestamp: string | null;
+  level: string | null;
+  session_id: string | null;
+  operation_id: string | null;
+  message: string;
+}
+
+export interface LogQueryPage {
+  entries: LogLine[];
+  total: number;
+}
+
+export interface AntumbraRelease {
+  tag: string;
+  name: string | null;
+  published_at: string | null;
+  prerelease: boolean;
+  has_asset: boolean;
+}
+
+export interface InstalledAntumbraVersion {
+  version: string;
+  path: string;
+  active: boolean;
 }
 
 export interface AntumbraUpdateResult {
@@ -125,12 +329,98 @@ export interface AdbStatResult {
 
 export type AdbRebootMode = 'normal' | 'bootloader' | 'recovery' | 'fastboot';
 
+export type AdbCliRebootTarget = AdbRebootMode | 'edl';
+
+/** Post-flash steps run with the platform fastboot binary */
+export type FastbootStep =
+  | 'flashing_unlock'
+  | 'flashing_lock'
+  | 'wipe'
+  | 'reboot'
+  | 'reboot_bootloader';
+
+export interface FastbootCliStatus {
+  path: string | null;
+  version: string | null;
+}
+
+/** A device seen by the platform adb binary */
+export interface AdbCliDevice {
+  serial: string;
+  state: string;
+  model: string | null;
+  product: string | null;
+}
+
+export interface AdbCliStatus {
+  path: string | null;
+  version: string | null;
+}
+
 export type OperationType = 'read' | 'write' | null;
 
 // Windows diagnostics types
-export interface WindowsDiagnostics {
+export interface SelfTestCheck {
+  name: string;
+  passed: boolean;
+  detail: string;
+}
+
+export interface SelfTestReport {
+  passed: boolean;
+  checks: SelfTestCheck[];
+}
+
+export interface ReplayReport {
+  original: AntumbraCommandInfo;
+  dry_run: boolean;
+  command_line: string;
+  /** Troubleshooting log that was written */
+  log_path: string;
+  exit_code: number | null;
+  success: boolean | null;
+  duration_ms: number | null;
+}
+
+export interface UsageReport {
+  app_version: string;
+  antumbra_version: string | null;
+  os: string;
+  arch: string;
+  since: string;
+  operations: Record<string, { successes: number; failures: number }>;
+}
+
+export interface DiskSpace {
+  path: string;
+  total_bytes: number;
+  free_bytes: number;
+}
+
+export interface NetworkCheck {
+  ok: boolean;
+  duration_ms: number | null;
+  detail: string | null;
+}
+
+export interface NetworkDiagnostics {
+  dns: NetworkCheck;
+  tcp_connect: NetworkCheck;
+  github_api: NetworkCheck;
+  proxy: {
+    configured: string | null;
+    configured_valid: boolean;
+    environment: string[];
+  };
+  latency_ms: number | null;
+  rate_limit_remaining: number | null;
+}
+
+export interface EnvironmentDiagnostics {
+  platform: string;
   os_info: string;
   binary_location: string | null;
+  binary_overridden: boolean;
   binary_version: string | null;
   config_location: string;
   config_exists: boolean;
@@ -139,9 +429,27 @@ export interface WindowsDiagnostics {
   running_antumbra_processes: string[];
   permissions_ok: boolean;
   network_connectivity: boolean;
+  usb_drivers: UsbDriverReport;
   recommendations: string[];
 }
 
+export interface UsbDriverInfo {
+  name: string;
+  instance_id: string;
+  mode: 'brom' | 'preloader' | 'da' | 'unknown';
+  service: string | null;
+  problem_code: number;
+}
+
+export interface UsbDriverReport {
+  platform: string;
+  devices: UsbDriverInfo[];
+  driver_packages: string[];
+  class_filters: string[];
+  udev_rules: string[];
+  modem_manager_running: boolean;
+}
+
 // Scatter file types
 export interface ScatterPartition {
   index: string;                    // "SYS0"
@@ -165,5 +473,148 @@ export interface ScatterFile {
   file_path: string;
 }
 
+/** Scatter file opened through the OS file association (`app:open-scatter`) */
+export interface OpenScatterEvent {
+  path: string;
+  scatter: ScatterFile | null;
+  error: string | null;
+}
+
+export interface PreloaderMetadata {
+  model?: string | null;            // "Redmi Note 12 (tapas)"
+  chipset?: string | null;          // "MT6789"
+  source?: string | null;           // Firmware package or dump it came from
+  notes?: string | null;
+}
+
+export interface PreloaderEntry extends PreloaderMetadata {
+  id: string;
+  file_name: string;
+  path: string;                     // Library copy to pass to antumbra
+  sha256: string;
+  size: number;
+  imported_at: string;
+}
+
+export interface Workspace {
+  name?: string | null;
+  scatter_path?: string | null;
+  storage_type?: string | null;
+  da_path?: string | null;
+  preloader_path?: string | null;
+  images: Record<string, string>;   // partition name -> image path
+  selected_partitions: string[];
+  backup_dir?: string | null;
+  saved_at?: string | null;
+}
+
+export interface OpenedWorkspace {
+  folder: string;
+  workspace: Workspace;
+  schema_version: number;
+}
+
+/** JSON Schema of every backend model, by model name */
+export interface SchemaCatalog {
+  schema_version: number;
+  schemas: Record<string, Record<string, unknown>>;
+}
+
+export type JobStepStatus = 'running' | 'succeeded' | 'failed' | 'skipped';
+
+/** Sent on `job:step` when a job step starts and when it ends */
+export interface JobStepEvent {
+  job_id: string;
+  index: number;
+  action: 'backup' | 'read' | 'flash' | 'erase' | 'reboot' | 'fastboot';
+  partition: string | null;
+  operation_id: string | null;
+  status: JobStepStatus;
+  error: string | null;
+  duration_ms: number | null;
+}
+
+export interface JobReport {
+  job_id: string;
+  name: string | null;
+  steps: JobStepEvent[];
+  succeeded: number;
+  failed: number;
+  skipped: number;
+  duration_ms: number;
+}
+
+export type PluginParamKind = 'text' | 'file' | 'save_file' | 'directory';
+
+export interface PluginParam {
+  name: string;
+  label: string;
+  kind: PluginParamKind;
+  required: boolean;
+}
+
+/** A tool registered by an installed plugin */
+export interface PluginToolInfo {
+  plugin_id: string;
+  plugin_name: string;
+  id: string;
+  name: string;
+  description: string | null;
+  executable: string;
+  args: string[];
+  params: PluginParam[];
+  writes_device: boolean;
+}
+
 // Re-export error types
 export * from './errors';
+
+export type HashAlgorithm = 'sha256' | 'md5' | 'crc32';
+
+export interface FileHash {
+  path: string;
+  algorithm: HashAlgorithm;
+  /** Lowercase hex */
+  hash: string;
+  size: number;
+  duration_ms: number;
+  /** Whether `hash` equals the expected one, when one was given */
+  matches: boolean | null;
+}
+
+/** Sent on `hash:progress` while a file is hashed */
+export interface HashProgressEvent {
+  operation_id: string;
+  path: string;
+  bytes_hashed: number;
+  total_bytes: number;
+  percentage: number;
+}
+
+/** A byte range of a local file, for the hex viewer */
+export interface FileSlice {
+  offset: number;
+  /** Bytes actually returned; less than asked for near the end of the file */
+  length: number;
+  file_size: number;
+  /** Base64 of the bytes */
+  data: string;
+}
+
+export interface ImageCandidate {
+  file: string;
+  score: number;
+}
+
+/** Image files matched to a scatter partition */
+export interface ImageMatch {
+  partition_name: string;
+  best: string | null;
+  /** Absolute paths, best first */
+  candidates: ImageCandidate[];
+  ambiguous: boolean;
+  /** Size of the best match in bytes */
+  size: number | null;
+  /** SHA-256 of the best match, when hashing was asked for */
+  sha256: string | null;
+}